@@ -0,0 +1,209 @@
+use chrono::{DateTime, Utc};
+use dioxus::prelude::*;
+
+use crate::{
+    app::pages::LoginStatus,
+    backend::{SharedAuthState, account},
+};
+
+async fn select_export_file() -> Option<std::path::PathBuf> {
+    use rfd::AsyncFileDialog;
+
+    AsyncFileDialog::new()
+        .set_title("Export Account Data")
+        .set_file_name("arenabuddy-export.jsonl")
+        .save_file()
+        .await
+        .map(|file| file.path().to_path_buf())
+}
+
+fn format_scheduled(at: &str) -> String {
+    DateTime::parse_from_rfc3339(at)
+        .map(|dt| super::format_local_datetime(dt.with_timezone(&Utc)))
+        .unwrap_or_else(|_| at.to_string())
+}
+
+#[component]
+fn ExportSection() -> Element {
+    let auth_state = use_context::<SharedAuthState>();
+    let mut exporting = use_signal(|| false);
+    let mut status = use_signal(|| None::<String>);
+
+    let on_export = move |_| {
+        let auth_state = auth_state.clone();
+        spawn(async move {
+            let Some(path) = select_export_file().await else {
+                return;
+            };
+            exporting.set(true);
+            status.set(None);
+            match account::export_my_data(&auth_state, &path).await {
+                Ok(records) => status.set(Some(format!("Exported {records} records to {}", path.display()))),
+                Err(e) => status.set(Some(format!("Export failed: {e}"))),
+            }
+            exporting.set(false);
+        });
+    };
+
+    rsx! {
+        div { class: "bg-gray-800 rounded-lg border border-gray-700 p-6",
+            h2 { class: "text-lg font-semibold text-gray-300 mb-2", "Export my data" }
+            p { class: "text-sm text-gray-400 mb-4",
+                "Download your profile and every match, draft and parse error report stored on the server as a JSON Lines file."
+            }
+            button {
+                onclick: on_export,
+                disabled: exporting(),
+                class: "bg-amber-600 hover:bg-amber-700 disabled:bg-gray-600 text-white py-2 px-4 rounded transition-colors duration-150",
+                if exporting() { "Exporting..." } else { "Export" }
+            }
+            if let Some(message) = status() {
+                p { class: "mt-3 text-sm text-gray-300", "{message}" }
+            }
+        }
+    }
+}
+
+#[component]
+fn DeleteSection(username: String, scheduled_for: Option<String>, on_changed: EventHandler<()>) -> Element {
+    let auth_state = use_context::<SharedAuthState>();
+    let mut login_status = use_context::<LoginStatus>().0;
+    let mut confirming = use_signal(|| false);
+    let mut confirm_username = use_signal(String::new);
+    let mut status = use_signal(|| None::<String>);
+
+    let on_delete = {
+        let auth_state = auth_state.clone();
+        move |_| {
+            let auth_state = auth_state.clone();
+            spawn(async move {
+                match account::delete_my_account(&auth_state, confirm_username()).await {
+                    Ok(Some(_)) => {
+                        confirming.set(false);
+                        confirm_username.set(String::new());
+                        status.set(None);
+                        on_changed.call(());
+                    }
+                    Ok(None) => login_status.set(None),
+                    Err(e) => status.set(Some(format!("Failed to delete account: {e}"))),
+                }
+            });
+        }
+    };
+
+    let on_cancel_deletion = move |_| {
+        let auth_state = auth_state.clone();
+        spawn(async move {
+            match account::cancel_account_deletion(&auth_state).await {
+                Ok(()) => on_changed.call(()),
+                Err(e) => status.set(Some(format!("Failed to cancel deletion: {e}"))),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "bg-gray-800 rounded-lg border border-red-900 p-6",
+            h2 { class: "text-lg font-semibold text-red-300 mb-2", "Delete my account" }
+            if let Some(at) = scheduled_for {
+                p { class: "text-sm text-gray-300 mb-4",
+                    "Your account and all of its data will be deleted on {format_scheduled(&at)}."
+                }
+                button {
+                    onclick: on_cancel_deletion,
+                    class: "bg-gray-600 hover:bg-gray-500 text-white py-2 px-4 rounded transition-colors duration-150",
+                    "Keep my account"
+                }
+            } else if confirming() {
+                p { class: "text-sm text-gray-300 mb-3",
+                    "This deletes your account, matches, drafts, shares, webhooks and team memberships from the server. Matches stored on this computer are kept. Type "
+                    span { class: "font-mono text-amber-400", "{username}" }
+                    " to confirm."
+                }
+                div { class: "flex space-x-2",
+                    input {
+                        r#type: "text",
+                        value: "{confirm_username}",
+                        placeholder: "Username",
+                        class: "bg-gray-700 text-gray-200 border border-gray-600 rounded py-2 px-3 text-sm focus:outline-none focus:border-red-500 w-full",
+                        oninput: move |evt| confirm_username.set(evt.value())
+                    }
+                    button {
+                        onclick: on_delete,
+                        disabled: confirm_username() != username,
+                        class: "bg-red-600 hover:bg-red-700 disabled:bg-gray-600 text-white py-2 px-4 rounded transition-colors duration-150",
+                        "Delete"
+                    }
+                    button {
+                        onclick: move |_| {
+                            confirming.set(false);
+                            confirm_username.set(String::new());
+                        },
+                        class: "bg-gray-600 hover:bg-gray-500 text-white py-2 px-4 rounded transition-colors duration-150",
+                        "Cancel"
+                    }
+                }
+            } else {
+                p { class: "text-sm text-gray-400 mb-4",
+                    "Permanently remove your account and everything you uploaded from the server."
+                }
+                button {
+                    onclick: move |_| confirming.set(true),
+                    class: "bg-red-600 hover:bg-red-700 text-white py-2 px-4 rounded transition-colors duration-150",
+                    "Delete account..."
+                }
+            }
+            if let Some(message) = status() {
+                p { class: "mt-3 text-sm text-red-300", "{message}" }
+            }
+        }
+    }
+}
+
+#[component]
+pub(crate) fn Account() -> Element {
+    let auth_state = use_context::<SharedAuthState>();
+    let login_status = use_context::<LoginStatus>().0;
+
+    let mut pending_resource = use_resource(move || {
+        let auth_state = auth_state.clone();
+        // Reload when the user signs in or out.
+        let signed_in = login_status().is_some();
+        async move {
+            if signed_in {
+                account::pending_deletion(&auth_state).await.map_err(|e| e.to_string())
+            } else {
+                Ok(None)
+            }
+        }
+    });
+    let pending_value = pending_resource.value();
+    let pending = pending_value.read();
+
+    rsx! {
+        div { class: "container mx-auto px-4 py-8 max-w-3xl space-y-6",
+            h1 { class: "text-2xl font-bold text-gray-100", "Account" }
+            if let Some(username) = login_status() {
+                ExportSection {}
+                match &*pending {
+                    None => rsx! {
+                        div { class: "animate-pulse text-gray-500", "Loading account..." }
+                    },
+                    Some(Err(err)) => rsx! {
+                        p { class: "text-red-300 text-sm", "Failed to load account: {err}" }
+                    },
+                    Some(Ok(scheduled_for)) => rsx! {
+                        DeleteSection {
+                            username,
+                            scheduled_for: scheduled_for.clone(),
+                            on_changed: move |()| pending_resource.restart(),
+                        }
+                    },
+                }
+            } else {
+                div { class: "bg-gray-800 rounded-lg border border-gray-700 p-6 text-gray-400",
+                    "Sign in to export or delete the data stored on the server."
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,36 @@
-use arenabuddy_core::display::match_summary::format_event_id;
+use arenabuddy_core::display::{classification::ClassificationEvidence, match_summary::format_event_id};
 use dioxus::prelude::*;
 
+/// Matched cards shown per classification.
+const EVIDENCE_CARDS: usize = 5;
+
+/// Why a deck got its archetype: the heaviest matched signature cards and the runner-up.
+#[component]
+fn ClassificationWhy(evidence: ClassificationEvidence) -> Element {
+    let cards = evidence
+        .matched_cards
+        .iter()
+        .take(EVIDENCE_CARDS)
+        .map(|c| format!("{} ({:.2})", c.card_name, c.weight))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let runner_up = evidence
+        .runners_up
+        .first()
+        .map(|a| format!("{} ({:.2})", a.archetype_name, a.score));
+
+    rsx! {
+        div { class: "mt-1 text-xs text-gray-400",
+            if !cards.is_empty() {
+                p { "Matched: {cards}" }
+            }
+            if let Some(runner_up) = runner_up {
+                p { "Runner-up: {runner_up}" }
+            }
+        }
+    }
+}
+
 #[component]
 pub fn MatchInfo(
     controller_player_name: String,
@@ -9,6 +39,8 @@ pub fn MatchInfo(
     format: Option<String>,
     #[props(optional)] controller_archetype: Option<String>,
     #[props(optional)] opponent_archetype: Option<String>,
+    #[props(optional)] controller_evidence: Option<ClassificationEvidence>,
+    #[props(optional)] opponent_evidence: Option<ClassificationEvidence>,
 ) -> Element {
     let display_format = format.as_deref().map_or("Unknown", format_event_id);
 
@@ -29,6 +61,9 @@ pub fn MatchInfo(
                                     "{archetype}"
                                 }
                             }
+                            if let Some(ref evidence) = controller_evidence {
+                                ClassificationWhy { evidence: evidence.clone() }
+                            }
                         }
                         div { class: "bg-red-900/20 p-3 rounded-md",
                             span { class: "font-semibold", "Opponent" }
@@ -38,6 +73,9 @@ pub fn MatchInfo(
                                     "{archetype}"
                                 }
                             }
+                            if let Some(ref evidence) = opponent_evidence {
+                                ClassificationWhy { evidence: evidence.clone() }
+                            }
                         }
                     }
                 }
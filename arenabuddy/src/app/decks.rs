@@ -0,0 +1,163 @@
+use arenabuddy_core::display::stats::{DeckStats, TimeWindow};
+use dioxus::prelude::*;
+use dioxus_router::Link;
+
+use crate::{
+    app::{
+        Route,
+        stats::{RecordLine, format_rate},
+    },
+    backend::Service,
+};
+
+#[component]
+fn DeckCard(deck: DeckStats) -> Element {
+    let name = deck.display_name();
+    let last_played = super::format_local_datetime(deck.last_played);
+    let average_turns = deck
+        .average_turns
+        .map_or("N/A".to_string(), |turns| format!("{turns:.1} turns"));
+
+    rsx! {
+        div { class: "bg-gray-800 rounded-lg border border-gray-700 p-6",
+            div { class: "flex justify-between items-baseline mb-4",
+                h2 { class: "text-lg font-semibold text-gray-300 truncate mr-4", "{name}" }
+                Link {
+                    to: Route::MatchDetails { id: deck.latest_match_id.clone() },
+                    class: "text-sm text-amber-400 hover:text-amber-300 flex-shrink-0",
+                    "Latest match"
+                }
+            }
+            RecordLine {
+                label: "Matches",
+                wins: deck.match_wins,
+                losses: deck.match_losses,
+                rate: deck.match_win_rate(),
+            }
+            RecordLine {
+                label: "Games",
+                wins: deck.game_wins,
+                losses: deck.game_losses,
+                rate: deck.game_win_rate(),
+            }
+            RecordLine {
+                label: "Game 1",
+                wins: deck.game1_wins,
+                losses: deck.game1_losses,
+                rate: deck.game1_win_rate(),
+            }
+            RecordLine {
+                label: "Games 2/3",
+                wins: deck.post_board_wins,
+                losses: deck.post_board_losses,
+                rate: deck.post_board_win_rate(),
+            }
+            RecordLine {
+                label: "On the Play",
+                wins: deck.play_wins,
+                losses: deck.play_losses,
+                rate: deck.play_win_rate(),
+            }
+            RecordLine {
+                label: "On the Draw",
+                wins: deck.draw_wins,
+                losses: deck.draw_losses,
+                rate: deck.draw_win_rate(),
+            }
+            div { class: "flex justify-between items-center py-2 border-b border-gray-700",
+                span { class: "text-gray-400", "Mulligans" }
+                span { class: "text-gray-300",
+                    "{deck.mulligans} of {deck.kept_hands} hands ({format_rate(deck.mulligan_rate())})"
+                }
+            }
+            div { class: "flex justify-between items-center py-2",
+                span { class: "text-gray-400", "Average game length" }
+                span { class: "text-gray-300", "{average_turns}" }
+            }
+            div { class: "pt-2 text-sm text-gray-500",
+                "{deck.matches} matches · last played {last_played}"
+            }
+        }
+    }
+}
+
+#[component]
+pub(crate) fn Decks() -> Element {
+    let service = use_context::<Service>();
+    let mut time_window = use_signal(TimeWindow::default);
+
+    let mut decks_resource = use_resource(move || {
+        let service = service.clone();
+        let tw = time_window();
+        async move { service.get_deck_stats(tw).await }
+    });
+
+    let resource_value = decks_resource.value();
+    let data = resource_value.read();
+
+    rsx! {
+        div { class: "container mx-auto px-4 py-8 max-w-5xl",
+            div { class: "flex justify-between items-center mb-6",
+                h1 { class: "text-2xl font-bold text-gray-100", "Deck Statistics" }
+                div { class: "flex items-center space-x-3",
+                    select {
+                        class: "bg-gray-700 text-gray-200 border border-gray-600 rounded py-2 px-3 text-sm focus:outline-none focus:border-amber-500",
+                        onchange: move |evt| {
+                            let idx: usize = evt.value().parse().unwrap_or(3);
+                            time_window.set(TimeWindow::ALL[idx]);
+                        },
+                        for (i, tw) in TimeWindow::ALL.iter().enumerate() {
+                            option {
+                                value: "{i}",
+                                selected: *tw == time_window(),
+                                "{tw.label()}"
+                            }
+                        }
+                    }
+                    button {
+                        onclick: move |_| decks_resource.restart(),
+                        class: "bg-amber-600 hover:bg-amber-700 text-white py-2 px-4 rounded transition-colors duration-150",
+                        disabled: data.is_none(),
+                        if data.is_none() {
+                            "Loading..."
+                        } else {
+                            "Refresh"
+                        }
+                    }
+                }
+            }
+
+            match &*data {
+                None => rsx! {
+                    div { class: "bg-gray-800 rounded-lg border border-gray-700 p-12 text-center text-gray-500",
+                        div { class: "animate-pulse", "Loading decks..." }
+                    }
+                },
+
+                Some(Err(err)) => rsx! {
+                    div { class: "bg-red-900/30 border border-red-700 text-red-300 px-4 py-3 rounded",
+                        p { "Failed to load deck statistics: {err}" }
+                    }
+                },
+
+                Some(Ok(decks)) => {
+                    if decks.is_empty() {
+                        rsx! {
+                            div { class: "bg-gray-800 rounded-lg border border-gray-700 p-12 text-center text-gray-500",
+                                "No decks played in this period."
+                            }
+                        }
+                    } else {
+                        rsx! {
+                            div { class: "grid grid-cols-1 md:grid-cols-2 gap-6",
+                                for deck in decks.iter() {
+                                    DeckCard { key: "{deck.deck_id}", deck: deck.clone() }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
         Route,
         components::{DeckList, EventLogDisplay, MatchInfo, MulliganDisplay},
     },
-    backend::{Service, SharedAuthState, sync},
+    backend::{Service, SharedAuthState, share, sync},
 };
 
 #[component]
@@ -15,6 +15,8 @@ pub(crate) fn MatchDetails(id: String) -> Element {
     let mut sync_loading = use_signal(|| false);
     let mut sync_status = use_signal(|| None::<String>);
     let mut active_tab = use_signal(|| 0u8);
+    let mut share_loading = use_signal(|| false);
+    let mut share_link = use_signal(|| None::<Result<String, String>>);
 
     let mut match_details = use_resource({
         let service = service.clone();
@@ -60,6 +62,23 @@ pub(crate) fn MatchDetails(id: String) -> Element {
         }
     };
 
+    let on_share = {
+        let auth_state = auth_state.clone();
+        let id = id.clone();
+        move |_| {
+            let auth_state = auth_state.clone();
+            let id = id.clone();
+            spawn(async move {
+                share_loading.set(true);
+                let link = share::create_share_link(&auth_state, &id)
+                    .await
+                    .map_err(|e| format!("Share failed: {e}"));
+                share_link.set(Some(link));
+                share_loading.set(false);
+            });
+        }
+    };
+
     let resource_value = match_details.value();
     let data = resource_value.read();
 
@@ -110,6 +129,12 @@ pub(crate) fn MatchDetails(id: String) -> Element {
                                 }
                             }
                         }
+                        button {
+                            onclick: on_share,
+                            class: "bg-black bg-opacity-20 hover:bg-opacity-30 text-white font-semibold py-2 px-4 rounded-full transition-all duration-200 shadow-md hover:shadow-lg disabled:opacity-60 disabled:cursor-not-allowed",
+                            disabled: share_loading(),
+                            if share_loading() { "Sharing..." } else { "Share" }
+                        }
                         button {
                             onclick: refresh,
                             class: "bg-black bg-opacity-20 hover:bg-opacity-30 text-white font-semibold py-2 px-4 rounded-full transition-all duration-200 shadow-md hover:shadow-lg flex items-center",
@@ -141,6 +166,18 @@ pub(crate) fn MatchDetails(id: String) -> Element {
                 if let Some(message) = sync_status() {
                     p { class: "text-sm opacity-90 mt-2", "{message}" }
                 }
+                match share_link() {
+                    Some(Ok(link)) => rsx! {
+                        p { class: "text-sm opacity-90 mt-2 select-all",
+                            span { class: "font-semibold", "Share link: " }
+                            "{link}"
+                        }
+                    },
+                    Some(Err(message)) => rsx! {
+                        p { class: "text-sm opacity-90 mt-2", "{message}" }
+                    },
+                    None => rsx! {},
+                }
             }
 
             match data.as_ref() {
@@ -178,6 +215,8 @@ pub(crate) fn MatchDetails(id: String) -> Element {
                             format: details.format.clone(),
                             controller_archetype: details.controller_archetype.clone(),
                             opponent_archetype: details.opponent_archetype.clone(),
+                            controller_evidence: details.controller_evidence.clone(),
+                            opponent_evidence: details.opponent_evidence.clone(),
                         }
 
                         div { class: "flex gap-1 mb-6 border-b border-gray-700",
@@ -268,6 +307,43 @@ pub(crate) fn MatchDetails(id: String) -> Element {
                                     }
                                 }
 
+                                if let Some(ref comparison) = details.stock_comparison {
+                                    div { class: "mt-8",
+                                        h2 { class: "text-xl font-bold text-gray-100 mb-1", "Compared to Stock" }
+                                        p { class: "text-sm text-gray-500 mb-4",
+                                            "Average of {comparison.decks} tournament {comparison.archetype} decks"
+                                        }
+                                        if comparison.difference.added.is_empty() && comparison.difference.removed.is_empty() {
+                                            p { class: "text-gray-500 text-sm", "Your deck plays the stock list" }
+                                        } else {
+                                            div { class: "grid grid-cols-1 md:grid-cols-2 gap-4",
+                                                if !comparison.difference.added.is_empty() {
+                                                    div { class: "bg-gray-800 rounded-lg border border-gray-700 p-4",
+                                                        p { class: "text-sm font-medium text-amber-400 mb-1", "Unusual picks" }
+                                                        for card in comparison.difference.added.iter() {
+                                                            div { class: "flex justify-between text-sm py-0.5",
+                                                                span { class: "text-gray-300", "{card.name}" }
+                                                                span { class: "text-amber-400", "{card.quantity}" }
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                                if !comparison.difference.removed.is_empty() {
+                                                    div { class: "bg-gray-800 rounded-lg border border-gray-700 p-4",
+                                                        p { class: "text-sm font-medium text-red-400 mb-1", "Missing staples" }
+                                                        for card in comparison.difference.removed.iter() {
+                                                            div { class: "flex justify-between text-sm py-0.5",
+                                                                span { class: "text-gray-300", "{card.name}" }
+                                                                span { class: "text-red-400", "-{card.quantity}" }
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+
                                 div { class: "mt-8 col-span-full",
                                     MulliganDisplay { mulligans: details.mulligans.clone() }
                                 }
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use arenabuddy_core::display::stats::{ArchetypeMatchup, MatchupGrouping, TimeWindow};
+use dioxus::prelude::*;
+
+use crate::{app::stats::format_rate, backend::Service};
+
+/// Formats offered in the filter, as named by the event rules. The empty string means all formats.
+const MATCHUP_FORMATS: [(&str, &str); 7] = [
+    ("", "All Formats"),
+    ("standard", "Standard"),
+    ("explorer", "Explorer"),
+    ("historic", "Historic"),
+    ("timeless", "Timeless"),
+    ("alchemy", "Alchemy"),
+    ("limited", "Limited"),
+];
+
+/// Rows (our archetypes or decks) and columns (opponent archetypes) of the matrix, most played
+/// first, with each cell looked up by row and column index.
+struct MatchupMatrix<'a> {
+    rows: Vec<String>,
+    columns: Vec<&'a str>,
+    cells: HashMap<(usize, usize), &'a ArchetypeMatchup>,
+}
+
+impl<'a> MatchupMatrix<'a> {
+    fn new(matchups: &'a [ArchetypeMatchup]) -> Self {
+        let mut row_totals: Vec<((Option<&str>, &str), i64)> = Vec::new();
+        let mut column_totals: Vec<(&str, i64)> = Vec::new();
+        for matchup in matchups {
+            let row = (matchup.deck_id.as_deref(), matchup.archetype.as_str());
+            match row_totals.iter_mut().find(|(key, _)| *key == row) {
+                Some((_, total)) => *total += matchup.matches,
+                None => row_totals.push((row, matchup.matches)),
+            }
+            let column = matchup.opponent_archetype.as_str();
+            match column_totals.iter_mut().find(|(key, _)| *key == column) {
+                Some((_, total)) => *total += matchup.matches,
+                None => column_totals.push((column, matchup.matches)),
+            }
+        }
+        row_totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.1.cmp(b.0.1)));
+        column_totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+        let mut cells = HashMap::new();
+        for matchup in matchups {
+            let row = (matchup.deck_id.as_deref(), matchup.archetype.as_str());
+            let row_index = row_totals.iter().position(|(key, _)| *key == row);
+            let column_index = column_totals
+                .iter()
+                .position(|(key, _)| *key == matchup.opponent_archetype);
+            if let (Some(row_index), Some(column_index)) = (row_index, column_index) {
+                cells.insert((row_index, column_index), matchup);
+            }
+        }
+
+        Self {
+            rows: row_totals
+                .into_iter()
+                .map(|((deck_id, name), _)| match deck_id {
+                    // Decks of the same archetype are told apart by their fingerprint.
+                    Some(deck_id) => format!("{name} · {}", &deck_id[..deck_id.len().min(8)]),
+                    None => name.to_string(),
+                })
+                .collect(),
+            columns: column_totals.into_iter().map(|(name, _)| name).collect(),
+            cells,
+        }
+    }
+}
+
+fn format_interval(interval: Option<(f64, f64)>) -> String {
+    interval.map_or("N/A".to_string(), |(low, high)| format!("{low:.0}–{high:.0}%"))
+}
+
+fn cell_color(rate: Option<f64>) -> &'static str {
+    match rate {
+        Some(rate) if rate >= 55.0 => "bg-green-900/40",
+        Some(rate) if rate <= 45.0 => "bg-red-900/40",
+        _ => "",
+    }
+}
+
+#[component]
+fn MatchupCell(matchup: ArchetypeMatchup) -> Element {
+    let details = format!(
+        "{} vs {}\n{} matches, 95% CI {}\nGames {}-{}\nGame 1 {}-{} ({}, 95% CI {})\nGames 2/3 {}-{} ({}, 95% CI {})",
+        matchup.archetype,
+        matchup.opponent_archetype,
+        matchup.matches,
+        format_interval(matchup.confidence_interval()),
+        matchup.game_wins(),
+        matchup.game_losses(),
+        matchup.game1_wins,
+        matchup.game1_losses,
+        format_rate(matchup.game1_win_rate()),
+        format_interval(matchup.game1_confidence_interval()),
+        matchup.post_board_wins,
+        matchup.post_board_losses,
+        format_rate(matchup.post_board_win_rate()),
+        format_interval(matchup.post_board_confidence_interval()),
+    );
+    let color = cell_color(matchup.win_rate());
+
+    rsx! {
+        td { class: "py-2 px-3 border-b border-l border-gray-700 text-center whitespace-nowrap {color}",
+            title: "{details}",
+            div { class: "text-gray-100 font-medium", "{format_rate(matchup.win_rate())}" }
+            div { class: "text-xs text-gray-400", "{matchup.wins}-{matchup.losses}" }
+            div { class: "text-xs text-gray-500", "{format_interval(matchup.confidence_interval())}" }
+            div { class: "text-xs text-gray-500",
+                "G1 {format_rate(matchup.game1_win_rate())} · G2/3 {format_rate(matchup.post_board_win_rate())}"
+            }
+        }
+    }
+}
+
+#[component]
+pub(crate) fn Matchups() -> Element {
+    let service = use_context::<Service>();
+    let mut time_window = use_signal(TimeWindow::default);
+    let mut grouping = use_signal(MatchupGrouping::default);
+    let mut format = use_signal(String::new);
+
+    let mut matchups_resource = use_resource(move || {
+        let service = service.clone();
+        let tw = time_window();
+        let by = grouping();
+        let format = format();
+        async move {
+            let format = (!format.is_empty()).then_some(format.as_str());
+            service.get_archetype_matchups(tw, by, format).await
+        }
+    });
+
+    let resource_value = matchups_resource.value();
+    let data = resource_value.read();
+    let row_heading = match grouping() {
+        MatchupGrouping::Archetype => "Our Archetype",
+        MatchupGrouping::Deck => "Our Deck",
+    };
+
+    rsx! {
+        div { class: "container mx-auto px-4 py-8",
+            div { class: "flex justify-between items-center mb-6",
+                h1 { class: "text-2xl font-bold text-gray-100", "Matchups" }
+                div { class: "flex items-center space-x-3",
+                    select {
+                        class: "bg-gray-700 text-gray-200 border border-gray-600 rounded py-2 px-3 text-sm focus:outline-none focus:border-amber-500",
+                        onchange: move |evt| {
+                            grouping
+                                .set(
+                                    if evt.value() == "deck" {
+                                        MatchupGrouping::Deck
+                                    } else {
+                                        MatchupGrouping::Archetype
+                                    },
+                                );
+                        },
+                        option {
+                            value: "archetype",
+                            selected: grouping() == MatchupGrouping::Archetype,
+                            "By Archetype"
+                        }
+                        option {
+                            value: "deck",
+                            selected: grouping() == MatchupGrouping::Deck,
+                            "By Deck"
+                        }
+                    }
+                    select {
+                        class: "bg-gray-700 text-gray-200 border border-gray-600 rounded py-2 px-3 text-sm focus:outline-none focus:border-amber-500",
+                        onchange: move |evt| format.set(evt.value()),
+                        for (value, label) in MATCHUP_FORMATS {
+                            option { value: "{value}", selected: format() == value, "{label}" }
+                        }
+                    }
+                    select {
+                        class: "bg-gray-700 text-gray-200 border border-gray-600 rounded py-2 px-3 text-sm focus:outline-none focus:border-amber-500",
+                        onchange: move |evt| {
+                            let idx: usize = evt.value().parse().unwrap_or(3);
+                            time_window.set(TimeWindow::ALL[idx]);
+                        },
+                        for (i, tw) in TimeWindow::ALL.iter().enumerate() {
+                            option {
+                                value: "{i}",
+                                selected: *tw == time_window(),
+                                "{tw.label()}"
+                            }
+                        }
+                    }
+                    button {
+                        onclick: move |_| matchups_resource.restart(),
+                        class: "bg-amber-600 hover:bg-amber-700 text-white py-2 px-4 rounded transition-colors duration-150",
+                        disabled: data.is_none(),
+                        if data.is_none() {
+                            "Loading..."
+                        } else {
+                            "Refresh"
+                        }
+                    }
+                }
+            }
+
+            match &*data {
+                None => rsx! {
+                    div { class: "bg-gray-800 rounded-lg border border-gray-700 p-12 text-center text-gray-500",
+                        div { class: "animate-pulse", "Loading matchups..." }
+                    }
+                },
+
+                Some(Err(err)) => rsx! {
+                    div { class: "bg-red-900/30 border border-red-700 text-red-300 px-4 py-3 rounded",
+                        p { "Failed to load matchups: {err}" }
+                    }
+                },
+
+                Some(Ok(matchups)) => {
+                    if matchups.is_empty() {
+                        rsx! {
+                            div { class: "bg-gray-800 rounded-lg border border-gray-700 p-12 text-center text-gray-500",
+                                "No classified matches in this period."
+                            }
+                        }
+                    } else {
+                        let matrix = MatchupMatrix::new(matchups);
+                        rsx! {
+                            div { class: "bg-gray-800 rounded-lg border border-gray-700 overflow-x-auto",
+                                table { class: "min-w-full",
+                                    thead {
+                                        tr { class: "bg-gray-900 text-left",
+                                            th { class: "py-3 px-4 font-semibold text-gray-400", "{row_heading}" }
+                                            for column in matrix.columns.iter() {
+                                                th { class: "py-3 px-3 font-semibold text-gray-400 text-center text-sm",
+                                                    "vs {column}"
+                                                }
+                                            }
+                                        }
+                                    }
+                                    tbody {
+                                        for (row_index, row) in matrix.rows.iter().enumerate() {
+                                            tr { key: "{row_index}",
+                                                td { class: "py-2 px-4 border-b border-gray-700 text-gray-300 whitespace-nowrap",
+                                                    "{row}"
+                                                }
+                                                for column_index in 0..matrix.columns.len() {
+                                                    match matrix.cells.get(&(row_index, column_index)) {
+                                                        Some(matchup) => rsx! {
+                                                            MatchupCell { matchup: (*matchup).clone() }
+                                                        },
+                                                        None => rsx! {
+                                                            td { class: "py-2 px-3 border-b border-l border-gray-700 text-center text-gray-600",
+                                                                "–"
+                                                            }
+                                                        },
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            p { class: "mt-3 text-sm text-gray-500",
+                                "Match win rate, record and 95% confidence interval, with game 1 and post-sideboard game win rates. Hover a cell for game counts."
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,17 @@
+mod account;
 mod cards;
 mod components;
 mod debug_logs;
+mod decks;
 mod draft_details;
 mod drafts;
 mod error_logs;
 mod match_details;
 mod matches;
+mod matchups;
 mod pages;
 mod stats;
+mod teams;
 use chrono::{DateTime, Local, Utc};
 use dioxus::prelude::*;
 use pages::Route;
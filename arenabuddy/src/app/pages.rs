@@ -3,8 +3,9 @@ use dioxus_router::{Link, Outlet, Routable};
 
 use crate::{
     app::{
-        cards::Cards, debug_logs::DebugLogs, draft_details::DraftDetails, drafts::Drafts, error_logs::ErrorLogs,
-        match_details::MatchDetails, matches::Matches, stats::Stats,
+        account::Account, cards::Cards, debug_logs::DebugLogs, decks::Decks, draft_details::DraftDetails,
+        drafts::Drafts, error_logs::ErrorLogs, match_details::MatchDetails, matches::Matches, matchups::Matchups,
+        stats::Stats, teams::Teams,
     },
     backend::{BackgroundRuntime, Service, SharedAuthState, auth_controller},
 };
@@ -15,6 +16,10 @@ fn open_github() {
     }
 }
 
+/// The signed-in username shown in the nav bar, shared so pages can sign the user out.
+#[derive(Clone, Copy)]
+pub(crate) struct LoginStatus(pub(crate) Signal<Option<String>>);
+
 #[derive(Clone, Routable, Debug, PartialEq)]
 #[rustfmt::skip]
 pub enum Route {
@@ -39,6 +44,14 @@ pub enum Route {
         DebugLogs {},
         #[route("/stats")]
         Stats {},
+        #[route("/decks")]
+        Decks {},
+        #[route("/matchups")]
+        Matchups {},
+        #[route("/teams")]
+        Teams {},
+        #[route("/account")]
+        Account {},
     #[end_layout]
     #[route("/:..route")]
     PageNotFound { route: Vec<String> },
@@ -86,7 +99,7 @@ fn PageNotFound(route: Vec<String>) -> Element {
 #[component]
 fn Layout() -> Element {
     let auth_state = use_context::<SharedAuthState>();
-    let mut login_status = use_signal(|| None::<String>);
+    let mut login_status = use_context_provider(|| LoginStatus(Signal::new(None::<String>))).0;
     let mut login_loading = use_signal(|| false);
 
     // Check current auth state on render
@@ -167,6 +180,27 @@ fn Layout() -> Element {
                             "Stats"
                         }
                     }
+                    li {
+                        Link {
+                            to: Route::Decks {},
+                            class: "hover:text-amber-400 transition-colors duration-200",
+                            "Decks"
+                        }
+                    }
+                    li {
+                        Link {
+                            to: Route::Matchups {},
+                            class: "hover:text-amber-400 transition-colors duration-200",
+                            "Matchups"
+                        }
+                    }
+                    li {
+                        Link {
+                            to: Route::Teams {},
+                            class: "hover:text-amber-400 transition-colors duration-200",
+                            "Teams"
+                        }
+                    }
                     li {
                         Link {
                             to: Route::Cards {},
@@ -198,7 +232,11 @@ fn Layout() -> Element {
                 }
                 div { class: "text-white flex items-center space-x-3",
                     if let Some(username) = login_status() {
-                        span { class: "text-emerald-400 text-sm", "Logged in as {username}" }
+                        Link {
+                            to: Route::Account {},
+                            class: "text-emerald-400 text-sm hover:text-emerald-300 transition-colors duration-200",
+                            "Logged in as {username}"
+                        }
                         button {
                             class: "bg-red-600 hover:bg-red-700 text-white text-sm px-3 py-1 rounded transition-colors duration-200",
                             onclick: on_logout,
@@ -1,9 +1,12 @@
-use arenabuddy_core::display::stats::{MatchStats, TimeWindow};
+use arenabuddy_core::display::{
+    metagame::{ArchetypeTrend, MetagameTrends, TrendInterval},
+    stats::{LimitedRecord, MatchStats, TimeWindow},
+};
 use dioxus::prelude::*;
 
 use crate::backend::Service;
 
-fn format_rate(rate: Option<f64>) -> String {
+pub(crate) fn format_rate(rate: Option<f64>) -> String {
     rate.map_or("N/A".to_string(), |r| format!("{r:.1}%"))
 }
 
@@ -18,7 +21,7 @@ fn StatCard(title: &'static str, children: Element) -> Element {
 }
 
 #[component]
-fn RecordLine(label: &'static str, wins: i64, losses: i64, rate: Option<f64>) -> Element {
+pub(crate) fn RecordLine(label: &'static str, wins: i64, losses: i64, rate: Option<f64>) -> Element {
     rsx! {
         div { class: "flex justify-between items-center py-2 border-b border-gray-700 last:border-0",
             span { class: "text-gray-400", "{label}" }
@@ -33,7 +36,7 @@ fn RecordLine(label: &'static str, wins: i64, losses: i64, rate: Option<f64>) ->
 }
 
 #[component]
-fn StatsDisplay(stats: MatchStats) -> Element {
+pub(crate) fn StatsDisplay(stats: MatchStats) -> Element {
     rsx! {
         div { class: "grid grid-cols-1 md:grid-cols-2 gap-6",
             StatCard { title: "Match Record",
@@ -117,23 +120,257 @@ fn StatsDisplay(stats: MatchStats) -> Element {
     }
 }
 
+/// Limited win rates by controller deck label, grouped by set.
+#[component]
+fn LimitedRecords(records: Vec<LimitedRecord>) -> Element {
+    let mut sets: Vec<(String, Vec<LimitedRecord>)> = Vec::new();
+    for record in records {
+        match sets.last_mut() {
+            Some((set, set_records)) if *set == record.set_code => set_records.push(record),
+            _ => sets.push((record.set_code.clone(), vec![record])),
+        }
+    }
+
+    rsx! {
+        div { class: "mt-6",
+            StatCard { title: "Limited Decks",
+                for (set, set_records) in sets {
+                    div { class: "mb-4 last:mb-0",
+                        h3 { class: "text-sm font-semibold text-gray-500 uppercase mb-1", "{set}" }
+                        for record in set_records {
+                            div { class: "flex justify-between items-center py-2 border-b border-gray-700 last:border-0",
+                                span { class: "text-gray-400 truncate mr-4", "{record.label}" }
+                                div { class: "flex items-center space-x-3 flex-shrink-0",
+                                    span { class: "text-amber-400 font-medium", "{record.wins}W" }
+                                    span { class: "text-gray-600", "-" }
+                                    span { class: "text-red-400 font-medium", "{record.losses}L" }
+                                    span { class: "text-gray-500 text-sm ml-2",
+                                        "({format_rate(record.win_rate())})"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+const TREND_FORMATS: [&str; 4] = ["standard", "pioneer", "explorer", "historic"];
+const TREND_COLORS: [&str; 6] = ["#f59e0b", "#60a5fa", "#34d399", "#f87171", "#a78bfa", "#f472b6"];
+const CHART_WIDTH: f64 = 600.0;
+const CHART_HEIGHT: f64 = 200.0;
+
+/// SVG polyline points for each share, scaled so `max_share` reaches the top of the chart.
+#[expect(clippy::cast_precision_loss)]
+fn chart_points(shares: &[f64], max_share: f64) -> String {
+    let step = if shares.len() > 1 {
+        CHART_WIDTH / (shares.len() - 1) as f64
+    } else {
+        0.0
+    };
+    shares
+        .iter()
+        .enumerate()
+        .map(|(i, share)| {
+            let x = if shares.len() > 1 {
+                i as f64 * step
+            } else {
+                CHART_WIDTH / 2.0
+            };
+            let y = CHART_HEIGHT - share / max_share * (CHART_HEIGHT - 10.0);
+            format!("{x:.1},{y:.1}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn trend_delta(trend: &ArchetypeTrend) -> String {
+    trend.delta().map_or_else(String::new, |d| format!("{d:+.1}"))
+}
+
+#[component]
+fn TrendChart(trends: MetagameTrends) -> Element {
+    let shown: Vec<&ArchetypeTrend> = trends.archetypes.iter().take(TREND_COLORS.len()).collect();
+    let max_share = shown.iter().flat_map(|t| t.shares.iter().copied()).fold(1.0, f64::max);
+    let lines: Vec<(String, &str)> = shown
+        .iter()
+        .zip(TREND_COLORS)
+        .map(|(trend, color)| (chart_points(&trend.shares, max_share), color))
+        .collect();
+    let legend: Vec<(String, String, String, &str)> = shown
+        .iter()
+        .zip(TREND_COLORS)
+        .map(|(trend, color)| {
+            (
+                trend.archetype.clone(),
+                format!("{:.1}%", trend.current()),
+                trend_delta(trend),
+                color,
+            )
+        })
+        .collect();
+    let first = trends
+        .periods
+        .first()
+        .map(|p| p.format("%b %d").to_string())
+        .unwrap_or_default();
+    let last = trends
+        .periods
+        .last()
+        .map(|p| p.format("%b %d").to_string())
+        .unwrap_or_default();
+    let emerging = trends
+        .emerging()
+        .map(|t| format!("{} ({:.1}%)", t.archetype, t.current()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let declining = trends
+        .declining()
+        .iter()
+        .map(|t| format!("{} ({})", t.archetype, trend_delta(t)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    rsx! {
+        svg {
+            xmlns: "http://www.w3.org/2000/svg",
+            class: "w-full h-48",
+            view_box: "0 0 {CHART_WIDTH} {CHART_HEIGHT}",
+            preserve_aspect_ratio: "none",
+            for (points, color) in lines {
+                polyline {
+                    points: "{points}",
+                    fill: "none",
+                    stroke: "{color}",
+                    stroke_width: "2",
+                }
+            }
+        }
+        div { class: "flex justify-between text-xs text-gray-500 mt-1",
+            span { "{first}" }
+            span { "{last}" }
+        }
+        div { class: "mt-4",
+            for (name, share, delta, color) in legend {
+                div { class: "flex justify-between items-center py-1 border-b border-gray-700 last:border-0",
+                    div { class: "flex items-center space-x-2 truncate mr-4",
+                        span { class: "inline-block w-3 h-3 rounded-full", style: "background-color: {color}" }
+                        span { class: "text-gray-400 truncate", "{name}" }
+                    }
+                    div { class: "flex items-center space-x-3 flex-shrink-0",
+                        span { class: "text-gray-200 font-medium", "{share}" }
+                        span { class: "text-gray-500 text-sm w-12 text-right", "{delta}" }
+                    }
+                }
+            }
+        }
+        if !emerging.is_empty() {
+            p { class: "pt-3 text-sm text-emerald-400", "Emerging: {emerging}" }
+        }
+        if !declining.is_empty() {
+            p { class: "pt-1 text-sm text-red-400", "Declining: {declining}" }
+        }
+    }
+}
+
+#[component]
+fn MetagameTrendsPanel() -> Element {
+    let service = use_context::<Service>();
+    let mut format = use_signal(|| TREND_FORMATS[0].to_string());
+    let mut interval = use_signal(TrendInterval::default);
+
+    let trends_resource = use_resource(move || {
+        let service = service.clone();
+        let format = format();
+        let interval = interval();
+        async move { service.get_metagame_trends(&format, interval).await }
+    });
+    let resource_value = trends_resource.value();
+    let data = resource_value.read();
+
+    rsx! {
+        div { class: "bg-gray-800 rounded-lg border border-gray-700 p-6 mt-6",
+            div { class: "flex justify-between items-center mb-4",
+                h2 { class: "text-lg font-semibold text-gray-300", "Metagame Share" }
+                div { class: "flex items-center space-x-3",
+                    select {
+                        class: "bg-gray-700 text-gray-200 border border-gray-600 rounded py-1 px-2 text-sm focus:outline-none focus:border-amber-500",
+                        onchange: move |evt| format.set(evt.value()),
+                        for f in TREND_FORMATS {
+                            option { value: "{f}", selected: f == format(), "{f}" }
+                        }
+                    }
+                    select {
+                        class: "bg-gray-700 text-gray-200 border border-gray-600 rounded py-1 px-2 text-sm focus:outline-none focus:border-amber-500",
+                        onchange: move |evt| {
+                            let idx: usize = evt.value().parse().unwrap_or(1);
+                            interval.set(TrendInterval::ALL[idx]);
+                        },
+                        for (i, iv) in TrendInterval::ALL.iter().enumerate() {
+                            option {
+                                value: "{i}",
+                                selected: *iv == interval(),
+                                "{iv.label()}"
+                            }
+                        }
+                    }
+                }
+            }
+
+            match &*data {
+                None => rsx! {
+                    div { class: "animate-pulse text-gray-500 text-sm", "Loading metagame..." }
+                },
+                Some(Err(err)) => rsx! {
+                    p { class: "text-red-300 text-sm", "Failed to load metagame trends: {err}" }
+                },
+                Some(Ok(trends)) => {
+                    if trends.archetypes.is_empty() {
+                        rsx! {
+                            p { class: "text-gray-500 text-sm", "No tournament decks scraped for this format yet" }
+                        }
+                    } else {
+                        rsx! { TrendChart { trends: trends.clone() } }
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[component]
 pub(crate) fn Stats() -> Element {
     let service = use_context::<Service>();
     let mut time_window = use_signal(TimeWindow::default);
 
+    let stats_service = service.clone();
     let mut stats_resource = use_resource(move || {
-        let service = service.clone();
+        let service = stats_service.clone();
         let tw = time_window();
         async move { service.get_stats(tw).await }
     });
 
+    let mut limited_resource = use_resource(move || {
+        let service = service.clone();
+        let tw = time_window();
+        async move { service.get_limited_stats(tw).await }
+    });
+
     let refresh = move |_| {
         stats_resource.restart();
+        limited_resource.restart();
     };
 
     let resource_value = stats_resource.value();
     let data = resource_value.read();
+    let limited_records = limited_resource
+        .read()
+        .as_ref()
+        .and_then(|records| records.as_ref().ok())
+        .filter(|records| !records.is_empty())
+        .cloned();
 
     rsx! {
         div { class: "container mx-auto px-4 py-8 max-w-5xl",
@@ -192,6 +429,12 @@ pub(crate) fn Stats() -> Element {
                     }
                 }
             }
+
+            if let Some(records) = limited_records {
+                LimitedRecords { records }
+            }
+
+            MetagameTrendsPanel {}
         }
     }
 }
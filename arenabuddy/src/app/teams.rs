@@ -0,0 +1,460 @@
+use arenabuddy_core::{
+    display::stats::TimeWindow,
+    services::team_service::{Team, TeamDraft, TeamMatch, TeamRole},
+};
+use chrono::{DateTime, Utc};
+use dioxus::prelude::*;
+
+use crate::{
+    app::stats::StatsDisplay,
+    backend::{
+        SharedAuthState,
+        team::{self, TeamOverview},
+    },
+};
+
+fn role_label(role: i32) -> &'static str {
+    match TeamRole::try_from(role).unwrap_or_default() {
+        TeamRole::Owner => "Owner",
+        TeamRole::Admin => "Admin",
+        TeamRole::Member | TeamRole::Unspecified => "Member",
+    }
+}
+
+#[component]
+fn TeamMatchRow(m: TeamMatch) -> Element {
+    let date = DateTime::parse_from_rfc3339(&m.created_at)
+        .map(|dt| super::format_local_datetime(dt.with_timezone(&Utc)))
+        .unwrap_or_default();
+
+    let (result_text, result_class) = match m.won {
+        Some(true) => ("Win", "text-green-400 font-medium"),
+        Some(false) => ("Loss", "text-red-400 font-medium"),
+        None => ("\u{2014}", "text-gray-500"),
+    };
+
+    let matchup = match (m.archetype.as_str(), m.opponent_archetype.as_str()) {
+        ("", "") => String::new(),
+        (ours, theirs) => format!(
+            "{} vs {}",
+            if ours.is_empty() { "?" } else { ours },
+            if theirs.is_empty() { "?" } else { theirs }
+        ),
+    };
+
+    rsx! {
+        tr { class: "hover:bg-gray-700/50 transition-colors duration-150",
+            td { class: "py-3 px-4 border-b border-gray-700 text-gray-200", "{m.username}" }
+            td { class: "py-3 px-4 border-b border-gray-700",
+                span { class: "{result_class}", "{result_text}" }
+            }
+            td { class: "py-3 px-4 border-b border-gray-700 text-gray-300", "{m.game_wins}-{m.game_losses}" }
+            td { class: "py-3 px-4 border-b border-gray-700 text-gray-400 text-sm", "{matchup}" }
+            td { class: "py-3 px-4 border-b border-gray-700", "{m.opponent_player_name}" }
+            td { class: "py-3 px-4 border-b border-gray-700 text-gray-500", "{date}" }
+        }
+    }
+}
+
+#[component]
+fn TeamDraftRow(d: TeamDraft) -> Element {
+    let draft = d.draft.unwrap_or_default();
+    let date = DateTime::parse_from_rfc3339(&draft.created_at)
+        .map(|dt| super::format_local_datetime(dt.with_timezone(&Utc)))
+        .unwrap_or_default();
+
+    rsx! {
+        tr { class: "hover:bg-gray-700/50 transition-colors duration-150",
+            td { class: "py-3 px-4 border-b border-gray-700 text-gray-200", "{d.username}" }
+            td { class: "py-3 px-4 border-b border-gray-700 text-gray-300", "{draft.set_code}" }
+            td { class: "py-3 px-4 border-b border-gray-700 text-gray-400 text-sm", "{draft.format}" }
+            td { class: "py-3 px-4 border-b border-gray-700 text-gray-500", "{date}" }
+        }
+    }
+}
+
+#[component]
+fn TeamDetail(overview: TeamOverview, on_changed: EventHandler<()>) -> Element {
+    let auth_state = use_context::<SharedAuthState>();
+    let mut status = use_signal(|| None::<String>);
+    let team = overview.team.clone();
+
+    let set_sharing = {
+        let auth_state = auth_state.clone();
+        let team = team.clone();
+        move |share_matches: bool, share_drafts: bool| {
+            let auth_state = auth_state.clone();
+            let team_id = team.id.clone();
+            spawn(async move {
+                match team::update_sharing(&auth_state, team_id, share_matches, share_drafts).await {
+                    Ok(()) => on_changed.call(()),
+                    Err(e) => status.set(Some(format!("Failed to update sharing: {e}"))),
+                }
+            });
+        }
+    };
+    let set_match_sharing = set_sharing.clone();
+    let set_draft_sharing = set_sharing;
+
+    let on_leave = {
+        let auth_state = auth_state.clone();
+        let team_id = team.id.clone();
+        move |_| {
+            let auth_state = auth_state.clone();
+            let team_id = team_id.clone();
+            spawn(async move {
+                match team::leave_team(&auth_state, team_id).await {
+                    Ok(()) => on_changed.call(()),
+                    Err(e) => status.set(Some(format!("Failed to leave team: {e}"))),
+                }
+            });
+        }
+    };
+
+    let share_matches = team.share_matches;
+    let share_drafts = team.share_drafts;
+
+    rsx! {
+        div { class: "space-y-6",
+            div { class: "bg-gray-800 rounded-lg border border-gray-700 p-6",
+                div { class: "flex justify-between items-start",
+                    div {
+                        h2 { class: "text-xl font-bold text-gray-100", "{team.name}" }
+                        p { class: "text-sm text-gray-500 mt-1",
+                            "{team.member_count} members \u{b7} you are {role_label(team.role)}"
+                        }
+                        if !team.invite_code.is_empty() {
+                            p { class: "text-sm text-gray-400 mt-2",
+                                "Invite code: "
+                                span { class: "font-mono text-amber-400", "{team.invite_code}" }
+                            }
+                        }
+                    }
+                    button {
+                        onclick: on_leave,
+                        class: "bg-red-600 hover:bg-red-700 text-white text-sm px-3 py-1 rounded transition-colors duration-200",
+                        "Leave Team"
+                    }
+                }
+                div { class: "mt-4 flex space-x-6 text-sm text-gray-300",
+                    label { class: "flex items-center space-x-2",
+                        input {
+                            r#type: "checkbox",
+                            checked: share_matches,
+                            onchange: move |evt| set_match_sharing(evt.checked(), share_drafts),
+                        }
+                        span { "Share my matches" }
+                    }
+                    label { class: "flex items-center space-x-2",
+                        input {
+                            r#type: "checkbox",
+                            checked: share_drafts,
+                            onchange: move |evt| set_draft_sharing(share_matches, evt.checked()),
+                        }
+                        span { "Share my drafts" }
+                    }
+                }
+                if let Some(message) = status() {
+                    p { class: "mt-2 text-sm text-red-300", "{message}" }
+                }
+            }
+
+            if overview.stats.total_matches > 0 {
+                StatsDisplay { stats: overview.stats.clone() }
+            }
+
+            div { class: "bg-gray-800 rounded-lg border border-gray-700 p-6",
+                h2 { class: "text-lg font-semibold text-gray-300 mb-4", "Teammates" }
+                for member in overview.members.iter() {
+                    {
+                        let record = overview.member_records.iter().find(|r| r.user_id == member.user_id);
+                        let record_text = record.map_or_else(
+                            || "not sharing".to_string(),
+                            |r| format!("{}W - {}L ({} matches)", r.wins, r.losses, r.matches),
+                        );
+                        rsx! {
+                            div { key: "{member.user_id}",
+                                class: "flex justify-between items-center py-2 border-b border-gray-700 last:border-0",
+                                div { class: "flex items-center space-x-3",
+                                    span { class: "text-gray-200", "{member.username}" }
+                                    span { class: "text-xs text-gray-500", "{role_label(member.role)}" }
+                                }
+                                span { class: "text-gray-400 text-sm", "{record_text}" }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "bg-gray-800 rounded-lg border border-gray-700 overflow-hidden",
+                h2 { class: "text-lg font-semibold text-gray-300 p-6 pb-2", "Recent Results" }
+                if overview.recent_matches.is_empty() {
+                    div { class: "p-6 text-center text-gray-500",
+                        "No shared matches yet. Teammates need to enable match sharing."
+                    }
+                } else {
+                    div { class: "overflow-x-auto",
+                        table { class: "min-w-full",
+                            thead {
+                                tr { class: "bg-gray-900 text-left",
+                                    th { class: "py-3 px-4 font-semibold text-gray-400", "Player" }
+                                    th { class: "py-3 px-4 font-semibold text-gray-400", "Result" }
+                                    th { class: "py-3 px-4 font-semibold text-gray-400", "Score" }
+                                    th { class: "py-3 px-4 font-semibold text-gray-400", "Matchup" }
+                                    th { class: "py-3 px-4 font-semibold text-gray-400", "Opponent" }
+                                    th { class: "py-3 px-4 font-semibold text-gray-400", "Date" }
+                                }
+                            }
+                            tbody {
+                                for m in overview.recent_matches.iter() {
+                                    TeamMatchRow { key: "{m.match_id}", m: m.clone() }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "bg-gray-800 rounded-lg border border-gray-700 overflow-hidden",
+                h2 { class: "text-lg font-semibold text-gray-300 p-6 pb-2", "Recent Drafts" }
+                if overview.recent_drafts.is_empty() {
+                    div { class: "p-6 text-center text-gray-500",
+                        "No shared drafts yet. Teammates need to enable draft sharing."
+                    }
+                } else {
+                    div { class: "overflow-x-auto",
+                        table { class: "min-w-full",
+                            thead {
+                                tr { class: "bg-gray-900 text-left",
+                                    th { class: "py-3 px-4 font-semibold text-gray-400", "Player" }
+                                    th { class: "py-3 px-4 font-semibold text-gray-400", "Set" }
+                                    th { class: "py-3 px-4 font-semibold text-gray-400", "Format" }
+                                    th { class: "py-3 px-4 font-semibold text-gray-400", "Date" }
+                                }
+                            }
+                            tbody {
+                                for (i, d) in overview.recent_drafts.iter().enumerate() {
+                                    TeamDraftRow { key: "{i}", d: d.clone() }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TeamForms(on_joined: EventHandler<Team>) -> Element {
+    let auth_state = use_context::<SharedAuthState>();
+    let mut team_name = use_signal(String::new);
+    let mut invite_code = use_signal(String::new);
+    let mut status = use_signal(|| None::<String>);
+
+    let on_create = {
+        let auth_state = auth_state.clone();
+        move |_| {
+            let auth_state = auth_state.clone();
+            spawn(async move {
+                match team::create_team(&auth_state, team_name()).await {
+                    Ok(team) => {
+                        team_name.set(String::new());
+                        status.set(None);
+                        on_joined.call(team);
+                    }
+                    Err(e) => status.set(Some(format!("Failed to create team: {e}"))),
+                }
+            });
+        }
+    };
+
+    let on_join = move |_| {
+        let auth_state = auth_state.clone();
+        spawn(async move {
+            match team::join_team(&auth_state, invite_code()).await {
+                Ok(team) => {
+                    invite_code.set(String::new());
+                    status.set(None);
+                    on_joined.call(team);
+                }
+                Err(e) => status.set(Some(format!("Failed to join team: {e}"))),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "bg-gray-800 rounded-lg border border-gray-700 p-6 space-y-4",
+            div {
+                label { class: "block text-sm text-gray-400 mb-2", "New team" }
+                div { class: "flex space-x-2",
+                    input {
+                        r#type: "text",
+                        value: "{team_name}",
+                        placeholder: "Team name",
+                        class: "bg-gray-700 text-gray-200 border border-gray-600 rounded py-2 px-3 text-sm focus:outline-none focus:border-amber-500 w-full",
+                        oninput: move |evt| team_name.set(evt.value())
+                    }
+                    button {
+                        onclick: on_create,
+                        disabled: team_name().trim().is_empty(),
+                        class: "bg-amber-600 hover:bg-amber-700 disabled:bg-gray-600 text-white py-2 px-4 rounded transition-colors duration-150",
+                        "Create"
+                    }
+                }
+            }
+            div {
+                label { class: "block text-sm text-gray-400 mb-2", "Join with invite code" }
+                div { class: "flex space-x-2",
+                    input {
+                        r#type: "text",
+                        value: "{invite_code}",
+                        placeholder: "ABCD2345",
+                        class: "bg-gray-700 text-gray-200 border border-gray-600 rounded py-2 px-3 text-sm font-mono focus:outline-none focus:border-amber-500 w-full",
+                        oninput: move |evt| invite_code.set(evt.value())
+                    }
+                    button {
+                        onclick: on_join,
+                        disabled: invite_code().trim().is_empty(),
+                        class: "bg-violet-600 hover:bg-violet-700 disabled:bg-gray-600 text-white py-2 px-4 rounded transition-colors duration-150",
+                        "Join"
+                    }
+                }
+            }
+            if let Some(message) = status() {
+                p { class: "text-sm text-red-300", "{message}" }
+            }
+        }
+    }
+}
+
+#[component]
+pub(crate) fn Teams() -> Element {
+    let auth_state = use_context::<SharedAuthState>();
+    let mut selected = use_signal(|| None::<String>);
+    let mut time_window = use_signal(TimeWindow::default);
+
+    let teams_auth = auth_state.clone();
+    let mut teams_resource = use_resource(move || {
+        let auth_state = teams_auth.clone();
+        async move { team::list_teams(&auth_state).await.map_err(|e| e.to_string()) }
+    });
+
+    let mut overview_resource = use_resource(move || {
+        let auth_state = auth_state.clone();
+        let team_id = selected();
+        let tw = time_window();
+        async move {
+            match team_id {
+                Some(team_id) => team::load_team(&auth_state, team_id, tw)
+                    .await
+                    .map(Some)
+                    .map_err(|e| e.to_string()),
+                None => Ok(None),
+            }
+        }
+    });
+
+    let on_joined = move |team: Team| {
+        selected.set(Some(team.id));
+        teams_resource.restart();
+    };
+
+    let on_changed = move |()| {
+        teams_resource.restart();
+        overview_resource.restart();
+    };
+
+    let teams_value = teams_resource.value();
+    let teams = teams_value.read();
+    let overview_value = overview_resource.value();
+    let overview = overview_value.read();
+
+    rsx! {
+        div { class: "container mx-auto px-4 py-8 max-w-6xl",
+            div { class: "flex justify-between items-center mb-6",
+                h1 { class: "text-2xl font-bold text-gray-100", "Teams" }
+                select {
+                    class: "bg-gray-700 text-gray-200 border border-gray-600 rounded py-2 px-3 text-sm focus:outline-none focus:border-amber-500",
+                    onchange: move |evt| {
+                        let idx: usize = evt.value().parse().unwrap_or(3);
+                        time_window.set(TimeWindow::ALL[idx]);
+                    },
+                    for (i, tw) in TimeWindow::ALL.iter().enumerate() {
+                        option {
+                            value: "{i}",
+                            selected: *tw == time_window(),
+                            "{tw.label()}"
+                        }
+                    }
+                }
+            }
+
+            div { class: "grid grid-cols-1 lg:grid-cols-4 gap-6",
+                div { class: "space-y-6",
+                    div { class: "bg-gray-800 rounded-lg border border-gray-700 p-4",
+                        match &*teams {
+                            None => rsx! {
+                                div { class: "animate-pulse text-gray-500", "Loading teams..." }
+                            },
+                            Some(Err(err)) => rsx! {
+                                p { class: "text-red-300 text-sm", "Failed to load teams: {err}" }
+                            },
+                            Some(Ok(teams)) => rsx! {
+                                if teams.is_empty() {
+                                    p { class: "text-gray-500 text-sm", "You are not on a team yet." }
+                                }
+                                ul { class: "space-y-1",
+                                    for t in teams.iter() {
+                                        {
+                                            let id = t.id.clone();
+                                            let active = selected().as_deref() == Some(t.id.as_str());
+                                            let class = if active {
+                                                "w-full text-left px-3 py-2 rounded bg-gray-700 text-amber-400"
+                                            } else {
+                                                "w-full text-left px-3 py-2 rounded text-gray-300 hover:bg-gray-700/50"
+                                            };
+                                            rsx! {
+                                                li { key: "{t.id}",
+                                                    button {
+                                                        class: "{class}",
+                                                        onclick: move |_| selected.set(Some(id.clone())),
+                                                        "{t.name}"
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            },
+                        }
+                    }
+                    TeamForms { on_joined }
+                }
+
+                div { class: "lg:col-span-3",
+                    match &*overview {
+                        None => rsx! {
+                            div { class: "bg-gray-800 rounded-lg border border-gray-700 p-12 text-center text-gray-500",
+                                div { class: "animate-pulse", "Loading team..." }
+                            }
+                        },
+                        Some(Err(err)) => rsx! {
+                            div { class: "bg-red-900/30 border border-red-700 text-red-300 px-4 py-3 rounded",
+                                p { "Failed to load team: {err}" }
+                            }
+                        },
+                        Some(Ok(None)) => rsx! {
+                            div { class: "bg-gray-800 rounded-lg border border-gray-700 p-12 text-center text-gray-500",
+                                "Select a team, or create one and share the invite code with your teammates."
+                            }
+                        },
+                        Some(Ok(Some(overview))) => rsx! {
+                            TeamDetail { overview: overview.clone(), on_changed }
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
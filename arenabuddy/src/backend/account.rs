@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use arenabuddy_core::services::{
+    account_service::{
+        CancelAccountDeletionRequest, DeleteMyAccountRequest, ExportMyDataRequest,
+        account_service_client::AccountServiceClient,
+    },
+    auth_service::{GetCurrentUserRequest, auth_service_client::AuthServiceClient},
+};
+use tokio::io::AsyncWriteExt;
+use tonic::transport::Channel;
+use tracing::info;
+
+use super::{
+    auth::{SharedAuthState, attach_bearer, delete_saved_auth},
+    sync::current_token,
+};
+
+type AccountResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+async fn connect(auth_state: &SharedAuthState) -> AccountResult<(AccountServiceClient<Channel>, String)> {
+    let grpc_url = super::paths::grpc_url();
+    let token = current_token(auth_state, &grpc_url).await.ok_or("not authenticated")?;
+    let client = AccountServiceClient::connect(grpc_url).await?;
+    Ok((client, token))
+}
+
+fn authed<T>(message: T, token: &str) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    attach_bearer(&mut request, Some(token));
+    request
+}
+
+/// When the signed-in account is due to be deleted, if a deletion is pending.
+pub(crate) async fn pending_deletion(auth_state: &SharedAuthState) -> AccountResult<Option<String>> {
+    let grpc_url = super::paths::grpc_url();
+    let token = current_token(auth_state, &grpc_url).await.ok_or("not authenticated")?;
+    let mut client = AuthServiceClient::connect(grpc_url).await?;
+    let user = client
+        .get_current_user(authed(GetCurrentUserRequest {}, &token))
+        .await?
+        .into_inner()
+        .user
+        .ok_or("server returned no user")?;
+    Ok(Some(user.deletion_scheduled_for).filter(|at| !at.is_empty()))
+}
+
+/// Download everything the server stores for the signed-in account to `path`, one JSON
+/// record per line. Returns the number of records written.
+pub(crate) async fn export_my_data(auth_state: &SharedAuthState, path: &Path) -> AccountResult<usize> {
+    let (mut client, token) = connect(auth_state).await?;
+    let mut stream = client
+        .export_my_data(authed(ExportMyDataRequest {}, &token))
+        .await?
+        .into_inner();
+
+    let mut file = tokio::fs::File::create(path).await?;
+    let mut records = 0;
+    while let Some(record) = stream.message().await? {
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        file.write_all(&line).await?;
+        records += 1;
+    }
+    file.flush().await?;
+
+    info!("Exported {records} account record(s) to {}", path.display());
+    Ok(records)
+}
+
+/// Ask the server to delete the signed-in account. Returns when the deletion is scheduled
+/// for, or `None` if the account was deleted immediately, in which case this also signs out.
+pub(crate) async fn delete_my_account(
+    auth_state: &SharedAuthState,
+    confirm_username: String,
+) -> AccountResult<Option<String>> {
+    let (mut client, token) = connect(auth_state).await?;
+    let response = client
+        .delete_my_account(authed(DeleteMyAccountRequest { confirm_username }, &token))
+        .await?
+        .into_inner();
+
+    if response.scheduled_for.is_empty() {
+        info!("Account deleted");
+        delete_saved_auth();
+        *auth_state.lock().await = None;
+        return Ok(None);
+    }
+    info!("Account scheduled for deletion at {}", response.scheduled_for);
+    Ok(Some(response.scheduled_for))
+}
+
+pub(crate) async fn cancel_account_deletion(auth_state: &SharedAuthState) -> AccountResult<()> {
+    let (mut client, token) = connect(auth_state).await?;
+    client
+        .cancel_account_deletion(authed(CancelAccountDeletionRequest {}, &token))
+        .await?;
+    info!("Account deletion cancelled");
+    Ok(())
+}
@@ -105,6 +105,7 @@ pub fn load_auth() -> Option<AuthState> {
             discord_id: saved.discord_id,
             username: saved.username,
             avatar_url: saved.avatar_url,
+            ..Default::default()
         },
     })
 }
@@ -265,6 +266,8 @@ pub async fn login(
             authorization_code: code,
             code_verifier,
             redirect_uri,
+            provider: "discord".to_string(),
+            ..Default::default()
         })
         .await
         .map_err(|e| {
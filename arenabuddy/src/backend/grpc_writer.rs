@@ -2,9 +2,12 @@ use std::time::Duration;
 
 use arenabuddy_core::{
     cards::CardsDatabase,
-    models::{MTGAMatch, MatchData, MatchResult, OpponentDeck},
+    models::{MTGADraft, MTGAMatch, MatchData, MatchResult, OpponentDeck},
     player_log::replay::MatchReplay,
-    services::match_service::{ClassifyMatchRequest, UpsertMatchDataRequest, match_service_client::MatchServiceClient},
+    services::{
+        draft_service::{UpsertDraftRequest, draft_service_client::DraftServiceClient},
+        match_service::{ClassifyMatchRequest, UpsertMatchDataRequest, match_service_client::MatchServiceClient},
+    },
 };
 use arenabuddy_data::{MatchDB, MetagameRepository, metagame_models::MatchArchetype};
 use chrono::Utc;
@@ -178,6 +181,8 @@ impl GrpcReplayWriter {
                         archetype_id: None,
                         archetype_name: c.archetype_name.clone(),
                         confidence: c.confidence,
+                        evidence: c.evidence.as_ref().map(Into::into),
+                        overridden: false,
                     };
                     if let Err(e) = self.local_db.upsert_match_archetype(&ma).await {
                         error!("Failed to cache archetype for match {match_id}: {e}");
@@ -271,3 +276,45 @@ fn build_request(match_data: &MatchData, token: Option<&str>) -> tonic::Request<
 
     request
 }
+
+/// Uploads completed drafts to the server so they show up on the web dashboard.
+pub struct GrpcDraftWriter {
+    client: DraftServiceClient<Channel>,
+    auth_state: SharedAuthState,
+    grpc_url: String,
+}
+
+impl GrpcDraftWriter {
+    pub async fn connect(url: &str, auth_state: SharedAuthState) -> Result<Self, tonic::transport::Error> {
+        let client = DraftServiceClient::connect(url.to_string()).await?;
+        Ok(Self {
+            client,
+            auth_state,
+            grpc_url: url.to_string(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl arenabuddy_core::player_log::ingest::DraftWriter for GrpcDraftWriter {
+    async fn write(&mut self, draft: &MTGADraft) -> arenabuddy_core::Result<()> {
+        let draft_id = draft.draft().id();
+        let Some(token) = super::sync::current_token(&self.auth_state, &self.grpc_url).await else {
+            info!("Not signed in, skipping upload of draft {draft_id}");
+            return Ok(());
+        };
+
+        let mut request = tonic::Request::new(UpsertDraftRequest {
+            draft: Some(draft.into()),
+        });
+        super::auth::attach_bearer(&mut request, Some(&token));
+
+        self.client.upsert_draft(request).await.map_err(|e| {
+            error!("gRPC draft upload failed for draft {draft_id}: {e}");
+            arenabuddy_core::Error::Io(format!("gRPC draft upload failed: {e}"))
+        })?;
+
+        info!("Sent draft {draft_id} to gRPC backend");
+        Ok(())
+    }
+}
@@ -13,7 +13,10 @@ use tokio::sync::Mutex;
 use tonic::transport::Channel;
 use tracing::{error, info};
 
-use super::{auth::SharedAuthState, grpc_writer::GrpcReplayWriter};
+use super::{
+    auth::SharedAuthState,
+    grpc_writer::{GrpcDraftWriter, GrpcReplayWriter},
+};
 
 /// Adapter that wraps shared debug storage for the `ReplayWriter` trait.
 ///
@@ -128,6 +131,13 @@ pub async fn start(
     // Add gRPC writer and debug reporter
     let mut debug_reporter: Option<Arc<Mutex<DebugReporter>>> = None;
     let grpc_url = super::paths::grpc_url();
+    let service = match GrpcDraftWriter::connect(&grpc_url, auth_state.clone()).await {
+        Ok(writer) => service.add_draft_writer(Box::new(writer)),
+        Err(e) => {
+            error!("Failed to connect draft uploads to gRPC backend at {grpc_url}: {e}");
+            service
+        }
+    };
     let service = {
         match GrpcReplayWriter::connect(&grpc_url, cards, auth_state.clone(), grpc_local_db).await {
             Ok(writer) => {
@@ -1,3 +1,4 @@
+pub(crate) mod account;
 pub(crate) mod auth;
 pub(crate) mod auth_controller;
 pub(crate) mod grpc_writer;
@@ -5,7 +6,9 @@ pub(crate) mod ingest;
 mod launch;
 pub(crate) mod paths;
 mod service;
+pub(crate) mod share;
 pub(crate) mod sync;
+pub(crate) mod team;
 
 pub use auth::{SharedAuthState, new_shared_auth_state};
 pub use launch::{BackgroundRuntime, launch};
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 const DEFAULT_GRPC_URL: &str = "https://api.arenabuddy.io";
+const DEFAULT_WEB_URL: &str = "https://arenabuddy.io";
 
 /// Returns the platform-specific application data directory for `ArenaBuddy`.
 pub fn app_data_dir() -> Option<PathBuf> {
@@ -18,3 +19,8 @@ pub fn app_data_dir() -> Option<PathBuf> {
 pub fn grpc_url() -> String {
     std::env::var("ARENABUDDY_GRPC_URL").unwrap_or_else(|_| DEFAULT_GRPC_URL.to_string())
 }
+
+/// Returns the public web app URL (used for share links) from `ARENABUDDY_WEB_URL`, or the default.
+pub fn web_url() -> String {
+    std::env::var("ARENABUDDY_WEB_URL").unwrap_or_else(|_| DEFAULT_WEB_URL.to_string())
+}
@@ -8,8 +8,9 @@ use arenabuddy_core::{
         game::GameResultDisplay,
         match_details::MatchDetails,
         match_summary::MatchSummary,
+        metagame::{MetagameTrends, TrendInterval},
         mulligan::Mulligan,
-        stats::{MatchStats, TimeWindow},
+        stats::{ArchetypeMatchup, DeckStats, LimitedRecord, MatchStats, MatchupGrouping, TimeWindow},
     },
     models::{Card, CardFace, Cost, Draft},
 };
@@ -213,9 +214,22 @@ where
             Vec::default()
         });
 
-        let (controller_archetype, opponent_archetype) = self.db.get_match_archetypes(&id).await.unwrap_or_default();
-        match_details.controller_archetype = controller_archetype;
-        match_details.opponent_archetype = opponent_archetype;
+        for c in self.db.get_match_classifications(&id).await.unwrap_or_default() {
+            match_details.set_classification(&c.side, c.archetype_name, c.evidence);
+        }
+
+        if let (Some(event), Some(archetype), Some(deck)) = (
+            mtga_match.event(),
+            match_details.controller_archetype.as_deref(),
+            match_details.decklists.first(),
+        ) {
+            match self.db.get_stock_list(&event.format, archetype).await {
+                Ok(stock) => {
+                    match_details.stock_comparison = stock.map(|stock| stock.compare(deck, &self.cards));
+                }
+                Err(e) => error!("Error retrieving stock list for {archetype}: {}", e),
+            }
+        }
 
         Ok(match_details)
     }
@@ -235,6 +249,35 @@ where
         Ok(self.db.get_match_stats(None, time_window).await?)
     }
 
+    pub async fn get_limited_stats(&self, time_window: TimeWindow) -> Result<Vec<LimitedRecord>> {
+        Ok(self.db.get_limited_stats(None, time_window).await?)
+    }
+
+    pub async fn get_deck_stats(&self, time_window: TimeWindow) -> Result<Vec<DeckStats>> {
+        Ok(self.db.get_deck_stats(None, time_window).await?)
+    }
+
+    pub async fn get_archetype_matchups(
+        &self,
+        time_window: TimeWindow,
+        grouping: MatchupGrouping,
+        format: Option<&str>,
+    ) -> Result<Vec<ArchetypeMatchup>> {
+        Ok(self
+            .db
+            .get_archetype_matchups(None, time_window, grouping, format)
+            .await?)
+    }
+
+    pub async fn get_metagame_trends(&self, format: &str, interval: TrendInterval) -> Result<MetagameTrends> {
+        let days = match interval {
+            TrendInterval::Daily => 14,
+            TrendInterval::Weekly => 56,
+        };
+        let since = chrono::Utc::now().date_naive() - chrono::Days::new(days);
+        Ok(self.db.metagame_trends(format, interval, since).await?)
+    }
+
     pub fn get_card_database_summary(&self) -> CardDatabaseSummary {
         card_database_summary(&self.cards)
     }
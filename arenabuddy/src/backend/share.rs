@@ -0,0 +1,29 @@
+use arenabuddy_core::services::share_service::{CreateMatchShareRequest, share_service_client::ShareServiceClient};
+use tracing::info;
+
+use super::{
+    auth::{SharedAuthState, attach_bearer},
+    sync::current_token,
+};
+
+/// Create a read-only share link for a synced match and return its web URL.
+///
+/// The match must already be on the server; use the Sync button first for local-only matches.
+pub(crate) async fn create_share_link(
+    auth_state: &SharedAuthState,
+    match_id: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let grpc_url = super::paths::grpc_url();
+    let token = current_token(auth_state, &grpc_url).await.ok_or("not authenticated")?;
+    let mut client = ShareServiceClient::connect(grpc_url).await?;
+
+    let mut request = tonic::Request::new(CreateMatchShareRequest {
+        match_id: match_id.to_string(),
+        expires_in_hours: 0,
+    });
+    attach_bearer(&mut request, Some(&token));
+    let response = client.create_match_share(request).await?.into_inner();
+
+    info!("Created share link for match {match_id}");
+    Ok(format!("{}/share/{}", super::paths::web_url(), response.token))
+}
@@ -11,7 +11,7 @@ use tracing::{error, info};
 
 use super::auth::{SharedAuthState, attach_bearer, needs_refresh, refresh};
 
-async fn current_token(auth_state: &SharedAuthState, grpc_url: &str) -> Option<String> {
+pub(crate) async fn current_token(auth_state: &SharedAuthState, grpc_url: &str) -> Option<String> {
     let mut guard = auth_state.lock().await;
     let state = guard.as_ref()?;
 
@@ -86,6 +86,7 @@ pub async fn sync_matches(
     for server_match in &missing {
         let mut request = tonic::Request::new(GetMatchDataRequest {
             match_id: server_match.id.clone(),
+            include_cards: false,
         });
         attach_bearer(&mut request, Some(&token));
 
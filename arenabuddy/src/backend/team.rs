@@ -0,0 +1,150 @@
+use arenabuddy_core::{
+    display::stats::{MatchStats, TimeWindow},
+    services::team_service::{
+        CreateTeamRequest, GetTeamRequest, GetTeamStatsRequest, JoinTeamRequest, LeaveTeamRequest,
+        ListTeamDraftsRequest, ListTeamMatchesRequest, ListTeamsRequest, MemberRecord, StatsTimeWindow, Team,
+        TeamDraft, TeamMatch, TeamMember, UpdateSharingRequest, team_service_client::TeamServiceClient,
+    },
+};
+use tonic::transport::Channel;
+use tracing::info;
+
+use super::{
+    auth::{SharedAuthState, attach_bearer},
+    sync::current_token,
+};
+
+type TeamResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Number of teammate matches shown on the team page.
+const RECENT_MATCH_LIMIT: i32 = 25;
+/// Number of teammate drafts shown on the team page.
+const RECENT_DRAFT_LIMIT: i32 = 10;
+
+/// Everything the team page shows for one team.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TeamOverview {
+    pub team: Team,
+    pub members: Vec<TeamMember>,
+    pub stats: MatchStats,
+    pub member_records: Vec<MemberRecord>,
+    pub recent_matches: Vec<TeamMatch>,
+    pub recent_drafts: Vec<TeamDraft>,
+}
+
+async fn connect(auth_state: &SharedAuthState) -> TeamResult<(TeamServiceClient<Channel>, String)> {
+    let grpc_url = super::paths::grpc_url();
+    let token = current_token(auth_state, &grpc_url).await.ok_or("not authenticated")?;
+    let client = TeamServiceClient::connect(grpc_url).await?;
+    Ok((client, token))
+}
+
+fn authed<T>(message: T, token: &str) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    attach_bearer(&mut request, Some(token));
+    request
+}
+
+pub(crate) async fn list_teams(auth_state: &SharedAuthState) -> TeamResult<Vec<Team>> {
+    let (mut client, token) = connect(auth_state).await?;
+    let response = client.list_teams(authed(ListTeamsRequest {}, &token)).await?;
+    Ok(response.into_inner().teams)
+}
+
+pub(crate) async fn create_team(auth_state: &SharedAuthState, name: String) -> TeamResult<Team> {
+    let (mut client, token) = connect(auth_state).await?;
+    let response = client.create_team(authed(CreateTeamRequest { name }, &token)).await?;
+    let team = response.into_inner().team.ok_or("server returned no team")?;
+    info!("Created team {}", team.name);
+    Ok(team)
+}
+
+pub(crate) async fn join_team(auth_state: &SharedAuthState, invite_code: String) -> TeamResult<Team> {
+    let (mut client, token) = connect(auth_state).await?;
+    let response = client
+        .join_team(authed(JoinTeamRequest { invite_code }, &token))
+        .await?;
+    let team = response.into_inner().team.ok_or("server returned no team")?;
+    info!("Joined team {}", team.name);
+    Ok(team)
+}
+
+pub(crate) async fn leave_team(auth_state: &SharedAuthState, team_id: String) -> TeamResult<()> {
+    let (mut client, token) = connect(auth_state).await?;
+    client.leave_team(authed(LeaveTeamRequest { team_id }, &token)).await?;
+    Ok(())
+}
+
+pub(crate) async fn update_sharing(
+    auth_state: &SharedAuthState,
+    team_id: String,
+    share_matches: bool,
+    share_drafts: bool,
+) -> TeamResult<()> {
+    let (mut client, token) = connect(auth_state).await?;
+    let request = UpdateSharingRequest {
+        team_id,
+        share_matches,
+        share_drafts,
+    };
+    client.update_sharing(authed(request, &token)).await?;
+    Ok(())
+}
+
+pub(crate) async fn load_team(
+    auth_state: &SharedAuthState,
+    team_id: String,
+    time_window: TimeWindow,
+) -> TeamResult<TeamOverview> {
+    let (mut client, token) = connect(auth_state).await?;
+
+    let team = client
+        .get_team(authed(
+            GetTeamRequest {
+                team_id: team_id.clone(),
+            },
+            &token,
+        ))
+        .await?
+        .into_inner();
+
+    let stats_request = GetTeamStatsRequest {
+        team_id: team_id.clone(),
+        time_window: StatsTimeWindow::from(time_window).into(),
+    };
+    let stats = client
+        .get_team_stats(authed(stats_request, &token))
+        .await?
+        .into_inner()
+        .stats
+        .unwrap_or_default();
+
+    let matches_request = ListTeamMatchesRequest {
+        team_id: team_id.clone(),
+        limit: RECENT_MATCH_LIMIT,
+    };
+    let recent_matches = client
+        .list_team_matches(authed(matches_request, &token))
+        .await?
+        .into_inner()
+        .matches;
+
+    let drafts_request = ListTeamDraftsRequest {
+        team_id,
+        limit: RECENT_DRAFT_LIMIT,
+    };
+    let recent_drafts = client
+        .list_team_drafts(authed(drafts_request, &token))
+        .await?
+        .into_inner()
+        .drafts;
+
+    Ok(TeamOverview {
+        team: team.team.ok_or("server returned no team")?,
+        members: team.members,
+        stats: MatchStats::from(&stats),
+        member_records: stats.members,
+        recent_matches,
+        recent_drafts,
+    })
+}
@@ -0,0 +1,90 @@
+use arenabuddy_core::{
+    cards::CardsDatabase,
+    player_log::{
+        processor::{ParseOutput, parse},
+        triage::signature,
+    },
+};
+use arenabuddy_data::{ArenabuddyRepository, DebugRepository, MatchDB};
+use tracing::info;
+use uuid::Uuid;
+
+use super::definitions::DebugCommands;
+use crate::{Error, Result};
+
+/// Reports fingerprinted per database round trip during a backfill.
+const FINGERPRINT_BATCH_SIZE: i64 = 500;
+
+async fn connect(db_url: &str) -> Result<MatchDB> {
+    let db = MatchDB::new(Some(db_url), CardsDatabase::default()).await?;
+    db.init().await?;
+    Ok(db)
+}
+
+fn output_kind(output: &ParseOutput) -> &'static str {
+    match output {
+        ParseOutput::GREMessage(_) => "GRE message",
+        ParseOutput::ClientMessage(_) => "client message",
+        ParseOutput::MGRSCMessage(_) => "match game room state change",
+        ParseOutput::BusinessMessage(_) => "business event",
+        ParseOutput::DraftNotify(_) => "draft notification",
+        ParseOutput::NoEvent => "no event (ignored)",
+    }
+}
+
+async fn replay(db: &MatchDB, id: &str, dump: bool) -> Result<()> {
+    let id: Uuid = id
+        .parse()
+        .map_err(|_| Error::Invalid(format!("invalid parse error id: {id}")))?;
+    let report = db
+        .get_parse_error(id)
+        .await?
+        .ok_or_else(|| Error::Invalid(format!("parse error {id} not found")))?;
+
+    let sig = signature(&report.raw_json);
+    println!("Reported:     {}", report.reported_at.to_rfc3339());
+    println!(
+        "Stored group: {}",
+        report.fingerprint.as_deref().unwrap_or("(not fingerprinted)")
+    );
+    println!("Fingerprint:  {}", sig.fingerprint());
+    println!("Message type: {}", sig.message_type);
+    println!("Serde path:   {}", sig.serde_path);
+    println!();
+
+    match parse(&report.raw_json) {
+        Ok(output) => {
+            println!("Parses as {} on this version", output_kind(&output));
+            if dump {
+                println!("{output:#?}");
+            }
+        }
+        Err(e) => println!("Reproduced: {e}"),
+    }
+    Ok(())
+}
+
+async fn fingerprint(db: &MatchDB) -> Result<()> {
+    let mut total = 0;
+    loop {
+        let reports = db.unfingerprinted_parse_errors(FINGERPRINT_BATCH_SIZE).await?;
+        if reports.is_empty() {
+            break;
+        }
+        for report in &reports {
+            db.set_parse_error_signature(report.id, &signature(&report.raw_json))
+                .await?;
+        }
+        total += reports.len();
+        info!("Fingerprinted {total} parse error(s)");
+    }
+    info!("Done: {total} parse error(s) fingerprinted");
+    Ok(())
+}
+
+pub async fn execute(command: &DebugCommands) -> Result<()> {
+    match command {
+        DebugCommands::Replay { id, db, dump } => replay(&connect(db).await?, id, *dump).await,
+        DebugCommands::Fingerprint { db } => fingerprint(&connect(db).await?).await,
+    }
+}
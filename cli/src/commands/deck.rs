@@ -6,6 +6,9 @@
 //! 3. `[FILE]` — file contents, or `-` to read stdin once  
 //! 4. `--main` / `--side` — literal JSON arrays of Arena IDs  
 //! 5. piped stdin — when stdin is not a TTY (no `[FILE]` and no `--main`)
+//!
+//! **`deck compare`** diffs a match's game 1 deck against the stock list of its archetype,
+//! averaged over scraped tournament decks.
 
 use std::{
     io::{self, IsTerminal, Read},
@@ -32,6 +35,15 @@ pub(crate) struct DeckShowOpts<'a> {
     pub side: Option<&'a str>,
 }
 
+/// Options collected from `arenabuddyctl deck compare …`.
+pub(crate) struct DeckCompareOpts<'a> {
+    pub cards_db: &'a Path,
+    pub db_url: &'a str,
+    pub match_id: &'a str,
+    pub archetype: Option<&'a str>,
+    pub format: Option<&'a str>,
+}
+
 /// What to render after precedence rules ([`determine_input`]) resolve.
 enum DeckShowInput<'a> {
     /// Postgres `deck` table for controller, optional `deck.game_number` filter.
@@ -75,6 +87,62 @@ pub async fn show(opts: DeckShowOpts<'_>) -> Result<()> {
     Ok(())
 }
 
+pub async fn compare(opts: DeckCompareOpts<'_>) -> Result<()> {
+    let catalog = CardsDatabase::new(opts.cards_db)?;
+    let db = MatchDB::new(Some(opts.db_url), catalog.clone()).await?;
+    db.init().await?;
+
+    let match_id = opts.match_id;
+    let mut decks = db.list_decklists(match_id).await?;
+    decks.sort_by_key(Deck::game_number);
+    let Some(deck) = decks.first() else {
+        return Err(Error::Invalid(format!(
+            "No deck rows for match `{match_id}` (controller rows in Postgres `deck` table)."
+        )));
+    };
+
+    let archetype = match opts.archetype {
+        Some(archetype) => archetype.to_owned(),
+        None => db.get_match_archetypes(match_id).await?.0.ok_or_else(|| {
+            Error::Invalid(format!(
+                "Match `{match_id}` has no controller archetype; pass `--archetype`."
+            ))
+        })?,
+    };
+    let format = if let Some(format) = opts.format {
+        format.to_owned()
+    } else {
+        let (mtga_match, _) = db.get_match(match_id, None).await?;
+        mtga_match.event().map(|event| event.format).ok_or_else(|| {
+            Error::Invalid(format!(
+                "Match `{match_id}` has no known event format; pass `--format`."
+            ))
+        })?
+    };
+
+    let Some(stock) = db.get_stock_list(&format, &archetype).await? else {
+        return Err(Error::Invalid(format!(
+            "No scraped {format} decks for archetype `{archetype}`."
+        )));
+    };
+
+    let comparison = stock.compare(deck, &catalog);
+    println!(
+        "Match `{match_id}` · {} vs {} stock list ({} decks)",
+        deck.name(),
+        comparison.archetype,
+        comparison.decks
+    );
+    println!();
+    let body = comparison.pretty_print();
+    if body.is_empty() {
+        println!("Your deck plays the stock list.");
+    } else {
+        print!("{body}");
+    }
+    Ok(())
+}
+
 // --- Resolve which input shape we have ---------------------------------------
 
 fn determine_input<'a>(opts: &'a DeckShowOpts<'a>) -> Result<DeckShowInput<'a>> {
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::Subcommand;
+use clap::{Subcommand, ValueEnum};
 
 // Constants used in command definitions
 pub const SCRYFALL_HOST_DEFAULT: &str = "https://api.scryfall.com";
@@ -39,6 +39,20 @@ pub enum Commands {
 
         #[arg(long, help = "Output directory for scraped data", default_value = "./cards.pb")]
         output: PathBuf,
+
+        #[arg(
+            long,
+            env = "ARENABUDDY_HTTP_CACHE",
+            help = "Cache responses in this directory, revalidating them on later runs"
+        )]
+        cache_dir: Option<PathBuf>,
+
+        #[arg(
+            long,
+            requires = "cache_dir",
+            help = "Replay responses from the cache without network access"
+        )]
+        offline: bool,
     },
 
     /// Scrape card data from MTGA database and enrich with Scryfall
@@ -51,6 +65,20 @@ pub enum Commands {
 
         #[arg(long, help = "Output file for card database", default_value = "./cards.pb")]
         output: PathBuf,
+
+        #[arg(
+            long,
+            env = "ARENABUDDY_HTTP_CACHE",
+            help = "Cache responses in this directory, revalidating them on later runs"
+        )]
+        cache_dir: Option<PathBuf>,
+
+        #[arg(
+            long,
+            requires = "cache_dir",
+            help = "Replay responses from the cache without network access"
+        )]
+        offline: bool,
     },
 
     /// Start an interactive REPL for card searches, analytics, and file info
@@ -94,6 +122,53 @@ pub enum Commands {
         #[command(subcommand)]
         command: DeckCommands,
     },
+
+    /// Triage parse errors reported by clients
+    Debug {
+        #[command(subcommand)]
+        command: DebugCommands,
+    },
+
+    /// Show match statistics from Postgres, overall, per deck or per matchup
+    Stats {
+        #[arg(long, value_enum, default_value_t = StatsGrouping::Overall, help = "How to group the matches")]
+        by: StatsGrouping,
+
+        #[arg(long, value_enum, default_value_t = StatsWindow::All, help = "Only count matches from this period")]
+        window: StatsWindow,
+
+        #[arg(
+            long,
+            help = "Only count matchups from events of this format, e.g. standard or limited"
+        )]
+        format: Option<String>,
+
+        #[arg(long, env = "ARENABUDDY_DATABASE_URL", help = "PostgreSQL database URL")]
+        db: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum StatsGrouping {
+    /// All matches together
+    Overall,
+    /// One row per deck, identified by its game 1 main deck
+    Deck,
+    /// One row per pair of our archetype and the opponent's
+    Matchup,
+    /// One row per pair of our deck and the opponent's archetype
+    DeckMatchup,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum StatsWindow {
+    #[value(name = "24h")]
+    Day,
+    #[value(name = "7d")]
+    Week,
+    #[value(name = "30d")]
+    Month,
+    All,
 }
 
 #[derive(Debug, Subcommand)]
@@ -133,6 +208,35 @@ pub enum DeckCommands {
         #[arg(long, requires = "main", help = "Sideboard as JSON integer array")]
         side: Option<String>,
     },
+
+    /// Compare a match's deck with its archetype's stock list from scraped tournament decks
+    Compare {
+        #[arg(
+            short = 'c',
+            long,
+            default_value = "data/cards-full.pb",
+            help = "Protobuf card database (same as parse --cards-db)"
+        )]
+        cards_db: PathBuf,
+
+        #[arg(long, env = "ARENABUDDY_DATABASE_URL", help = "PostgreSQL URL")]
+        db: String,
+
+        #[arg(long, help = "Match UUID; its game 1 deck is compared")]
+        match_id: String,
+
+        #[arg(
+            long,
+            help = "Archetype to compare against; defaults to the match's controller archetype"
+        )]
+        archetype: Option<String>,
+
+        #[arg(
+            long,
+            help = "Metagame format, e.g. `standard`; defaults to the match's event format"
+        )]
+        format: Option<String>,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -158,6 +262,14 @@ pub enum MetagameCommands {
         /// Read pages from a local directory instead of fetching from the web
         #[arg(long)]
         local_dir: Option<PathBuf>,
+
+        /// Cache fetched pages in this directory, revalidating them on later runs
+        #[arg(long, env = "ARENABUDDY_HTTP_CACHE")]
+        cache_dir: Option<PathBuf>,
+
+        /// Replay pages from the cache directory without fetching from the web
+        #[arg(long, requires = "cache_dir")]
+        offline: bool,
     },
 
     /// Scrape metagame archetype index from `MTGGoldfish`
@@ -173,6 +285,29 @@ pub enum MetagameCommands {
         /// Read pages from a local directory instead of fetching from the web
         #[arg(long)]
         local_dir: Option<PathBuf>,
+
+        /// Cache fetched pages in this directory, revalidating them on later runs
+        #[arg(long, env = "ARENABUDDY_HTTP_CACHE")]
+        cache_dir: Option<PathBuf>,
+
+        /// Replay pages from the cache directory without fetching from the web
+        #[arg(long, requires = "cache_dir")]
+        offline: bool,
+    },
+
+    /// Import a team's own decklists: a folder of `.txt`/`.dek` lists, one tournament per
+    /// subfolder, or JSON/CSV tournament dumps
+    ImportDecklists {
+        /// Folder of decklists
+        dir: PathBuf,
+
+        /// MTG format the lists belong to
+        #[arg(long, default_value = "standard")]
+        format: String,
+
+        /// Database URL
+        #[arg(long, env = "DATABASE_URL")]
+        db: String,
     },
 
     /// Compute signature cards from scraped metagame data
@@ -201,6 +336,26 @@ pub enum MetagameCommands {
         cards_db: Option<PathBuf>,
     },
 
+    /// Measure classifier accuracy by training on scraped decks and classifying held-out ones
+    EvaluateClassifier {
+        /// MTG format (standard, pioneer, explorer, historic)
+        #[arg(long, default_value = "standard")]
+        format: String,
+
+        /// Hold out every Nth deck
+        #[arg(long, default_value_t = 5)]
+        holdout_every: usize,
+
+        /// Also score the held-out decks from only this many of their cards, like a partly
+        /// seen opponent
+        #[arg(long, default_value_t = 8)]
+        revealed: usize,
+
+        /// Database URL
+        #[arg(long, env = "DATABASE_URL")]
+        db: String,
+    },
+
     /// Show metagame database statistics
     Stats {
         /// MTG format (standard, pioneer, explorer, historic)
@@ -212,3 +367,27 @@ pub enum MetagameCommands {
         db: String,
     },
 }
+
+#[derive(Debug, Subcommand)]
+pub enum DebugCommands {
+    /// Replay a reported payload through the log parser to reproduce its error
+    Replay {
+        /// Parse error UUID (`parse_error.id`)
+        id: String,
+
+        /// Database URL
+        #[arg(long, env = "DATABASE_URL")]
+        db: String,
+
+        /// Print the parsed event if the payload parses
+        #[arg(long)]
+        dump: bool,
+    },
+
+    /// Fingerprint reports stored before grouping so they show up in their error groups
+    Fingerprint {
+        /// Database URL
+        #[arg(long, env = "DATABASE_URL")]
+        db: String,
+    },
+}
@@ -3,6 +3,11 @@ use std::path::Path;
 use anyhow::Context;
 use arenabuddy_core::cards::CardsDatabase;
 use arenabuddy_data::{ArenabuddyRepository, MatchDB, MetagameRepository};
+use arenabuddy_metagame::{
+    classification,
+    scraper::{Fetcher, MtgGoldfish},
+    source::{self, LocalDecklists},
+};
 use tracing::{info, warn};
 
 use super::definitions::MetagameCommands;
@@ -35,11 +40,13 @@ pub async fn execute(command: &MetagameCommands) -> Result<()> {
             to,
             db,
             local_dir,
+            cache_dir,
+            offline,
         } => {
-            let fetcher = match local_dir {
-                Some(dir) => arenabuddy_metagame::scraper::Fetcher::local(dir)?,
-                None => arenabuddy_metagame::scraper::Fetcher::http(),
-            };
+            let goldfish = MtgGoldfish::new(match local_dir {
+                Some(dir) => Fetcher::local(dir)?,
+                None => Fetcher::cached(cache_dir.clone(), *offline)?,
+            });
 
             let today = chrono::Utc::now().date_naive();
             let from_date = match from {
@@ -55,33 +62,67 @@ pub async fn execute(command: &MetagameCommands) -> Result<()> {
             };
 
             let repo = connect(db, CardsDatabase::default()).await?;
-            arenabuddy_metagame::scraper::tournament::scrape_tournaments(&repo, &fetcher, format, from_date, to_date)
-                .await?;
+            let count = source::import_tournaments(&repo, &goldfish, format, from_date, to_date).await?;
+            info!("Imported {count} decks for {format}");
         }
 
-        MetagameCommands::ScrapeMetagame { format, db, local_dir } => {
-            let fetcher = match local_dir {
-                Some(dir) => arenabuddy_metagame::scraper::Fetcher::local(dir)?,
-                None => arenabuddy_metagame::scraper::Fetcher::http(),
-            };
+        MetagameCommands::ScrapeMetagame {
+            format,
+            db,
+            local_dir,
+            cache_dir,
+            offline,
+        } => {
+            let goldfish = MtgGoldfish::new(match local_dir {
+                Some(dir) => Fetcher::local(dir)?,
+                None => Fetcher::cached(cache_dir.clone(), *offline)?,
+            });
+
+            let repo = connect(db, CardsDatabase::default()).await?;
+            let count = source::import_archetypes(&repo, &goldfish, format).await?;
+            info!("Imported {count} archetypes for {format}");
+        }
 
+        MetagameCommands::ImportDecklists { dir, format, db } => {
+            let decklists = LocalDecklists::new(dir)?;
             let repo = connect(db, CardsDatabase::default()).await?;
-            arenabuddy_metagame::scraper::metagame::scrape_metagame(&repo, &fetcher, format).await?;
+            let today = chrono::Utc::now().date_naive();
+            let count = source::import_tournaments(&repo, &decklists, format, today, today).await?;
+            info!("Imported {count} decks for {format}");
         }
 
         MetagameCommands::ComputeSignatures { format, db } => {
             let repo = connect(db, CardsDatabase::default()).await?;
-            let count = arenabuddy_metagame::classification::compute_signature_cards(&repo, format).await?;
+            let count = classification::compute_signature_cards(&repo, format).await?;
             info!("Computed {count} signature cards for {format}");
         }
 
         MetagameCommands::Classify { format, db, cards_db } => {
             let cards = load_cards(cards_db.as_deref());
             let repo = connect(db, cards).await?;
-            let count = arenabuddy_metagame::classification::classify_matches(&repo, format).await?;
+            let count = classification::classify_matches(&repo, format).await?;
             info!("Classified {count} matches for {format}");
         }
 
+        MetagameCommands::EvaluateClassifier {
+            format,
+            holdout_every,
+            revealed,
+            db,
+        } => {
+            let repo = connect(db, CardsDatabase::default()).await?;
+            let decks = classification::labelled_decks(&repo, format).await?;
+            let deck_size = classification::deck_size(format);
+            for (label, revealed) in [("Full decks", None), ("Revealed cards", Some(*revealed))] {
+                let evaluation = classification::evaluate(&decks, deck_size, *holdout_every, revealed);
+                let accuracy = evaluation.accuracy().map_or("N/A".to_string(), |a| format!("{a:.1}%"));
+                info!(
+                    "{label}: {}/{} correct ({accuracy}), {} unclassified",
+                    evaluation.correct, evaluation.decks, evaluation.unclassified
+                );
+            }
+        }
+
         MetagameCommands::Stats { format, db } => {
             let repo = connect(db, CardsDatabase::default()).await?;
             let stats = repo.metagame_stats(format).await?;
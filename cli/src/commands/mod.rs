@@ -1,3 +1,4 @@
+pub mod debug;
 pub mod deck;
 pub mod definitions;
 pub mod event_log;
@@ -8,5 +9,6 @@ pub mod repl;
 pub mod scrape;
 pub mod scrape_mtga;
 pub mod scryfall;
+pub mod stats;
 
 pub use definitions::{Commands, DeckCommands};
@@ -1,22 +1,26 @@
 use std::{
     collections::{HashMap, HashSet},
     path::Path,
-    time::Duration,
 };
 
 use arenabuddy_core::models::{Card, CardCollection};
+use arenabuddy_metagame::fetch::FetchClient;
 use tracing::{debug, info};
 
-use super::scryfall::USER_AGENT;
 use crate::{Error, Result, errors::ParseError};
 
 /// Execute the Scrape command
-pub async fn execute(scryfall_host: &str, seventeen_lands_host: &str, output: &Path) -> Result<()> {
+pub async fn execute(
+    client: &FetchClient,
+    scryfall_host: &str,
+    seventeen_lands_host: &str,
+    output: &Path,
+) -> Result<()> {
     info!("Scraping 17Lands data...");
-    let seventeen_lands_data = scrape_seventeen_lands(seventeen_lands_host).await?;
+    let seventeen_lands_data = scrape_seventeen_lands(client, seventeen_lands_host).await?;
 
     info!("Scraping Scryfall per-set data...");
-    let scryfall_sets = scrape_sets(scryfall_host).await?;
+    let scryfall_sets = scrape_sets(client, scryfall_host).await?;
 
     info!("Merging data from both sources...");
     let collection = merge(&seventeen_lands_data, &scryfall_sets);
@@ -28,11 +32,13 @@ pub async fn execute(scryfall_host: &str, seventeen_lands_host: &str, output: &P
     Ok(())
 }
 
-async fn scrape_sets(base_url: &str) -> Result<HashMap<String, HashMap<String, serde_json::Value>>> {
-    let client = reqwest::Client::builder().user_agent(USER_AGENT).build()?;
+async fn scrape_sets(
+    client: &FetchClient,
+    base_url: &str,
+) -> Result<HashMap<String, HashMap<String, serde_json::Value>>> {
     let mut ret: HashMap<String, HashMap<String, serde_json::Value>> = HashMap::new();
 
-    let sets = find_sets(base_url, &client).await?;
+    let sets = find_sets(base_url, client).await?;
     info!("Found {} sets", sets.len());
 
     for set in &sets {
@@ -40,7 +46,7 @@ async fn scrape_sets(base_url: &str) -> Result<HashMap<String, HashMap<String, s
     }
 
     for set in sets {
-        ret.insert(set.to_uppercase(), extract_set(base_url, &client, &set).await?);
+        ret.insert(set.to_uppercase(), extract_set(base_url, client, &set).await?);
     }
 
     for (set_name, set_cards) in &ret {
@@ -49,11 +55,12 @@ async fn scrape_sets(base_url: &str) -> Result<HashMap<String, HashMap<String, s
     Ok(ret)
 }
 
-async fn find_sets(base_url: &str, client: &reqwest::Client) -> Result<Vec<String>> {
-    let response = client.get(format!("{base_url}/sets")).send().await?;
-    response.error_for_status_ref()?;
-
-    let data: serde_json::Value = response.json().await?;
+async fn find_sets(base_url: &str, client: &FetchClient) -> Result<Vec<String>> {
+    let data: serde_json::Value = client
+        .get(&format!("{base_url}/sets"))
+        .await?
+        .error_for_status()?
+        .json()?;
     let mut sets = vec![];
     if let Some(data) = data["data"].as_array() {
         sets = data
@@ -72,11 +79,11 @@ async fn find_sets(base_url: &str, client: &reqwest::Client) -> Result<Vec<Strin
 
 async fn extract_set(
     base_url: &str,
-    client: &reqwest::Client,
+    client: &FetchClient,
     set: &str,
 ) -> Result<HashMap<String, serde_json::Value>, Error> {
     debug!("Extracting set: {set}");
-    let ret = super::scryfall::fetch_set(client, base_url, set, Duration::from_millis(150), extract_set_cards)
+    let ret = super::scryfall::fetch_set(client, base_url, set, extract_set_cards)
         .await?
         .unwrap_or_default();
     debug!("Extracted {} cards from {set}", ret.len());
@@ -107,15 +114,14 @@ fn extract_set_cards(set_cards: &mut HashMap<String, serde_json::Value>, data: &
 }
 
 /// Scrape card data from 17Lands
-async fn scrape_seventeen_lands(base_url: &str) -> Result<Vec<HashMap<String, String>>> {
-    let client = reqwest::Client::builder().user_agent(USER_AGENT).build()?;
+async fn scrape_seventeen_lands(client: &FetchClient, base_url: &str) -> Result<Vec<HashMap<String, String>>> {
     let url = format!("{base_url}/analysis_data/cards/cards.csv");
 
-    let response = client.get(&url).send().await?;
-    info!("Response {}: {}", url, response.status());
-    response.error_for_status_ref()?;
+    let response = client.get(&url).await?;
+    info!("Response {}: {}", url, response.status);
+    let response = response.error_for_status()?;
 
-    csv::Reader::from_reader(response.bytes().await?.as_ref())
+    csv::Reader::from_reader(response.body.as_bytes())
         .deserialize()
         .map(|result| result.map_err(ParseError::from).map_err(Error::from))
         .collect()
@@ -2,19 +2,16 @@
 use std::{
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
-    time::Duration,
 };
 
 use arenabuddy_core::models::{Card, CardCollection};
+use arenabuddy_metagame::fetch::FetchClient;
 use reqwest::StatusCode;
 use rusqlite::Connection;
 use tracing::{debug, info, warn};
 
-use super::scryfall::USER_AGENT;
 use crate::{Error, Result};
 
-const SCRYFALL_RATE_LIMIT_MS: u64 = 150;
-
 // Canonical Arena IDs for basic lands (used as fallback when not found in Scryfall)
 const BASIC_LAND_FALLBACK_IDS: &[(&str, i64)] = &[
     ("Plains", 7193),
@@ -39,7 +36,12 @@ struct MtgaCard {
 }
 
 /// Execute the `ScrapeMtga` command
-pub async fn execute(mtga_path: Option<&PathBuf>, scryfall_host: &str, output: &Path) -> Result<()> {
+pub async fn execute(
+    client: &FetchClient,
+    mtga_path: Option<&PathBuf>,
+    scryfall_host: &str,
+    output: &Path,
+) -> Result<()> {
     info!("Starting MTGA database scrape...");
 
     let db_path = find_mtga_database(mtga_path)?;
@@ -48,7 +50,7 @@ pub async fn execute(mtga_path: Option<&PathBuf>, scryfall_host: &str, output: &
     let mtga_cards = extract_mtga_cards(&db_path)?;
     info!("Extracted {} cards from MTGA database", mtga_cards.len());
 
-    let cards = enrich_with_scryfall(mtga_cards, scryfall_host).await?;
+    let cards = enrich_with_scryfall(client, mtga_cards, scryfall_host).await?;
     info!("Successfully enriched {} cards with Scryfall data", cards.len());
 
     let collection = CardCollection::with_cards(cards);
@@ -162,9 +164,11 @@ fn get_basic_land_fallback_id(card_name: &str) -> Option<i64> {
 }
 
 /// Enrich MTGA cards with Scryfall metadata using batch-by-set approach
-async fn enrich_with_scryfall(mtga_cards: Vec<MtgaCard>, scryfall_host: &str) -> Result<Vec<Card>> {
-    let client = reqwest::Client::builder().user_agent(USER_AGENT).build()?;
-
+async fn enrich_with_scryfall(
+    client: &FetchClient,
+    mtga_cards: Vec<MtgaCard>,
+    scryfall_host: &str,
+) -> Result<Vec<Card>> {
     // Group MTGA cards by expansion code
     let mut cards_by_set: HashMap<String, Vec<MtgaCard>> = HashMap::new();
     for mtga_card in mtga_cards {
@@ -197,7 +201,7 @@ async fn enrich_with_scryfall(mtga_cards: Vec<MtgaCard>, scryfall_host: &str) ->
         );
 
         // Fetch all cards from this set from Scryfall
-        let Some(scryfall_cards) = fetch_scryfall_set(&client, scryfall_host, &set_code).await? else {
+        let Some(scryfall_cards) = fetch_scryfall_set(client, scryfall_host, &set_code).await? else {
             warn!(
                 "Set '{}' not found in Scryfall, skipping {} cards",
                 set_code,
@@ -243,7 +247,7 @@ async fn enrich_with_scryfall(mtga_cards: Vec<MtgaCard>, scryfall_host: &str) ->
             } else {
                 // Collector number miss — try fetching by the card's actual arena ID
                 let card_json =
-                    fetch_or_cache_by_arena_id(&client, scryfall_host, &mut arena_id_cache, mtga_card.grp_id).await?;
+                    fetch_or_cache_by_arena_id(client, scryfall_host, &mut arena_id_cache, mtga_card.grp_id).await?;
 
                 // If that failed and it's a basic land, try the canonical fallback ID
                 let card_json = match (card_json, get_basic_land_fallback_id(&mtga_card.name)) {
@@ -253,7 +257,7 @@ async fn enrich_with_scryfall(mtga_cards: Vec<MtgaCard>, scryfall_host: &str) ->
                             "Actual arena ID {} not found for '{}', trying fallback ID {}",
                             mtga_card.grp_id, mtga_card.name, fallback_id
                         );
-                        fetch_or_cache_by_arena_id(&client, scryfall_host, &mut arena_id_cache, fallback_id).await?
+                        fetch_or_cache_by_arena_id(client, scryfall_host, &mut arena_id_cache, fallback_id).await?
                     }
                     (None, None) => None,
                 };
@@ -283,9 +287,6 @@ async fn enrich_with_scryfall(mtga_cards: Vec<MtgaCard>, scryfall_host: &str) ->
                 }
             }
         }
-
-        // Rate limiting between sets
-        tokio::time::sleep(Duration::from_millis(SCRYFALL_RATE_LIMIT_MS)).await;
     }
 
     if !failed_cards.is_empty() {
@@ -306,7 +307,7 @@ async fn enrich_with_scryfall(mtga_cards: Vec<MtgaCard>, scryfall_host: &str) ->
 
 /// Fetch a card by arena ID, using a cache to avoid redundant Scryfall requests
 async fn fetch_or_cache_by_arena_id(
-    client: &reqwest::Client,
+    client: &FetchClient,
     scryfall_host: &str,
     cache: &mut HashMap<i64, serde_json::Value>,
     arena_id: i64,
@@ -316,8 +317,6 @@ async fn fetch_or_cache_by_arena_id(
         return Ok(Some(cached.clone()));
     }
 
-    tokio::time::sleep(Duration::from_millis(SCRYFALL_RATE_LIMIT_MS)).await;
-
     if let Some(json) = fetch_scryfall_card_by_arena_id(client, scryfall_host, arena_id).await? {
         cache.insert(arena_id, json.clone());
         Ok(Some(json))
@@ -328,19 +327,12 @@ async fn fetch_or_cache_by_arena_id(
 
 /// Fetch all cards from a set via Scryfall, indexed by collector number
 async fn fetch_scryfall_set(
-    client: &reqwest::Client,
+    client: &FetchClient,
     scryfall_host: &str,
     set: &str,
 ) -> Result<Option<HashMap<String, serde_json::Value>>> {
     debug!("Fetching set from Scryfall: {}", set);
-    let cards = super::scryfall::fetch_set(
-        client,
-        scryfall_host,
-        set,
-        Duration::from_millis(SCRYFALL_RATE_LIMIT_MS),
-        extract_set_cards,
-    )
-    .await?;
+    let cards = super::scryfall::fetch_set(client, scryfall_host, set, extract_set_cards).await?;
     Ok(cards)
 }
 
@@ -357,7 +349,7 @@ fn extract_set_cards(cards: &mut HashMap<String, serde_json::Value>, data: &serd
 
 /// Fetch a card from Scryfall by its Arena ID
 async fn fetch_scryfall_card_by_arena_id(
-    client: &reqwest::Client,
+    client: &FetchClient,
     scryfall_host: &str,
     arena_id: i64,
 ) -> Result<Option<serde_json::Value>> {
@@ -365,11 +357,11 @@ async fn fetch_scryfall_card_by_arena_id(
 
     debug!("Fetching from Scryfall by Arena ID: {}", url);
 
-    let response = client.get(&url).send().await?;
+    let response = client.get(&url).await?;
 
-    match response.status() {
+    match response.status {
         StatusCode::OK => {
-            let json = response.json().await?;
+            let json = response.json()?;
             Ok(Some(json))
         }
         StatusCode::NOT_FOUND => {
@@ -378,7 +370,7 @@ async fn fetch_scryfall_card_by_arena_id(
         }
         status => {
             warn!("Unexpected status {} for Arena ID {}", status, arena_id);
-            response.error_for_status_ref()?;
+            response.error_for_status()?;
             Ok(None)
         }
     }
@@ -1,20 +1,33 @@
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
-use reqwest::StatusCode;
+use arenabuddy_metagame::fetch::{FetchClient, FetchOptions};
+use reqwest::{StatusCode, Url};
 
 /// User agent sent with all Scryfall requests.
 pub const USER_AGENT: &str = "arenabuddy/1.0";
 
+/// Minimum delay between requests to the same host.
+pub const RATE_LIMIT: Duration = Duration::from_millis(150);
+
+/// Client for the card data scrapers. Responses are cached in `cache_dir` when given and,
+/// when `offline`, only replayed from there.
+pub fn client(cache_dir: Option<PathBuf>, offline: bool) -> anyhow::Result<FetchClient> {
+    FetchClient::new(FetchOptions {
+        cache_dir,
+        offline,
+        min_interval: RATE_LIMIT,
+        ..FetchOptions::new(USER_AGENT)
+    })
+}
+
 /// Fetch every card in a Scryfall set, indexing each page via `extract`.
 ///
 /// Returns `Ok(None)` when the set search returns 404 (unknown set); otherwise
-/// the accumulated map across all pages. `rate_limit` is the delay between page
-/// requests.
+/// the accumulated map across all pages.
 pub async fn fetch_set<F>(
-    client: &reqwest::Client,
+    client: &FetchClient,
     base_url: &str,
     set: &str,
-    rate_limit: Duration,
     extract: F,
 ) -> anyhow::Result<Option<HashMap<String, serde_json::Value>>>
 where
@@ -27,45 +40,36 @@ where
         ("q", set_query.as_str()),
         ("unique", "cards"),
     ];
+    let url = Url::parse_with_params(&format!("{base_url}/cards/search"), query)?;
 
-    let response = client
-        .get(format!("{base_url}/cards/search"))
-        .query(&query)
-        .send()
-        .await?;
-
-    if response.status() == StatusCode::NOT_FOUND {
+    let response = client.get(url.as_str()).await?;
+    if response.status == StatusCode::NOT_FOUND {
         return Ok(None);
     }
-    response.error_for_status_ref()?;
 
-    let mut data: serde_json::Value = response.json().await?;
+    let mut data: serde_json::Value = response.error_for_status()?.json()?;
     let mut results = HashMap::new();
     extract(&mut results, &data);
-    paginate(client, &mut data, &mut results, rate_limit, extract).await?;
+    paginate(client, &mut data, &mut results, extract).await?;
     Ok(Some(results))
 }
 
 /// Paginate through Scryfall search results, calling `extract` on each page.
 ///
 /// `data` is the JSON response from the initial request. This function follows
-/// `next_page` links until there are no more pages, sleeping `rate_limit` between
-/// requests to respect Scryfall's rate limit.
+/// `next_page` links until there are no more pages; `client` spaces the requests out
+/// to respect Scryfall's rate limit.
 pub async fn paginate<F>(
-    client: &reqwest::Client,
+    client: &FetchClient,
     data: &mut serde_json::Value,
     results: &mut HashMap<String, serde_json::Value>,
-    rate_limit: Duration,
     extract: F,
 ) -> anyhow::Result<()>
 where
     F: Fn(&mut HashMap<String, serde_json::Value>, &serde_json::Value),
 {
     while let Some(next_page) = data["next_page"].as_str() {
-        tokio::time::sleep(rate_limit).await;
-        let response = client.get(next_page).send().await?;
-        response.error_for_status_ref()?;
-        *data = response.json().await?;
+        *data = client.get(next_page).await?.error_for_status()?.json()?;
         extract(results, data);
     }
     Ok(())
@@ -0,0 +1,184 @@
+//! `arenabuddyctl stats`: match records from Postgres, overall, per deck or per matchup.
+
+use arenabuddy_core::{
+    cards::CardsDatabase,
+    display::stats::{ArchetypeMatchup, DeckStats, MatchStats, MatchupGrouping, TimeWindow},
+};
+use arenabuddy_data::{ArenabuddyRepository, MatchDB};
+
+use super::definitions::{StatsGrouping, StatsWindow};
+use crate::Result;
+
+impl From<StatsWindow> for TimeWindow {
+    fn from(window: StatsWindow) -> Self {
+        match window {
+            StatsWindow::Day => TimeWindow::Last24Hours,
+            StatsWindow::Week => TimeWindow::Last7Days,
+            StatsWindow::Month => TimeWindow::Last30Days,
+            StatsWindow::All => TimeWindow::AllTime,
+        }
+    }
+}
+
+pub async fn execute(by: StatsGrouping, window: StatsWindow, format: Option<&str>, db_url: &str) -> Result<()> {
+    let db = MatchDB::new(Some(db_url), CardsDatabase::default()).await?;
+    db.init().await?;
+
+    let time_window = TimeWindow::from(window);
+    match by {
+        StatsGrouping::Overall => print_overall(&db.get_match_stats(None, time_window).await?, time_window),
+        StatsGrouping::Deck => print_decks(&db.get_deck_stats(None, time_window).await?, time_window),
+        StatsGrouping::Matchup | StatsGrouping::DeckMatchup => {
+            let grouping = if matches!(by, StatsGrouping::DeckMatchup) {
+                MatchupGrouping::Deck
+            } else {
+                MatchupGrouping::Archetype
+            };
+            let matchups = db.get_archetype_matchups(None, time_window, grouping, format).await?;
+            print_matchups(&matchups, time_window, format);
+        }
+    }
+    Ok(())
+}
+
+fn rate(rate: Option<f64>) -> String {
+    rate.map_or("-".to_string(), |r| format!("{r:.1}%"))
+}
+
+fn record(wins: i64, losses: i64, win_rate: Option<f64>) -> String {
+    format!("{wins}-{losses} ({})", rate(win_rate))
+}
+
+fn print_overall(stats: &MatchStats, time_window: TimeWindow) {
+    println!("Match statistics · {time_window}");
+    println!();
+    println!(
+        "{:<12}{}",
+        "Matches",
+        record(stats.match_wins, stats.match_losses, stats.match_win_rate())
+    );
+    println!(
+        "{:<12}{}",
+        "Games",
+        record(stats.game_wins, stats.game_losses, stats.game_win_rate())
+    );
+    println!(
+        "{:<12}{}",
+        "On the play",
+        record(stats.play_wins, stats.play_losses, stats.play_win_rate())
+    );
+    println!(
+        "{:<12}{}",
+        "On the draw",
+        record(stats.draw_wins, stats.draw_losses, stats.draw_win_rate())
+    );
+    for bucket in &stats.mulligan_stats {
+        println!(
+            "{:<12}{}",
+            format!("Kept {}", bucket.cards_kept),
+            record(bucket.wins, bucket.losses, bucket.win_rate())
+        );
+    }
+}
+
+fn print_decks(decks: &[DeckStats], time_window: TimeWindow) {
+    println!("Deck statistics · {time_window}");
+    println!();
+    if decks.is_empty() {
+        println!("No decks played in this period.");
+        return;
+    }
+
+    println!(
+        "{:<28}{:>8}{:>9}{:>9}{:>9}{:>11}{:>9}{:>9}{:>9}{:>7}",
+        "Deck", "Matches", "Match", "Game", "Game 1", "Games 2/3", "Play", "Draw", "Mull", "Turns"
+    );
+    for deck in decks {
+        let turns = deck.average_turns.map_or("-".to_string(), |t| format!("{t:.1}"));
+        println!(
+            "{:<28}{:>8}{:>9}{:>9}{:>9}{:>11}{:>9}{:>9}{:>9}{:>7}",
+            truncate(&deck.display_name(), 27),
+            deck.matches,
+            rate(deck.match_win_rate()),
+            rate(deck.game_win_rate()),
+            rate(deck.game1_win_rate()),
+            rate(deck.post_board_win_rate()),
+            rate(deck.play_win_rate()),
+            rate(deck.draw_win_rate()),
+            rate(deck.mulligan_rate()),
+            turns,
+        );
+    }
+}
+
+fn interval(interval: Option<(f64, f64)>) -> String {
+    interval.map_or("-".to_string(), |(low, high)| format!("{low:.0}-{high:.0}%"))
+}
+
+fn print_matchups(matchups: &[ArchetypeMatchup], time_window: TimeWindow, format: Option<&str>) {
+    match format {
+        Some(format) => println!("Matchups · {format} · {time_window}"),
+        None => println!("Matchups · {time_window}"),
+    }
+    println!();
+    if matchups.is_empty() {
+        println!("No classified matches in this period.");
+        return;
+    }
+
+    println!(
+        "{:<28}{:<24}{:>8}{:>9}{:>10}{:>8}{:>9}{:>10}{:>11}{:>10}",
+        "Ours", "Opponent", "Matches", "Match", "95% CI", "Games", "Game 1", "95% CI", "Games 2/3", "95% CI"
+    );
+    for matchup in matchups {
+        let ours = match &matchup.deck_id {
+            Some(deck_id) => format!("{} · {}", matchup.archetype, &deck_id[..deck_id.len().min(8)]),
+            None => matchup.archetype.clone(),
+        };
+        println!(
+            "{:<28}{:<24}{:>8}{:>9}{:>10}{:>8}{:>9}{:>10}{:>11}{:>10}",
+            truncate(&ours, 27),
+            truncate(&matchup.opponent_archetype, 23),
+            matchup.matches,
+            rate(matchup.win_rate()),
+            interval(matchup.confidence_interval()),
+            matchup.games(),
+            rate(matchup.game1_win_rate()),
+            interval(matchup.game1_confidence_interval()),
+            rate(matchup.post_board_win_rate()),
+            interval(matchup.post_board_confidence_interval()),
+        );
+    }
+}
+
+fn truncate(name: &str, max: usize) -> String {
+    if name.chars().count() <= max {
+        return name.to_string();
+    }
+    let mut short: String = name.chars().take(max - 1).collect();
+    short.push('…');
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_map_to_time_windows() {
+        assert_eq!(TimeWindow::from(StatsWindow::Day), TimeWindow::Last24Hours);
+        assert_eq!(TimeWindow::from(StatsWindow::All), TimeWindow::AllTime);
+    }
+
+    #[test]
+    fn long_deck_names_are_truncated() {
+        assert_eq!(truncate("Mono Red", 27), "Mono Red");
+        assert_eq!(truncate("Azorius Control", 8), "Azorius…");
+    }
+
+    #[test]
+    fn intervals_are_whole_percentages() {
+        assert_eq!(interval(Some((21.54, 78.46))), "22-78%");
+        assert_eq!(interval(None), "-");
+    }
+}
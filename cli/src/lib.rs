@@ -16,6 +16,7 @@ struct Cli {
     debug: bool,
 }
 
+#[expect(clippy::too_many_lines)]
 pub async fn run() -> Result<()> {
     let cli = Cli::parse();
     tracing_subscriber::fmt()
@@ -47,16 +48,22 @@ pub async fn run() -> Result<()> {
             scryfall_host,
             seventeen_lands_host,
             output,
+            cache_dir,
+            offline,
         } => {
-            commands::scrape::execute(scryfall_host, seventeen_lands_host, output).await?;
+            let client = commands::scryfall::client(cache_dir.clone(), *offline)?;
+            commands::scrape::execute(&client, scryfall_host, seventeen_lands_host, output).await?;
         }
 
         Commands::ScrapeMtga {
             mtga_path,
             scryfall_host,
             output,
+            cache_dir,
+            offline,
         } => {
-            commands::scrape_mtga::execute(mtga_path.as_ref(), scryfall_host, output).await?;
+            let client = commands::scryfall::client(cache_dir.clone(), *offline)?;
+            commands::scrape_mtga::execute(&client, mtga_path.as_ref(), scryfall_host, output).await?;
         }
 
         Commands::Repl { cards_db } => {
@@ -103,7 +110,31 @@ pub async fn run() -> Result<()> {
                 })
                 .await?;
             }
+            DeckCommands::Compare {
+                cards_db,
+                db,
+                match_id,
+                archetype,
+                format,
+            } => {
+                commands::deck::compare(commands::deck::DeckCompareOpts {
+                    cards_db: cards_db.as_path(),
+                    db_url: db,
+                    match_id,
+                    archetype: archetype.as_deref(),
+                    format: format.as_deref(),
+                })
+                .await?;
+            }
         },
+
+        Commands::Debug { command } => {
+            commands::debug::execute(command).await?;
+        }
+
+        Commands::Stats { by, window, format, db } => {
+            commands::stats::execute(*by, *window, format.as_deref(), db).await?;
+        }
     }
 
     Ok(())
@@ -19,12 +19,22 @@ fn main() -> Result<()> {
     // Convert PathBuf to &str references
     let proto_paths: Vec<&str> = proto_files.iter().filter_map(|p| p.to_str()).collect();
 
+    // The generated `connect` constructors need tonic's transport, which isn't available on wasm.
+    let native = std::env::var_os("CARGO_FEATURE_NATIVE").is_some();
+
     // Compile the proto files with tonic-prost-build (generates gRPC service stubs + prost messages)
     tonic_prost_build::configure()
         .build_server(true)
         .build_client(true)
+        .build_transport(native)
         .btree_map(".")
         .type_attribute(".", "#[derive(serde::Serialize, serde::Deserialize)]")
+        // Let JSON clients omit fields, the same way protobuf treats missing fields as defaults.
+        .message_attribute(".", "#[serde(default)]")
+        // Served by the server's gRPC reflection service.
+        .file_descriptor_set_path(
+            PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR is set")).join("arenabuddy_descriptor.bin"),
+        )
         .compile_protos(&proto_paths, &[proto_dir])?;
 
     Ok(())
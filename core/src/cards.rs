@@ -37,6 +37,28 @@ impl CardsDatabase {
         Ok(Self { db: Arc::new(cards_db) })
     }
 
+    /// Builds a database from cards already in memory, such as the subset a server
+    /// returns alongside a match or draft.
+    pub fn from_cards(cards: impl IntoIterator<Item = Card>) -> Self {
+        let cards_db: BTreeMap<String, Card> = cards.into_iter().map(|card| (card.id.to_string(), card)).collect();
+        Self { db: Arc::new(cards_db) }
+    }
+
+    /// Returns the known cards among `grp_ids` as a collection, without duplicates.
+    pub fn collection_for<T>(&self, grp_ids: impl IntoIterator<Item = T>) -> CardCollection
+    where
+        T: Display,
+    {
+        let cards: BTreeMap<String, Card> = grp_ids
+            .into_iter()
+            .filter_map(|grp_id| {
+                let grp_id = grp_id.to_string();
+                self.db.get(&grp_id).map(|card| (grp_id, card.clone()))
+            })
+            .collect();
+        CardCollection::with_cards(cards.into_values().collect())
+    }
+
     /// Returns the pretty name of the card, or `None` if not found.
     pub fn get_pretty_name<T>(&self, grp_id: &T) -> Option<String>
     where
@@ -71,3 +93,18 @@ impl Default for CardsDatabase {
         Self::from_bytes(CARDS).expect("library should ship with correct cards database")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collection_for_skips_unknown_and_duplicate_ids() {
+        let cards = CardsDatabase::from_cards([Card::new(1, "DMU", "Opt"), Card::new(2, "DMU", "Shock")]);
+
+        let collection = cards.collection_for([1, 1, 3]);
+
+        assert_eq!(collection.cards.len(), 1);
+        assert_eq!(collection.cards[0].name, "Opt");
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// A signature card a deck played and how much it added to the archetype's score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchedCard {
+    pub card_name: String,
+    /// The card's IDF times the copies seen, counting no more than the archetype plays.
+    pub weight: f32,
+}
+
+/// An archetype the deck scored for and the score it reached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchetypeScore {
+    pub archetype_name: String,
+    pub score: f32,
+}
+
+/// Why a deck was classified as its archetype.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassificationEvidence {
+    /// The chosen archetype's signature cards the deck played, heaviest first.
+    pub matched_cards: Vec<MatchedCard>,
+    /// The next best archetypes, best first.
+    pub runners_up: Vec<ArchetypeScore>,
+}
+
+impl ClassificationEvidence {
+    /// The heaviest matched cards, for a one-line explanation.
+    pub fn top_cards(&self, limit: usize) -> impl Iterator<Item = &str> {
+        self.matched_cards.iter().take(limit).map(|c| c.card_name.as_str())
+    }
+}
@@ -96,7 +96,7 @@ impl DeckDisplayRecord {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Difference {
     pub added: Vec<CardDisplayRecord>,
     pub removed: Vec<CardDisplayRecord>,
@@ -166,3 +166,225 @@ impl Difference {
         output
     }
 }
+
+/// Share of an archetype's decks below which a card we play counts as unusual for it.
+pub const UNUSUAL_PLAY_RATE: f64 = 0.25;
+
+/// Share of an archetype's decks at or above which a card counts as one of its staples.
+pub const STAPLE_PLAY_RATE: f64 = 0.75;
+
+/// How many of an archetype's decks play a card, and how many copies they play in total.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StockCard {
+    pub name: String,
+    pub decks: i64,
+    pub copies: i64,
+}
+
+/// An archetype's typical mainboard, from the scraped decks labelled with it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StockList {
+    pub archetype: String,
+    pub decks: i64,
+    pub cards: Vec<StockCard>,
+}
+
+impl StockList {
+    /// Share of the archetype's decks that play `card`.
+    #[expect(clippy::cast_precision_loss)]
+    pub fn play_rate(&self, card: &StockCard) -> f64 {
+        if self.decks > 0 {
+            card.decks as f64 / self.decks as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Copies of `card` per deck of the archetype, counting decks without it as zero.
+    #[expect(clippy::cast_precision_loss)]
+    pub fn average_copies(&self, card: &StockCard) -> f64 {
+        if self.decks > 0 {
+            card.copies as f64 / self.decks as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Compare `deck`'s mainboard against the stock list. The difference's `added` cards are
+    /// the ones we play that fewer than [`UNUSUAL_PLAY_RATE`] of the archetype's decks do, and
+    /// its `removed` cards the staples we play fewer copies of than the archetype's average.
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn compare(&self, deck: &Deck, cards_database: &CardsDatabase) -> StockComparison {
+        let quantities = deck.quantities();
+        let name_of = |card_id: i32| {
+            cards_database
+                .get(&card_id)
+                .map_or_else(|| card_id.to_string(), |card| card.name.clone())
+        };
+        let mut ours: HashMap<String, usize> = HashMap::new();
+        for (card_id, quantity) in &quantities {
+            *ours.entry(name_of(card_id)).or_default() += quantity;
+        }
+        let stock: HashMap<&str, &StockCard> = self.cards.iter().map(|c| (c.name.as_str(), c)).collect();
+        // Stock cards are known by name only; look them up in one pass over the database.
+        let stock_records: HashMap<&str, CardDisplayRecord> = cards_database
+            .values()
+            .filter(|card| stock.contains_key(card.name.as_str()))
+            .map(|card| (card.name.as_str(), card.into()))
+            .collect();
+
+        let added = quantities
+            .keys()
+            .filter(|card_id| {
+                stock
+                    .get(name_of(*card_id).as_str())
+                    .is_none_or(|card| self.play_rate(card) < UNUSUAL_PLAY_RATE)
+            })
+            .map(|card_id| get_card(cards_database, &quantities, card_id))
+            .sorted()
+            .collect();
+
+        let removed = self
+            .cards
+            .iter()
+            .filter(|card| self.play_rate(card) >= STAPLE_PLAY_RATE)
+            .filter_map(|card| {
+                let expected = self.average_copies(card).round() as usize;
+                let missing = expected.saturating_sub(ours.get(&card.name).copied().unwrap_or(0));
+                (missing > 0).then(|| {
+                    let mut record = stock_records
+                        .get(card.name.as_str())
+                        .cloned()
+                        .unwrap_or_else(|| CardDisplayRecord::new(card.name.clone()));
+                    record.quantity = missing;
+                    record
+                })
+            })
+            .sorted()
+            .collect();
+
+        StockComparison {
+            archetype: self.archetype.clone(),
+            decks: self.decks,
+            difference: Difference::new(added, removed),
+        }
+    }
+}
+
+/// How a deck differs from its archetype's stock list.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StockComparison {
+    pub archetype: String,
+    /// Scraped decks the stock list was averaged over.
+    pub decks: i64,
+    /// `added` holds the unusual cards we play, `removed` the staples we're missing.
+    pub difference: Difference,
+}
+
+impl StockComparison {
+    pub fn pretty_print(&self) -> String {
+        let mut output = String::new();
+        if !self.difference.added.is_empty() {
+            writeln!(output, "Unusual for {}:", self.archetype).expect("valid write");
+            for card in &self.difference.added {
+                writeln!(output, "{} {}", card.quantity, card.name).expect("valid write");
+            }
+        }
+        if !self.difference.removed.is_empty() {
+            writeln!(output, "\nMissing staples:").expect("valid write");
+            for card in &self.difference.removed {
+                writeln!(output, "{} {}", card.quantity, card.name).expect("valid write");
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Card;
+
+    fn cards() -> CardsDatabase {
+        CardsDatabase::from_cards([
+            Card::new(1, "dsk", "Monastery Swiftspear"),
+            Card::new(2, "dsk", "Lightning Strike"),
+            Card::new(3, "dsk", "Mountain"),
+            Card::new(4, "dsk", "Pet Rock"),
+            Card::new(5, "dsk", "Play with Fire"),
+        ])
+    }
+
+    fn stock(name: &str, decks: i64, copies: i64) -> StockCard {
+        StockCard {
+            name: name.to_string(),
+            decks,
+            copies,
+        }
+    }
+
+    fn mono_red() -> StockList {
+        StockList {
+            archetype: "Mono-Red Aggro".to_string(),
+            decks: 10,
+            cards: vec![
+                stock("Monastery Swiftspear", 10, 40),
+                stock("Lightning Strike", 9, 30),
+                stock("Play with Fire", 8, 28),
+                stock("Mountain", 10, 200),
+                stock("Pet Rock", 1, 1),
+            ],
+        }
+    }
+
+    #[test]
+    fn play_rate_and_average() {
+        let list = mono_red();
+        let strike = &list.cards[1];
+        assert!((list.play_rate(strike) - 0.9).abs() < 1e-9);
+        assert!((list.average_copies(strike) - 3.0).abs() < 1e-9);
+        assert!(list.play_rate(&StockCard::default()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compare_highlights_unusual_cards_and_missing_staples() {
+        let mut main = vec![1; 4];
+        main.extend([2; 1]);
+        main.extend([3; 20]);
+        main.extend([4; 2]);
+        let deck = Deck::new("Ours".to_string(), 1, main, Vec::new());
+
+        let comparison = mono_red().compare(&deck, &cards());
+        let added: Vec<(&str, usize)> = comparison
+            .difference
+            .added
+            .iter()
+            .map(|c| (c.name.as_str(), c.quantity))
+            .collect();
+        let removed: Vec<(&str, usize)> = comparison
+            .difference
+            .removed
+            .iter()
+            .map(|c| (c.name.as_str(), c.quantity))
+            .collect();
+
+        assert_eq!(added, vec![("Pet Rock", 2)]);
+        assert_eq!(removed.len(), 2);
+        assert!(removed.contains(&("Lightning Strike", 2)));
+        assert!(removed.contains(&("Play with Fire", 3)));
+        assert!(comparison.pretty_print().contains("Missing staples:"));
+    }
+
+    #[test]
+    fn stock_deck_has_no_differences() {
+        let mut main = vec![1; 4];
+        main.extend([2; 3]);
+        main.extend([5; 3]);
+        main.extend([3; 20]);
+        let deck = Deck::new("Ours".to_string(), 1, main, Vec::new());
+
+        let comparison = mono_red().compare(&deck, &cards());
+        assert!(comparison.difference.added.is_empty());
+        assert!(comparison.difference.removed.is_empty());
+    }
+}
@@ -0,0 +1,284 @@
+use crate::display::{
+    deck::{DeckDisplayRecord, Difference},
+    match_details::MatchDetails,
+    mulligan::Mulligan,
+};
+
+/// A column that can be written when exporting matches to a spreadsheet or file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportColumn {
+    MatchId,
+    DateTime,
+    Format,
+    WonMatch,
+    PlayerName,
+    OpponentName,
+    GameScore,
+    PlayerArchetype,
+    OpponentArchetype,
+    PlayerDeck,
+    OpponentDeck,
+    Game2Sideboarding,
+    Game3Sideboarding,
+    Game1Mulligans,
+    Game2Mulligans,
+    Game3Mulligans,
+    Game1Result,
+    Game2Result,
+    Game3Result,
+}
+
+impl ExportColumn {
+    pub const ALL: [ExportColumn; 19] = [
+        ExportColumn::MatchId,
+        ExportColumn::DateTime,
+        ExportColumn::Format,
+        ExportColumn::WonMatch,
+        ExportColumn::PlayerName,
+        ExportColumn::OpponentName,
+        ExportColumn::GameScore,
+        ExportColumn::PlayerArchetype,
+        ExportColumn::OpponentArchetype,
+        ExportColumn::PlayerDeck,
+        ExportColumn::OpponentDeck,
+        ExportColumn::Game2Sideboarding,
+        ExportColumn::Game3Sideboarding,
+        ExportColumn::Game1Mulligans,
+        ExportColumn::Game2Mulligans,
+        ExportColumn::Game3Mulligans,
+        ExportColumn::Game1Result,
+        ExportColumn::Game2Result,
+        ExportColumn::Game3Result,
+    ];
+    /// The columns of the original Google Sheets export, in order.
+    pub const DEFAULT: [ExportColumn; 16] = [
+        ExportColumn::MatchId,
+        ExportColumn::DateTime,
+        ExportColumn::WonMatch,
+        ExportColumn::PlayerName,
+        ExportColumn::OpponentName,
+        ExportColumn::GameScore,
+        ExportColumn::PlayerDeck,
+        ExportColumn::OpponentDeck,
+        ExportColumn::Game2Sideboarding,
+        ExportColumn::Game3Sideboarding,
+        ExportColumn::Game1Mulligans,
+        ExportColumn::Game2Mulligans,
+        ExportColumn::Game3Mulligans,
+        ExportColumn::Game1Result,
+        ExportColumn::Game2Result,
+        ExportColumn::Game3Result,
+    ];
+
+    /// Stable identifier used in column mappings.
+    pub fn key(self) -> &'static str {
+        match self {
+            ExportColumn::MatchId => "match_id",
+            ExportColumn::DateTime => "date_time",
+            ExportColumn::Format => "format",
+            ExportColumn::WonMatch => "won_match",
+            ExportColumn::PlayerName => "player_name",
+            ExportColumn::OpponentName => "opponent_name",
+            ExportColumn::GameScore => "game_score",
+            ExportColumn::PlayerArchetype => "player_archetype",
+            ExportColumn::OpponentArchetype => "opponent_archetype",
+            ExportColumn::PlayerDeck => "player_deck",
+            ExportColumn::OpponentDeck => "opponent_deck",
+            ExportColumn::Game2Sideboarding => "game_2_sideboarding",
+            ExportColumn::Game3Sideboarding => "game_3_sideboarding",
+            ExportColumn::Game1Mulligans => "game_1_mulligans",
+            ExportColumn::Game2Mulligans => "game_2_mulligans",
+            ExportColumn::Game3Mulligans => "game_3_mulligans",
+            ExportColumn::Game1Result => "game_1_result",
+            ExportColumn::Game2Result => "game_2_result",
+            ExportColumn::Game3Result => "game_3_result",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|column| column.key() == key)
+    }
+
+    /// Header row label.
+    pub fn header(self) -> &'static str {
+        match self {
+            ExportColumn::MatchId => "Match ID",
+            ExportColumn::DateTime => "Date/Time",
+            ExportColumn::Format => "Format",
+            ExportColumn::WonMatch => "Won Match",
+            ExportColumn::PlayerName => "Player Name",
+            ExportColumn::OpponentName => "Opponent Name",
+            ExportColumn::GameScore => "Game Score",
+            ExportColumn::PlayerArchetype => "Player Archetype",
+            ExportColumn::OpponentArchetype => "Opponent Archetype",
+            ExportColumn::PlayerDeck => "Player Deck",
+            ExportColumn::OpponentDeck => "Opponent Deck",
+            ExportColumn::Game2Sideboarding => "Game 2 Sideboarding",
+            ExportColumn::Game3Sideboarding => "Game 3 Sideboarding",
+            ExportColumn::Game1Mulligans => "Game 1 Mulligans",
+            ExportColumn::Game2Mulligans => "Game 2 Mulligans",
+            ExportColumn::Game3Mulligans => "Game 3 Mulligans",
+            ExportColumn::Game1Result => "Game 1 Result",
+            ExportColumn::Game2Result => "Game 2 Result",
+            ExportColumn::Game3Result => "Game 3 Result",
+        }
+    }
+
+    /// The cell value of this column for a match.
+    pub fn value(self, md: &MatchDetails) -> String {
+        match self {
+            ExportColumn::MatchId => md.id.clone(),
+            ExportColumn::DateTime => md.created_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            ExportColumn::Format => md.format.clone().unwrap_or_default(),
+            ExportColumn::WonMatch => if md.did_controller_win { "TRUE" } else { "FALSE" }.to_string(),
+            ExportColumn::PlayerName => md.controller_player_name.clone(),
+            ExportColumn::OpponentName => md.opponent_player_name.clone(),
+            ExportColumn::GameScore => {
+                let wins = md
+                    .game_results
+                    .iter()
+                    .filter(|g| g.winning_player == md.controller_player_name)
+                    .count();
+                format!("{wins}-{}", md.game_results.len() - wins)
+            }
+            ExportColumn::PlayerArchetype => md.controller_archetype.clone().unwrap_or_default(),
+            ExportColumn::OpponentArchetype => md.opponent_archetype.clone().unwrap_or_default(),
+            ExportColumn::PlayerDeck => md
+                .primary_decklist
+                .as_ref()
+                .map_or_else(|| "Player Deck Not Found".to_string(), DeckDisplayRecord::pretty_print),
+            ExportColumn::OpponentDeck => md
+                .opponent_deck
+                .as_ref()
+                .map_or_else(|| "Unknown".to_string(), DeckDisplayRecord::pretty_print),
+            ExportColumn::Game2Sideboarding => {
+                sideboarding(md, 0).unwrap_or_else(|| "No Game 2 Sideboarding".to_string())
+            }
+            ExportColumn::Game3Sideboarding => {
+                sideboarding(md, 1).unwrap_or_else(|| "No Game 3 Sideboarding".to_string())
+            }
+            ExportColumn::Game1Mulligans => mulligans(md, 1),
+            ExportColumn::Game2Mulligans => mulligans(md, 2),
+            ExportColumn::Game3Mulligans => mulligans(md, 3),
+            ExportColumn::Game1Result => game_result(md, 1),
+            ExportColumn::Game2Result => game_result(md, 2),
+            ExportColumn::Game3Result => game_result(md, 3),
+        }
+    }
+}
+
+fn sideboarding(md: &MatchDetails, index: usize) -> Option<String> {
+    md.differences
+        .as_ref()
+        .and_then(|ds| ds.get(index).map(Difference::pretty_print))
+}
+
+fn mulligans(md: &MatchDetails, game_number: i32) -> String {
+    md.mulligans
+        .iter()
+        .filter(|m| m.game_number == game_number)
+        .map(Mulligan::pretty_print)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn game_result(md: &MatchDetails, game_number: i32) -> String {
+    md.game_results
+        .iter()
+        .find(|game| game.game_number == game_number)
+        .map(|game| {
+            let result = if game.winning_player == md.controller_player_name {
+                "W"
+            } else {
+                "L"
+            };
+            format!("Game {game_number}: {result}")
+        })
+        .unwrap_or_default()
+}
+
+/// Parse a column mapping. An empty mapping means [`ExportColumn::DEFAULT`]. Rows are keyed on
+/// the match id, so [`ExportColumn::MatchId`] is always the first column.
+///
+/// # Errors
+/// Returns the first key that doesn't name a column.
+pub fn parse_columns<S: AsRef<str>>(keys: &[S]) -> Result<Vec<ExportColumn>, String> {
+    if keys.is_empty() {
+        return Ok(ExportColumn::DEFAULT.to_vec());
+    }
+
+    let mut columns = vec![ExportColumn::MatchId];
+    for key in keys {
+        let column = ExportColumn::from_key(key.as_ref()).ok_or_else(|| key.as_ref().to_string())?;
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+    Ok(columns)
+}
+
+/// The header row for `columns`.
+pub fn export_headers(columns: &[ExportColumn]) -> Vec<String> {
+    columns.iter().map(|column| column.header().to_string()).collect()
+}
+
+/// One exported row for a match.
+pub fn export_row(md: &MatchDetails, columns: &[ExportColumn]) -> Vec<String> {
+    columns.iter().map(|column| column.value(md)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::game::GameResultDisplay;
+
+    #[test]
+    fn column_keys_round_trip() {
+        for column in ExportColumn::ALL {
+            assert_eq!(ExportColumn::from_key(column.key()), Some(column));
+        }
+        assert_eq!(ExportColumn::from_key("nope"), None);
+    }
+
+    #[test]
+    fn parse_columns_keys_rows_on_match_id() {
+        assert_eq!(
+            parse_columns::<&str>(&[]).expect("default columns"),
+            ExportColumn::DEFAULT.to_vec()
+        );
+        assert_eq!(
+            parse_columns(&["format", "match_id", "format"]).expect("known columns"),
+            vec![ExportColumn::MatchId, ExportColumn::Format]
+        );
+        assert_eq!(parse_columns(&["format", "bogus"]), Err("bogus".to_string()));
+    }
+
+    #[test]
+    fn export_row_formats_scores_and_results() {
+        let md = MatchDetails {
+            id: "m1".to_string(),
+            controller_player_name: "me".to_string(),
+            did_controller_win: true,
+            game_results: vec![
+                GameResultDisplay {
+                    game_number: 1,
+                    winning_player: "me".to_string(),
+                },
+                GameResultDisplay {
+                    game_number: 2,
+                    winning_player: "them".to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+        let columns = [
+            ExportColumn::MatchId,
+            ExportColumn::WonMatch,
+            ExportColumn::GameScore,
+            ExportColumn::Game2Result,
+            ExportColumn::Game3Result,
+        ];
+
+        assert_eq!(export_row(&md, &columns), vec!["m1", "TRUE", "1-1", "Game 2: L", ""]);
+    }
+}
@@ -1,12 +1,14 @@
 use chrono::{DateTime, Utc};
 
 use crate::{
+    cards::CardsDatabase,
     display::{
-        deck::{DeckDisplayRecord, Difference},
+        classification::ClassificationEvidence,
+        deck::{DeckDisplayRecord, Difference, StockComparison},
         game::GameResultDisplay,
         mulligan::Mulligan,
     },
-    models::Deck,
+    models::{ArenaId, Deck, MatchData},
     player_log::event_log::GameEventLog,
 };
 
@@ -28,4 +30,94 @@ pub struct MatchDetails {
     pub event_logs: Vec<GameEventLog>,
     pub controller_archetype: Option<String>,
     pub opponent_archetype: Option<String>,
+    pub controller_evidence: Option<ClassificationEvidence>,
+    pub opponent_evidence: Option<ClassificationEvidence>,
+    /// How our deck differs from its archetype's stock list, when both are known.
+    pub stock_comparison: Option<StockComparison>,
+}
+
+impl MatchDetails {
+    /// Builds the display form of a match from its raw data, e.g. as returned by
+    /// `GetMatchData`. Archetypes aren't part of `MatchData` and are left unset, as is the
+    /// stock list comparison.
+    pub fn from_match_data(data: &MatchData, cards: &CardsDatabase) -> Self {
+        let mtga_match = &data.mtga_match;
+        let controller_seat_id = mtga_match.controller_seat_id();
+
+        let differences: Vec<Difference> = data
+            .decks
+            .windows(2)
+            .filter_map(|pair| match pair {
+                [prev, next] => Some(Difference::diff(prev, next, cards)),
+                _ => None,
+            })
+            .collect();
+
+        let mut mulligans: Vec<Mulligan> = data
+            .mulligans
+            .iter()
+            .map(|mulligan| Mulligan::from_model(mulligan, cards))
+            .collect();
+        mulligans.sort();
+
+        let game_results = data
+            .results
+            .iter()
+            .filter(|mr| mr.game_number() > 0)
+            .map(|mr| {
+                GameResultDisplay::from_match_result(
+                    mr,
+                    controller_seat_id,
+                    mtga_match.controller_player_name(),
+                    mtga_match.opponent_player_name(),
+                )
+            })
+            .collect();
+
+        let opponent_cards: Vec<i32> = data.opponent_deck.cards.iter().map(ArenaId::inner).collect();
+        let opponent_deck = (!opponent_cards.is_empty()).then(|| {
+            let deck = Deck::new("Opponent_deck".to_string(), 0, opponent_cards, Vec::new());
+            DeckDisplayRecord::from_decklist(&deck, cards)
+        });
+
+        Self {
+            id: mtga_match.id().to_string(),
+            did_controller_win: data
+                .results
+                .iter()
+                .any(|r| r.is_match_result() && r.is_winner(controller_seat_id)),
+            controller_seat_id,
+            controller_player_name: mtga_match.controller_player_name().to_string(),
+            opponent_player_name: mtga_match.opponent_player_name().to_string(),
+            created_at: mtga_match.created_at(),
+            format: mtga_match.format().map(ToString::to_string),
+            primary_decklist: data
+                .decks
+                .first()
+                .map(|deck| DeckDisplayRecord::from_decklist(deck, cards)),
+            differences: (!differences.is_empty()).then_some(differences),
+            game_results,
+            decklists: data.decks.clone(),
+            opponent_deck,
+            mulligans,
+            event_logs: data.event_logs.clone(),
+            controller_archetype: None,
+            opponent_archetype: None,
+            controller_evidence: None,
+            opponent_evidence: None,
+            stock_comparison: None,
+        }
+    }
+
+    /// Set one side's archetype and the evidence for it. `side` is `controller` or `opponent`;
+    /// anything else is ignored.
+    pub fn set_classification(&mut self, side: &str, archetype_name: String, evidence: Option<ClassificationEvidence>) {
+        let (archetype, slot) = match side {
+            "controller" => (&mut self.controller_archetype, &mut self.controller_evidence),
+            "opponent" => (&mut self.opponent_archetype, &mut self.opponent_evidence),
+            _ => return,
+        };
+        *archetype = Some(archetype_name);
+        *slot = evidence;
+    }
 }
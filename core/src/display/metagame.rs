@@ -0,0 +1,295 @@
+#![expect(clippy::cast_precision_loss)]
+
+use std::collections::BTreeMap;
+
+use chrono::{Days, NaiveDate};
+
+/// Share of the tournament metagame below which an archetype is left out of trend reports.
+pub const MIN_TREND_SHARE: f64 = 1.0;
+
+/// Drop in share, in percentage points, between the last two periods that marks an
+/// archetype as declining.
+pub const DECLINE_POINTS: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrendInterval {
+    Daily,
+    #[default]
+    Weekly,
+}
+
+impl TrendInterval {
+    pub const ALL: [TrendInterval; 2] = [TrendInterval::Daily, TrendInterval::Weekly];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TrendInterval::Daily => "Daily",
+            TrendInterval::Weekly => "Weekly",
+        }
+    }
+
+    /// The `date_trunc` field periods are bucketed by.
+    pub fn key(self) -> &'static str {
+        match self {
+            TrendInterval::Daily => "day",
+            TrendInterval::Weekly => "week",
+        }
+    }
+
+    /// Distance between the first days of consecutive periods.
+    pub fn step(self) -> Days {
+        match self {
+            TrendInterval::Daily => Days::new(1),
+            TrendInterval::Weekly => Days::new(7),
+        }
+    }
+}
+
+impl std::fmt::Display for TrendInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Number of tournament decks of one archetype in one period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchetypeCount {
+    /// First day of the period.
+    pub period: NaiveDate,
+    pub archetype: String,
+    pub decks: i64,
+}
+
+/// One archetype's share of the metagame over time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArchetypeTrend {
+    pub archetype: String,
+    /// Share per period in percent, aligned with [`MetagameTrends::periods`].
+    pub shares: Vec<f64>,
+}
+
+impl ArchetypeTrend {
+    /// Share in the most recent period.
+    pub fn current(&self) -> f64 {
+        self.shares.last().copied().unwrap_or_default()
+    }
+
+    /// Change in share between the last two periods, in percentage points.
+    pub fn delta(&self) -> Option<f64> {
+        match self.shares.as_slice() {
+            [.., previous, current] => Some(current - previous),
+            _ => None,
+        }
+    }
+
+    /// Played in the most recent period but in none before it.
+    pub fn is_emerging(&self) -> bool {
+        match self.shares.split_last() {
+            Some((current, earlier)) => *current > 0.0 && !earlier.is_empty() && earlier.iter().all(|s| *s <= 0.0),
+            None => false,
+        }
+    }
+
+    pub fn is_declining(&self) -> bool {
+        self.delta().is_some_and(|d| d <= -DECLINE_POINTS)
+    }
+}
+
+/// Archetype shares of a format's tournament decks, bucketed by day or week.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetagameTrends {
+    pub interval: TrendInterval,
+    /// First day of each period, oldest first.
+    pub periods: Vec<NaiveDate>,
+    /// Decks per period.
+    pub deck_totals: Vec<i64>,
+    /// Archetypes by current share, largest first.
+    pub archetypes: Vec<ArchetypeTrend>,
+}
+
+impl MetagameTrends {
+    /// Build trends from per-period deck counts. Periods run contiguously from the
+    /// first counted period to the last, with periods that have no decks filled in
+    /// as zero. Archetypes that never reach [`MIN_TREND_SHARE`] in any period are
+    /// dropped.
+    pub fn from_counts(interval: TrendInterval, counts: &[ArchetypeCount]) -> Self {
+        let mut periods: Vec<NaiveDate> = Vec::new();
+        if let (Some(first), Some(last)) = (
+            counts.iter().map(|c| c.period).min(),
+            counts.iter().map(|c| c.period).max(),
+        ) {
+            let mut period = first;
+            while period <= last {
+                periods.push(period);
+                let Some(next) = period.checked_add_days(interval.step()) else {
+                    break;
+                };
+                period = next;
+            }
+        }
+
+        let mut deck_totals = vec![0i64; periods.len()];
+        let mut by_archetype: BTreeMap<&str, Vec<i64>> = BTreeMap::new();
+        for count in counts {
+            let Ok(idx) = periods.binary_search(&count.period) else {
+                continue;
+            };
+            deck_totals[idx] += count.decks;
+            by_archetype
+                .entry(&count.archetype)
+                .or_insert_with(|| vec![0; periods.len()])[idx] += count.decks;
+        }
+
+        let mut archetypes: Vec<ArchetypeTrend> = by_archetype
+            .into_iter()
+            .map(|(archetype, decks)| ArchetypeTrend {
+                archetype: archetype.to_string(),
+                shares: decks
+                    .iter()
+                    .zip(&deck_totals)
+                    .map(|(&n, &total)| {
+                        if total > 0 {
+                            n as f64 / total as f64 * 100.0
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect(),
+            })
+            .filter(|trend| trend.shares.iter().any(|s| *s >= MIN_TREND_SHARE))
+            .collect();
+        archetypes.sort_by(|a, b| b.current().total_cmp(&a.current()));
+
+        Self {
+            interval,
+            periods,
+            deck_totals,
+            archetypes,
+        }
+    }
+
+    /// Archetypes seen for the first time in the most recent period, largest first.
+    pub fn emerging(&self) -> impl Iterator<Item = &ArchetypeTrend> {
+        self.archetypes.iter().filter(|a| a.is_emerging())
+    }
+
+    /// Archetypes whose share fell by at least [`DECLINE_POINTS`], steepest drop first.
+    pub fn declining(&self) -> Vec<&ArchetypeTrend> {
+        let mut declining: Vec<&ArchetypeTrend> = self.archetypes.iter().filter(|a| a.is_declining()).collect();
+        declining.sort_by(|a, b| a.delta().unwrap_or_default().total_cmp(&b.delta().unwrap_or_default()));
+        declining
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(day: u32, archetype: &str, decks: i64) -> ArchetypeCount {
+        ArchetypeCount {
+            period: NaiveDate::from_ymd_opt(2026, 10, day).expect("valid date"),
+            archetype: archetype.to_string(),
+            decks,
+        }
+    }
+
+    fn trend<'a>(trends: &'a MetagameTrends, name: &str) -> &'a ArchetypeTrend {
+        trends
+            .archetypes
+            .iter()
+            .find(|a| a.archetype == name)
+            .expect("archetype should be present")
+    }
+
+    #[test]
+    fn shares_are_per_period() {
+        let trends = MetagameTrends::from_counts(
+            TrendInterval::Weekly,
+            &[
+                count(5, "Domain", 3),
+                count(5, "Gruul", 1),
+                count(12, "Domain", 1),
+                count(12, "Gruul", 1),
+            ],
+        );
+        assert_eq!(trends.periods.len(), 2);
+        assert_eq!(trends.deck_totals, vec![4, 2]);
+        let domain = trend(&trends, "Domain");
+        assert!((domain.shares[0] - 75.0).abs() < 1e-9);
+        assert!((domain.current() - 50.0).abs() < 1e-9);
+        assert!((domain.delta().expect("two periods") + 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn missing_periods_count_as_zero_share() {
+        let trends = MetagameTrends::from_counts(
+            TrendInterval::Daily,
+            &[count(5, "Domain", 2), count(6, "Domain", 1), count(6, "Rakdos", 1)],
+        );
+        let rakdos = trend(&trends, "Rakdos");
+        assert_eq!(rakdos.shares.len(), 2);
+        assert!(rakdos.shares[0].abs() < 1e-9);
+        assert!(rakdos.is_emerging());
+        assert!(!trend(&trends, "Domain").is_emerging());
+        assert_eq!(trends.emerging().count(), 1);
+    }
+
+    #[test]
+    fn gaps_between_periods_are_zero_filled() {
+        let trends = MetagameTrends::from_counts(
+            TrendInterval::Weekly,
+            &[count(5, "Domain", 4), count(19, "Domain", 1), count(19, "Gruul", 1)],
+        );
+        let days: Vec<u32> = trends.periods.iter().map(chrono::Datelike::day).collect();
+        assert_eq!(days, vec![5, 12, 19]);
+        assert_eq!(trends.deck_totals, vec![4, 0, 2]);
+        let domain = trend(&trends, "Domain");
+        assert_eq!(domain.shares.len(), 3);
+        assert!(domain.shares[1].abs() < 1e-9);
+        assert!((domain.current() - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn single_period_has_no_delta_or_emerging() {
+        let trends = MetagameTrends::from_counts(TrendInterval::Weekly, &[count(5, "Domain", 2)]);
+        let domain = trend(&trends, "Domain");
+        assert!(domain.delta().is_none());
+        assert!(!domain.is_emerging());
+        assert!(!domain.is_declining());
+    }
+
+    #[test]
+    fn declining_sorted_by_steepest_drop() {
+        let trends = MetagameTrends::from_counts(
+            TrendInterval::Weekly,
+            &[
+                count(5, "Domain", 5),
+                count(5, "Gruul", 3),
+                count(5, "Azorius", 2),
+                count(12, "Domain", 2),
+                count(12, "Gruul", 2),
+                count(12, "Azorius", 6),
+            ],
+        );
+        let declining: Vec<&str> = trends.declining().iter().map(|a| a.archetype.as_str()).collect();
+        assert_eq!(declining, vec!["Domain", "Gruul"]);
+        assert_eq!(trends.archetypes[0].archetype, "Azorius");
+    }
+
+    #[test]
+    fn rare_archetypes_are_dropped() {
+        let mut counts = vec![count(5, "Fringe", 1)];
+        counts.push(count(5, "Domain", 200));
+        let trends = MetagameTrends::from_counts(TrendInterval::Weekly, &counts);
+        assert_eq!(trends.archetypes.len(), 1);
+        assert_eq!(trends.deck_totals, vec![201]);
+    }
+
+    #[test]
+    fn empty_counts() {
+        let trends = MetagameTrends::from_counts(TrendInterval::Daily, &[]);
+        assert!(trends.periods.is_empty());
+        assert!(trends.archetypes.is_empty());
+        assert!(trends.declining().is_empty());
+    }
+}
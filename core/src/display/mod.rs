@@ -1,9 +1,12 @@
 pub mod card;
+pub mod classification;
 pub mod deck;
 pub mod draft;
 pub mod event_log;
+pub mod export;
 pub mod game;
 pub mod match_details;
 pub mod match_summary;
+pub mod metagame;
 pub mod mulligan;
 pub mod stats;
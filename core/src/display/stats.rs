@@ -28,6 +28,16 @@ impl TimeWindow {
         }
     }
 
+    /// Stable identifier used when a window is persisted (e.g. precomputed aggregates).
+    pub fn key(self) -> &'static str {
+        match self {
+            TimeWindow::Last24Hours => "24h",
+            TimeWindow::Last7Days => "7d",
+            TimeWindow::Last30Days => "30d",
+            TimeWindow::AllTime => "all",
+        }
+    }
+
     pub fn cutoff(self) -> Option<DateTime<Utc>> {
         let now = Utc::now();
         match self {
@@ -113,6 +123,201 @@ impl OpponentRecord {
     }
 }
 
+/// What the controller's side of an [`ArchetypeMatchup`] is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchupGrouping {
+    /// The controller's classified archetype.
+    #[default]
+    Archetype,
+    /// The controller's deck, identified as in [`DeckStats`].
+    Deck,
+}
+
+/// Match record of one archetype against another, from the controller's point of view.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArchetypeMatchup {
+    /// The controller's archetype, or the deck's name when grouped by deck.
+    pub archetype: String,
+    /// Fingerprint of the controller's deck when grouped by deck.
+    pub deck_id: Option<String>,
+    pub opponent_archetype: String,
+    pub matches: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub game1_wins: i64,
+    pub game1_losses: i64,
+    /// Games 2 and 3, after sideboarding.
+    pub post_board_wins: i64,
+    pub post_board_losses: i64,
+}
+
+impl ArchetypeMatchup {
+    pub fn win_rate(&self) -> Option<f64> {
+        let total = self.wins + self.losses;
+        (total > 0).then(|| self.wins as f64 / total as f64 * 100.0)
+    }
+
+    /// 95% Wilson score interval for the win rate, in percent.
+    pub fn confidence_interval(&self) -> Option<(f64, f64)> {
+        wilson_interval(self.wins, self.wins + self.losses, Z_95)
+    }
+
+    pub fn game_wins(&self) -> i64 {
+        self.game1_wins + self.post_board_wins
+    }
+
+    pub fn game_losses(&self) -> i64 {
+        self.game1_losses + self.post_board_losses
+    }
+
+    pub fn games(&self) -> i64 {
+        self.game_wins() + self.game_losses()
+    }
+
+    pub fn game_win_rate(&self) -> Option<f64> {
+        percentage(self.game_wins(), self.games())
+    }
+
+    pub fn game1_win_rate(&self) -> Option<f64> {
+        percentage(self.game1_wins, self.game1_wins + self.game1_losses)
+    }
+
+    /// 95% Wilson score interval for the game 1 win rate, in percent.
+    pub fn game1_confidence_interval(&self) -> Option<(f64, f64)> {
+        wilson_interval(self.game1_wins, self.game1_wins + self.game1_losses, Z_95)
+    }
+
+    pub fn post_board_win_rate(&self) -> Option<f64> {
+        percentage(self.post_board_wins, self.post_board_wins + self.post_board_losses)
+    }
+
+    /// 95% Wilson score interval for the post-sideboard win rate, in percent.
+    pub fn post_board_confidence_interval(&self) -> Option<(f64, f64)> {
+        wilson_interval(
+            self.post_board_wins,
+            self.post_board_wins + self.post_board_losses,
+            Z_95,
+        )
+    }
+}
+
+/// Match record of one Limited deck label within a set, from the controller's point of view.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LimitedRecord {
+    /// The set the event was played with, or its format for events without one (e.g. `cube`).
+    pub set_code: String,
+    /// The controller's deck label, e.g. `UB Ninjas` or `WR splash g`.
+    pub label: String,
+    pub matches: i64,
+    pub wins: i64,
+    pub losses: i64,
+}
+
+impl LimitedRecord {
+    pub fn win_rate(&self) -> Option<f64> {
+        let total = self.wins + self.losses;
+        (total > 0).then(|| self.wins as f64 / total as f64 * 100.0)
+    }
+}
+
+/// Results of one of the controller's decks. Arena doesn't log deck names or ids, so a deck is
+/// identified by its game 1 main deck: matches with the same 60 (or 40) cards are the same deck.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeckStats {
+    /// Fingerprint of the sorted game 1 main deck, stable across matches.
+    pub deck_id: String,
+    /// The controller archetype most of the deck's matches were classified as.
+    pub name: Option<String>,
+    /// Most recent match played with the deck.
+    pub latest_match_id: String,
+    pub last_played: DateTime<Utc>,
+    pub matches: i64,
+    pub match_wins: i64,
+    pub match_losses: i64,
+    pub game_wins: i64,
+    pub game_losses: i64,
+    pub game1_wins: i64,
+    pub game1_losses: i64,
+    /// Games 2 and 3, after sideboarding.
+    pub post_board_wins: i64,
+    pub post_board_losses: i64,
+    pub play_wins: i64,
+    pub play_losses: i64,
+    pub draw_wins: i64,
+    pub draw_losses: i64,
+    /// Games with a recorded opening hand decision, and how many of them kept fewer than 7.
+    pub kept_hands: i64,
+    pub mulligans: i64,
+    /// Average number of turns of the games with an event log.
+    pub average_turns: Option<f64>,
+}
+
+impl DeckStats {
+    /// `name`, or a short form of the fingerprint for decks that were never classified.
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("Deck {}", &self.deck_id[..self.deck_id.len().min(8)]),
+        }
+    }
+
+    pub fn games(&self) -> i64 {
+        self.game_wins + self.game_losses
+    }
+
+    pub fn match_win_rate(&self) -> Option<f64> {
+        percentage(self.match_wins, self.match_wins + self.match_losses)
+    }
+
+    pub fn game_win_rate(&self) -> Option<f64> {
+        percentage(self.game_wins, self.games())
+    }
+
+    pub fn game1_win_rate(&self) -> Option<f64> {
+        percentage(self.game1_wins, self.game1_wins + self.game1_losses)
+    }
+
+    pub fn post_board_win_rate(&self) -> Option<f64> {
+        percentage(self.post_board_wins, self.post_board_wins + self.post_board_losses)
+    }
+
+    pub fn play_win_rate(&self) -> Option<f64> {
+        percentage(self.play_wins, self.play_wins + self.play_losses)
+    }
+
+    pub fn draw_win_rate(&self) -> Option<f64> {
+        percentage(self.draw_wins, self.draw_wins + self.draw_losses)
+    }
+
+    /// Share of recorded opening hands that were mulliganed.
+    pub fn mulligan_rate(&self) -> Option<f64> {
+        percentage(self.mulligans, self.kept_hands)
+    }
+}
+
+fn percentage(part: i64, total: i64) -> Option<f64> {
+    (total > 0).then(|| part as f64 / total as f64 * 100.0)
+}
+
+/// z-score for a two-sided 95% confidence level.
+pub const Z_95: f64 = 1.96;
+
+/// Wilson score interval for `wins` successes out of `total` trials, returned as
+/// `(low, high)` percentages. Unlike the normal approximation it stays within
+/// 0–100% and behaves sensibly for small samples and extreme win rates.
+pub fn wilson_interval(wins: i64, total: i64, z: f64) -> Option<(f64, f64)> {
+    if total <= 0 {
+        return None;
+    }
+    let n = total as f64;
+    let p = wins as f64 / n;
+    let z2 = z * z;
+    let denominator = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denominator;
+    let margin = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+    Some(((center - margin).max(0.0) * 100.0, (center + margin).min(1.0) * 100.0))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -249,6 +454,132 @@ mod tests {
         assert!(record.win_rate().is_none());
     }
 
+    // -- ArchetypeMatchup -----------------------------------------------------
+
+    #[test]
+    fn archetype_matchup_win_rate() {
+        let matchup = ArchetypeMatchup {
+            archetype: "Mono-Red Aggro".to_string(),
+            opponent_archetype: "Azorius Control".to_string(),
+            matches: 5,
+            wins: 2,
+            losses: 3,
+            ..Default::default()
+        };
+        let rate = matchup.win_rate().expect("should have rate");
+        assert!((rate - 40.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn archetype_matchup_zero_games() {
+        assert!(ArchetypeMatchup::default().win_rate().is_none());
+        assert!(ArchetypeMatchup::default().confidence_interval().is_none());
+        assert!(ArchetypeMatchup::default().game1_confidence_interval().is_none());
+        assert!(ArchetypeMatchup::default().post_board_win_rate().is_none());
+    }
+
+    #[test]
+    fn archetype_matchup_game_splits() {
+        let matchup = ArchetypeMatchup {
+            game1_wins: 1,
+            game1_losses: 3,
+            post_board_wins: 5,
+            post_board_losses: 1,
+            ..Default::default()
+        };
+        assert_eq!(matchup.games(), 10);
+        let rate = matchup.game_win_rate().expect("should have rate");
+        assert!((rate - 60.0).abs() < f64::EPSILON);
+        let rate = matchup.game1_win_rate().expect("should have rate");
+        assert!((rate - 25.0).abs() < f64::EPSILON);
+
+        let (low, high) = matchup.post_board_confidence_interval().expect("should have interval");
+        let rate = matchup.post_board_win_rate().expect("should have rate");
+        assert!(low < rate && rate < high);
+    }
+
+    // -- LimitedRecord --------------------------------------------------------
+
+    #[test]
+    fn limited_record_win_rate() {
+        let record = LimitedRecord {
+            set_code: "BLB".to_string(),
+            label: "WR Mice".to_string(),
+            matches: 8,
+            wins: 6,
+            losses: 2,
+        };
+        let rate = record.win_rate().expect("should have rate");
+        assert!((rate - 75.0).abs() < f64::EPSILON);
+        assert!(LimitedRecord::default().win_rate().is_none());
+    }
+
+    // -- DeckStats ------------------------------------------------------------
+
+    #[test]
+    fn deck_stats_rates() {
+        let stats = DeckStats {
+            game_wins: 5,
+            game_losses: 3,
+            game1_wins: 3,
+            game1_losses: 1,
+            post_board_wins: 2,
+            post_board_losses: 2,
+            kept_hands: 8,
+            mulligans: 2,
+            ..Default::default()
+        };
+        assert_eq!(stats.games(), 8);
+        assert!((stats.game_win_rate().expect("rate") - 62.5).abs() < f64::EPSILON);
+        assert!((stats.game1_win_rate().expect("rate") - 75.0).abs() < f64::EPSILON);
+        assert!((stats.post_board_win_rate().expect("rate") - 50.0).abs() < f64::EPSILON);
+        assert!((stats.mulligan_rate().expect("rate") - 25.0).abs() < f64::EPSILON);
+        assert!(stats.match_win_rate().is_none());
+        assert!(stats.play_win_rate().is_none());
+    }
+
+    #[test]
+    fn deck_stats_display_name() {
+        let mut stats = DeckStats {
+            deck_id: "0123456789abcdef".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(stats.display_name(), "Deck 01234567");
+        stats.name = Some("Mono Red".to_string());
+        assert_eq!(stats.display_name(), "Mono Red");
+    }
+
+    // -- Wilson interval ------------------------------------------------------
+
+    #[test]
+    fn wilson_interval_contains_point_estimate() {
+        let (low, high) = wilson_interval(60, 100, Z_95).expect("should have interval");
+        assert!(low < 60.0 && 60.0 < high);
+        // Reference values for 60/100 at 95%: [50.20%, 69.06%]
+        assert!((low - 50.20).abs() < 0.01);
+        assert!((high - 69.06).abs() < 0.01);
+    }
+
+    #[test]
+    fn wilson_interval_stays_in_bounds_at_extremes() {
+        let (low, high) = wilson_interval(5, 5, Z_95).expect("should have interval");
+        assert!(low > 0.0 && high <= 100.0);
+        let (low, high) = wilson_interval(0, 5, Z_95).expect("should have interval");
+        assert!(low >= 0.0 && high < 100.0);
+    }
+
+    #[test]
+    fn wilson_interval_narrows_with_sample_size() {
+        let (small_low, small_high) = wilson_interval(6, 10, Z_95).expect("should have interval");
+        let (big_low, big_high) = wilson_interval(600, 1000, Z_95).expect("should have interval");
+        assert!(big_high - big_low < small_high - small_low);
+    }
+
+    #[test]
+    fn wilson_interval_empty_sample() {
+        assert!(wilson_interval(0, 0, Z_95).is_none());
+    }
+
     // -- TimeWindow -----------------------------------------------------------
 
     #[test]
@@ -271,4 +602,10 @@ mod tests {
         assert_eq!(TimeWindow::Last30Days.label(), "Last 30 Days");
         assert_eq!(TimeWindow::AllTime.label(), "All Time");
     }
+
+    #[test]
+    fn time_window_keys_are_unique() {
+        let keys: std::collections::HashSet<_> = TimeWindow::ALL.iter().map(|tw| tw.key()).collect();
+        assert_eq!(keys.len(), TimeWindow::ALL.len());
+    }
 }
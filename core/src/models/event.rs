@@ -0,0 +1,298 @@
+//! What an MTGA event id says about the queue a match was played in.
+//!
+//! Event ids are mapped by an ordered table of regex rules, [`EventRules`]. The built-in
+//! table is `data/event_rules.json`; bump its `version` when the file's shape changes. A
+//! newer table can be loaded from disk with [`EventRules::from_path`] and made the process-wide
+//! [`EventRules::active`] table with [`EventRules::install`].
+
+use std::{
+    path::Path,
+    sync::{LazyLock, OnceLock},
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{Error, ParseError};
+
+/// Newest rules file version this build reads.
+pub const EVENT_RULES_VERSION: u32 = 1;
+
+const BUILTIN_RULES: &str = include_str!("../../data/event_rules.json");
+
+static BUILTIN: LazyLock<EventRules> =
+    LazyLock::new(|| EventRules::from_json(BUILTIN_RULES).expect("invalid built-in event rules"));
+
+static INSTALLED: OnceLock<EventRules> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    Constructed,
+    Limited,
+}
+
+/// A parsed MTGA event id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventDescriptor {
+    pub event_id: String,
+    /// Format name, e.g. `standard`, `historic_brawl` or `limited`.
+    pub format: String,
+    /// 1 or 3.
+    pub best_of: u8,
+    pub ranked: bool,
+    pub kind: EventKind,
+    /// The set a Limited event is played with.
+    pub set_code: Option<String>,
+}
+
+impl EventDescriptor {
+    pub fn is_limited(&self) -> bool {
+        self.kind == EventKind::Limited
+    }
+
+    /// Stable label of the queue, e.g. `standard ranked bo3` or `limited ranked bo1 MKM`.
+    pub fn queue(&self) -> String {
+        let ranked = if self.ranked { "ranked" } else { "unranked" };
+        match &self.set_code {
+            Some(set) => format!("{} {ranked} bo{} {set}", self.format, self.best_of),
+            None => format!("{} {ranked} bo{}", self.format, self.best_of),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    version: u32,
+    rules: Vec<RuleEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleEntry {
+    pattern: String,
+    format: String,
+    best_of: u8,
+    ranked: bool,
+    kind: EventKind,
+}
+
+#[derive(Debug, Clone)]
+struct EventRule {
+    /// A `set` capture group, if present, gives the set code.
+    pattern: Regex,
+    format: String,
+    best_of: u8,
+    ranked: bool,
+    kind: EventKind,
+}
+
+/// Ordered event id rules. The first rule whose pattern matches describes the event.
+#[derive(Debug, Clone)]
+pub struct EventRules {
+    version: u32,
+    rules: Vec<EventRule>,
+}
+
+impl EventRules {
+    /// The rules shipped with this build.
+    pub fn builtin() -> &'static Self {
+        &BUILTIN
+    }
+
+    /// The rules passed to [`install`](Self::install), or the built-in rules if none were.
+    pub fn active() -> &'static Self {
+        INSTALLED.get().unwrap_or(&BUILTIN)
+    }
+
+    /// Make `rules` the [`active`](Self::active) rules for the rest of the process. Returns
+    /// `false`, leaving the active rules unchanged, if rules were already installed.
+    pub fn install(rules: Self) -> bool {
+        INSTALLED.set(rules).is_ok()
+    }
+
+    /// Read and parse a rules file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or [`from_json`](Self::from_json) rejects it.
+    pub fn from_path(path: &Path) -> crate::Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse a rules file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is malformed, the file is newer than
+    /// [`EVENT_RULES_VERSION`], or a pattern isn't a valid regex.
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        let file: RulesFile = serde_json::from_str(json)?;
+        if file.version > EVENT_RULES_VERSION {
+            return Err(Error::Parse(ParseError::Error(format!(
+                "event rules version {} is newer than supported version {EVENT_RULES_VERSION}",
+                file.version
+            ))));
+        }
+        let rules = file
+            .rules
+            .into_iter()
+            .map(|entry| {
+                let pattern = Regex::new(&entry.pattern).map_err(|e| {
+                    Error::Parse(ParseError::Error(format!(
+                        "invalid event rule pattern {:?}: {e}",
+                        entry.pattern
+                    )))
+                })?;
+                Ok(EventRule {
+                    pattern,
+                    format: entry.format,
+                    best_of: entry.best_of,
+                    ranked: entry.ranked,
+                    kind: entry.kind,
+                })
+            })
+            .collect::<crate::Result<_>>()?;
+        Ok(Self {
+            version: file.version,
+            rules,
+        })
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Describe an event id, or `None` if no rule matches it.
+    pub fn describe(&self, event_id: &str) -> Option<EventDescriptor> {
+        self.rules.iter().find_map(|rule| {
+            let captures = rule.pattern.captures(event_id)?;
+            Some(EventDescriptor {
+                event_id: event_id.to_string(),
+                format: rule.format.clone(),
+                best_of: rule.best_of,
+                ranked: rule.ranked,
+                kind: rule.kind,
+                set_code: captures.name("set").map(|m| m.as_str().to_string()),
+            })
+        })
+    }
+
+    /// Whether `format`'s events are constructed or Limited, or `None` if no rule maps to it.
+    pub fn format_kind(&self, format: &str) -> Option<EventKind> {
+        self.rules
+            .iter()
+            .find(|rule| rule.format.eq_ignore_ascii_case(format))
+            .map(|rule| rule.kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe(event_id: &str) -> Option<EventDescriptor> {
+        EventRules::builtin().describe(event_id)
+    }
+
+    #[test]
+    fn builtin_rules_parse() {
+        assert_eq!(EventRules::builtin().version(), EVENT_RULES_VERSION);
+    }
+
+    #[test]
+    fn describes_ranked_constructed_queues() {
+        let bo1 = describe("Ladder").expect("standard ladder");
+        assert_eq!(bo1.format, "standard");
+        assert_eq!(bo1.best_of, 1);
+        assert!(bo1.ranked);
+        assert_eq!(bo1.kind, EventKind::Constructed);
+
+        let bo3 = describe("Traditional_Explorer_Ladder").expect("explorer ladder");
+        assert_eq!(bo3.format, "explorer");
+        assert_eq!(bo3.best_of, 3);
+        assert_eq!(bo3.queue(), "explorer ranked bo3");
+
+        assert_eq!(describe("Alchemy_Ladder").expect("alchemy").format, "alchemy");
+        assert_eq!(describe("Timeless_Play").expect("timeless").format, "timeless");
+        assert!(!describe("Play").expect("unranked standard").ranked);
+    }
+
+    #[test]
+    fn describes_brawl() {
+        assert_eq!(describe("Play_Brawl").expect("brawl").format, "brawl");
+        assert_eq!(
+            describe("Play_Brawl_Historic").expect("historic brawl").format,
+            "historic_brawl"
+        );
+    }
+
+    #[test]
+    fn describes_limited_with_set_code() {
+        let draft = describe("PremierDraft_MKM_20240206").expect("premier draft");
+        assert!(draft.is_limited());
+        assert_eq!(draft.set_code.as_deref(), Some("MKM"));
+        assert_eq!(draft.queue(), "limited ranked bo1 MKM");
+
+        let trad = describe("TradSealed_OTJ").expect("traditional sealed");
+        assert_eq!(trad.best_of, 3);
+        assert_eq!(trad.set_code.as_deref(), Some("OTJ"));
+
+        let cube = describe("ArenaCube_Draft_20240110").expect("cube");
+        assert_eq!(cube.format, "cube");
+        assert!(cube.set_code.is_none());
+    }
+
+    #[test]
+    fn unknown_events_are_undescribed() {
+        assert!(describe("SomeRandomEvent").is_none());
+        assert!(describe("Ladder_Extra").is_none());
+    }
+
+    #[test]
+    fn match_exposes_its_event() {
+        let mtga_match =
+            crate::models::MTGAMatch::new("id", 1, "me", "them").with_format(Some("Traditional_Ladder".to_string()));
+        let event = mtga_match.event().expect("described");
+        assert_eq!(event.queue(), "standard ranked bo3");
+        assert!(crate::models::MTGAMatch::new("id", 1, "me", "them").event().is_none());
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = EventRules::from_json(
+            r#"{"version": 1, "rules": [
+                {"pattern": "^Special_", "format": "event", "best_of": 1, "ranked": false, "kind": "constructed"},
+                {"pattern": "Ladder", "format": "standard", "best_of": 1, "ranked": true, "kind": "constructed"}
+            ]}"#,
+        )
+        .expect("valid rules");
+        assert_eq!(rules.describe("Special_Ladder").expect("special").format, "event");
+        assert_eq!(rules.format_kind("Standard"), Some(EventKind::Constructed));
+        assert!(rules.format_kind("pioneer").is_none());
+        assert_eq!(EventRules::builtin().format_kind("cube"), Some(EventKind::Limited));
+    }
+
+    #[test]
+    fn loads_rules_from_a_file() {
+        let path = std::env::temp_dir().join(format!("event_rules_{}.json", std::process::id()));
+        std::fs::write(&path, BUILTIN_RULES).expect("write rules");
+        let rules = EventRules::from_path(&path).expect("valid rules file");
+        std::fs::remove_file(&path).expect("remove rules");
+        assert_eq!(rules.version(), EVENT_RULES_VERSION);
+        assert_eq!(rules.describe("Ladder").expect("standard ladder").format, "standard");
+        assert!(EventRules::from_path(&path).is_err());
+    }
+
+    #[test]
+    fn rejects_newer_versions_and_bad_patterns() {
+        assert!(EventRules::from_json(r#"{"version": 99, "rules": []}"#).is_err());
+        assert!(
+            EventRules::from_json(
+                r#"{"version": 1, "rules": [
+                    {"pattern": "(", "format": "x", "best_of": 1, "ranked": false, "kind": "limited"}
+                ]}"#
+            )
+            .is_err()
+        );
+    }
+}
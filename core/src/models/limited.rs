@@ -0,0 +1,272 @@
+//! Labels for Limited decks, which signature cards can't classify: the deck's colors, named
+//! after the set's archetype for those colors when the set defines one.
+//!
+//! Set archetypes are `data/limited_archetypes.json`, keyed by set code and then by main colors
+//! in WUBRG order; bump its `version` when the file's shape changes.
+
+use std::{collections::HashMap, str::FromStr, sync::LazyLock};
+
+use serde::Deserialize;
+
+use crate::{
+    errors::{Error, ParseError},
+    models::{Card, Color},
+};
+
+/// Newest set archetypes file version this build reads.
+pub const LIMITED_ARCHETYPES_VERSION: u32 = 1;
+
+/// Most cards of a color that can still count as a splash.
+pub const MAX_SPLASH_CARDS: usize = 3;
+
+/// Percentage of a deck's colored cards below which a color counts as a splash.
+pub const SPLASH_PERCENT: usize = 20;
+
+const BUILTIN_ARCHETYPES: &str = include_str!("../../data/limited_archetypes.json");
+
+static BUILTIN: LazyLock<LimitedArchetypes> =
+    LazyLock::new(|| LimitedArchetypes::from_json(BUILTIN_ARCHETYPES).expect("invalid built-in limited archetypes"));
+
+/// The colors a Limited deck plays, in WUBRG order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeckColors {
+    pub main: Vec<Color>,
+    pub splash: Vec<Color>,
+}
+
+impl DeckColors {
+    /// Colors of `cards`, one entry per copy. A card counts towards each of its colors, and a
+    /// color with few enough cards is a splash unless it's the deck's most played. `None` if
+    /// no card has a color.
+    pub fn from_cards<'a>(cards: impl IntoIterator<Item = &'a Card>) -> Option<Self> {
+        let mut counts = [0usize; Color::ALL.len()];
+        let mut colored = 0usize;
+        for card in cards {
+            let colors = card_colors(card);
+            if colors.iter().any(|c| *c) {
+                colored += 1;
+            }
+            for (count, has) in counts.iter_mut().zip(colors) {
+                *count += usize::from(has);
+            }
+        }
+        let top = counts.iter().copied().max().filter(|n| *n > 0)?;
+
+        let mut deck_colors = Self::default();
+        for (color, n) in Color::ALL.into_iter().zip(counts) {
+            if n == 0 {
+                continue;
+            }
+            let is_splash = n < top && n <= MAX_SPLASH_CARDS && n * 100 < SPLASH_PERCENT * colored;
+            if is_splash {
+                deck_colors.splash.push(color);
+            } else {
+                deck_colors.main.push(color);
+            }
+        }
+        Some(deck_colors)
+    }
+
+    /// Main colors, e.g. `UB`.
+    pub fn main_code(&self) -> String {
+        self.main.iter().map(ToString::to_string).collect::<Vec<_>>().concat()
+    }
+
+    /// Color label, e.g. `UB` or `WR splash g`.
+    pub fn label(&self) -> String {
+        format!("{}{}", self.main_code(), self.splash_suffix())
+    }
+
+    fn splash_suffix(&self) -> String {
+        if self.splash.is_empty() {
+            return String::new();
+        }
+        let splash = self
+            .splash
+            .iter()
+            .map(|c| c.to_string().to_lowercase())
+            .collect::<Vec<_>>()
+            .concat();
+        format!(" splash {splash}")
+    }
+}
+
+/// Which colors a card is, from its faces when the card itself lists none.
+fn card_colors(card: &Card) -> [bool; Color::ALL.len()] {
+    let mut colors = [false; Color::ALL.len()];
+    let names: Vec<&String> = if card.colors().is_empty() {
+        card.faces().iter().flat_map(|face| &face.colors).collect()
+    } else {
+        card.colors().iter().collect()
+    };
+    for name in names {
+        if let Ok(color) = Color::from_str(name)
+            && let Some(idx) = Color::ALL.iter().position(|c| *c == color)
+        {
+            colors[idx] = true;
+        }
+    }
+    colors
+}
+
+#[derive(Debug, Deserialize)]
+struct ArchetypesFile {
+    version: u32,
+    sets: HashMap<String, HashMap<String, String>>,
+}
+
+/// Named Limited archetypes per set, keyed by main colors.
+#[derive(Debug, Clone)]
+pub struct LimitedArchetypes {
+    version: u32,
+    sets: HashMap<String, HashMap<String, String>>,
+}
+
+impl LimitedArchetypes {
+    /// The set archetypes shipped with this build.
+    pub fn builtin() -> &'static Self {
+        &BUILTIN
+    }
+
+    /// Parse a set archetypes file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is malformed or the file is newer than
+    /// [`LIMITED_ARCHETYPES_VERSION`].
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        let file: ArchetypesFile = serde_json::from_str(json)?;
+        if file.version > LIMITED_ARCHETYPES_VERSION {
+            return Err(Error::Parse(ParseError::Error(format!(
+                "limited archetypes version {} is newer than supported version {LIMITED_ARCHETYPES_VERSION}",
+                file.version
+            ))));
+        }
+        let sets = file
+            .sets
+            .into_iter()
+            .map(|(set, archetypes)| (set.to_ascii_uppercase(), archetypes))
+            .collect();
+        Ok(Self {
+            version: file.version,
+            sets,
+        })
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The set's name for decks of these main colors, e.g. `Ninjas` for `UB` in `NEO`.
+    pub fn name(&self, set_code: &str, colors: &DeckColors) -> Option<&str> {
+        self.sets
+            .get(&set_code.to_ascii_uppercase())?
+            .get(&colors.main_code())
+            .map(String::as_str)
+    }
+
+    /// Label a deck by its colors, e.g. `UB Ninjas` or `WR splash g`. `None` if no card has
+    /// a color.
+    pub fn label_deck<'a>(&self, cards: impl IntoIterator<Item = &'a Card>, set_code: Option<&str>) -> Option<String> {
+        let colors = DeckColors::from_cards(cards)?;
+        Some(match set_code.and_then(|set| self.name(set, &colors)) {
+            Some(name) => format!("{} {name}{}", colors.main_code(), colors.splash_suffix()),
+            None => colors.label(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CardFace;
+
+    fn card(colors: &[&str]) -> Card {
+        let mut card = Card::new(1, "blb", "Test Card");
+        card.colors = colors.iter().map(ToString::to_string).collect();
+        card
+    }
+
+    fn deck(parts: &[(&[&str], usize)]) -> Vec<Card> {
+        parts
+            .iter()
+            .flat_map(|(colors, n)| std::iter::repeat_n(card(colors), *n))
+            .collect()
+    }
+
+    #[test]
+    fn builtin_archetypes_parse() {
+        assert_eq!(LimitedArchetypes::builtin().version(), LIMITED_ARCHETYPES_VERSION);
+    }
+
+    #[test]
+    fn two_color_deck() {
+        let cards = deck(&[(&["U"], 10), (&["B"], 9), (&["U", "B"], 2), (&[], 17)]);
+        let colors = DeckColors::from_cards(&cards).expect("colored");
+        assert_eq!(colors.main, vec![Color::Blue, Color::Black]);
+        assert!(colors.splash.is_empty());
+        assert_eq!(colors.label(), "UB");
+    }
+
+    #[test]
+    fn small_third_color_is_a_splash() {
+        let cards = deck(&[(&["R"], 11), (&["W"], 9), (&["G"], 2)]);
+        let colors = DeckColors::from_cards(&cards).expect("colored");
+        assert_eq!(colors.label(), "WR splash g");
+    }
+
+    #[test]
+    fn partial_deck_keeps_second_color() {
+        // A few cards seen of the opponent's deck: two of six is no splash.
+        let cards = deck(&[(&["G"], 4), (&["W"], 2)]);
+        assert_eq!(DeckColors::from_cards(&cards).expect("colored").label(), "WG");
+    }
+
+    #[test]
+    fn colorless_deck_has_no_label() {
+        let cards = deck(&[(&[], 5)]);
+        assert!(DeckColors::from_cards(&cards).is_none());
+        assert!(LimitedArchetypes::builtin().label_deck(&cards, Some("BLB")).is_none());
+    }
+
+    #[test]
+    fn face_colors_count_when_card_has_none() {
+        let mut mdfc = card(&[]);
+        mdfc.card_faces = vec![CardFace {
+            name: "Front".to_string(),
+            type_line: "Creature".to_string(),
+            mana_cost: "{1}{B}".to_string(),
+            image_uri: None,
+            colors: vec!["B".to_string()],
+        }];
+        let colors = DeckColors::from_cards([&mdfc]).expect("colored");
+        assert_eq!(colors.main, vec![Color::Black]);
+    }
+
+    #[test]
+    fn set_archetypes_name_the_colors() {
+        let archetypes = LimitedArchetypes::builtin();
+        let ninjas = deck(&[(&["U"], 9), (&["B"], 9)]);
+        assert_eq!(
+            archetypes.label_deck(&ninjas, Some("NEO")).as_deref(),
+            Some("UB Ninjas")
+        );
+        assert_eq!(
+            archetypes.label_deck(&ninjas, Some("neo")).as_deref(),
+            Some("UB Ninjas")
+        );
+        assert_eq!(archetypes.label_deck(&ninjas, Some("MKM")).as_deref(), Some("UB"));
+        assert_eq!(archetypes.label_deck(&ninjas, None).as_deref(), Some("UB"));
+
+        let mice = deck(&[(&["R"], 10), (&["W"], 9), (&["B"], 1)]);
+        assert_eq!(
+            archetypes.label_deck(&mice, Some("BLB")).as_deref(),
+            Some("WR Mice splash b")
+        );
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        assert!(LimitedArchetypes::from_json(r#"{"version": 99, "sets": {}}"#).is_err());
+    }
+}
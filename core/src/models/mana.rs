@@ -14,6 +14,9 @@ pub enum Color {
 }
 
 impl Color {
+    /// Every color, in WUBRG order.
+    pub const ALL: [Color; 5] = [Color::White, Color::Blue, Color::Black, Color::Red, Color::Green];
+
     pub fn svg_file(&self) -> &'static str {
         match self {
             Color::White => "W.svg",
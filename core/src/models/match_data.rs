@@ -46,3 +46,17 @@ pub struct MatchData {
     pub opponent_deck: OpponentDeck,
     pub event_logs: Vec<GameEventLog>,
 }
+
+impl MatchData {
+    /// Arena ids of every card the match references: decklists, cards seen from the
+    /// opponent and mulligan hands. May contain duplicates.
+    pub fn card_ids(&self) -> impl Iterator<Item = i32> + '_ {
+        let deck_cards = self
+            .decks
+            .iter()
+            .flat_map(|deck| deck.mainboard().iter().chain(deck.sideboard()).copied());
+        let opponent_cards = self.opponent_deck.cards.iter().map(ArenaId::inner);
+        let hand_cards = self.mulligans.iter().flat_map(Mulligan::hand_cards);
+        deck_cards.chain(opponent_cards).chain(hand_cards)
+    }
+}
@@ -8,7 +8,9 @@
 mod card;
 mod deck;
 mod draft;
+mod event;
 mod id;
+mod limited;
 mod mana;
 mod match_data;
 mod match_result;
@@ -18,7 +20,9 @@ mod mulligan;
 pub use card::{Card, CardCollection, CardFace, CardType};
 pub use deck::{Deck, Quantities};
 pub use draft::{Draft, DraftPack, Format, MTGADraft};
+pub use event::{EVENT_RULES_VERSION, EventDescriptor, EventKind, EventRules};
 pub use id::ArenaId;
+pub use limited::{DeckColors, LIMITED_ARCHETYPES_VERSION, LimitedArchetypes, MAX_SPLASH_CARDS, SPLASH_PERCENT};
 pub use mana::{Color, Cost, CostSymbol};
 pub use match_data::{MatchData, OpponentDeck};
 pub use match_result::{MatchResult, MatchResultBuilder, MatchResultBuilderError};
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
+use super::{EventDescriptor, EventRules};
+
 /// Represents a match in Magic: The Gathering Arena
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Builder, PartialEq)]
 #[builder(setter(into))]
@@ -49,6 +51,12 @@ impl MTGAMatch {
         }
     }
 
+    #[must_use]
+    pub fn with_format(mut self, format: Option<String>) -> Self {
+        self.format = format;
+        self
+    }
+
     pub fn id(&self) -> &str {
         &self.id
     }
@@ -69,10 +77,17 @@ impl MTGAMatch {
         self.created_at
     }
 
+    /// The MTGA event id the match was played in.
     pub fn format(&self) -> Option<&str> {
         self.format.as_deref()
     }
 
+    /// The match's queue, parsed from its event id by the active [`EventRules`].
+    pub fn event(&self) -> Option<EventDescriptor> {
+        self.format()
+            .and_then(|event_id| EventRules::active().describe(event_id))
+    }
+
     pub fn is_controller(&self, seat_id: i32) -> bool {
         self.controller_seat_id == seat_id
     }
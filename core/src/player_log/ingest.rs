@@ -1,27 +1,31 @@
-use std::{
-    fmt::Display,
-    path::{Path, PathBuf},
-    sync::Arc,
-    time::Duration,
-};
+#[cfg(feature = "native")]
+use std::path::Path;
+use std::{fmt::Display, path::PathBuf, sync::Arc, time::Duration};
 
+#[cfg(feature = "native")]
 use tokio::{
     sync::mpsc::{self},
     time::interval,
 };
+#[cfg(feature = "native")]
 use tracing::{debug, error, info};
 
+#[cfg(feature = "native")]
 use crate::{
-    Error, Result,
+    Error,
     errors::ParseError,
-    events::{business::BusinessEvent, draft::RequestTypeDraftNotify},
-    models::MTGADraft,
     player_log::{
         draft::DraftBuilder,
         processor::{ParseOutput, PlayerLogProcessor},
-        replay::{MatchReplay, MatchReplayBuilder},
+        replay::MatchReplayBuilder,
     },
 };
+use crate::{
+    Result,
+    events::{business::BusinessEvent, draft::RequestTypeDraftNotify},
+    models::MTGADraft,
+    player_log::replay::MatchReplay,
+};
 
 /// Storage trait for writing match replays
 #[async_trait::async_trait]
@@ -109,6 +113,7 @@ pub type EventCallback =
     Arc<dyn Fn(IngestionEvent) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>> + Send + Sync>;
 
 /// Service for ingesting and processing MTGA player logs
+#[cfg(feature = "native")]
 pub struct LogIngestionService {
     config: IngestionConfig,
     processor: PlayerLogProcessor,
@@ -118,6 +123,7 @@ pub struct LogIngestionService {
     shutdown_rx: Option<mpsc::UnboundedReceiver<()>>,
 }
 
+#[cfg(feature = "native")]
 impl LogIngestionService {
     /// Create a new log ingestion service
     ///
@@ -329,6 +335,7 @@ impl LogIngestionService {
 }
 
 /// Create a shutdown channel for graceful termination
+#[cfg(feature = "native")]
 fn create_shutdown_channel() -> mpsc::UnboundedReceiver<()> {
     let (tx, rx) = mpsc::unbounded_channel();
 
@@ -3,3 +3,4 @@ pub mod event_log;
 pub mod ingest;
 pub mod processor;
 pub mod replay;
+pub mod triage;
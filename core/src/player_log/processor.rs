@@ -1,20 +1,26 @@
+#[cfg(feature = "native")]
 use std::{collections::VecDeque, path::Path};
 
+#[cfg(feature = "native")]
 use tokio::{
     fs::File,
     io::{AsyncBufReadExt, BufReader},
 };
+#[cfg(feature = "native")]
 use tracing::{debug, error};
 
+#[cfg(feature = "native")]
+use crate::errors::ParseError;
 use crate::{
     Result,
-    errors::ParseError,
     events::{
         business::RequestTypeBusinessEvent, client::RequestTypeClientToMatchServiceMessage,
         draft::RequestTypeDraftNotify, gre::RequestTypeGREToClientEvent, mgrsc::RequestTypeMGRSCEvent,
     },
 };
 
+/// Incrementally reads a player log file and extracts the JSON events in it.
+#[cfg(feature = "native")]
 #[derive(Debug)]
 pub struct PlayerLogProcessor {
     player_log_reader: BufReader<File>,
@@ -23,6 +29,7 @@ pub struct PlayerLogProcessor {
     bracket_depth: usize,
 }
 
+#[cfg(feature = "native")]
 impl PlayerLogProcessor {
     /// # Errors
     ///
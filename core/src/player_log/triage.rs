@@ -0,0 +1,176 @@
+//! Grouping of events that failed to parse.
+//!
+//! Clients report the raw JSON of every event [`parse`](super::processor::parse) rejects. An
+//! [`ErrorSignature`] reduces a report to the message type that failed, the serde path to the
+//! value that didn't decode, and the decode error with positions and values stripped, so reports
+//! of the same bug share a [`fingerprint`](ErrorSignature::fingerprint).
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use serde_path_to_error::{Path, Segment};
+use sha2::{Digest, Sha256};
+
+use crate::events::{
+    client::RequestTypeClientToMatchServiceMessage, gre::RequestTypeGREToClientEvent, mgrsc::RequestTypeMGRSCEvent,
+};
+
+static POSITION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r" at line \d+ column \d+$").expect("invalid regex"));
+static UNEXPECTED_VALUE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^(invalid (?:type|value): [a-z ]+?) (?:`[^`]*`|"(?:[^"\\]|\\.)*")"#).expect("invalid regex")
+});
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorSignature {
+    /// The event kind, refined by the `type` of the innermost object on the failing path, e.g.
+    /// `greToClientEvent/GREMessageType_GameStateMessage`.
+    pub message_type: String,
+    /// Path to the value that failed to decode, with sequence indexes dropped, e.g.
+    /// `greToClientEvent.greToClientMessages[].gameStateMessage`.
+    pub serde_path: String,
+    /// The decode error without its position or offending value. `None` if the event parses.
+    pub reason: Option<String>,
+}
+
+impl ErrorSignature {
+    /// Short stable identifier of the signature, used to group reports.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.message_type.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.serde_path.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.reason.as_deref().unwrap_or_default().as_bytes());
+        hex::encode(&hasher.finalize()[..8])
+    }
+}
+
+fn decode<T: DeserializeOwned>(event: &str) -> Result<(), serde_path_to_error::Error<serde_json::Error>> {
+    let deserializer = &mut serde_json::Deserializer::from_str(event);
+    serde_path_to_error::deserialize::<_, T>(deserializer).map(|_| ())
+}
+
+fn normalize_path(path: &Path) -> String {
+    let mut normalized = String::new();
+    for segment in path {
+        let part = match segment {
+            Segment::Seq { .. } => {
+                normalized.push_str("[]");
+                continue;
+            }
+            Segment::Map { key } => key.as_str(),
+            Segment::Enum { variant } => variant.as_str(),
+            Segment::Unknown => "?",
+        };
+        if !normalized.is_empty() {
+            normalized.push('.');
+        }
+        normalized.push_str(part);
+    }
+    normalized
+}
+
+fn normalize_reason(error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    let message = POSITION.replace(&message, "");
+    UNEXPECTED_VALUE.replace(&message, "$1").into_owned()
+}
+
+/// The `type` of the innermost object along `path`, which names the message variant for
+/// internally tagged enums like GRE messages.
+fn innermost_type<'a>(mut value: &'a Value, path: &Path) -> Option<&'a str> {
+    let mut found = value.get("type").and_then(Value::as_str);
+    for segment in path {
+        let next = match segment {
+            Segment::Seq { index } => value.get(index),
+            Segment::Map { key } => value.get(key),
+            Segment::Enum { .. } | Segment::Unknown => None,
+        };
+        let Some(next) = next else { break };
+        value = next;
+        if let Some(kind) = value.get("type").and_then(Value::as_str) {
+            found = Some(kind);
+        }
+    }
+    found
+}
+
+/// Compute the signature of a reported event, decoding it the same way
+/// [`parse`](super::processor::parse) does.
+pub fn signature(event: &str) -> ErrorSignature {
+    let (kind, result) = if event.contains("clientToMatchServiceMessage") {
+        (
+            "clientToMatchServiceMessage",
+            decode::<RequestTypeClientToMatchServiceMessage>(event),
+        )
+    } else if event.contains("matchGameRoomStateChangedEvent") {
+        ("matchGameRoomStateChangedEvent", decode::<RequestTypeMGRSCEvent>(event))
+    } else if event.contains("greToClientEvent") {
+        ("greToClientEvent", decode::<RequestTypeGREToClientEvent>(event))
+    } else {
+        // Anything else is tried as a business or draft event and skipped if neither decodes,
+        // so it never fails to parse.
+        ("other", Ok(()))
+    };
+
+    let Err(error) = result else {
+        return ErrorSignature {
+            message_type: kind.to_string(),
+            serde_path: String::new(),
+            reason: None,
+        };
+    };
+
+    let message_type = serde_json::from_str::<Value>(event)
+        .ok()
+        .as_ref()
+        .and_then(|value| innermost_type(value, error.path()))
+        .map_or_else(|| kind.to_string(), |inner| format!("{kind}/{inner}"));
+
+    ErrorSignature {
+        message_type,
+        serde_path: normalize_path(error.path()),
+        reason: Some(normalize_reason(error.inner())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_that_parse_have_no_reason() {
+        let sig = signature(r#"{"greToClientEvent":{"greToClientMessages":[]},"timestamp":"1"}"#);
+        assert_eq!(sig.message_type, "greToClientEvent");
+        assert_eq!(sig.reason, None);
+
+        assert_eq!(signature(r#"{"foo":1}"#).message_type, "other");
+    }
+
+    #[test]
+    fn values_and_positions_do_not_split_groups() {
+        let a = signature(r#"{"greToClientEvent":{"greToClientMessages":[]},"timestamp":5}"#);
+        let b = signature(r#"{"greToClientEvent": {"greToClientMessages": []}, "timestamp": 12345}"#);
+
+        assert_eq!(a.serde_path, "timestamp");
+        assert_eq!(a.reason.as_deref(), Some("invalid type: integer, expected a string"));
+        assert_eq!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[test]
+    fn failing_messages_are_typed_and_indexes_dropped() {
+        let sig = signature(
+            r#"{"greToClientEvent":{"greToClientMessages":[{"type":"GREMessageType_NotYetKnown"}]},"timestamp":"1"}"#,
+        );
+
+        assert_eq!(sig.message_type, "greToClientEvent/GREMessageType_NotYetKnown");
+        assert_eq!(sig.serde_path, "greToClientEvent.greToClientMessages[].type");
+        assert!(
+            sig.reason
+                .expect("decode error reason")
+                .starts_with("unknown variant `GREMessageType_NotYetKnown`")
+        );
+    }
+}
@@ -11,30 +11,48 @@
 //! do not need conversions — the proto type *is* the domain type.
 
 use chrono::{DateTime, Utc};
+use uuid::Uuid;
 
 use super::{
-    GameEventLog as GameEventLogProto, MatchData as MatchDataProto, MatchResult as MatchResultProto,
-    MtgaMatch as MtgaMatchProto, Mulligan as MulliganProto, OpponentDeck as OpponentDeckProto,
+    Draft as DraftProto, DraftData as DraftDataProto, DraftPack as DraftPackProto, GameEventLog as GameEventLogProto,
+    MatchData as MatchDataProto, MatchResult as MatchResultProto, MtgaMatch as MtgaMatchProto,
+    Mulligan as MulliganProto, OpponentDeck as OpponentDeckProto,
+    arenabuddy::api::v1::{
+        ArchetypeMatchupRecord, ArchetypeScore as ArchetypeScoreProto,
+        ClassificationEvidence as ClassificationEvidenceProto, MatchSummaryRecord, MatchedCard as MatchedCardProto,
+        MulliganRecord, OpponentStatsRecord, StatsMatchupGrouping, StatsTimeWindow, TeamStats,
+    },
 };
-use crate::models::{
-    ArenaId, GameEventLog as GameEventLogDomain, MTGAMatch, MatchData as MatchDataDomain, MatchResult, Mulligan,
-    OpponentDeck,
+use crate::{
+    display::{
+        classification::{ArchetypeScore, ClassificationEvidence, MatchedCard},
+        match_summary::MatchSummary,
+        stats::{ArchetypeMatchup, MatchStats, MatchupGrouping, MulliganBucket, OpponentRecord, TimeWindow},
+    },
+    models::{
+        ArenaId, Draft, DraftPack, Format, GameEventLog as GameEventLogDomain, MTGADraft, MTGAMatch,
+        MatchData as MatchDataDomain, MatchResult, Mulligan, OpponentDeck,
+    },
 };
 
+fn parse_rfc3339(value: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_default()
+}
+
 // --- MTGAMatch ↔ MtgaMatch proto ---
 
 impl From<&MtgaMatchProto> for MTGAMatch {
     fn from(proto: &MtgaMatchProto) -> Self {
-        let created_at = DateTime::parse_from_rfc3339(&proto.created_at)
-            .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_default();
         Self::new_with_timestamp(
             &proto.id,
             proto.controller_seat_id,
             &proto.controller_player_name,
             &proto.opponent_player_name,
-            created_at,
+            parse_rfc3339(&proto.created_at),
         )
+        .with_format(proto.format.clone())
     }
 }
 
@@ -46,6 +64,7 @@ impl From<&MTGAMatch> for MtgaMatchProto {
             controller_player_name: m.controller_player_name().to_string(),
             opponent_player_name: m.opponent_player_name().to_string(),
             created_at: m.created_at().to_rfc3339(),
+            format: m.format().map(ToString::to_string),
         }
     }
 }
@@ -186,3 +205,337 @@ impl From<&MatchDataDomain> for MatchDataProto {
         }
     }
 }
+
+// --- MatchSummary ↔ MatchSummaryRecord proto ---
+
+impl From<&MatchSummaryRecord> for MatchSummary {
+    fn from(record: &MatchSummaryRecord) -> Self {
+        Self {
+            id: record.id.clone(),
+            controller_player_name: record.controller_player_name.clone(),
+            opponent_player_name: record.opponent_player_name.clone(),
+            created_at: parse_rfc3339(&record.created_at),
+            format: record.format.clone(),
+            did_controller_win: record.did_controller_win,
+            game_wins: record.game_wins,
+            game_losses: record.game_losses,
+            controller_archetype: record.controller_archetype.clone(),
+            opponent_archetype: record.opponent_archetype.clone(),
+        }
+    }
+}
+
+impl From<&MatchSummary> for MatchSummaryRecord {
+    fn from(summary: &MatchSummary) -> Self {
+        Self {
+            id: summary.id.clone(),
+            controller_player_name: summary.controller_player_name.clone(),
+            opponent_player_name: summary.opponent_player_name.clone(),
+            created_at: summary.created_at.to_rfc3339(),
+            format: summary.format.clone(),
+            did_controller_win: summary.did_controller_win,
+            game_wins: summary.game_wins,
+            game_losses: summary.game_losses,
+            controller_archetype: summary.controller_archetype.clone(),
+            opponent_archetype: summary.opponent_archetype.clone(),
+        }
+    }
+}
+
+// --- ClassificationEvidence ↔ ClassificationEvidence proto ---
+
+impl From<&ClassificationEvidenceProto> for ClassificationEvidence {
+    fn from(evidence: &ClassificationEvidenceProto) -> Self {
+        Self {
+            matched_cards: evidence
+                .matched_cards
+                .iter()
+                .map(|c| MatchedCard {
+                    card_name: c.card_name.clone(),
+                    weight: c.weight,
+                })
+                .collect(),
+            runners_up: evidence
+                .runners_up
+                .iter()
+                .map(|a| ArchetypeScore {
+                    archetype_name: a.archetype_name.clone(),
+                    score: a.score,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&ClassificationEvidence> for ClassificationEvidenceProto {
+    fn from(evidence: &ClassificationEvidence) -> Self {
+        Self {
+            matched_cards: evidence
+                .matched_cards
+                .iter()
+                .map(|c| MatchedCardProto {
+                    card_name: c.card_name.clone(),
+                    weight: c.weight,
+                })
+                .collect(),
+            runners_up: evidence
+                .runners_up
+                .iter()
+                .map(|a| ArchetypeScoreProto {
+                    archetype_name: a.archetype_name.clone(),
+                    score: a.score,
+                })
+                .collect(),
+        }
+    }
+}
+
+// --- Draft ↔ Draft proto ---
+
+impl TryFrom<&DraftProto> for Draft {
+    type Error = crate::Error;
+
+    fn try_from(proto: &DraftProto) -> crate::Result<Self> {
+        let id = Uuid::parse_str(&proto.id).map_err(|_| crate::Error::DecodeError)?;
+        Ok(Self::new(
+            id,
+            proto.set_code.clone(),
+            Format::parse_format(&proto.format),
+            proto.status.clone(),
+        )
+        .with_created_at(parse_rfc3339(&proto.created_at)))
+    }
+}
+
+impl From<&Draft> for DraftProto {
+    fn from(draft: &Draft) -> Self {
+        Self {
+            id: draft.id().to_string(),
+            set_code: draft.set_code().to_string(),
+            format: draft.format().to_string(),
+            status: draft.status().to_string(),
+            created_at: draft.created_at().to_rfc3339(),
+        }
+    }
+}
+
+// --- DraftPack ↔ DraftPack proto ---
+//
+// Note: Like Mulligan, the domain DraftPack carries its `draft_id`, which must be
+// supplied when converting from the proto.
+
+impl From<(Uuid, &DraftPackProto)> for DraftPack {
+    fn from((draft_id, proto): (Uuid, &DraftPackProto)) -> Self {
+        Self::new(
+            draft_id,
+            u8::try_from(proto.pack_number).unwrap_or_default(),
+            u8::try_from(proto.pick_number).unwrap_or_default(),
+            u8::try_from(proto.selection_number).unwrap_or_default(),
+            ArenaId::from(proto.picked_card),
+            proto.cards.iter().map(|&id| ArenaId::from(id)).collect(),
+        )
+    }
+}
+
+impl From<&DraftPack> for DraftPackProto {
+    fn from(pack: &DraftPack) -> Self {
+        Self {
+            pack_number: pack.pack_number().into(),
+            pick_number: pack.pick_number().into(),
+            selection_number: pack.selection_number().into(),
+            picked_card: pack.picked_card().inner(),
+            cards: pack.cards().iter().map(ArenaId::inner).collect(),
+        }
+    }
+}
+
+// --- MTGADraft ↔ DraftData proto ---
+
+impl TryFrom<&DraftDataProto> for MTGADraft {
+    type Error = crate::Error;
+
+    fn try_from(proto: &DraftDataProto) -> crate::Result<Self> {
+        let draft = Draft::try_from(proto.draft.as_ref().ok_or(crate::Error::DecodeError)?)?;
+        let packs = proto
+            .packs
+            .iter()
+            .map(|pack| DraftPack::from((draft.id(), pack)))
+            .collect();
+        Ok(Self::new(draft, packs))
+    }
+}
+
+impl From<&MTGADraft> for DraftDataProto {
+    fn from(draft: &MTGADraft) -> Self {
+        Self {
+            draft: Some(DraftProto::from(draft.draft())),
+            packs: draft.packs().iter().map(DraftPackProto::from).collect(),
+        }
+    }
+}
+
+// --- TimeWindow ↔ StatsTimeWindow proto ---
+
+impl From<StatsTimeWindow> for TimeWindow {
+    fn from(window: StatsTimeWindow) -> Self {
+        match window {
+            StatsTimeWindow::AllTime => Self::AllTime,
+            StatsTimeWindow::Last24Hours => Self::Last24Hours,
+            StatsTimeWindow::Last7Days => Self::Last7Days,
+            StatsTimeWindow::Last30Days => Self::Last30Days,
+        }
+    }
+}
+
+impl From<TimeWindow> for StatsTimeWindow {
+    fn from(window: TimeWindow) -> Self {
+        match window {
+            TimeWindow::AllTime => Self::AllTime,
+            TimeWindow::Last24Hours => Self::Last24Hours,
+            TimeWindow::Last7Days => Self::Last7Days,
+            TimeWindow::Last30Days => Self::Last30Days,
+        }
+    }
+}
+
+// --- MatchupGrouping ↔ StatsMatchupGrouping proto ---
+
+impl From<StatsMatchupGrouping> for MatchupGrouping {
+    fn from(grouping: StatsMatchupGrouping) -> Self {
+        match grouping {
+            StatsMatchupGrouping::Archetype => Self::Archetype,
+            StatsMatchupGrouping::Deck => Self::Deck,
+        }
+    }
+}
+
+impl From<MatchupGrouping> for StatsMatchupGrouping {
+    fn from(grouping: MatchupGrouping) -> Self {
+        match grouping {
+            MatchupGrouping::Archetype => Self::Archetype,
+            MatchupGrouping::Deck => Self::Deck,
+        }
+    }
+}
+
+// --- Stats ↔ stats protos ---
+
+impl From<&MulliganBucket> for MulliganRecord {
+    fn from(bucket: &MulliganBucket) -> Self {
+        Self {
+            cards_kept: bucket.cards_kept,
+            count: bucket.count,
+            wins: bucket.wins,
+            losses: bucket.losses,
+        }
+    }
+}
+
+impl From<&MulliganRecord> for MulliganBucket {
+    fn from(record: &MulliganRecord) -> Self {
+        Self {
+            cards_kept: record.cards_kept,
+            count: record.count,
+            wins: record.wins,
+            losses: record.losses,
+        }
+    }
+}
+
+impl From<&ArchetypeMatchup> for ArchetypeMatchupRecord {
+    fn from(matchup: &ArchetypeMatchup) -> Self {
+        Self {
+            archetype: matchup.archetype.clone(),
+            opponent_archetype: matchup.opponent_archetype.clone(),
+            matches: matchup.matches,
+            wins: matchup.wins,
+            losses: matchup.losses,
+            game1_wins: matchup.game1_wins,
+            game1_losses: matchup.game1_losses,
+            post_board_wins: matchup.post_board_wins,
+            post_board_losses: matchup.post_board_losses,
+            deck_id: matchup.deck_id.clone(),
+        }
+    }
+}
+
+impl From<&ArchetypeMatchupRecord> for ArchetypeMatchup {
+    fn from(record: &ArchetypeMatchupRecord) -> Self {
+        Self {
+            archetype: record.archetype.clone(),
+            deck_id: record.deck_id.clone(),
+            opponent_archetype: record.opponent_archetype.clone(),
+            matches: record.matches,
+            wins: record.wins,
+            losses: record.losses,
+            game1_wins: record.game1_wins,
+            game1_losses: record.game1_losses,
+            post_board_wins: record.post_board_wins,
+            post_board_losses: record.post_board_losses,
+        }
+    }
+}
+
+impl From<&OpponentRecord> for OpponentStatsRecord {
+    fn from(record: &OpponentRecord) -> Self {
+        Self {
+            name: record.name.clone(),
+            matches: record.matches,
+            wins: record.wins,
+            losses: record.losses,
+        }
+    }
+}
+
+impl From<&OpponentStatsRecord> for OpponentRecord {
+    fn from(record: &OpponentStatsRecord) -> Self {
+        Self {
+            name: record.name.clone(),
+            matches: record.matches,
+            wins: record.wins,
+            losses: record.losses,
+        }
+    }
+}
+
+// Note: `TeamStats` also carries matchups and per-member records, which have no
+// `MatchStats` counterpart; callers build those separately.
+
+impl From<&MatchStats> for TeamStats {
+    fn from(stats: &MatchStats) -> Self {
+        Self {
+            total_matches: stats.total_matches,
+            match_wins: stats.match_wins,
+            match_losses: stats.match_losses,
+            total_games: stats.total_games,
+            game_wins: stats.game_wins,
+            game_losses: stats.game_losses,
+            play_wins: stats.play_wins,
+            play_losses: stats.play_losses,
+            draw_wins: stats.draw_wins,
+            draw_losses: stats.draw_losses,
+            mulligans: stats.mulligan_stats.iter().map(MulliganRecord::from).collect(),
+            matchups: Vec::new(),
+            members: Vec::new(),
+        }
+    }
+}
+
+impl From<&TeamStats> for MatchStats {
+    fn from(stats: &TeamStats) -> Self {
+        Self {
+            total_matches: stats.total_matches,
+            match_wins: stats.match_wins,
+            match_losses: stats.match_losses,
+            total_games: stats.total_games,
+            game_wins: stats.game_wins,
+            game_losses: stats.game_losses,
+            play_wins: stats.play_wins,
+            play_losses: stats.play_losses,
+            draw_wins: stats.draw_wins,
+            draw_losses: stats.draw_losses,
+            mulligan_stats: stats.mulligans.iter().map(MulliganBucket::from).collect(),
+            opponents: Vec::new(),
+        }
+    }
+}
@@ -20,7 +20,8 @@
 /// Use this when the domain type needs rich Rust types (e.g. `DateTime<Utc>`), external
 /// context not present in the proto (e.g. `match_id`), builders, serde derives, or
 /// significantly different field types (e.g. `ArenaId` vs `i32`).
-/// Currently used for: `MTGAMatch`, `Deck`, `Mulligan`, `MatchResult`, `OpponentDeck`, `MatchData`.
+/// Currently used for: `MTGAMatch`, `Deck`, `Mulligan`, `MatchResult`, `OpponentDeck`, `MatchData`, `Draft`,
+/// `DraftPack`, `MTGADraft`.
 pub mod arenabuddy {
     pub mod models {
         pub mod v1 {
@@ -39,5 +40,6 @@ mod convert;
 
 // Re-export model types at proto module level for convenience
 pub use arenabuddy::models::v1::{
-    Card, CardCollection, CardFace, Deck, GameEventLog, MatchData, MatchResult, MtgaMatch, Mulligan, OpponentDeck,
+    Card, CardCollection, CardFace, Deck, Draft, DraftData, DraftPack, GameEventLog, MatchData, MatchResult, MtgaMatch,
+    Mulligan, OpponentDeck,
 };
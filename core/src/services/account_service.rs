@@ -0,0 +1,5 @@
+pub use crate::proto::arenabuddy::api::v1::{
+    CancelAccountDeletionRequest, CancelAccountDeletionResponse, DeleteMyAccountRequest, DeleteMyAccountResponse,
+    ExportMyDataRequest, ExportMyDataResponse, account_service_client, account_service_server,
+    export_my_data_response::Record,
+};
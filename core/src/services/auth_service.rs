@@ -1,7 +1,10 @@
 pub use crate::proto::arenabuddy::{
     api::v1::{
-        ExchangeTokenRequest, ExchangeTokenResponse, GetCurrentUserRequest, GetCurrentUserResponse, LogoutRequest,
-        LogoutResponse, RefreshTokenRequest, RefreshTokenResponse, auth_service_client, auth_service_server,
+        ExchangeTokenRequest, ExchangeTokenResponse, GetCurrentUserRequest, GetCurrentUserResponse,
+        IdentityProviderInfo, LinkIdentityRequest, LinkIdentityResponse, ListIdentityProvidersRequest,
+        ListIdentityProvidersResponse, LogoutRequest, LogoutResponse, RefreshTokenRequest, RefreshTokenResponse,
+        RegisterLocalUserRequest, RegisterLocalUserResponse, UnlinkIdentityRequest, UnlinkIdentityResponse,
+        auth_service_client, auth_service_server,
     },
-    models::v1::User,
+    models::v1::{Identity, User},
 };
@@ -0,0 +1,5 @@
+pub use crate::proto::arenabuddy::api::v1::{
+    GetCommunitySettingsRequest, GetCommunitySettingsResponse, GetMatchupMatrixRequest, GetMatchupMatrixResponse,
+    MatchupCell, SetCommunityOptOutRequest, SetCommunityOptOutResponse, community_service_client,
+    community_service_server,
+};
@@ -1,3 +1,5 @@
 pub use crate::proto::arenabuddy::api::v1::{
-    ParseErrorReport, ReportParseErrorsRequest, ReportParseErrorsResponse, debug_service_client, debug_service_server,
+    ListParseErrorGroupsRequest, ListParseErrorGroupsResponse, ParseErrorGroup, ParseErrorReport, ParseErrorSample,
+    ReportParseErrorsRequest, ReportParseErrorsResponse, ResolveParseErrorGroupRequest, ResolveParseErrorGroupResponse,
+    debug_service_client, debug_service_server,
 };
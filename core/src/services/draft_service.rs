@@ -0,0 +1,4 @@
+pub use crate::proto::arenabuddy::api::v1::{
+    GetDraftRequest, GetDraftResponse, ListDraftsRequest, ListDraftsResponse, UpsertDraftRequest, UpsertDraftResponse,
+    draft_service_client, draft_service_server,
+};
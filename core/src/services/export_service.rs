@@ -0,0 +1,7 @@
+pub use crate::proto::arenabuddy::api::v1::{
+    DeleteExportDestinationRequest, DeleteExportDestinationResponse, ExportColumnInfo, ExportDestination,
+    ExportSinkKind, GetExportDestinationRequest, GetExportDestinationResponse, ListExportColumnsRequest,
+    ListExportColumnsResponse, ResyncExportRequest, ResyncExportResponse, SetExportDestinationRequest,
+    SetExportDestinationResponse, VerifyExportDestinationRequest, VerifyExportDestinationResponse,
+    export_service_client, export_service_server,
+};
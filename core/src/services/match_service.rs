@@ -1,5 +1,8 @@
 pub use crate::proto::arenabuddy::api::v1::{
-    ArchetypeClassification, ClassifyMatchRequest, ClassifyMatchResponse, DeleteMatchRequest, DeleteMatchResponse,
-    GetMatchDataRequest, GetMatchDataResponse, ListMatchesRequest, ListMatchesResponse, UpsertMatchDataRequest,
-    UpsertMatchDataResponse, match_service_client, match_service_server,
+    ArchetypeClassification, ArchetypeScore, ClassificationEvidence, ClassifyMatchRequest, ClassifyMatchResponse,
+    DeleteMatchRequest, DeleteMatchResponse, GetMatchDataRequest, GetMatchDataResponse, GetMatchStatsRequest,
+    GetMatchStatsResponse, ListMatchSummariesRequest, ListMatchSummariesResponse, ListMatchesRequest,
+    ListMatchesResponse, MatchSummaryRecord, MatchedCard, OpponentStatsRecord, SetArchetypeOverrideRequest,
+    SetArchetypeOverrideResponse, UpsertMatchDataRequest, UpsertMatchDataResponse, match_service_client,
+    match_service_server,
 };
@@ -0,0 +1,7 @@
+pub use crate::proto::arenabuddy::api::v1::{
+    AcceptArchetypeClusterRequest, AcceptArchetypeClusterResponse, ArchetypeCluster, ArchetypeClusterCard,
+    ArchetypeClusterMember, DiscoverArchetypeClustersRequest, DiscoverArchetypeClustersResponse,
+    DismissArchetypeClusterRequest, DismissArchetypeClusterResponse, GetMetagameJobStatusRequest,
+    GetMetagameJobStatusResponse, ListArchetypeClustersRequest, ListArchetypeClustersResponse, MetagameJobRun,
+    metagame_service_client, metagame_service_server,
+};
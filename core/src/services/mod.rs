@@ -1,3 +1,14 @@
+pub mod account_service;
 pub mod auth_service;
+pub mod community_service;
 pub mod debug_service;
+pub mod draft_service;
+pub mod export_service;
 pub mod match_service;
+pub mod metagame_service;
+pub mod share_service;
+pub mod team_service;
+pub mod webhook_service;
+
+/// Encoded `FileDescriptorSet` of every arenabuddy proto, for gRPC reflection.
+pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/arenabuddy_descriptor.bin"));
@@ -0,0 +1,5 @@
+pub use crate::proto::arenabuddy::api::v1::{
+    CreateMatchShareRequest, CreateMatchShareResponse, GetSharedMatchRequest, GetSharedMatchResponse,
+    ListMatchSharesRequest, ListMatchSharesResponse, MatchShare, RevokeMatchShareRequest, RevokeMatchShareResponse,
+    share_service_client, share_service_server, shared_match_service_client, shared_match_service_server,
+};
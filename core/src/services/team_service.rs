@@ -0,0 +1,9 @@
+pub use crate::proto::arenabuddy::api::v1::{
+    ArchetypeMatchupRecord, CreateTeamRequest, CreateTeamResponse, GetTeamRequest, GetTeamResponse,
+    GetTeamStatsRequest, GetTeamStatsResponse, JoinTeamRequest, JoinTeamResponse, LeaveTeamRequest, LeaveTeamResponse,
+    ListTeamDraftsRequest, ListTeamDraftsResponse, ListTeamMatchesRequest, ListTeamMatchesResponse, ListTeamsRequest,
+    ListTeamsResponse, MemberRecord, MulliganRecord, RemoveMemberRequest, RemoveMemberResponse,
+    RotateInviteCodeRequest, RotateInviteCodeResponse, SetMemberRoleRequest, SetMemberRoleResponse, StatsTimeWindow,
+    Team, TeamDraft, TeamMatch, TeamMember, TeamRole, TeamStats, UpdateSharingRequest, UpdateSharingResponse,
+    team_service_client, team_service_server,
+};
@@ -0,0 +1,5 @@
+pub use crate::proto::arenabuddy::api::v1::{
+    CreateWebhookRequest, CreateWebhookResponse, DeleteWebhookRequest, DeleteWebhookResponse,
+    ListWebhookDeliveriesRequest, ListWebhookDeliveriesResponse, ListWebhooksRequest, ListWebhooksResponse, Webhook,
+    WebhookDelivery, WebhookEvent, webhook_service_client, webhook_service_server,
+};
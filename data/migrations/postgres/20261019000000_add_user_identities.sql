@@ -0,0 +1,32 @@
+-- Generalise authentication beyond Discord: a user can have several linked
+-- identities, each keyed by (provider, subject).
+CREATE TABLE user_identity (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id       UUID NOT NULL REFERENCES app_user(id) ON DELETE CASCADE,
+    provider      TEXT NOT NULL,
+    subject       TEXT NOT NULL,
+    display_name  TEXT,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_login_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (provider, subject)
+);
+
+CREATE INDEX idx_user_identity_user_id ON user_identity(user_id);
+
+-- Existing accounts were all created through Discord.
+INSERT INTO user_identity (user_id, provider, subject, display_name)
+SELECT id, 'discord', discord_id, username FROM app_user;
+
+-- Users created through other providers have no Discord id.
+ALTER TABLE app_user ALTER COLUMN discord_id DROP NOT NULL;
+
+-- Username/password credentials for the self-hosted "local" provider. A password belongs to a
+-- user rather than a username, so a local login can be linked to an existing account and is
+-- removed along with it.
+CREATE TABLE local_credential (
+    user_id       UUID PRIMARY KEY REFERENCES app_user(id) ON DELETE CASCADE,
+    username      TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+);
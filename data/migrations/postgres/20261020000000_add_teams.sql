@@ -0,0 +1,21 @@
+-- Teams: groups of users that pool match data.
+CREATE TABLE team (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name        TEXT NOT NULL,
+    invite_code TEXT NOT NULL UNIQUE,
+    created_by  UUID REFERENCES app_user(id) ON DELETE SET NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Membership with role and opt-in sharing flags (off by default).
+CREATE TABLE team_member (
+    team_id       UUID NOT NULL REFERENCES team(id) ON DELETE CASCADE,
+    user_id       UUID NOT NULL REFERENCES app_user(id) ON DELETE CASCADE,
+    role          TEXT NOT NULL CHECK (role IN ('owner', 'admin', 'member')),
+    share_matches BOOLEAN NOT NULL DEFAULT FALSE,
+    share_drafts  BOOLEAN NOT NULL DEFAULT FALSE,
+    joined_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (team_id, user_id)
+);
+
+CREATE INDEX idx_team_member_user_id ON team_member(user_id);
@@ -0,0 +1,17 @@
+-- Per-user opt-out from the community matchup aggregate.
+ALTER TABLE app_user ADD COLUMN community_opt_out BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Anonymised archetype-vs-archetype records, recomputed periodically by the server.
+-- Only aggregate counts are stored; `players` is the number of distinct contributing users.
+CREATE TABLE community_matchup (
+    format             TEXT NOT NULL,
+    time_window        TEXT NOT NULL,
+    archetype          TEXT NOT NULL,
+    opponent_archetype TEXT NOT NULL,
+    matches            BIGINT NOT NULL,
+    wins               BIGINT NOT NULL,
+    losses             BIGINT NOT NULL,
+    players            BIGINT NOT NULL,
+    computed_at        TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (format, time_window, archetype, opponent_archetype)
+);
@@ -0,0 +1,14 @@
+-- Read-only share links for individual matches. Only a hash of the token is stored;
+-- the raw token is returned once, when the share is created.
+CREATE TABLE match_share (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    match_id    UUID NOT NULL REFERENCES match(id) ON DELETE CASCADE,
+    user_id     UUID NOT NULL REFERENCES app_user(id) ON DELETE CASCADE,
+    token_hash  BYTEA NOT NULL UNIQUE,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    -- NULL means the link never expires
+    expires_at  TIMESTAMPTZ,
+    revoked_at  TIMESTAMPTZ
+);
+
+CREATE INDEX idx_match_share_user_match ON match_share(user_id, match_id);
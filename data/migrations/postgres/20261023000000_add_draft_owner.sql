@@ -0,0 +1,5 @@
+-- Drafts uploaded to the server belong to the uploading user. Nullable for
+-- local-only mode and existing data, matching match.user_id.
+ALTER TABLE draft ADD COLUMN user_id UUID REFERENCES app_user(id);
+
+CREATE INDEX idx_draft_user_id ON draft(user_id);
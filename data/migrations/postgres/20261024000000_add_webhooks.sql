@@ -0,0 +1,29 @@
+-- Outbound webhooks registered by users. The secret signs each payload (HMAC-SHA256),
+-- so unlike share tokens it has to be stored as-is.
+CREATE TABLE webhook (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id     UUID NOT NULL REFERENCES app_user(id) ON DELETE CASCADE,
+    url         TEXT NOT NULL,
+    secret      TEXT NOT NULL,
+    -- Subscribed event names, e.g. 'match.completed'
+    events      TEXT[] NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_webhook_user_id ON webhook(user_id);
+
+-- One row per delivery attempt, so retries show up in the log.
+CREATE TABLE webhook_delivery (
+    id           BIGSERIAL PRIMARY KEY,
+    webhook_id   UUID NOT NULL REFERENCES webhook(id) ON DELETE CASCADE,
+    delivery_id  UUID NOT NULL,
+    event        TEXT NOT NULL,
+    attempt      INTEGER NOT NULL,
+    -- NULL when no HTTP response was received
+    status_code  INTEGER,
+    error        TEXT,
+    succeeded    BOOLEAN NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_webhook_delivery_webhook_created ON webhook_delivery(webhook_id, created_at DESC);
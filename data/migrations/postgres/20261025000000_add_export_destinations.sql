@@ -0,0 +1,17 @@
+-- Where each user's matches are exported to. One destination per user.
+CREATE TABLE export_destination (
+    user_id     UUID PRIMARY KEY REFERENCES app_user(id) ON DELETE CASCADE,
+    kind        TEXT NOT NULL CHECK (kind IN ('google_sheets', 'csv')),
+    -- Spreadsheet id for Google Sheets, file name for CSV
+    target      TEXT NOT NULL,
+    -- Worksheet (tab) name; unused by CSV
+    sheet_name  TEXT NOT NULL DEFAULT 'Matches',
+    -- Column keys in order; empty means the default columns
+    columns     TEXT[] NOT NULL DEFAULT '{}',
+    -- Google spreadsheets are written with the server's service account, so a user has to prove
+    -- they can edit a spreadsheet (by putting verification_code in it) before matches are
+    -- exported there. CSV files live in the user's own export directory and need no proof.
+    verification_code TEXT,
+    verified_at TIMESTAMPTZ,
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,16 @@
+-- Parse error reports are grouped by the fingerprint of their signature: the message type,
+-- serde path and reason the event failed with (see arenabuddy_core::player_log::triage).
+CREATE TABLE parse_error_group (
+    fingerprint  TEXT PRIMARY KEY,
+    message_type TEXT NOT NULL,
+    serde_path   TEXT NOT NULL,
+    reason       TEXT,
+    resolved_at  TIMESTAMPTZ,
+    resolved_by  UUID REFERENCES app_user(id) ON DELETE SET NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- NULL until fingerprinted; existing reports are backfilled with `arenabuddyctl debug fingerprint`.
+ALTER TABLE parse_error ADD COLUMN fingerprint TEXT REFERENCES parse_error_group(fingerprint);
+
+CREATE INDEX idx_parse_error_fingerprint ON parse_error(fingerprint, reported_at);
@@ -0,0 +1,19 @@
+-- Self-service account deletion. Accounts are deleted once `deletion_scheduled_for` passes.
+ALTER TABLE app_user ADD COLUMN deletion_scheduled_for TIMESTAMPTZ;
+
+CREATE INDEX idx_app_user_deletion_scheduled_for ON app_user(deletion_scheduled_for)
+    WHERE deletion_scheduled_for IS NOT NULL;
+
+-- Deleting a user deletes everything they uploaded. Matches and drafts cascade on to
+-- their decks, results, event logs, packs and picks through the existing foreign keys.
+ALTER TABLE match DROP CONSTRAINT IF EXISTS match_user_id_fkey;
+ALTER TABLE match ADD CONSTRAINT match_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES app_user(id) ON DELETE CASCADE;
+
+ALTER TABLE draft DROP CONSTRAINT IF EXISTS draft_user_id_fkey;
+ALTER TABLE draft ADD CONSTRAINT draft_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES app_user(id) ON DELETE CASCADE;
+
+ALTER TABLE parse_error DROP CONSTRAINT IF EXISTS parse_error_user_id_fkey;
+ALTER TABLE parse_error ADD CONSTRAINT parse_error_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES app_user(id) ON DELETE CASCADE;
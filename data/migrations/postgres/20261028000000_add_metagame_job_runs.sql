@@ -0,0 +1,16 @@
+-- Runs of the server's scheduled metagame jobs: scraping, signature cards and reclassification.
+CREATE TABLE IF NOT EXISTS metagame_job_run (
+    id BIGSERIAL PRIMARY KEY,
+    job TEXT NOT NULL,
+    format TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('running', 'succeeded', 'failed')),
+    items BIGINT NOT NULL DEFAULT 0,
+    error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_metagame_job_run_started ON metagame_job_run (started_at DESC);
+
+-- Finding classifications older than their format's signature cards.
+CREATE INDEX IF NOT EXISTS idx_signature_card_format_computed ON archetype_signature_card (format, computed_at);
@@ -0,0 +1,15 @@
+-- Metagame tournaments and decks can come from sources other than MTGGoldfish, such as a
+-- team's local gauntlet lists. Ids are only unique within a source, and needn't be numeric.
+ALTER TABLE metagame_tournament ADD COLUMN source TEXT NOT NULL DEFAULT 'mtggoldfish';
+ALTER TABLE metagame_tournament RENAME COLUMN goldfish_id TO source_id;
+ALTER TABLE metagame_tournament ALTER COLUMN source_id TYPE TEXT USING source_id::text;
+ALTER TABLE metagame_tournament DROP CONSTRAINT IF EXISTS metagame_tournament_goldfish_id_key;
+ALTER TABLE metagame_tournament ADD CONSTRAINT metagame_tournament_source_key UNIQUE (source, source_id);
+ALTER TABLE metagame_tournament ALTER COLUMN source DROP DEFAULT;
+
+ALTER TABLE metagame_deck ADD COLUMN source TEXT NOT NULL DEFAULT 'mtggoldfish';
+ALTER TABLE metagame_deck RENAME COLUMN goldfish_id TO source_id;
+ALTER TABLE metagame_deck ALTER COLUMN source_id TYPE TEXT USING source_id::text;
+ALTER TABLE metagame_deck DROP CONSTRAINT IF EXISTS metagame_deck_goldfish_id_key;
+ALTER TABLE metagame_deck ADD CONSTRAINT metagame_deck_source_key UNIQUE (source, source_id);
+ALTER TABLE metagame_deck ALTER COLUMN source DROP DEFAULT;
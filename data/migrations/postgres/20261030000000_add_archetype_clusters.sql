@@ -0,0 +1,28 @@
+-- Archetype discovery: clusters of similar unclassified decks, proposed as new archetypes.
+CREATE TABLE IF NOT EXISTS archetype_cluster (
+    id SERIAL PRIMARY KEY,
+    format TEXT NOT NULL,
+    suggested_name TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'proposed' CHECK (status IN ('proposed', 'accepted', 'dismissed')),
+    archetype_id INTEGER REFERENCES metagame_archetype(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS archetype_cluster_card (
+    cluster_id INTEGER NOT NULL REFERENCES archetype_cluster(id) ON DELETE CASCADE,
+    card_name TEXT NOT NULL,
+    frequency REAL NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (cluster_id, card_name)
+);
+
+CREATE TABLE IF NOT EXISTS archetype_cluster_member (
+    cluster_id INTEGER NOT NULL REFERENCES archetype_cluster(id) ON DELETE CASCADE,
+    match_id UUID NOT NULL REFERENCES match(id) ON DELETE CASCADE,
+    side TEXT NOT NULL CHECK (side IN ('controller', 'opponent')),
+    similarity REAL NOT NULL,
+    PRIMARY KEY (cluster_id, match_id, side)
+);
+
+CREATE INDEX IF NOT EXISTS idx_archetype_cluster_format_status ON archetype_cluster (format, status);
@@ -0,0 +1,3 @@
+-- Why a match side was classified as its archetype: the matched signature cards and the
+-- runner-up archetypes, as JSON. NULL for classifications stored before evidence was kept.
+ALTER TABLE match_archetype ADD COLUMN IF NOT EXISTS evidence TEXT;
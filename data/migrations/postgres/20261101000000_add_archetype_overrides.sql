@@ -0,0 +1,24 @@
+-- Archetypes set by hand for a match side. They take precedence over the classifier's result,
+-- which stays in match_archetype so clearing an override restores it.
+CREATE TABLE IF NOT EXISTS match_archetype_override (
+    match_id UUID NOT NULL REFERENCES match(id) ON DELETE CASCADE,
+    side TEXT NOT NULL CHECK (side IN ('controller', 'opponent')),
+    archetype_name TEXT NOT NULL,
+    set_by UUID REFERENCES app_user(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (match_id, side)
+);
+
+-- The archetype every reader should use: the override when there is one, else the classifier's.
+CREATE OR REPLACE VIEW effective_match_archetype AS
+SELECT o.match_id, o.side, NULL::integer AS archetype_id, o.archetype_name,
+       1.0::real AS confidence, NULL::text AS evidence, TRUE AS overridden, o.updated_at AS classified_at
+FROM match_archetype_override o
+UNION ALL
+SELECT ma.match_id, ma.side, ma.archetype_id, ma.archetype_name,
+       ma.confidence, ma.evidence, FALSE AS overridden, ma.classified_at
+FROM match_archetype ma
+WHERE NOT EXISTS (
+    SELECT 1 FROM match_archetype_override o WHERE o.match_id = ma.match_id AND o.side = ma.side
+);
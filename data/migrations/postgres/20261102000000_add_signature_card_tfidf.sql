@@ -0,0 +1,10 @@
+-- Signature cards become quantity-aware TF-IDF profiles: every card an archetype plays, with
+-- its mean copies per deck and the card's inverse document frequency across the format.
+-- `weight` keeps the combined TF-IDF weight for ordering.
+--
+-- Existing rows have no copies or IDF to carry over, so the columns stay nullable for one
+-- release: a format whose signature cards lack them is retrained before it classifies anything,
+-- and the next release makes the columns NOT NULL.
+ALTER TABLE archetype_signature_card
+    ADD COLUMN copies REAL,
+    ADD COLUMN idf REAL;
@@ -0,0 +1,10 @@
+-- A deck is identified across matches by its sorted arena ids, hashed. `deck_cards` is the JSON
+-- array of arena ids stored on `deck`.
+CREATE FUNCTION deck_fingerprint(deck_cards TEXT) RETURNS TEXT
+LANGUAGE sql IMMUTABLE PARALLEL SAFE
+AS $$
+    SELECT md5(COALESCE((
+        SELECT string_agg(card.value, ',' ORDER BY card.value::bigint)
+        FROM jsonb_array_elements_text(COALESCE(NULLIF(deck_cards, ''), '[]')::jsonb) AS card(value)
+    ), ''))
+$$;
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Uuid;
+use tracing::instrument;
+
+use super::{account_repository::AccountRepository, debug_repository::ParseErrorReport, postgres::PostgresMatchDB};
+use crate::Result;
+
+#[async_trait::async_trait]
+impl AccountRepository for PostgresMatchDB {
+    async fn list_user_match_ids(&self, user_id: Uuid) -> Result<Vec<String>> {
+        let ids: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM match WHERE user_id = $1 ORDER BY created_at")
+            .bind(user_id)
+            .fetch_all(self.pool())
+            .await?;
+        Ok(ids.into_iter().map(|id| id.to_string()).collect())
+    }
+
+    async fn list_user_parse_errors(&self, user_id: Uuid) -> Result<Vec<ParseErrorReport>> {
+        let reports = sqlx::query_as(
+            "SELECT id, user_id, raw_json, fingerprint, reported_at, created_at
+             FROM parse_error
+             WHERE user_id = $1
+             ORDER BY reported_at",
+        )
+        .bind(user_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(reports)
+    }
+
+    async fn schedule_account_deletion(&self, user_id: Uuid, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE app_user SET deletion_scheduled_for = $2, updated_at = now() WHERE id = $1")
+            .bind(user_id)
+            .bind(at)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    async fn cancel_account_deletion(&self, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE app_user SET deletion_scheduled_for = NULL, updated_at = now()
+             WHERE id = $1 AND deletion_scheduled_for IS NOT NULL",
+        )
+        .bind(user_id)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn accounts_due_for_deletion(&self, now: DateTime<Utc>) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar("SELECT id FROM app_user WHERE deletion_scheduled_for <= $1")
+            .bind(now)
+            .fetch_all(self.pool())
+            .await?;
+        Ok(ids)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_account(&self, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM app_user WHERE id = $1")
+            .bind(user_id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
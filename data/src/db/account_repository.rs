@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Uuid;
+
+use super::debug_repository::ParseErrorReport;
+use crate::Result;
+
+/// Self-service export and deletion of a user's data.
+#[async_trait::async_trait]
+pub trait AccountRepository: Send + Sync + 'static {
+    /// Ids of the matches uploaded by `user_id`, oldest first.
+    async fn list_user_match_ids(&self, user_id: Uuid) -> Result<Vec<String>>;
+    /// Parse errors reported by `user_id`, oldest first.
+    async fn list_user_parse_errors(&self, user_id: Uuid) -> Result<Vec<ParseErrorReport>>;
+    /// Schedules the account for deletion at `at`, replacing any earlier schedule.
+    async fn schedule_account_deletion(&self, user_id: Uuid, at: DateTime<Utc>) -> Result<()>;
+    /// Returns `false` if no deletion was pending.
+    async fn cancel_account_deletion(&self, user_id: Uuid) -> Result<bool>;
+    /// Accounts whose scheduled deletion is due at `now`.
+    async fn accounts_due_for_deletion(&self, now: DateTime<Utc>) -> Result<Vec<Uuid>>;
+    /// Deletes the user, its local password, and everything that cascades from it: identities,
+    /// refresh tokens, matches, drafts, parse errors, shares, webhooks and team memberships.
+    /// Returns `false` if the user didn't exist.
+    async fn delete_account(&self, user_id: Uuid) -> Result<bool>;
+}
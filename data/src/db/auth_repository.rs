@@ -1,12 +1,44 @@
 use chrono::{DateTime, Utc};
 use sqlx::types::Uuid;
 
-use super::models::{AppUser, RefreshToken};
+use super::models::{AppUser, RefreshToken, UserIdentity};
 use crate::Result;
 
 #[async_trait::async_trait]
 pub trait AuthRepository: Send + Sync + 'static {
-    async fn upsert_user(&self, discord_id: &str, username: &str, avatar_url: Option<&str>) -> Result<Uuid>;
+    /// Resolves the user owning `(provider, subject)`, creating a new user and identity if none exists.
+    /// The user's username and avatar are taken from the provider that created the account; later
+    /// logins only refresh the identity's display name and fill in a missing avatar.
+    async fn upsert_identity_user(
+        &self,
+        provider: &str,
+        subject: &str,
+        username: &str,
+        avatar_url: Option<&str>,
+    ) -> Result<Uuid>;
+    /// Links `(provider, subject)` to `user_id`. Returns `false` if the identity belongs to another user.
+    async fn link_identity(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        subject: &str,
+        display_name: Option<&str>,
+    ) -> Result<bool>;
+    async fn list_identities(&self, user_id: Uuid) -> Result<Vec<UserIdentity>>;
+    /// Removes a linked identity from `user_id`, along with its password for `local` identities.
+    /// Returns `false` if no such identity was linked.
+    async fn unlink_identity(&self, user_id: Uuid, provider: &str, subject: &str) -> Result<bool>;
+    /// Stores a local password for `user_id`, or for a new user named `display_name` when `None`,
+    /// together with its `local` identity. Returns the owning user, or `None` if the username is
+    /// already taken or the user already has a local password.
+    async fn create_local_credential(
+        &self,
+        user_id: Option<Uuid>,
+        username: &str,
+        display_name: &str,
+        password_hash: &str,
+    ) -> Result<Option<Uuid>>;
+    async fn get_local_password_hash(&self, username: &str) -> Result<Option<String>>;
     async fn get_user(&self, user_id: Uuid) -> Result<Option<AppUser>>;
     async fn create_refresh_token(&self, user_id: Uuid, token_hash: &[u8], expires_at: DateTime<Utc>) -> Result<()>;
     async fn find_refresh_token(&self, token_hash: &[u8]) -> Result<Option<RefreshToken>>;
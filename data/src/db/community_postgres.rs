@@ -0,0 +1,238 @@
+use arenabuddy_core::display::stats::TimeWindow;
+use chrono::{DateTime, Utc};
+use sqlx::types::Uuid;
+
+use super::{
+    community_repository::{CommunityMatchup, CommunityRepository, RawCommunityMatchup},
+    postgres::PostgresMatchDB,
+};
+use crate::Result;
+
+#[async_trait::async_trait]
+impl CommunityRepository for PostgresMatchDB {
+    async fn aggregate_community_matchups(&self, cutoff: Option<DateTime<Utc>>) -> Result<Vec<RawCommunityMatchup>> {
+        let rows: Vec<RawCommunityMatchup> = sqlx::query_as(
+            r"SELECT
+                m.format AS event_id,
+                ca.archetype_name AS archetype,
+                oa.archetype_name AS opponent_archetype,
+                COUNT(DISTINCT m.id) AS matches,
+                COUNT(DISTINCT CASE WHEN mr.winning_team_id = m.controller_seat_id THEN m.id END) AS wins,
+                COUNT(DISTINCT CASE WHEN mr.winning_team_id != m.controller_seat_id THEN m.id END) AS losses,
+                ARRAY_AGG(DISTINCT m.user_id) AS user_ids
+            FROM match m
+            JOIN app_user u ON u.id = m.user_id AND NOT u.community_opt_out
+            JOIN match_result mr ON m.id = mr.match_id AND mr.result_scope = 'MatchScope_Match'
+            JOIN effective_match_archetype ca ON m.id = ca.match_id AND ca.side = 'controller'
+            JOIN effective_match_archetype oa ON m.id = oa.match_id AND oa.side = 'opponent'
+            WHERE m.format IS NOT NULL
+              AND ($1::timestamptz IS NULL OR m.created_at >= $1)
+            GROUP BY m.format, ca.archetype_name, oa.archetype_name",
+        )
+        .bind(cutoff)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows)
+    }
+
+    async fn replace_community_matchups(&self, time_window: TimeWindow, cells: &[CommunityMatchup]) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+
+        sqlx::query("DELETE FROM community_matchup WHERE time_window = $1")
+            .bind(time_window.key())
+            .execute(&mut *tx)
+            .await?;
+
+        for cell in cells {
+            sqlx::query(
+                "INSERT INTO community_matchup
+                    (format, time_window, archetype, opponent_archetype, matches, wins, losses, players)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            )
+            .bind(&cell.format)
+            .bind(time_window.key())
+            .bind(&cell.archetype)
+            .bind(&cell.opponent_archetype)
+            .bind(cell.matches)
+            .bind(cell.wins)
+            .bind(cell.losses)
+            .bind(cell.players)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_community_matchups(
+        &self,
+        format: &str,
+        time_window: TimeWindow,
+        min_matches: i64,
+        min_players: i64,
+    ) -> Result<(Vec<CommunityMatchup>, Option<DateTime<Utc>>)> {
+        let cells: Vec<CommunityMatchup> = sqlx::query_as(
+            "SELECT format, archetype, opponent_archetype, matches, wins, losses, players
+             FROM community_matchup
+             WHERE format = $1 AND time_window = $2 AND matches >= $3 AND players >= $4
+             ORDER BY archetype, opponent_archetype",
+        )
+        .bind(format)
+        .bind(time_window.key())
+        .bind(min_matches)
+        .bind(min_players)
+        .fetch_all(self.pool())
+        .await?;
+
+        let computed_at: Option<(DateTime<Utc>,)> =
+            sqlx::query_as("SELECT MAX(computed_at) FROM community_matchup WHERE time_window = $1 HAVING COUNT(*) > 0")
+                .bind(time_window.key())
+                .fetch_optional(self.pool())
+                .await?;
+
+        Ok((cells, computed_at.map(|(at,)| at)))
+    }
+
+    async fn list_community_formats(&self) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT DISTINCT format FROM community_matchup ORDER BY format")
+            .fetch_all(self.pool())
+            .await?;
+        Ok(rows.into_iter().map(|(format,)| format).collect())
+    }
+
+    async fn get_community_opt_out(&self, user_id: Uuid) -> Result<bool> {
+        let row: Option<(bool,)> = sqlx::query_as("SELECT community_opt_out FROM app_user WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(self.pool())
+            .await?;
+        Ok(row.is_some_and(|(opt_out,)| opt_out))
+    }
+
+    async fn set_community_opt_out(&self, user_id: Uuid, opt_out: bool) -> Result<()> {
+        sqlx::query("UPDATE app_user SET community_opt_out = $2, updated_at = now() WHERE id = $1")
+            .bind(user_id)
+            .bind(opt_out)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthRepository, MetagameRepository, db::metagame_models::MatchArchetype, testing::test_db};
+
+    /// A Mono Red vs Azorius Control match `user_id` played in `event_id`.
+    async fn seed_match(db: &PostgresMatchDB, user_id: Uuid, event_id: &str, won: bool) {
+        let match_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO match (id, controller_seat_id, controller_player_name, opponent_player_name, user_id, format)
+             VALUES ($1, 1, 'me', 'them', $2, $3)",
+        )
+        .bind(match_id)
+        .bind(user_id)
+        .bind(event_id)
+        .execute(db.pool())
+        .await
+        .expect("match");
+        sqlx::query(
+            "INSERT INTO match_result (match_id, game_number, result_scope, winning_team_id)
+             VALUES ($1, 0, 'MatchScope_Match', $2)",
+        )
+        .bind(match_id)
+        .bind(if won { 1 } else { 2 })
+        .execute(db.pool())
+        .await
+        .expect("result");
+        for (side, archetype_name) in [("controller", "Mono Red"), ("opponent", "Azorius Control")] {
+            db.upsert_match_archetype(&MatchArchetype {
+                match_id: match_id.to_string(),
+                side: side.to_string(),
+                archetype_id: None,
+                archetype_name: archetype_name.to_string(),
+                confidence: 0.9,
+                evidence: None,
+                overridden: false,
+            })
+            .await
+            .expect("classified");
+        }
+    }
+
+    fn cell(format: &str, archetype: &str, matches: i64, players: i64) -> CommunityMatchup {
+        CommunityMatchup {
+            format: format.to_string(),
+            archetype: archetype.to_string(),
+            opponent_archetype: "Azorius Control".to_string(),
+            matches,
+            wins: matches / 2,
+            losses: matches - matches / 2,
+            players,
+        }
+    }
+
+    #[tokio::test]
+    async fn opted_out_users_are_left_out_of_the_aggregate() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let alice = db
+            .upsert_identity_user("local", "alice", "alice", None)
+            .await
+            .expect("alice");
+        let bob = db.upsert_identity_user("local", "bob", "bob", None).await.expect("bob");
+        seed_match(&db, alice, "Ladder", true).await;
+        seed_match(&db, alice, "Ladder", false).await;
+        seed_match(&db, bob, "Ladder", true).await;
+
+        db.set_community_opt_out(bob, true).await.expect("opt out");
+        assert!(db.get_community_opt_out(bob).await.expect("opt-out"));
+        let rows = db.aggregate_community_matchups(None).await.expect("aggregate");
+        assert_eq!(rows.len(), 1);
+        assert_eq!((rows[0].matches, rows[0].wins, rows[0].losses), (2, 1, 1));
+        assert_eq!(rows[0].user_ids, [alice]);
+
+        db.set_community_opt_out(bob, false).await.expect("opt back in");
+        let rows = db.aggregate_community_matchups(None).await.expect("aggregate");
+        assert_eq!((rows[0].matches, rows[0].wins), (3, 2));
+        assert_eq!(rows[0].user_ids.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn cells_below_the_thresholds_are_hidden() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        db.replace_community_matchups(
+            TimeWindow::AllTime,
+            &[
+                cell("standard", "Mono Red", 20, 3),
+                cell("standard", "Gruul", 19, 10),
+                cell("standard", "Domain", 100, 2),
+                cell("explorer", "Mono Red", 50, 5),
+            ],
+        )
+        .await
+        .expect("replaced");
+
+        let (cells, computed_at) = db
+            .get_community_matchups("standard", TimeWindow::AllTime, 20, 3)
+            .await
+            .expect("cells");
+        assert_eq!(cells, [cell("standard", "Mono Red", 20, 3)]);
+        assert!(computed_at.is_some());
+
+        let (cells, computed_at) = db
+            .get_community_matchups("standard", TimeWindow::Last7Days, 0, 0)
+            .await
+            .expect("cells");
+        assert!(cells.is_empty());
+        assert!(computed_at.is_none());
+        assert_eq!(
+            db.list_community_formats().await.expect("formats"),
+            ["explorer", "standard"]
+        );
+    }
+}
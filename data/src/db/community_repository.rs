@@ -0,0 +1,51 @@
+use arenabuddy_core::display::stats::TimeWindow;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, types::Uuid};
+
+use crate::Result;
+
+/// Anonymous archetype matchup counts for one MTGA event id, before formats are merged.
+#[derive(Debug, Clone, FromRow)]
+pub struct RawCommunityMatchup {
+    pub event_id: String,
+    pub archetype: String,
+    pub opponent_archetype: String,
+    pub matches: i64,
+    pub wins: i64,
+    pub losses: i64,
+    /// Contributing users, as ids so counts stay exact when event ids are merged into a format.
+    pub user_ids: Vec<Uuid>,
+}
+
+/// A precomputed community matchup cell.
+#[derive(Debug, Clone, Default, PartialEq, FromRow)]
+pub struct CommunityMatchup {
+    pub format: String,
+    pub archetype: String,
+    pub opponent_archetype: String,
+    pub matches: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub players: i64,
+}
+
+#[async_trait::async_trait]
+pub trait CommunityRepository: Send + Sync + 'static {
+    /// Count classified matches by event id and archetype pair since `cutoff`, skipping users
+    /// who opted out of community stats.
+    async fn aggregate_community_matchups(&self, cutoff: Option<DateTime<Utc>>) -> Result<Vec<RawCommunityMatchup>>;
+    /// Atomically replace all cells for `time_window`.
+    async fn replace_community_matchups(&self, time_window: TimeWindow, cells: &[CommunityMatchup]) -> Result<()>;
+    /// Cells for a format and window with at least `min_matches` matches and `min_players` players,
+    /// plus when they were computed.
+    async fn get_community_matchups(
+        &self,
+        format: &str,
+        time_window: TimeWindow,
+        min_matches: i64,
+        min_players: i64,
+    ) -> Result<(Vec<CommunityMatchup>, Option<DateTime<Utc>>)>;
+    async fn list_community_formats(&self) -> Result<Vec<String>>;
+    async fn get_community_opt_out(&self, user_id: Uuid) -> Result<bool>;
+    async fn set_community_opt_out(&self, user_id: Uuid, opt_out: bool) -> Result<()>;
+}
@@ -0,0 +1,141 @@
+use arenabuddy_core::player_log::triage::ErrorSignature;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, types::Uuid};
+use tracing::instrument;
+
+use super::{
+    debug_repository::{DebugRepository, ParseErrorGroup, ParseErrorReport},
+    postgres::PostgresMatchDB,
+};
+use crate::Result;
+
+/// Create the group for a signature if this is its first report, returning its fingerprint.
+async fn upsert_group(conn: &mut PgConnection, signature: &ErrorSignature) -> Result<String> {
+    let fingerprint = signature.fingerprint();
+    sqlx::query(
+        "INSERT INTO parse_error_group (fingerprint, message_type, serde_path, reason)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (fingerprint) DO NOTHING",
+    )
+    .bind(&fingerprint)
+    .bind(&signature.message_type)
+    .bind(&signature.serde_path)
+    .bind(signature.reason.as_deref())
+    .execute(conn)
+    .await?;
+    Ok(fingerprint)
+}
+
+#[async_trait::async_trait]
+impl DebugRepository for PostgresMatchDB {
+    #[instrument(skip(self, raw_json, signature))]
+    async fn insert_parse_error(
+        &self,
+        user_id: Option<Uuid>,
+        raw_json: &str,
+        reported_at: DateTime<Utc>,
+        signature: &ErrorSignature,
+    ) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+        let fingerprint = upsert_group(&mut tx, signature).await?;
+        sqlx::query("INSERT INTO parse_error (user_id, raw_json, reported_at, fingerprint) VALUES ($1, $2, $3, $4)")
+            .bind(user_id)
+            .bind(raw_json)
+            .bind(reported_at)
+            .bind(fingerprint)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_parse_error(&self, id: Uuid) -> Result<Option<ParseErrorReport>> {
+        let report: Option<ParseErrorReport> = sqlx::query_as(
+            "SELECT id, user_id, raw_json, fingerprint, reported_at, created_at
+             FROM parse_error
+             WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(report)
+    }
+
+    async fn list_parse_error_groups(&self, include_resolved: bool) -> Result<Vec<ParseErrorGroup>> {
+        let groups: Vec<ParseErrorGroup> = sqlx::query_as(
+            "SELECT g.fingerprint, g.message_type, g.serde_path, g.reason, g.resolved_at,
+                    count(e.id) AS count,
+                    count(DISTINCT e.user_id) AS affected_users,
+                    min(e.reported_at) AS first_seen,
+                    max(e.reported_at) AS last_seen,
+                    count(e.id) FILTER (WHERE e.created_at > g.resolved_at) AS reports_since_resolved
+             FROM parse_error_group g
+             JOIN parse_error e ON e.fingerprint = g.fingerprint
+             GROUP BY g.fingerprint
+             HAVING $1
+                 OR g.resolved_at IS NULL
+                 OR count(e.id) FILTER (WHERE e.created_at > g.resolved_at) > 0
+             ORDER BY last_seen DESC",
+        )
+        .bind(include_resolved)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(groups)
+    }
+
+    async fn parse_error_samples(&self, fingerprint: &str, limit: i64) -> Result<Vec<ParseErrorReport>> {
+        let reports: Vec<ParseErrorReport> = sqlx::query_as(
+            "SELECT id, user_id, raw_json, fingerprint, reported_at, created_at
+             FROM parse_error
+             WHERE fingerprint = $1
+             ORDER BY reported_at DESC
+             LIMIT $2",
+        )
+        .bind(fingerprint)
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(reports)
+    }
+
+    async fn set_parse_error_group_resolved(&self, fingerprint: &str, user_id: Uuid, resolved: bool) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE parse_error_group
+             SET resolved_at = CASE WHEN $3::boolean THEN now() END,
+                 resolved_by = CASE WHEN $3::boolean THEN $2::uuid END
+             WHERE fingerprint = $1",
+        )
+        .bind(fingerprint)
+        .bind(user_id)
+        .bind(resolved)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn unfingerprinted_parse_errors(&self, limit: i64) -> Result<Vec<ParseErrorReport>> {
+        let reports: Vec<ParseErrorReport> = sqlx::query_as(
+            "SELECT id, user_id, raw_json, fingerprint, reported_at, created_at
+             FROM parse_error
+             WHERE fingerprint IS NULL
+             ORDER BY created_at
+             LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(reports)
+    }
+
+    async fn set_parse_error_signature(&self, id: Uuid, signature: &ErrorSignature) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+        let fingerprint = upsert_group(&mut tx, signature).await?;
+        sqlx::query("UPDATE parse_error SET fingerprint = $2 WHERE id = $1")
+            .bind(id)
+            .bind(fingerprint)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -1,10 +1,54 @@
+use arenabuddy_core::player_log::triage::ErrorSignature;
 use chrono::{DateTime, Utc};
-use sqlx::types::Uuid;
+use sqlx::{FromRow, types::Uuid};
 
 use crate::Result;
 
+/// A reported event that failed to parse.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct ParseErrorReport {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub raw_json: String,
+    pub fingerprint: Option<String>,
+    pub reported_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Reports sharing a signature fingerprint, with counts over all of its reports.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct ParseErrorGroup {
+    pub fingerprint: String,
+    pub message_type: String,
+    pub serde_path: String,
+    pub reason: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub count: i64,
+    pub affected_users: i64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// Reports received after the group was resolved; non-zero means it regressed.
+    pub reports_since_resolved: i64,
+}
+
 #[async_trait::async_trait]
 pub trait DebugRepository: Send + Sync + 'static {
-    async fn insert_parse_error(&self, user_id: Option<Uuid>, raw_json: &str, reported_at: DateTime<Utc>)
-    -> Result<()>;
+    async fn insert_parse_error(
+        &self,
+        user_id: Option<Uuid>,
+        raw_json: &str,
+        reported_at: DateTime<Utc>,
+        signature: &ErrorSignature,
+    ) -> Result<()>;
+    async fn get_parse_error(&self, id: Uuid) -> Result<Option<ParseErrorReport>>;
+    /// Error groups, most recently seen first. Resolved groups are only included if
+    /// `include_resolved` is set or they have been reported again since.
+    async fn list_parse_error_groups(&self, include_resolved: bool) -> Result<Vec<ParseErrorGroup>>;
+    /// The most recent reports of a group, newest first.
+    async fn parse_error_samples(&self, fingerprint: &str, limit: i64) -> Result<Vec<ParseErrorReport>>;
+    /// Marks a group resolved by `user_id`, or reopens it. Returns `false` if no such group exists.
+    async fn set_parse_error_group_resolved(&self, fingerprint: &str, user_id: Uuid, resolved: bool) -> Result<bool>;
+    /// Reports stored before fingerprinting, oldest first.
+    async fn unfingerprinted_parse_errors(&self, limit: i64) -> Result<Vec<ParseErrorReport>>;
+    async fn set_parse_error_signature(&self, id: Uuid, signature: &ErrorSignature) -> Result<()>;
 }
@@ -0,0 +1,114 @@
+use arenabuddy_core::models::{Draft, Format, MTGADraft};
+use chrono::NaiveDateTime;
+use sqlx::types::Uuid;
+
+use super::{draft_repository::DraftRepository, postgres::PostgresMatchDB, repository::ArenabuddyRepository};
+use crate::Result;
+
+#[async_trait::async_trait]
+impl DraftRepository for PostgresMatchDB {
+    async fn upsert_user_draft(&self, user_id: Uuid, draft: &MTGADraft) -> Result<bool> {
+        // The draft row is locked by the upsert, so a concurrent upload of the same draft by
+        // another user waits here and then fails the ownership check instead of claiming it.
+        let mut tx = self.pool().begin().await?;
+        Self::insert_draft_with_packs(draft, &mut tx).await?;
+        let claimed = sqlx::query("UPDATE draft SET user_id = $2 WHERE id = $1 AND (user_id IS NULL OR user_id = $2)")
+            .bind(draft.draft().id())
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        if claimed.rows_affected() == 0 {
+            // Another user's draft: roll back instead of overwriting their picks.
+            return Ok(false);
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn list_user_drafts(&self, user_id: Uuid) -> Result<Vec<Draft>> {
+        let rows: Vec<(Uuid, String, Option<String>, Option<String>, Option<NaiveDateTime>)> = sqlx::query_as(
+            "SELECT id, set_code, draft_format, status, created_at
+             FROM draft
+             WHERE user_id = $1
+             ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, set_code, draft_format, status, created_at)| {
+                Draft::new(
+                    id,
+                    set_code,
+                    draft_format.map(Format::parse_format).unwrap_or_default(),
+                    status.unwrap_or_default(),
+                )
+                .with_created_at(created_at.unwrap_or_default().and_utc())
+            })
+            .collect())
+    }
+
+    async fn get_user_draft(&self, user_id: Uuid, draft_id: &str) -> Result<Option<MTGADraft>> {
+        let id = Uuid::parse_str(draft_id)?;
+        let owned: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM draft WHERE id = $1 AND user_id = $2)")
+            .bind(id)
+            .bind(user_id)
+            .fetch_one(self.pool())
+            .await?;
+        if !owned {
+            return Ok(None);
+        }
+        Ok(Some(self.get_draft(draft_id).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthRepository, testing::test_db};
+
+    fn draft(id: Uuid, status: &str) -> MTGADraft {
+        MTGADraft::new(
+            Draft::new(id, "MKM".to_string(), Format::default(), status.to_string()),
+            Vec::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn drafts_are_only_written_by_their_owner() {
+        let Some(db) = test_db().await else { return };
+        let owner = db
+            .upsert_identity_user("local", "owner", "owner", None)
+            .await
+            .expect("owner");
+        let other = db
+            .upsert_identity_user("local", "other", "other", None)
+            .await
+            .expect("other");
+        let id = Uuid::new_v4();
+
+        assert!(
+            db.upsert_user_draft(owner, &draft(id, "started"))
+                .await
+                .expect("upsert")
+        );
+        assert!(
+            db.upsert_user_draft(owner, &draft(id, "complete"))
+                .await
+                .expect("re-upload")
+        );
+        assert!(
+            !db.upsert_user_draft(other, &draft(id, "stolen"))
+                .await
+                .expect("other upload")
+        );
+
+        let drafts = db.list_user_drafts(owner).await.expect("list");
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].status(), "complete");
+        assert!(db.list_user_drafts(other).await.expect("list").is_empty());
+        assert!(db.get_user_draft(other, &id.to_string()).await.expect("get").is_none());
+    }
+}
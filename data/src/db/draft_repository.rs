@@ -0,0 +1,15 @@
+use arenabuddy_core::models::{Draft, MTGADraft};
+use sqlx::types::Uuid;
+
+use crate::Result;
+
+/// User-scoped access to drafts uploaded to the server.
+#[async_trait::async_trait]
+pub trait DraftRepository: Send + Sync + 'static {
+    /// Stores `draft` for `user_id`. Returns `false` without writing if the draft already belongs to another user.
+    async fn upsert_user_draft(&self, user_id: Uuid, draft: &MTGADraft) -> Result<bool>;
+    /// Drafts owned by `user_id`, newest first.
+    async fn list_user_drafts(&self, user_id: Uuid) -> Result<Vec<Draft>>;
+    /// A draft and its picks, or `None` if it doesn't exist or isn't owned by `user_id`.
+    async fn get_user_draft(&self, user_id: Uuid, draft_id: &str) -> Result<Option<MTGADraft>>;
+}
@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Uuid;
+
+use super::{
+    export_repository::{ExportDestination, ExportRepository, ExportSinkKind},
+    postgres::PostgresMatchDB,
+};
+use crate::Result;
+
+type DestinationRow = (
+    Uuid,
+    String,
+    String,
+    String,
+    Vec<String>,
+    DateTime<Utc>,
+    Option<String>,
+    Option<DateTime<Utc>>,
+);
+
+fn from_row(
+    (user_id, kind, target, sheet_name, columns, updated_at, verification_code, verified_at): DestinationRow,
+) -> ExportDestination {
+    ExportDestination {
+        user_id,
+        kind: ExportSinkKind::from_db(&kind),
+        target,
+        sheet_name,
+        columns,
+        updated_at,
+        verification_code,
+        verified_at,
+    }
+}
+
+#[async_trait::async_trait]
+impl ExportRepository for PostgresMatchDB {
+    async fn get_export_destination(&self, user_id: Uuid) -> Result<Option<ExportDestination>> {
+        let row: Option<DestinationRow> = sqlx::query_as(
+            "SELECT user_id, kind, target, sheet_name, columns, updated_at, verification_code, verified_at
+             FROM export_destination
+             WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(row.map(from_row))
+    }
+
+    async fn set_export_destination(&self, destination: &ExportDestination) -> Result<ExportDestination> {
+        let row: DestinationRow = sqlx::query_as(
+            "INSERT INTO export_destination
+                 (user_id, kind, target, sheet_name, columns, verification_code, verified_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (user_id) DO UPDATE
+             SET kind = excluded.kind, target = excluded.target, sheet_name = excluded.sheet_name,
+                 columns = excluded.columns, updated_at = now(),
+                 verification_code = CASE
+                     WHEN (export_destination.kind, export_destination.target) = (excluded.kind, excluded.target)
+                     THEN export_destination.verification_code ELSE excluded.verification_code END,
+                 verified_at = CASE
+                     WHEN (export_destination.kind, export_destination.target) = (excluded.kind, excluded.target)
+                     THEN export_destination.verified_at ELSE excluded.verified_at END
+             RETURNING user_id, kind, target, sheet_name, columns, updated_at, verification_code, verified_at",
+        )
+        .bind(destination.user_id)
+        .bind(destination.kind.as_str())
+        .bind(&destination.target)
+        .bind(&destination.sheet_name)
+        .bind(&destination.columns)
+        .bind(&destination.verification_code)
+        .bind(destination.verified_at)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(from_row(row))
+    }
+
+    async fn mark_export_destination_verified(&self, user_id: Uuid, code: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE export_destination SET verified_at = now()
+             WHERE user_id = $1 AND verification_code = $2",
+        )
+        .bind(user_id)
+        .bind(code)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_export_destination(&self, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM export_destination WHERE user_id = $1")
+            .bind(user_id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::{AuthRepository, testing::test_db};
+
+    fn sheet(user_id: Uuid, target: &str, code: &str) -> ExportDestination {
+        ExportDestination {
+            user_id,
+            kind: ExportSinkKind::GoogleSheets,
+            target: target.to_string(),
+            sheet_name: "Matches".to_string(),
+            columns: Vec::new(),
+            updated_at: Utc::now(),
+            verification_code: Some(code.to_string()),
+            verified_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn verification_survives_column_changes_but_not_a_new_target() {
+        let Some(db) = test_db().await else { return };
+        let user_id = db
+            .upsert_identity_user("local", "owner", "owner", None)
+            .await
+            .expect("user");
+
+        let saved = db
+            .set_export_destination(&sheet(user_id, "sheet-a", "code-1"))
+            .await
+            .expect("destination");
+        assert!(!saved.is_verified());
+        assert!(
+            !db.mark_export_destination_verified(user_id, "wrong")
+                .await
+                .expect("destination")
+        );
+        assert!(
+            db.mark_export_destination_verified(user_id, "code-1")
+                .await
+                .expect("destination")
+        );
+
+        let mut columns_changed = sheet(user_id, "sheet-a", "code-2");
+        columns_changed.columns = vec!["match_id".to_string()];
+        let saved = db.set_export_destination(&columns_changed).await.expect("destination");
+        assert!(saved.is_verified());
+        assert_eq!(saved.verification_code.as_deref(), Some("code-1"));
+
+        let saved = db
+            .set_export_destination(&sheet(user_id, "sheet-b", "code-3"))
+            .await
+            .expect("destination");
+        assert!(!saved.is_verified());
+        assert_eq!(saved.verification_code.as_deref(), Some("code-3"));
+        assert!(
+            !db.mark_export_destination_verified(user_id, "code-1")
+                .await
+                .expect("destination")
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Uuid;
+
+use crate::Result;
+
+/// The kind of destination matches are exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportSinkKind {
+    GoogleSheets,
+    Csv,
+}
+
+impl ExportSinkKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportSinkKind::GoogleSheets => "google_sheets",
+            ExportSinkKind::Csv => "csv",
+        }
+    }
+
+    /// Decode an `export_destination.kind` value. The column has a CHECK constraint, so
+    /// anything other than `google_sheets` is a CSV file.
+    pub fn from_db(kind: &str) -> Self {
+        match kind {
+            "google_sheets" => ExportSinkKind::GoogleSheets,
+            _ => ExportSinkKind::Csv,
+        }
+    }
+}
+
+/// A user's export destination and column mapping.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportDestination {
+    pub user_id: Uuid,
+    pub kind: ExportSinkKind,
+    /// Spreadsheet id for Google Sheets, file name for CSV.
+    pub target: String,
+    pub sheet_name: String,
+    /// Column keys in order; empty means the default columns.
+    pub columns: Vec<String>,
+    pub updated_at: DateTime<Utc>,
+    /// Code the user puts in the spreadsheet to prove they can edit it. Unused by CSV.
+    pub verification_code: Option<String>,
+    /// When ownership of the target was proven; nothing is exported until then.
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+impl ExportDestination {
+    pub fn is_verified(&self) -> bool {
+        self.verified_at.is_some()
+    }
+}
+
+#[async_trait::async_trait]
+pub trait ExportRepository: Send + Sync + 'static {
+    async fn get_export_destination(&self, user_id: Uuid) -> Result<Option<ExportDestination>>;
+    /// Creates or replaces the destination of `destination.user_id`. The stored verification is
+    /// kept while the kind and target stay the same, so editing the columns doesn't require
+    /// proving ownership again.
+    async fn set_export_destination(&self, destination: &ExportDestination) -> Result<ExportDestination>;
+    /// Marks the destination of `user_id` verified. Returns `false` if the user has no
+    /// destination or its verification code is no longer `code`.
+    async fn mark_export_destination_verified(&self, user_id: Uuid, code: &str) -> Result<bool>;
+    /// Returns `false` if the user had no destination.
+    async fn delete_export_destination(&self, user_id: Uuid) -> Result<bool>;
+}
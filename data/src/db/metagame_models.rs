@@ -1,9 +1,13 @@
-use chrono::NaiveDate;
+use arenabuddy_core::display::classification::ClassificationEvidence;
+use chrono::{DateTime, NaiveDate, Utc};
 use sqlx::FromRow;
 
 #[derive(Debug, Clone)]
 pub struct MetagameTournament {
-    pub goldfish_id: i32,
+    /// Where the tournament came from, e.g. `mtggoldfish` or `local`.
+    pub source: String,
+    /// The tournament's id within its source.
+    pub source_id: String,
     pub name: String,
     pub format: String,
     pub date: NaiveDate,
@@ -19,7 +23,9 @@ pub struct MetagameArchetype {
 
 #[derive(Debug, Clone)]
 pub struct MetagameDeck {
-    pub goldfish_id: i32,
+    pub source: String,
+    /// The deck's id within its source.
+    pub source_id: String,
     pub archetype_name: Option<String>,
     pub player_name: Option<String>,
     pub placement: Option<String>,
@@ -71,7 +77,8 @@ pub fn parse_deck_download(text: &str) -> Vec<MetagameDeckCard> {
 #[derive(Debug, FromRow)]
 pub struct MetagameTournamentRow {
     pub id: i32,
-    pub goldfish_id: i32,
+    pub source: String,
+    pub source_id: String,
     pub name: String,
     pub format: String,
     pub date: NaiveDate,
@@ -89,7 +96,8 @@ pub struct MetagameArchetypeRow {
 #[derive(Debug, FromRow)]
 pub struct MetagameDeckRow {
     pub id: i32,
-    pub goldfish_id: i32,
+    pub source: String,
+    pub source_id: String,
     pub tournament_id: Option<i32>,
     pub archetype_id: Option<i32>,
     pub player_name: Option<String>,
@@ -104,7 +112,12 @@ pub struct SignatureCard {
     pub archetype_id: i32,
     pub archetype_name: String,
     pub card_name: String,
+    /// TF-IDF weight: `copies` as a fraction of the deck size, times `idf`.
     pub weight: f32,
+    /// Mean copies per deck of the archetype.
+    pub copies: f32,
+    /// Inverse document frequency of the card across the format's decks.
+    pub idf: f32,
     pub format: String,
 }
 
@@ -114,6 +127,10 @@ pub struct SignatureCardRow {
     pub archetype_name: String,
     pub card_name: String,
     pub weight: f32,
+    /// `None` on rows stored before signature cards carried copies and IDF; a format with such
+    /// rows is retrained before it classifies anything.
+    pub copies: Option<f32>,
+    pub idf: Option<f32>,
 }
 
 #[derive(Debug, Clone)]
@@ -123,6 +140,18 @@ pub struct MatchArchetype {
     pub archetype_id: Option<i32>,
     pub archetype_name: String,
     pub confidence: f32,
+    /// Why the deck was classified this way, when known.
+    pub evidence: Option<ClassificationEvidence>,
+    /// Set by hand rather than by the classifier. Ignored when upserting.
+    pub overridden: bool,
+}
+
+/// A hand-set archetype for one side of a match.
+#[derive(Debug, Clone, FromRow)]
+pub struct ArchetypeOverrideRow {
+    pub match_id: sqlx::types::Uuid,
+    pub side: String,
+    pub archetype_name: String,
 }
 
 /// A match that hasn't been classified yet, with its card data.
@@ -130,16 +159,81 @@ pub struct MatchArchetype {
 pub struct UnclassifiedMatchRow {
     pub match_id: sqlx::types::Uuid,
     pub format: Option<String>,
+    pub user_id: Option<sqlx::types::Uuid>,
 }
 
+/// A mainboard card of a deck labelled with an archetype, for training the classifier.
 #[derive(Debug, Clone, FromRow)]
-pub struct CardFrequencyRow {
+pub struct LabelledDeckCardRow {
+    pub deck_id: i32,
     pub archetype_id: i32,
     pub archetype_name: String,
     pub card_name: String,
-    pub archetype_deck_count: i64,
-    pub total_archetype_decks: i64,
-    pub total_decks_with_card: i64,
+    pub quantity: i32,
+}
+
+/// One run of a scheduled metagame job for one format.
+#[derive(Debug, Clone, FromRow)]
+pub struct MetagameJobRun {
+    pub id: i64,
+    /// `scrape`, `signatures` or `reclassify`.
+    pub job: String,
+    pub format: String,
+    /// `running`, `succeeded` or `failed`.
+    pub status: String,
+    /// Decks scraped, signature cards stored or matches classified.
+    pub items: i64,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// A match side without a classification, a candidate for archetype discovery.
+#[derive(Debug, Clone, FromRow)]
+pub struct UnclassifiedDeckRow {
+    pub match_id: sqlx::types::Uuid,
+    /// `controller` or `opponent`.
+    pub side: String,
+}
+
+/// A card shared by a cluster's decks.
+#[derive(Debug, Clone, FromRow)]
+pub struct ArchetypeClusterCard {
+    pub card_name: String,
+    /// Fraction of the cluster's decks that play the card.
+    pub frequency: f32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ArchetypeClusterMember {
+    pub match_id: sqlx::types::Uuid,
+    pub side: String,
+    /// Cosine similarity of the deck to the cluster's centroid.
+    pub similarity: f32,
+}
+
+/// A cluster found by a discovery pass, before it's stored.
+#[derive(Debug, Clone)]
+pub struct NewArchetypeCluster {
+    pub suggested_name: String,
+    /// Defining cards, most characteristic first.
+    pub cards: Vec<ArchetypeClusterCard>,
+    pub members: Vec<ArchetypeClusterMember>,
+}
+
+/// A proposed archetype: similar unclassified decks an admin can name and accept.
+#[derive(Debug, Clone, FromRow)]
+pub struct ArchetypeClusterRow {
+    pub id: i32,
+    pub format: String,
+    pub suggested_name: String,
+    /// `proposed`, `accepted` or `dismissed`.
+    pub status: String,
+    /// The archetype the cluster was accepted as.
+    pub archetype_id: Option<i32>,
+    pub member_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
 }
 
 #[cfg(test)]
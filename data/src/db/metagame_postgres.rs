@@ -1,29 +1,209 @@
-use sqlx::{FromRow, types::Uuid};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use arenabuddy_core::{
+    display::{
+        deck::{StockCard, StockList},
+        metagame::{ArchetypeCount, MetagameTrends, TrendInterval},
+    },
+    models::{Card, EventRules},
+};
+use chrono::NaiveDate;
+use sqlx::{FromRow, PgConnection, Postgres, pool::PoolConnection, types::Uuid};
 use tracing::warn;
 
 use super::{
     metagame_models::{
-        CardFrequencyRow, MatchArchetype, MetagameDeck, MetagameDeckCard, MetagameTournament, SignatureCard,
-        SignatureCardRow, UnclassifiedMatchRow,
+        ArchetypeClusterCard, ArchetypeClusterMember, ArchetypeClusterRow, ArchetypeOverrideRow, LabelledDeckCardRow,
+        MatchArchetype, MetagameDeck, MetagameDeckCard, MetagameJobRun, MetagameTournament, NewArchetypeCluster,
+        SignatureCard, SignatureCardRow, UnclassifiedDeckRow, UnclassifiedMatchRow,
     },
     metagame_repository::{MetagameRepository, MetagameStatsResult},
     postgres::PostgresMatchDB,
 };
 use crate::Result;
 
+/// How long the event ids behind `format_event_ids` are reused before `match` is scanned again
+/// for event ids stored by other processes.
+const EVENT_FORMATS_TTL: Duration = Duration::from_mins(5);
+
+/// Event ids stored in `match`, each with the format the active event rules put it in.
+#[derive(Debug, Default)]
+pub(crate) struct EventFormatCache {
+    /// `None` for event ids no rule describes.
+    formats: HashMap<String, Option<String>>,
+    refreshed_at: Option<Instant>,
+}
+
+impl EventFormatCache {
+    fn remember(&mut self, event_id: &str) {
+        if !self.formats.contains_key(event_id) {
+            let format = EventRules::active().describe(event_id).map(|event| event.format);
+            self.formats.insert(event_id.to_string(), format);
+        }
+    }
+
+    fn is_fresh(&self, now: Instant) -> bool {
+        self.refreshed_at
+            .is_some_and(|refreshed_at| now.duration_since(refreshed_at) < EVENT_FORMATS_TTL)
+    }
+
+    fn event_ids(&self, format: &str) -> Vec<String> {
+        let mut event_ids: Vec<String> = self
+            .formats
+            .iter()
+            .filter(|(_, event_format)| event_format.as_deref().is_some_and(|f| f.eq_ignore_ascii_case(format)))
+            .map(|(event_id, _)| event_id.clone())
+            .collect();
+        event_ids.sort_unstable();
+        event_ids
+    }
+}
+
+/// Advisory lock key held by whichever process is running the scheduled metagame jobs.
+const METAGAME_JOBS_LOCK_KEY: i64 = 0x6d65_7461_6761_6d65;
+
+/// The session-level advisory lock on [`METAGAME_JOBS_LOCK_KEY`], held on its own connection.
+/// The connection is closed rather than returned to the pool, which releases the lock even if
+/// this is dropped without [`release`](Self::release).
+pub struct MetagameJobsLock(PoolConnection<Postgres>);
+
+impl MetagameJobsLock {
+    pub async fn release(self) -> Result<()> {
+        self.0.close().await?;
+        Ok(())
+    }
+}
+
 #[derive(FromRow)]
 struct MatchArchetypeRow {
     side: String,
     archetype_name: String,
 }
 
+#[derive(FromRow)]
+struct MatchClassificationRow {
+    side: String,
+    archetype_id: Option<i32>,
+    archetype_name: String,
+    confidence: f32,
+    evidence: Option<String>,
+    overridden: bool,
+}
+
+impl PostgresMatchDB {
+    /// Take the lock that keeps the scheduled metagame jobs to one server replica, or `None` if
+    /// another replica holds it.
+    pub async fn try_lock_metagame_jobs(&self) -> Result<Option<MetagameJobsLock>> {
+        let mut conn = self.pool().acquire().await?;
+        let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(METAGAME_JOBS_LOCK_KEY)
+            .fetch_one(&mut *conn)
+            .await?;
+        if !locked {
+            return Ok(None);
+        }
+        conn.close_on_drop();
+        Ok(Some(MetagameJobsLock(conn)))
+    }
+
+    async fn insert_match_archetype(conn: &mut PgConnection, archetype: &MatchArchetype) -> Result<()> {
+        let match_id = Uuid::parse_str(&archetype.match_id)?;
+        let evidence = archetype.evidence.as_ref().map(serde_json::to_string).transpose()?;
+        sqlx::query(
+            "INSERT INTO match_archetype (match_id, side, archetype_id, archetype_name, confidence, evidence)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (match_id, side) DO UPDATE SET
+                 archetype_id = EXCLUDED.archetype_id,
+                 archetype_name = EXCLUDED.archetype_name,
+                 confidence = EXCLUDED.confidence,
+                 evidence = EXCLUDED.evidence,
+                 classified_at = NOW()",
+        )
+        .bind(match_id)
+        .bind(&archetype.side)
+        .bind(archetype.archetype_id)
+        .bind(&archetype.archetype_name)
+        .bind(archetype.confidence)
+        .bind(evidence)
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Arena ids of the controller's game 1 deck, one per copy.
+    async fn match_deck_arena_ids(&self, match_id: &str) -> Result<Vec<i32>> {
+        let match_uuid = Uuid::parse_str(match_id)?;
+        let row: Option<(String,)> =
+            sqlx::query_as(r"SELECT d.deck_cards FROM deck d WHERE d.match_id = $1 ORDER BY d.game_number LIMIT 1")
+                .bind(match_uuid)
+                .fetch_optional(self.pool())
+                .await?;
+
+        // deck_cards is JSON array of arena IDs
+        Ok(row.map_or_else(Vec::new, |(cards,)| {
+            serde_json::from_str(&cards).unwrap_or_else(|e| {
+                warn!("Failed to parse deck_cards for match {match_id}: {e}");
+                Vec::new()
+            })
+        }))
+    }
+
+    /// Arena ids of the opponent's cards seen during the match.
+    async fn match_opponent_arena_ids(&self, match_id: &str) -> Result<Vec<i32>> {
+        let match_uuid = Uuid::parse_str(match_id)?;
+        let row: Option<(String,)> = sqlx::query_as(r"SELECT od.cards FROM opponent_deck od WHERE od.match_id = $1")
+            .bind(match_uuid)
+            .fetch_optional(self.pool())
+            .await?;
+
+        Ok(row.map_or_else(Vec::new, |(cards,)| {
+            serde_json::from_str(&cards).unwrap_or_else(|e| {
+                warn!("Failed to parse opponent cards for match {match_id}: {e}");
+                Vec::new()
+            })
+        }))
+    }
+
+    /// Event ids of stored matches that the event rules put in `format`, or `None` if no rule
+    /// maps to the format, in which case matches aren't filtered. The event ids are cached for
+    /// [`EVENT_FORMATS_TTL`]; ones stored through this handle are added as they are written.
+    pub(crate) async fn format_event_ids(&self, format: &str) -> Result<Option<Vec<String>>> {
+        if EventRules::active().format_kind(format).is_none() {
+            return Ok(None);
+        }
+        let now = Instant::now();
+        if !self.event_formats().is_fresh(now) {
+            let event_ids: Vec<(String,)> =
+                sqlx::query_as("SELECT DISTINCT format FROM match WHERE format IS NOT NULL")
+                    .fetch_all(self.pool())
+                    .await?;
+            let mut cache = self.event_formats();
+            for (event_id,) in &event_ids {
+                cache.remember(event_id);
+            }
+            cache.refreshed_at = Some(now);
+        }
+        Ok(Some(self.event_formats().event_ids(format)))
+    }
+
+    /// Add a newly stored match's event id to the `format_event_ids` cache.
+    pub(crate) fn remember_event_id(&self, event_id: Option<&str>) {
+        if let Some(event_id) = event_id {
+            self.event_formats().remember(event_id);
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl MetagameRepository for PostgresMatchDB {
     async fn upsert_metagame_tournament(&self, tournament: &MetagameTournament) -> Result<i32> {
         let row: (i32,) = sqlx::query_as(
-            "INSERT INTO metagame_tournament (goldfish_id, name, format, date, url)
-             VALUES ($1, $2, $3, $4, $5)
-             ON CONFLICT (goldfish_id) DO UPDATE SET
+            "INSERT INTO metagame_tournament (source, source_id, name, format, date, url)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (source, source_id) DO UPDATE SET
                  name = EXCLUDED.name,
                  format = EXCLUDED.format,
                  date = EXCLUDED.date,
@@ -31,7 +211,8 @@ impl MetagameRepository for PostgresMatchDB {
                  scraped_at = NOW()
              RETURNING id",
         )
-        .bind(tournament.goldfish_id)
+        .bind(&tournament.source)
+        .bind(&tournament.source_id)
         .bind(&tournament.name)
         .bind(&tournament.format)
         .bind(tournament.date)
@@ -67,9 +248,9 @@ impl MetagameRepository for PostgresMatchDB {
         let mut tx = self.pool().begin().await?;
 
         let deck_id: (i32,) = sqlx::query_as(
-            "INSERT INTO metagame_deck (goldfish_id, tournament_id, archetype_id, player_name, placement, format, date, url)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-             ON CONFLICT (goldfish_id) DO UPDATE SET
+            "INSERT INTO metagame_deck (source, source_id, tournament_id, archetype_id, player_name, placement, format, date, url)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (source, source_id) DO UPDATE SET
                  tournament_id = EXCLUDED.tournament_id,
                  archetype_id = EXCLUDED.archetype_id,
                  player_name = EXCLUDED.player_name,
@@ -80,7 +261,8 @@ impl MetagameRepository for PostgresMatchDB {
                  scraped_at = NOW()
              RETURNING id",
         )
-        .bind(deck.goldfish_id)
+        .bind(&deck.source)
+        .bind(&deck.source_id)
         .bind(tournament_id)
         .bind(archetype_id)
         .bind(&deck.player_name)
@@ -146,22 +328,46 @@ impl MetagameRepository for PostgresMatchDB {
         })
     }
 
-    async fn get_card_frequencies(&self, format: &str) -> Result<Vec<CardFrequencyRow>> {
-        let rows: Vec<CardFrequencyRow> = sqlx::query_as(
+    async fn metagame_trends(&self, format: &str, interval: TrendInterval, since: NaiveDate) -> Result<MetagameTrends> {
+        let rows: Vec<(NaiveDate, String, i64)> = sqlx::query_as(
             r"SELECT
-                a.id AS archetype_id,
-                a.name AS archetype_name,
-                dc.card_name,
-                COUNT(DISTINCT dc.deck_id)::bigint AS archetype_deck_count,
-                (SELECT COUNT(DISTINCT d2.id) FROM metagame_deck d2 WHERE d2.archetype_id = a.id AND d2.format = $1)::bigint AS total_archetype_decks,
-                (SELECT COUNT(DISTINCT dc2.deck_id) FROM metagame_deck_card dc2
-                 JOIN metagame_deck d3 ON dc2.deck_id = d3.id
-                 WHERE dc2.card_name = dc.card_name AND d3.format = $1)::bigint AS total_decks_with_card
-            FROM metagame_archetype a
-            JOIN metagame_deck d ON d.archetype_id = a.id AND d.format = $1
-            JOIN metagame_deck_card dc ON dc.deck_id = d.id AND dc.is_sideboard = false
-            WHERE a.format = $1
-            GROUP BY a.id, a.name, dc.card_name",
+                -- Truncate in UTC so week boundaries don't follow the session time zone.
+                (date_trunc($2, d.date::timestamp AT TIME ZONE 'UTC', 'UTC') AT TIME ZONE 'UTC')::date AS period,
+                COALESCE(a.name, 'Unknown') AS archetype_name,
+                COUNT(*)::bigint AS decks
+            FROM metagame_deck d
+            LEFT JOIN metagame_archetype a ON a.id = d.archetype_id
+            WHERE d.format = $1
+              AND d.tournament_id IS NOT NULL
+              AND d.date >= $3
+            GROUP BY 1, 2
+            ORDER BY 1, 2",
+        )
+        .bind(format)
+        .bind(interval.key())
+        .bind(since)
+        .fetch_all(self.pool())
+        .await?;
+
+        let counts: Vec<ArchetypeCount> = rows
+            .into_iter()
+            .map(|(period, archetype, decks)| ArchetypeCount {
+                period,
+                archetype,
+                decks,
+            })
+            .collect();
+        Ok(MetagameTrends::from_counts(interval, &counts))
+    }
+
+    async fn get_labelled_deck_cards(&self, format: &str) -> Result<Vec<LabelledDeckCardRow>> {
+        let rows: Vec<LabelledDeckCardRow> = sqlx::query_as(
+            r"SELECT d.id AS deck_id, a.id AS archetype_id, a.name AS archetype_name, dc.card_name, dc.quantity
+              FROM metagame_deck d
+              JOIN metagame_archetype a ON a.id = d.archetype_id AND a.format = $1
+              JOIN metagame_deck_card dc ON dc.deck_id = d.id AND dc.is_sideboard = false
+              WHERE d.format = $1
+              ORDER BY d.id",
         )
         .bind(format)
         .fetch_all(self.pool())
@@ -170,9 +376,90 @@ impl MetagameRepository for PostgresMatchDB {
         Ok(rows)
     }
 
+    async fn get_stock_list(&self, format: &str, archetype_name: &str) -> Result<Option<StockList>> {
+        #[derive(FromRow)]
+        struct StockCardRow {
+            archetype: String,
+            deck_count: i64,
+            card_name: String,
+            decks: i64,
+            copies: i64,
+        }
+
+        let rows: Vec<StockCardRow> = sqlx::query_as(
+            r"WITH stock_deck AS (
+                  SELECT d.id, a.name AS archetype
+                  FROM metagame_deck d
+                  JOIN metagame_archetype a ON a.id = d.archetype_id AND a.format = $1
+                  WHERE d.format = $1
+                    AND d.tournament_id IS NOT NULL
+                    AND LOWER(a.name) = LOWER($2)
+              )
+              SELECT sd.archetype,
+                     (SELECT COUNT(*) FROM stock_deck) AS deck_count,
+                     dc.card_name,
+                     COUNT(DISTINCT sd.id) AS decks,
+                     SUM(dc.quantity)::bigint AS copies
+              FROM stock_deck sd
+              JOIN metagame_deck_card dc ON dc.deck_id = sd.id AND dc.is_sideboard = false
+              GROUP BY sd.archetype, dc.card_name
+              ORDER BY decks DESC, copies DESC, dc.card_name",
+        )
+        .bind(format)
+        .bind(archetype_name)
+        .fetch_all(self.pool())
+        .await?;
+
+        let Some(first) = rows.first() else {
+            return Ok(None);
+        };
+        Ok(Some(StockList {
+            archetype: first.archetype.clone(),
+            decks: first.deck_count,
+            cards: rows
+                .into_iter()
+                .map(|row| StockCard {
+                    name: row.card_name,
+                    decks: row.decks,
+                    copies: row.copies,
+                })
+                .collect(),
+        }))
+    }
+
     async fn replace_signature_cards(&self, format: &str, cards: &[SignatureCard]) -> Result<u64> {
         let mut tx = self.pool().begin().await?;
 
+        let mut stored: Vec<(i32, String, f32, Option<f32>, Option<f32>)> = sqlx::query_as(
+            "SELECT archetype_id, card_name, weight, copies, idf FROM archetype_signature_card WHERE format = $1",
+        )
+        .bind(format)
+        .fetch_all(&mut *tx)
+        .await?;
+        let mut computed: Vec<(i32, &str, f32, f32, f32)> = cards
+            .iter()
+            .map(|card| {
+                (
+                    card.archetype_id,
+                    card.card_name.as_str(),
+                    card.weight,
+                    card.copies,
+                    card.idf,
+                )
+            })
+            .collect();
+        stored.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        computed.sort_by_key(|card| (card.0, card.1));
+        let unchanged = stored.len() == computed.len()
+            && stored.iter().zip(&computed).all(|(old, new)| {
+                (old.0, old.1.as_str()) == (new.0, new.1)
+                    && [Some(old.2), old.3, old.4].map(|v| v.map(f32::to_bits))
+                        == [new.2, new.3, new.4].map(|v| Some(v.to_bits()))
+            });
+        if unchanged {
+            return Ok(0);
+        }
+
         sqlx::query("DELETE FROM archetype_signature_card WHERE format = $1")
             .bind(format)
             .execute(&mut *tx)
@@ -181,12 +468,14 @@ impl MetagameRepository for PostgresMatchDB {
         let mut count = 0u64;
         for card in cards {
             sqlx::query(
-                "INSERT INTO archetype_signature_card (archetype_id, card_name, weight, format)
-                 VALUES ($1, $2, $3, $4)",
+                "INSERT INTO archetype_signature_card (archetype_id, card_name, weight, copies, idf, format)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
             )
             .bind(card.archetype_id)
             .bind(&card.card_name)
             .bind(card.weight)
+            .bind(card.copies)
+            .bind(card.idf)
             .bind(&card.format)
             .execute(&mut *tx)
             .await?;
@@ -199,7 +488,7 @@ impl MetagameRepository for PostgresMatchDB {
 
     async fn get_signature_cards(&self, format: &str) -> Result<Vec<SignatureCardRow>> {
         let rows: Vec<SignatureCardRow> = sqlx::query_as(
-            r"SELECT sc.archetype_id, a.name AS archetype_name, sc.card_name, sc.weight
+            r"SELECT sc.archetype_id, a.name AS archetype_name, sc.card_name, sc.weight, sc.copies, sc.idf
               FROM archetype_signature_card sc
               JOIN metagame_archetype a ON sc.archetype_id = a.id
               WHERE sc.format = $1
@@ -213,111 +502,83 @@ impl MetagameRepository for PostgresMatchDB {
     }
 
     async fn get_unclassified_matches(&self, format: &str) -> Result<Vec<UnclassifiedMatchRow>> {
-        // MTGA stores event IDs like 'Ladder', 'Traditional_Ladder', etc.
-        // Map metagame format names to matching MTGA event ID patterns.
-        let format_patterns = match format.to_lowercase().as_str() {
-            "standard" => vec!["Ladder", "Traditional_Ladder"],
-            "explorer" => vec!["Explorer_Ladder", "Traditional_Explorer_Ladder"],
-            "historic" => vec!["Historic_Ladder", "Traditional_Historic_Ladder"],
-            "timeless" => vec!["Timeless_Ladder", "Traditional_Timeless_Ladder"],
-            _ => vec![],
-        };
-
-        let rows: Vec<UnclassifiedMatchRow> = if format_patterns.is_empty() {
-            // No filter or unknown format — return all unclassified matches
-            sqlx::query_as(
-                r"SELECT m.id AS match_id, m.format
-                  FROM match m
-                  WHERE m.format IS NOT NULL
-                    AND m.id NOT IN (SELECT match_id FROM match_archetype WHERE side = 'controller')
-                  ORDER BY m.created_at DESC",
-            )
-            .fetch_all(self.pool())
-            .await?
-        } else {
-            sqlx::query_as(
-                r"SELECT m.id AS match_id, m.format
-                  FROM match m
-                  WHERE m.format IS NOT NULL
-                    AND m.id NOT IN (SELECT match_id FROM match_archetype WHERE side = 'controller')
-                    AND m.format = ANY($1)
-                  ORDER BY m.created_at DESC",
-            )
-            .bind(&format_patterns)
-            .fetch_all(self.pool())
-            .await?
-        };
+        let rows: Vec<UnclassifiedMatchRow> = sqlx::query_as(
+            r"SELECT m.id AS match_id, m.format, m.user_id
+              FROM match m
+              WHERE m.format IS NOT NULL
+                AND m.id NOT IN (SELECT match_id FROM match_archetype WHERE side = 'controller')
+                AND ($1::text[] IS NULL OR m.format = ANY($1))
+              ORDER BY m.created_at DESC",
+        )
+        .bind(self.format_event_ids(format).await?)
+        .fetch_all(self.pool())
+        .await?;
 
         Ok(rows)
     }
 
-    async fn upsert_match_archetype(&self, archetype: &MatchArchetype) -> Result<()> {
-        let match_id = Uuid::parse_str(&archetype.match_id)?;
-        sqlx::query(
-            "INSERT INTO match_archetype (match_id, side, archetype_id, archetype_name, confidence)
-             VALUES ($1, $2, $3, $4, $5)
-             ON CONFLICT (match_id, side) DO UPDATE SET
-                 archetype_id = EXCLUDED.archetype_id,
-                 archetype_name = EXCLUDED.archetype_name,
-                 confidence = EXCLUDED.confidence,
-                 classified_at = NOW()",
+    async fn get_stale_classified_matches(&self, format: &str) -> Result<Vec<UnclassifiedMatchRow>> {
+        // With no signature cards the MAX is NULL and nothing counts as stale.
+        let rows: Vec<UnclassifiedMatchRow> = sqlx::query_as(
+            r"SELECT m.id AS match_id, m.format, m.user_id
+              FROM match m
+              JOIN match_archetype ma ON ma.match_id = m.id AND ma.side = 'controller'
+              WHERE m.format IS NOT NULL
+                AND ($2::text[] IS NULL OR m.format = ANY($2))
+                AND ma.classified_at < (
+                    SELECT MAX(computed_at) FROM archetype_signature_card WHERE format = $1
+                )
+              ORDER BY m.created_at DESC",
         )
-        .bind(match_id)
-        .bind(&archetype.side)
-        .bind(archetype.archetype_id)
-        .bind(&archetype.archetype_name)
-        .bind(archetype.confidence)
-        .execute(self.pool())
+        .bind(format)
+        .bind(self.format_event_ids(format).await?)
+        .fetch_all(self.pool())
         .await?;
-        Ok(())
+
+        Ok(rows)
     }
 
-    async fn get_match_deck_cards(&self, match_id: &str) -> Result<Vec<String>> {
-        let match_uuid = Uuid::parse_str(match_id)?;
-        // Get all arena IDs from the deck for game 1, then map to card names
-        let rows: Vec<(String,)> =
-            sqlx::query_as(r"SELECT d.deck_cards FROM deck d WHERE d.match_id = $1 ORDER BY d.game_number LIMIT 1")
-                .bind(match_uuid)
-                .fetch_all(self.pool())
-                .await?;
+    async fn replace_match_archetypes(&self, match_ids: &[Uuid], archetypes: &[MatchArchetype]) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+        sqlx::query("DELETE FROM match_archetype WHERE match_id = ANY($1)")
+            .bind(match_ids)
+            .execute(&mut *tx)
+            .await?;
+        for archetype in archetypes {
+            Self::insert_match_archetype(&mut tx, archetype).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
 
-        let Some(row) = rows.first() else {
-            return Ok(Vec::new());
-        };
+    async fn upsert_match_archetype(&self, archetype: &MatchArchetype) -> Result<()> {
+        Self::insert_match_archetype(&mut *self.pool().acquire().await?, archetype).await
+    }
 
-        // deck_cards is JSON array of arena IDs
-        let arena_ids: Vec<i32> = serde_json::from_str(&row.0).unwrap_or_else(|e| {
-            warn!("Failed to parse deck_cards for match {match_id}: {e}");
-            Vec::new()
-        });
-        let card_names = self.arena_ids_to_card_names(&arena_ids);
-        Ok(card_names)
+    async fn get_match_deck_cards(&self, match_id: &str) -> Result<Vec<String>> {
+        let arena_ids = self.match_deck_arena_ids(match_id).await?;
+        Ok(self.arena_ids_to_card_names(&arena_ids))
     }
 
     async fn get_match_opponent_cards(&self, match_id: &str) -> Result<Vec<String>> {
-        let match_uuid = Uuid::parse_str(match_id)?;
-        let rows: Vec<(String,)> = sqlx::query_as(r"SELECT od.cards FROM opponent_deck od WHERE od.match_id = $1")
-            .bind(match_uuid)
-            .fetch_all(self.pool())
-            .await?;
+        let arena_ids = self.match_opponent_arena_ids(match_id).await?;
+        Ok(self.arena_ids_to_card_names(&arena_ids))
+    }
 
-        let Some(row) = rows.first() else {
-            return Ok(Vec::new());
+    async fn get_match_side_cards(&self, match_id: &str, side: &str) -> Result<Vec<Card>> {
+        let arena_ids = if side == "controller" {
+            self.match_deck_arena_ids(match_id).await?
+        } else {
+            self.match_opponent_arena_ids(match_id).await?
         };
-
-        let arena_ids: Vec<i32> = serde_json::from_str(&row.0).unwrap_or_else(|e| {
-            warn!("Failed to parse opponent cards for match {match_id}: {e}");
-            Vec::new()
-        });
-        let card_names = self.arena_ids_to_card_names(&arena_ids);
-        Ok(card_names)
+        Ok(self.arena_ids_to_cards(&arena_ids))
     }
 
     async fn get_match_archetypes(&self, match_id: &str) -> Result<(Option<String>, Option<String>)> {
         let match_uuid = Uuid::parse_str(match_id)?;
 
         let rows: Vec<MatchArchetypeRow> =
-            sqlx::query_as("SELECT side, archetype_name FROM match_archetype WHERE match_id = $1")
+            sqlx::query_as("SELECT side, archetype_name FROM effective_match_archetype WHERE match_id = $1")
                 .bind(match_uuid)
                 .fetch_all(self.pool())
                 .await?;
@@ -334,4 +595,577 @@ impl MetagameRepository for PostgresMatchDB {
 
         Ok((controller, opponent))
     }
+
+    async fn get_match_classifications(&self, match_id: &str) -> Result<Vec<MatchArchetype>> {
+        let match_uuid = Uuid::parse_str(match_id)?;
+
+        let rows: Vec<MatchClassificationRow> = sqlx::query_as(
+            "SELECT side, archetype_id, archetype_name, confidence, evidence, overridden
+             FROM effective_match_archetype
+             WHERE match_id = $1
+             ORDER BY side",
+        )
+        .bind(match_uuid)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| MatchArchetype {
+                match_id: match_id.to_string(),
+                evidence: row.evidence.and_then(|evidence| {
+                    serde_json::from_str(&evidence)
+                        .inspect_err(|e| warn!("Failed to parse classification evidence for match {match_id}: {e}"))
+                        .ok()
+                }),
+                side: row.side,
+                archetype_id: row.archetype_id,
+                archetype_name: row.archetype_name,
+                confidence: row.confidence,
+                overridden: row.overridden,
+            })
+            .collect())
+    }
+
+    async fn set_match_archetype_override(
+        &self,
+        match_id: &str,
+        side: &str,
+        archetype_name: &str,
+        set_by: Option<Uuid>,
+    ) -> Result<()> {
+        let match_uuid = Uuid::parse_str(match_id)?;
+        sqlx::query(
+            "INSERT INTO match_archetype_override (match_id, side, archetype_name, set_by)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (match_id, side) DO UPDATE SET
+                 archetype_name = EXCLUDED.archetype_name,
+                 set_by = EXCLUDED.set_by,
+                 updated_at = NOW()",
+        )
+        .bind(match_uuid)
+        .bind(side)
+        .bind(archetype_name)
+        .bind(set_by)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn clear_match_archetype_override(&self, match_id: &str, side: &str) -> Result<bool> {
+        let match_uuid = Uuid::parse_str(match_id)?;
+        let result = sqlx::query("DELETE FROM match_archetype_override WHERE match_id = $1 AND side = $2")
+            .bind(match_uuid)
+            .bind(side)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_archetype_overrides(&self, format: &str) -> Result<Vec<ArchetypeOverrideRow>> {
+        let rows = sqlx::query_as(
+            r"SELECT o.match_id, o.side, o.archetype_name
+              FROM match_archetype_override o
+              JOIN match m ON m.id = o.match_id
+              WHERE m.format IS NOT NULL
+                AND ($1::text[] IS NULL OR m.format = ANY($1))
+              ORDER BY o.match_id, o.side",
+        )
+        .bind(self.format_event_ids(format).await?)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows)
+    }
+
+    async fn prune_metagame_decks(&self, source: &str, format: &str, keep: &[String]) -> Result<u64> {
+        let result =
+            sqlx::query("DELETE FROM metagame_deck WHERE source = $1 AND format = $2 AND NOT (source_id = ANY($3))")
+                .bind(source)
+                .bind(format)
+                .bind(keep)
+                .execute(self.pool())
+                .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn get_unclassified_decks(&self, format: &str, limit: i64) -> Result<Vec<UnclassifiedDeckRow>> {
+        let rows = sqlx::query_as(
+            r"SELECT m.id AS match_id, s.side
+              FROM match m
+              CROSS JOIN (VALUES ('controller'), ('opponent')) AS s(side)
+              WHERE m.format IS NOT NULL
+                AND ($1::text[] IS NULL OR m.format = ANY($1))
+                AND NOT EXISTS (
+                    SELECT 1 FROM effective_match_archetype ma WHERE ma.match_id = m.id AND ma.side = s.side
+                )
+              ORDER BY m.created_at DESC, s.side
+              LIMIT $2",
+        )
+        .bind(self.format_event_ids(format).await?)
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows)
+    }
+
+    async fn replace_proposed_clusters(&self, format: &str, clusters: &[NewArchetypeCluster]) -> Result<u64> {
+        let mut tx = self.pool().begin().await?;
+
+        sqlx::query("DELETE FROM archetype_cluster WHERE format = $1 AND status = 'proposed'")
+            .bind(format)
+            .execute(&mut *tx)
+            .await?;
+
+        for cluster in clusters {
+            let cluster_id: i32 = sqlx::query_scalar(
+                "INSERT INTO archetype_cluster (format, suggested_name) VALUES ($1, $2) RETURNING id",
+            )
+            .bind(format)
+            .bind(&cluster.suggested_name)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            for (position, card) in (0i32..).zip(&cluster.cards) {
+                sqlx::query(
+                    "INSERT INTO archetype_cluster_card (cluster_id, card_name, frequency, position)
+                     VALUES ($1, $2, $3, $4)",
+                )
+                .bind(cluster_id)
+                .bind(&card.card_name)
+                .bind(card.frequency)
+                .bind(position)
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            for member in &cluster.members {
+                sqlx::query(
+                    "INSERT INTO archetype_cluster_member (cluster_id, match_id, side, similarity)
+                     VALUES ($1, $2, $3, $4)",
+                )
+                .bind(cluster_id)
+                .bind(member.match_id)
+                .bind(&member.side)
+                .bind(member.similarity)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(clusters.len() as u64)
+    }
+
+    async fn list_archetype_clusters(
+        &self,
+        format: Option<&str>,
+        include_resolved: bool,
+    ) -> Result<Vec<ArchetypeClusterRow>> {
+        let rows = sqlx::query_as(
+            r"SELECT c.id, c.format, c.suggested_name, c.status, c.archetype_id,
+                     (SELECT COUNT(*) FROM archetype_cluster_member cm WHERE cm.cluster_id = c.id) AS member_count,
+                     c.created_at, c.resolved_at
+              FROM archetype_cluster c
+              WHERE ($1::text IS NULL OR c.format = $1)
+                AND ($2 OR c.status = 'proposed')
+              ORDER BY member_count DESC, c.id",
+        )
+        .bind(format)
+        .bind(include_resolved)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows)
+    }
+
+    async fn get_archetype_cluster(&self, cluster_id: i32) -> Result<Option<ArchetypeClusterRow>> {
+        let row = sqlx::query_as(
+            r"SELECT c.id, c.format, c.suggested_name, c.status, c.archetype_id,
+                     (SELECT COUNT(*) FROM archetype_cluster_member cm WHERE cm.cluster_id = c.id) AS member_count,
+                     c.created_at, c.resolved_at
+              FROM archetype_cluster c
+              WHERE c.id = $1",
+        )
+        .bind(cluster_id)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(row)
+    }
+
+    async fn get_archetype_cluster_cards(&self, cluster_id: i32) -> Result<Vec<ArchetypeClusterCard>> {
+        let rows = sqlx::query_as(
+            "SELECT card_name, frequency FROM archetype_cluster_card WHERE cluster_id = $1 ORDER BY position",
+        )
+        .bind(cluster_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows)
+    }
+
+    async fn get_archetype_cluster_members(&self, cluster_id: i32) -> Result<Vec<ArchetypeClusterMember>> {
+        let rows = sqlx::query_as(
+            "SELECT match_id, side, similarity FROM archetype_cluster_member
+             WHERE cluster_id = $1
+             ORDER BY similarity DESC",
+        )
+        .bind(cluster_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows)
+    }
+
+    async fn resolve_archetype_cluster(
+        &self,
+        cluster_id: i32,
+        status: &str,
+        archetype_id: Option<i32>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE archetype_cluster SET status = $2, archetype_id = $3, resolved_at = NOW()
+             WHERE id = $1 AND status = 'proposed'",
+        )
+        .bind(cluster_id)
+        .bind(status)
+        .bind(archetype_id)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn reopen_archetype_cluster(&self, cluster_id: i32) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE archetype_cluster SET status = 'proposed', archetype_id = NULL, resolved_at = NULL
+             WHERE id = $1 AND status = 'accepted'",
+        )
+        .bind(cluster_id)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn start_metagame_job_run(&self, job: &str, format: &str) -> Result<i64> {
+        let id = sqlx::query_scalar(
+            "INSERT INTO metagame_job_run (job, format, status) VALUES ($1, $2, 'running') RETURNING id",
+        )
+        .bind(job)
+        .bind(format)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(id)
+    }
+
+    async fn fail_interrupted_metagame_job_runs(&self) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE metagame_job_run SET status = 'failed', error = 'interrupted', finished_at = NOW()
+             WHERE status = 'running'",
+        )
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn finish_metagame_job_run(&self, id: i64, items: i64, error: Option<&str>) -> Result<()> {
+        sqlx::query(
+            "UPDATE metagame_job_run
+             SET status = CASE WHEN $3::text IS NULL THEN 'succeeded' ELSE 'failed' END,
+                 items = $2,
+                 error = $3,
+                 finished_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(items)
+        .bind(error)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn list_metagame_job_runs(&self, limit: i64) -> Result<Vec<MetagameJobRun>> {
+        let rows = sqlx::query_as(
+            "SELECT id, job, format, status, items, error, started_at, finished_at
+             FROM metagame_job_run
+             ORDER BY started_at DESC, id DESC
+             LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::testing::test_db;
+
+    /// A match with no games, for classifications to point at.
+    async fn seed_match(db: &PostgresMatchDB) -> String {
+        let match_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO match (id, controller_seat_id, controller_player_name, opponent_player_name)
+             VALUES ($1, 1, 'me', 'them')",
+        )
+        .bind(match_id)
+        .execute(db.pool())
+        .await
+        .expect("match");
+        match_id.to_string()
+    }
+
+    fn classified(match_id: &str, side: &str, archetype_name: &str) -> MatchArchetype {
+        MatchArchetype {
+            match_id: match_id.to_string(),
+            side: side.to_string(),
+            archetype_id: None,
+            archetype_name: archetype_name.to_string(),
+            confidence: 0.8,
+            evidence: None,
+            overridden: false,
+        }
+    }
+
+    #[tokio::test]
+    pub(crate) async fn format_event_ids_are_cached_until_refreshed() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let insert = |event_id: &'static str| {
+            sqlx::query(
+                "INSERT INTO match (id, controller_seat_id, controller_player_name, opponent_player_name, format)
+                 VALUES ($1, 1, 'me', 'them', $2)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(event_id)
+            .execute(db.pool())
+        };
+        insert("Ladder").await.expect("match");
+        insert("Explorer_Ladder").await.expect("match");
+        assert_eq!(
+            db.format_event_ids("Standard").await.expect("event ids"),
+            Some(vec!["Ladder".to_string()])
+        );
+        assert!(db.format_event_ids("pioneer").await.expect("event ids").is_none());
+
+        // Written behind the cache's back, so only seen once remembered or refreshed.
+        insert("Traditional_Ladder").await.expect("match");
+        assert_eq!(
+            db.format_event_ids("standard").await.expect("event ids"),
+            Some(vec!["Ladder".to_string()])
+        );
+        db.remember_event_id(Some("Traditional_Ladder"));
+        let expected = Some(vec!["Ladder".to_string(), "Traditional_Ladder".to_string()]);
+        assert_eq!(db.format_event_ids("standard").await.expect("event ids"), expected);
+
+        db.event_formats().refreshed_at = None;
+        insert("Play").await.expect("match");
+        let refreshed = db
+            .format_event_ids("standard")
+            .await
+            .expect("event ids")
+            .expect("standard");
+        assert_eq!(refreshed, ["Ladder", "Play", "Traditional_Ladder"]);
+    }
+
+    #[tokio::test]
+    async fn overrides_take_precedence_over_classifications() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let match_id = seed_match(&db).await;
+        db.upsert_match_archetype(&classified(&match_id, "controller", "Mono Red"))
+            .await
+            .expect("classified");
+        db.upsert_match_archetype(&classified(&match_id, "opponent", "Azorius Control"))
+            .await
+            .expect("classified");
+
+        db.set_match_archetype_override(&match_id, "opponent", "Esper Control", None)
+            .await
+            .expect("override");
+        let expected = (Some("Mono Red".to_string()), Some("Esper Control".to_string()));
+        assert_eq!(db.get_match_archetypes(&match_id).await.expect("archetypes"), expected);
+        let classifications = db.get_match_classifications(&match_id).await.expect("classifications");
+        let overridden: Vec<_> = classifications
+            .iter()
+            .map(|c| (c.side.as_str(), c.overridden))
+            .collect();
+        assert_eq!(overridden, [("controller", false), ("opponent", true)]);
+
+        // Reclassifying leaves the override in place.
+        let match_uuid = Uuid::parse_str(&match_id).expect("uuid");
+        db.replace_match_archetypes(&[match_uuid], &[classified(&match_id, "opponent", "Dimir Control")])
+            .await
+            .expect("reclassified");
+        assert_eq!(
+            db.get_match_archetypes(&match_id).await.expect("archetypes"),
+            (None, expected.1)
+        );
+
+        assert!(
+            db.clear_match_archetype_override(&match_id, "opponent")
+                .await
+                .expect("cleared")
+        );
+        assert!(
+            !db.clear_match_archetype_override(&match_id, "opponent")
+                .await
+                .expect("cleared")
+        );
+        assert_eq!(
+            db.get_match_archetypes(&match_id).await.expect("archetypes"),
+            (None, Some("Dimir Control".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn metagame_jobs_lock_is_held_by_one_caller_at_a_time() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+
+        let lock = db
+            .try_lock_metagame_jobs()
+            .await
+            .expect("lock query")
+            .expect("lock is free");
+        assert!(db.try_lock_metagame_jobs().await.expect("lock query").is_none());
+        lock.release().await.expect("released");
+
+        let lock = db.try_lock_metagame_jobs().await.expect("lock query");
+        assert!(lock.is_some(), "releasing frees the lock");
+    }
+
+    #[tokio::test]
+    async fn unchanged_signature_cards_keep_their_computed_at() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let archetype_id = db
+            .upsert_metagame_archetype("Mono Red", "standard", None)
+            .await
+            .expect("archetype");
+        let card = |card_name: &str, weight: f32| SignatureCard {
+            archetype_id,
+            archetype_name: "Mono Red".to_string(),
+            card_name: card_name.to_string(),
+            weight,
+            copies: 4.0,
+            idf: 1.5,
+            format: "standard".to_string(),
+        };
+        let computed_at = || async {
+            sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+                "SELECT MAX(computed_at) FROM archetype_signature_card WHERE format = 'standard'",
+            )
+            .fetch_one(db.pool())
+            .await
+            .expect("computed_at")
+        };
+
+        let cards = [card("Lightning Bolt", 0.1), card("Monastery Swiftspear", 0.2)];
+        assert_eq!(db.replace_signature_cards("standard", &cards).await.expect("stored"), 2);
+        let first = computed_at().await;
+
+        let reordered = [cards[1].clone(), cards[0].clone()];
+        assert_eq!(
+            db.replace_signature_cards("standard", &reordered)
+                .await
+                .expect("stored"),
+            0
+        );
+        assert_eq!(computed_at().await, first);
+
+        let reweighted = [card("Lightning Bolt", 0.3), cards[1].clone()];
+        assert_eq!(
+            db.replace_signature_cards("standard", &reweighted)
+                .await
+                .expect("stored"),
+            2
+        );
+        assert!(computed_at().await > first);
+
+        // Rows stored before copies and IDF never match freshly computed cards.
+        sqlx::query("UPDATE archetype_signature_card SET copies = NULL, idf = NULL")
+            .execute(db.pool())
+            .await
+            .expect("legacy rows");
+        assert_eq!(
+            db.replace_signature_cards("standard", &reweighted)
+                .await
+                .expect("stored"),
+            2
+        );
+        let stored = db.get_signature_cards("standard").await.expect("signature cards");
+        assert!(stored.iter().all(|sc| sc.copies == Some(4.0) && sc.idf == Some(1.5)));
+    }
+
+    #[tokio::test]
+    async fn clusters_are_claimed_once_and_can_be_reopened() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let cluster = NewArchetypeCluster {
+            suggested_name: "Lightning Bolt".to_string(),
+            cards: Vec::new(),
+            members: Vec::new(),
+        };
+        db.replace_proposed_clusters("standard", &[cluster])
+            .await
+            .expect("proposed");
+        let cluster_id = db
+            .list_archetype_clusters(Some("standard"), false)
+            .await
+            .expect("clusters")[0]
+            .id;
+        let archetype_id = db
+            .upsert_metagame_archetype("Mono Red", "standard", None)
+            .await
+            .expect("archetype");
+
+        assert!(!db.reopen_archetype_cluster(cluster_id).await.expect("reopen"));
+        assert!(
+            db.resolve_archetype_cluster(cluster_id, "accepted", Some(archetype_id))
+                .await
+                .expect("claim")
+        );
+        assert!(
+            !db.resolve_archetype_cluster(cluster_id, "accepted", Some(archetype_id))
+                .await
+                .expect("claim"),
+            "a cluster is only claimed once"
+        );
+
+        assert!(db.reopen_archetype_cluster(cluster_id).await.expect("reopen"));
+        let cluster = db
+            .get_archetype_cluster(cluster_id)
+            .await
+            .expect("cluster")
+            .expect("exists");
+        assert_eq!(cluster.status, "proposed");
+        assert_eq!(cluster.archetype_id, None);
+    }
+
+    #[tokio::test]
+    async fn interrupted_job_runs_are_marked_failed() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let interrupted = db.start_metagame_job_run("scrape", "standard").await.expect("started");
+        let finished = db
+            .start_metagame_job_run("signatures", "standard")
+            .await
+            .expect("started");
+        db.finish_metagame_job_run(finished, 3, None).await.expect("finished");
+
+        assert_eq!(db.fail_interrupted_metagame_job_runs().await.expect("marked"), 1);
+        let runs = db.list_metagame_job_runs(10).await.expect("runs");
+        let run = |id| runs.iter().find(|r| r.id == id).expect("run");
+        assert_eq!(run(interrupted).status, "failed");
+        assert_eq!(run(interrupted).error.as_deref(), Some("interrupted"));
+        assert!(run(interrupted).finished_at.is_some());
+        assert_eq!(run(finished).status, "succeeded");
+    }
 }
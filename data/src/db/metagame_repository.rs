@@ -1,6 +1,17 @@
+use arenabuddy_core::{
+    display::{
+        deck::StockList,
+        metagame::{MetagameTrends, TrendInterval},
+    },
+    models::Card,
+};
+use chrono::NaiveDate;
+use sqlx::types::Uuid;
+
 use super::metagame_models::{
-    CardFrequencyRow, MatchArchetype, MetagameDeck, MetagameDeckCard, MetagameTournament, SignatureCard,
-    SignatureCardRow, UnclassifiedMatchRow,
+    ArchetypeClusterCard, ArchetypeClusterMember, ArchetypeClusterRow, ArchetypeOverrideRow, LabelledDeckCardRow,
+    MatchArchetype, MetagameDeck, MetagameDeckCard, MetagameJobRun, MetagameTournament, NewArchetypeCluster,
+    SignatureCard, SignatureCardRow, UnclassifiedDeckRow, UnclassifiedMatchRow,
 };
 use crate::Result;
 
@@ -16,16 +27,78 @@ pub trait MetagameRepository: Send + Sync + 'static {
         cards: &[MetagameDeckCard],
     ) -> Result<i32>;
     async fn metagame_stats(&self, format: &str) -> Result<MetagameStatsResult>;
+    /// Archetype shares of the format's tournament decks dated `since` or later, bucketed by `interval`.
+    async fn metagame_trends(&self, format: &str, interval: TrendInterval, since: NaiveDate) -> Result<MetagameTrends>;
 
     // Classification methods
-    async fn get_card_frequencies(&self, format: &str) -> Result<Vec<CardFrequencyRow>>;
+    /// Mainboard cards of the format's decks that have an archetype, ordered by deck.
+    async fn get_labelled_deck_cards(&self, format: &str) -> Result<Vec<LabelledDeckCardRow>>;
+    /// The archetype's average mainboard over the format's scraped decks, or `None` if none
+    /// were scraped. The archetype name is matched case-insensitively.
+    async fn get_stock_list(&self, format: &str, archetype_name: &str) -> Result<Option<StockList>>;
+    /// Replace the format's signature cards. Returns the number written, 0 if they're unchanged,
+    /// in which case the stored cards keep their `computed_at` so no match turns stale.
     async fn replace_signature_cards(&self, format: &str, cards: &[SignatureCard]) -> Result<u64>;
     async fn get_signature_cards(&self, format: &str) -> Result<Vec<SignatureCardRow>>;
     async fn get_unclassified_matches(&self, format: &str) -> Result<Vec<UnclassifiedMatchRow>>;
+    /// Classified matches in `format` whose classification predates its current signature cards.
+    async fn get_stale_classified_matches(&self, format: &str) -> Result<Vec<UnclassifiedMatchRow>>;
+    /// Replace the classifier's archetypes for `match_ids` with `archetypes` in one transaction.
+    /// Overrides are kept.
+    async fn replace_match_archetypes(&self, match_ids: &[Uuid], archetypes: &[MatchArchetype]) -> Result<()>;
     async fn upsert_match_archetype(&self, archetype: &MatchArchetype) -> Result<()>;
     async fn get_match_deck_cards(&self, match_id: &str) -> Result<Vec<String>>;
     async fn get_match_opponent_cards(&self, match_id: &str) -> Result<Vec<String>>;
+    /// One side's cards resolved through the cards database, one entry per copy. Only the
+    /// cards seen are known for the opponent.
+    async fn get_match_side_cards(&self, match_id: &str, side: &str) -> Result<Vec<Card>>;
     async fn get_match_archetypes(&self, match_id: &str) -> Result<(Option<String>, Option<String>)>;
+    /// Both sides' classifications with their evidence. Overrides take precedence.
+    async fn get_match_classifications(&self, match_id: &str) -> Result<Vec<MatchArchetype>>;
+
+    // Manual overrides
+    async fn set_match_archetype_override(
+        &self,
+        match_id: &str,
+        side: &str,
+        archetype_name: &str,
+        set_by: Option<Uuid>,
+    ) -> Result<()>;
+    /// Returns false if the side had no override.
+    async fn clear_match_archetype_override(&self, match_id: &str, side: &str) -> Result<bool>;
+    async fn get_archetype_overrides(&self, format: &str) -> Result<Vec<ArchetypeOverrideRow>>;
+    /// Delete the format's decks from `source` whose id isn't in `keep`. Returns the number deleted.
+    async fn prune_metagame_decks(&self, source: &str, format: &str, keep: &[String]) -> Result<u64>;
+
+    // Archetype discovery
+    /// Unclassified controller and opponent decks in `format`, newest matches first.
+    async fn get_unclassified_decks(&self, format: &str, limit: i64) -> Result<Vec<UnclassifiedDeckRow>>;
+    /// Replace the format's proposed clusters; accepted and dismissed ones are kept.
+    async fn replace_proposed_clusters(&self, format: &str, clusters: &[NewArchetypeCluster]) -> Result<u64>;
+    /// Clusters in `format`, or every format if `None`, largest first.
+    async fn list_archetype_clusters(
+        &self,
+        format: Option<&str>,
+        include_resolved: bool,
+    ) -> Result<Vec<ArchetypeClusterRow>>;
+    async fn get_archetype_cluster(&self, cluster_id: i32) -> Result<Option<ArchetypeClusterRow>>;
+    async fn get_archetype_cluster_cards(&self, cluster_id: i32) -> Result<Vec<ArchetypeClusterCard>>;
+    async fn get_archetype_cluster_members(&self, cluster_id: i32) -> Result<Vec<ArchetypeClusterMember>>;
+    /// Mark a proposed cluster `accepted` or `dismissed`. Returns false if it wasn't proposed.
+    async fn resolve_archetype_cluster(&self, cluster_id: i32, status: &str, archetype_id: Option<i32>)
+    -> Result<bool>;
+    /// Return an accepted cluster to `proposed`. Returns false if it wasn't accepted.
+    async fn reopen_archetype_cluster(&self, cluster_id: i32) -> Result<bool>;
+
+    // Scheduled job bookkeeping
+    async fn start_metagame_job_run(&self, job: &str, format: &str) -> Result<i64>;
+    /// Mark runs still `running` as failed, for runs cut short by a crash. Only call this while
+    /// holding the lock that keeps jobs to one process. Returns the number marked.
+    async fn fail_interrupted_metagame_job_runs(&self) -> Result<u64>;
+    /// Mark a run finished: failed if `error` is set, succeeded otherwise.
+    async fn finish_metagame_job_run(&self, id: i64, items: i64, error: Option<&str>) -> Result<()>;
+    /// The most recent runs, newest first.
+    async fn list_metagame_job_runs(&self, limit: i64) -> Result<Vec<MetagameJobRun>>;
 }
 
 #[derive(Debug, Clone)]
@@ -1,18 +1,44 @@
+mod account_postgres;
+pub mod account_repository;
 pub mod auth_repository;
 mod card_postgres;
 pub mod card_repository;
+mod community_postgres;
+pub mod community_repository;
+mod debug_postgres;
 pub mod debug_repository;
+mod draft_postgres;
+pub mod draft_repository;
+mod export_postgres;
+pub mod export_repository;
 pub mod metagame_models;
 mod metagame_postgres;
 pub mod metagame_repository;
 pub mod models;
 mod postgres;
 mod repository;
+mod share_postgres;
+pub mod share_repository;
+pub mod team_models;
+mod team_postgres;
+pub mod team_repository;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+mod webhook_postgres;
+pub mod webhook_repository;
 
+pub use account_repository::AccountRepository;
 pub use auth_repository::AuthRepository;
 pub use card_repository::CardRepository;
+pub use community_repository::CommunityRepository;
 pub use debug_repository::DebugRepository;
+pub use draft_repository::DraftRepository;
+pub use export_repository::ExportRepository;
+pub use metagame_postgres::MetagameJobsLock;
 pub use metagame_repository::MetagameRepository;
-pub use models::{AppUser, RefreshToken};
+pub use models::{AppUser, RefreshToken, UserIdentity};
 pub use postgres::PostgresMatchDB as MatchDB;
 pub use repository::ArenabuddyRepository;
+pub use share_repository::ShareRepository;
+pub use team_repository::TeamRepository;
+pub use webhook_repository::WebhookRepository;
@@ -1,11 +1,14 @@
+use chrono::{DateTime, Utc};
 use sqlx::{FromRow, types::Uuid};
 
 #[derive(Debug, FromRow)]
 pub struct AppUser {
     pub id: Uuid,
-    pub discord_id: String,
+    pub discord_id: Option<String>,
     pub username: String,
     pub avatar_url: Option<String>,
+    /// Set while a requested account deletion is pending.
+    pub deletion_scheduled_for: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, FromRow)]
@@ -14,3 +17,12 @@ pub struct RefreshToken {
     pub user_id: Uuid,
     pub revoked: bool,
 }
+
+/// A login identity (Discord account, OIDC subject, local username) linked to an [`AppUser`].
+#[derive(Debug, Clone, FromRow)]
+pub struct UserIdentity {
+    pub user_id: Uuid,
+    pub provider: String,
+    pub subject: String,
+    pub display_name: Option<String>,
+}
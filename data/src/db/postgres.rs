@@ -5,8 +5,8 @@
 use arenabuddy_core::{
     cards::CardsDatabase,
     models::{
-        ArenaId, Deck, Draft, DraftPack, Format, GameEventLog, MTGADraft, MTGAMatch, MTGAMatchBuilder, MatchResult,
-        MatchResultBuilder, Mulligan,
+        ArenaId, Card, Deck, Draft, DraftPack, EventDescriptor, EventRules, Format, GameEventLog, MTGADraft, MTGAMatch,
+        MTGAMatchBuilder, MatchResult, MatchResultBuilder, Mulligan,
     },
     player_log::{
         ingest::{DraftWriter, ReplayWriter},
@@ -60,17 +60,23 @@ struct EventLogRow {
     events_json: String,
 }
 
-use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
 
 use arenabuddy_core::display::{
     match_summary::MatchSummary,
-    stats::{MatchStats, MulliganBucket, OpponentRecord, TimeWindow},
+    stats::{
+        ArchetypeMatchup, DeckStats, LimitedRecord, MatchStats, MatchupGrouping, MulliganBucket, OpponentRecord,
+        TimeWindow,
+    },
 };
 
 use super::{
     auth_repository::AuthRepository,
-    debug_repository::DebugRepository,
-    models::{AppUser, RefreshToken},
+    metagame_postgres::EventFormatCache,
+    models::{AppUser, RefreshToken, UserIdentity},
 };
 use crate::{Error, Result, db::repository::ArenabuddyRepository};
 
@@ -79,6 +85,7 @@ pub struct PostgresMatchDB {
     pool: PgPool,
     _db: Option<Arc<PostgreSQL>>,
     cards: CardsDatabase,
+    event_formats: Arc<Mutex<EventFormatCache>>,
 }
 
 impl PostgresMatchDB {
@@ -86,25 +93,58 @@ impl PostgresMatchDB {
         &self.pool
     }
 
-    /// Map Arena IDs to card names using the cards database.
-    pub(crate) fn arena_ids_to_card_names(&self, arena_ids: &[i32]) -> Vec<String> {
-        let mut names = Vec::new();
-        let mut seen = std::collections::HashSet::new();
-        for id in arena_ids {
-            if !seen.insert(id) {
-                continue;
-            }
-            if let Some(card) = self.cards.get(id) {
-                names.push(card.name.clone());
-            }
+    pub(crate) fn event_formats(&self) -> std::sync::MutexGuard<'_, EventFormatCache> {
+        self.event_formats.lock().expect("event format cache poisoned")
+    }
+
+    #[cfg(any(test, feature = "test-util"))]
+    pub(crate) fn with_pool(pool: PgPool, cards: CardsDatabase) -> Self {
+        Self {
+            pool,
+            _db: None,
+            cards,
+            event_formats: Arc::default(),
         }
-        names
+    }
+
+    /// Connections currently open in the pool, and how many of them are idle.
+    pub fn pool_stats(&self) -> (u32, usize) {
+        (self.pool.size(), self.pool.num_idle())
+    }
+
+    /// Check that the database accepts queries.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no connection could be acquired or the query fails.
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Map Arena IDs to card names using the cards database, keeping one name per copy.
+    pub(crate) fn arena_ids_to_card_names(&self, arena_ids: &[i32]) -> Vec<String> {
+        arena_ids
+            .iter()
+            .filter_map(|id| self.cards.get(id))
+            .map(|card| card.name.clone())
+            .collect()
+    }
+
+    /// Resolve Arena IDs through the cards database, keeping one card per copy.
+    pub(crate) fn arena_ids_to_cards(&self, arena_ids: &[i32]) -> Vec<Card> {
+        arena_ids.iter().filter_map(|id| self.cards.get(id)).cloned().collect()
     }
 
     pub async fn new(url: Option<&str>, cards: CardsDatabase) -> Result<Self> {
         if let Some(url) = url {
             let pool = PgPool::connect(url).await?;
-            Ok(Self { pool, _db: None, cards })
+            Ok(Self {
+                pool,
+                _db: None,
+                cards,
+                event_formats: Arc::default(),
+            })
         } else {
             // Configure persistent embedded PostgreSQL
             let db_path = Self::get_embedded_db_path()?;
@@ -161,6 +201,7 @@ impl PostgresMatchDB {
                 pool,
                 _db: Some(Arc::new(db)),
                 cards,
+                event_formats: Arc::default(),
             })
         }
     }
@@ -394,8 +435,14 @@ impl PostgresMatchDB {
         info!("Writing draft to database!");
 
         let mut tx = self.pool.begin().await?;
+        Self::insert_draft_with_packs(draft, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
 
-        Self::insert_draft(draft.draft(), &mut tx).await?;
+    /// Writes the draft and its picks inside `tx`, replacing an existing draft with the same id.
+    pub(crate) async fn insert_draft_with_packs(draft: &MTGADraft, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+        Self::insert_draft(draft.draft(), tx).await?;
 
         for pack in draft.packs() {
             Self::insert_draft_pack(
@@ -405,18 +452,16 @@ impl PostgresMatchDB {
                 pack.selection_number().into(),
                 pack.picked_card(),
                 pack.cards(),
-                &mut tx,
+                tx,
             )
             .await?;
         }
-
-        tx.commit().await?;
         Ok(())
     }
 
     async fn query_record(
         &self,
-        user_id: Option<Uuid>,
+        user_ids: Option<&[Uuid]>,
         scope: &str,
         cutoff: Option<DateTime<Utc>>,
     ) -> Result<(i64, i64)> {
@@ -432,10 +477,10 @@ impl PostgresMatchDB {
                 COUNT(DISTINCT CASE WHEN mr.winning_team_id = m.controller_seat_id THEN m.id END) AS wins
             FROM match m
             JOIN match_result mr ON m.id = mr.match_id AND mr.result_scope = $2
-            WHERE ($1::uuid IS NULL OR m.user_id = $1)
+            WHERE ($1::uuid[] IS NULL OR m.user_id = ANY($1))
               AND ($3::timestamptz IS NULL OR m.created_at >= $3)",
         )
-        .bind(user_id)
+        .bind(user_ids)
         .bind(scope)
         .bind(cutoff)
         .fetch_one(&self.pool)
@@ -446,7 +491,7 @@ impl PostgresMatchDB {
 
     async fn query_play_draw_stats(
         &self,
-        user_id: Option<Uuid>,
+        user_ids: Option<&[Uuid]>,
         cutoff: Option<DateTime<Utc>>,
     ) -> Result<(i64, i64, i64, i64)> {
         #[derive(FromRow)]
@@ -465,11 +510,11 @@ impl PostgresMatchDB {
             JOIN mulligan mul ON m.id = mul.match_id AND mul.decision = 'Keep'
                 AND mul.play_draw IN ('Play', 'Draw')
             JOIN match_result mr ON m.id = mr.match_id AND mr.result_scope = 'MatchScope_Game' AND mr.game_number = mul.game_number
-            WHERE ($1::uuid IS NULL OR m.user_id = $1)
+            WHERE ($1::uuid[] IS NULL OR m.user_id = ANY($1))
               AND ($2::timestamptz IS NULL OR m.created_at >= $2)
             GROUP BY mul.play_draw",
         )
-        .bind(user_id)
+        .bind(user_ids)
         .bind(cutoff)
         .fetch_all(&self.pool)
         .await?;
@@ -492,7 +537,7 @@ impl PostgresMatchDB {
 
     async fn query_mulligan_stats(
         &self,
-        user_id: Option<Uuid>,
+        user_ids: Option<&[Uuid]>,
         cutoff: Option<DateTime<Utc>>,
     ) -> Result<Vec<MulliganBucket>> {
         #[derive(FromRow)]
@@ -512,12 +557,12 @@ impl PostgresMatchDB {
             FROM match m
             JOIN mulligan mul ON m.id = mul.match_id AND mul.decision = 'Keep'
             JOIN match_result mr ON m.id = mr.match_id AND mr.result_scope = 'MatchScope_Game' AND mr.game_number = mul.game_number
-            WHERE ($1::uuid IS NULL OR m.user_id = $1)
+            WHERE ($1::uuid[] IS NULL OR m.user_id = ANY($1))
               AND ($2::timestamptz IS NULL OR m.created_at >= $2)
             GROUP BY mul.number_to_keep
             ORDER BY mul.number_to_keep DESC",
         )
-        .bind(user_id)
+        .bind(user_ids)
         .bind(cutoff)
         .fetch_all(&self.pool)
         .await?;
@@ -535,7 +580,7 @@ impl PostgresMatchDB {
 
     async fn query_opponent_stats(
         &self,
-        user_id: Option<Uuid>,
+        user_ids: Option<&[Uuid]>,
         cutoff: Option<DateTime<Utc>>,
     ) -> Result<Vec<OpponentRecord>> {
         #[derive(FromRow)]
@@ -554,13 +599,13 @@ impl PostgresMatchDB {
                 COUNT(DISTINCT CASE WHEN mr.winning_team_id != m.controller_seat_id THEN m.id END) AS losses
             FROM match m
             JOIN match_result mr ON m.id = mr.match_id AND mr.result_scope = 'MatchScope_Match'
-            WHERE ($1::uuid IS NULL OR m.user_id = $1)
+            WHERE ($1::uuid[] IS NULL OR m.user_id = ANY($1))
               AND ($2::timestamptz IS NULL OR m.created_at >= $2)
             GROUP BY m.opponent_player_name
             ORDER BY matches DESC
             LIMIT 10",
         )
-        .bind(user_id)
+        .bind(user_ids)
         .bind(cutoff)
         .fetch_all(&self.pool)
         .await?;
@@ -575,6 +620,148 @@ impl PostgresMatchDB {
             })
             .collect())
     }
+
+    /// Aggregate [`MatchStats`] over the matches of `user_ids` (all matches when `None`).
+    pub(crate) async fn match_stats_for_users(
+        &self,
+        user_ids: Option<&[Uuid]>,
+        time_window: TimeWindow,
+    ) -> Result<MatchStats> {
+        let cutoff = time_window.cutoff();
+        let (total_matches, match_wins) = self.query_record(user_ids, "MatchScope_Match", cutoff).await?;
+        let (total_games, game_wins) = self.query_record(user_ids, "MatchScope_Game", cutoff).await?;
+        let (play_wins, play_losses, draw_wins, draw_losses) = self.query_play_draw_stats(user_ids, cutoff).await?;
+        let mulligan_stats = self.query_mulligan_stats(user_ids, cutoff).await?;
+        let opponents = self.query_opponent_stats(user_ids, cutoff).await?;
+
+        Ok(MatchStats {
+            total_matches,
+            match_wins,
+            match_losses: total_matches - match_wins,
+            total_games,
+            game_wins,
+            game_losses: total_games - game_wins,
+            play_wins,
+            play_losses,
+            draw_wins,
+            draw_losses,
+            mulligan_stats,
+            opponents,
+        })
+    }
+
+    /// Match and game records grouped by (controller archetype or deck, opponent archetype). Only
+    /// matches where both sides have been classified are counted, and with a `format` only those
+    /// whose event is of that format (e.g. `standard`).
+    pub(crate) async fn query_archetype_matchups(
+        &self,
+        user_ids: Option<&[Uuid]>,
+        cutoff: Option<DateTime<Utc>>,
+        grouping: MatchupGrouping,
+        format: Option<&str>,
+    ) -> Result<Vec<ArchetypeMatchup>> {
+        #[derive(FromRow)]
+        struct MatchupRow {
+            archetype: String,
+            deck_id: Option<String>,
+            opponent_archetype: String,
+            matches: i64,
+            wins: i64,
+            losses: i64,
+            game1_wins: i64,
+            game1_losses: i64,
+            post_board_wins: i64,
+            post_board_losses: i64,
+        }
+
+        let event_ids = match format {
+            Some(format) => self.format_event_ids(format).await?,
+            None => None,
+        };
+
+        // Decks are fingerprinted as in `get_deck_stats` and named after the controller archetype
+        // most of their matches were classified as.
+        let rows: Vec<MatchupRow> = sqlx::query_as(
+            r"WITH matchup_match AS (
+                SELECT
+                    m.id,
+                    m.controller_seat_id,
+                    ca.archetype_name AS archetype,
+                    oa.archetype_name AS opponent_archetype,
+                    mr.winning_team_id = m.controller_seat_id AS won,
+                    CASE WHEN $3 THEN deck_fingerprint(d.deck_cards) END AS deck_id
+                FROM match m
+                JOIN match_result mr ON m.id = mr.match_id AND mr.result_scope = 'MatchScope_Match'
+                JOIN effective_match_archetype ca ON m.id = ca.match_id AND ca.side = 'controller'
+                JOIN effective_match_archetype oa ON m.id = oa.match_id AND oa.side = 'opponent'
+                LEFT JOIN deck d ON m.id = d.match_id AND d.game_number = 1
+                WHERE ($1::uuid[] IS NULL OR m.user_id = ANY($1))
+                  AND ($2::timestamptz IS NULL OR m.created_at >= $2)
+                  AND (NOT $3 OR d.match_id IS NOT NULL)
+                  AND ($4::text[] IS NULL OR m.format = ANY($4))
+            ),
+            deck_names AS (
+                SELECT DISTINCT ON (deck_id) deck_id, archetype
+                FROM matchup_match
+                WHERE deck_id IS NOT NULL
+                GROUP BY deck_id, archetype
+                ORDER BY deck_id, COUNT(*) DESC, archetype
+            ),
+            match_games AS (
+                SELECT
+                    mm.id,
+                    COUNT(*) FILTER (WHERE gr.winning_team_id = mm.controller_seat_id AND gr.game_number = 1)
+                        AS game1_wins,
+                    COUNT(*) FILTER (WHERE gr.winning_team_id != mm.controller_seat_id AND gr.game_number = 1)
+                        AS game1_losses,
+                    COUNT(*) FILTER (WHERE gr.winning_team_id = mm.controller_seat_id AND gr.game_number > 1)
+                        AS post_board_wins,
+                    COUNT(*) FILTER (WHERE gr.winning_team_id != mm.controller_seat_id AND gr.game_number > 1)
+                        AS post_board_losses
+                FROM matchup_match mm
+                JOIN match_result gr ON mm.id = gr.match_id AND gr.result_scope = 'MatchScope_Game'
+                GROUP BY mm.id
+            )
+            SELECT
+                COALESCE(n.archetype, mm.archetype) AS archetype,
+                mm.deck_id,
+                mm.opponent_archetype,
+                COUNT(*) AS matches,
+                COUNT(*) FILTER (WHERE mm.won) AS wins,
+                COUNT(*) FILTER (WHERE NOT mm.won) AS losses,
+                COALESCE(SUM(g.game1_wins), 0)::bigint AS game1_wins,
+                COALESCE(SUM(g.game1_losses), 0)::bigint AS game1_losses,
+                COALESCE(SUM(g.post_board_wins), 0)::bigint AS post_board_wins,
+                COALESCE(SUM(g.post_board_losses), 0)::bigint AS post_board_losses
+            FROM matchup_match mm
+            LEFT JOIN match_games g ON mm.id = g.id
+            LEFT JOIN deck_names n ON mm.deck_id = n.deck_id
+            GROUP BY COALESCE(n.archetype, mm.archetype), mm.deck_id, mm.opponent_archetype
+            ORDER BY matches DESC, archetype, opponent_archetype, mm.deck_id",
+        )
+        .bind(user_ids)
+        .bind(cutoff)
+        .bind(grouping == MatchupGrouping::Deck)
+        .bind(event_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ArchetypeMatchup {
+                archetype: row.archetype,
+                deck_id: row.deck_id,
+                opponent_archetype: row.opponent_archetype,
+                matches: row.matches,
+                wins: row.wins,
+                losses: row.losses,
+                game1_wins: row.game1_wins,
+                game1_losses: row.game1_losses,
+                post_board_wins: row.post_board_wins,
+                post_board_losses: row.post_board_losses,
+            })
+            .collect())
+    }
 }
 
 #[async_trait::async_trait]
@@ -643,6 +830,7 @@ impl ArenabuddyRepository for PostgresMatchDB {
         }
 
         tx.commit().await?;
+        self.remember_event_id(mtga_match.format());
         Ok(())
     }
 
@@ -743,8 +931,8 @@ impl ArenabuddyRepository for PostgresMatchDB {
                 FROM match_result gr
                 WHERE gr.match_id = m.id AND gr.result_scope = 'MatchScope_Game'
             ) gs ON true
-            LEFT JOIN match_archetype ca ON m.id = ca.match_id AND ca.side = 'controller'
-            LEFT JOIN match_archetype oa ON m.id = oa.match_id AND oa.side = 'opponent'
+            LEFT JOIN effective_match_archetype ca ON m.id = ca.match_id AND ca.side = 'controller'
+            LEFT JOIN effective_match_archetype oa ON m.id = oa.match_id AND oa.side = 'opponent'
             WHERE ($1::uuid IS NULL OR m.user_id = $1)
             ORDER BY m.created_at DESC",
         )
@@ -978,6 +1166,7 @@ impl ArenabuddyRepository for PostgresMatchDB {
         }
 
         tx.commit().await?;
+        self.remember_event_id(mtga_match.format());
         Ok(())
     }
 
@@ -1024,27 +1213,239 @@ impl ArenabuddyRepository for PostgresMatchDB {
 
     #[instrument(skip(self))]
     async fn get_match_stats(&self, user_id: Option<Uuid>, time_window: TimeWindow) -> Result<MatchStats> {
-        let cutoff = time_window.cutoff();
-        let (total_matches, match_wins) = self.query_record(user_id, "MatchScope_Match", cutoff).await?;
-        let (total_games, game_wins) = self.query_record(user_id, "MatchScope_Game", cutoff).await?;
-        let (play_wins, play_losses, draw_wins, draw_losses) = self.query_play_draw_stats(user_id, cutoff).await?;
-        let mulligan_stats = self.query_mulligan_stats(user_id, cutoff).await?;
-        let opponents = self.query_opponent_stats(user_id, cutoff).await?;
+        self.match_stats_for_users(user_id.as_ref().map(std::slice::from_ref), time_window)
+            .await
+    }
 
-        Ok(MatchStats {
-            total_matches,
-            match_wins,
-            match_losses: total_matches - match_wins,
-            total_games,
-            game_wins,
-            game_losses: total_games - game_wins,
-            play_wins,
-            play_losses,
-            draw_wins,
-            draw_losses,
-            mulligan_stats,
-            opponents,
-        })
+    #[instrument(skip(self))]
+    async fn get_limited_stats(&self, user_id: Option<Uuid>, time_window: TimeWindow) -> Result<Vec<LimitedRecord>> {
+        #[derive(FromRow)]
+        struct LabelRow {
+            event_id: String,
+            label: String,
+            matches: i64,
+            wins: i64,
+            losses: i64,
+        }
+
+        let rows: Vec<LabelRow> = sqlx::query_as(
+            r"SELECT
+                m.format AS event_id,
+                ca.archetype_name AS label,
+                COUNT(DISTINCT m.id) AS matches,
+                COUNT(DISTINCT CASE WHEN mr.winning_team_id = m.controller_seat_id THEN m.id END) AS wins,
+                COUNT(DISTINCT CASE WHEN mr.winning_team_id != m.controller_seat_id THEN m.id END) AS losses
+            FROM match m
+            JOIN match_result mr ON m.id = mr.match_id AND mr.result_scope = 'MatchScope_Match'
+            JOIN effective_match_archetype ca ON m.id = ca.match_id AND ca.side = 'controller'
+            WHERE m.format IS NOT NULL
+              AND ($1::uuid IS NULL OR m.user_id = $1)
+              AND ($2::timestamptz IS NULL OR m.created_at >= $2)
+            GROUP BY m.format, ca.archetype_name",
+        )
+        .bind(user_id)
+        .bind(time_window.cutoff())
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Event ids carry the set, so records are merged per set once the events are described.
+        let rules = EventRules::active();
+        let mut by_set: BTreeMap<(String, String), LimitedRecord> = BTreeMap::new();
+        for row in rows {
+            let Some(event) = rules.describe(&row.event_id).filter(EventDescriptor::is_limited) else {
+                continue;
+            };
+            let set_code = event.set_code.unwrap_or(event.format);
+            let record = by_set
+                .entry((set_code.clone(), row.label.clone()))
+                .or_insert_with(|| LimitedRecord {
+                    set_code,
+                    label: row.label,
+                    ..Default::default()
+                });
+            record.matches += row.matches;
+            record.wins += row.wins;
+            record.losses += row.losses;
+        }
+
+        let mut records: Vec<LimitedRecord> = by_set.into_values().collect();
+        records.sort_by(|a, b| a.set_code.cmp(&b.set_code).then(b.matches.cmp(&a.matches)));
+        Ok(records)
+    }
+
+    #[instrument(skip(self))]
+    async fn get_archetype_matchups(
+        &self,
+        user_id: Option<Uuid>,
+        time_window: TimeWindow,
+        grouping: MatchupGrouping,
+        format: Option<&str>,
+    ) -> Result<Vec<ArchetypeMatchup>> {
+        let user_ids = user_id.map(|id| [id]);
+        self.query_archetype_matchups(
+            user_ids.as_ref().map(<[Uuid; 1]>::as_slice),
+            time_window.cutoff(),
+            grouping,
+            format,
+        )
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn get_deck_stats(&self, user_id: Option<Uuid>, time_window: TimeWindow) -> Result<Vec<DeckStats>> {
+        #[derive(FromRow)]
+        struct DeckRow {
+            deck_id: String,
+            name: Option<String>,
+            latest_match_id: Uuid,
+            last_played: Option<NaiveDateTime>,
+            matches: i64,
+            match_wins: i64,
+            match_losses: i64,
+            game_wins: i64,
+            game_losses: i64,
+            game1_wins: i64,
+            game1_losses: i64,
+            post_board_wins: i64,
+            post_board_losses: i64,
+            play_wins: i64,
+            play_losses: i64,
+            draw_wins: i64,
+            draw_losses: i64,
+            kept_hands: i64,
+            mulligans: i64,
+            average_turns: Option<f64>,
+        }
+
+        // A deck is its game 1 main deck, fingerprinted by `deck_fingerprint`. Game length is the last
+        // turn number in the game's event log.
+        let rows: Vec<DeckRow> = sqlx::query_as(
+            r"WITH match_deck AS (
+                SELECT
+                    m.id,
+                    m.controller_seat_id,
+                    m.created_at,
+                    deck_fingerprint(d.deck_cards) AS deck_id
+                FROM match m
+                JOIN deck d ON m.id = d.match_id AND d.game_number = 1
+                WHERE ($1::uuid IS NULL OR m.user_id = $1)
+                  AND ($2::timestamptz IS NULL OR m.created_at >= $2)
+            ),
+            match_records AS (
+                SELECT
+                    md.deck_id,
+                    COUNT(*) AS matches,
+                    COUNT(CASE WHEN mr.winning_team_id = md.controller_seat_id THEN 1 END) AS match_wins,
+                    COUNT(CASE WHEN mr.winning_team_id != md.controller_seat_id THEN 1 END) AS match_losses,
+                    MAX(md.created_at) AS last_played,
+                    (ARRAY_AGG(md.id ORDER BY md.created_at DESC))[1] AS latest_match_id
+                FROM match_deck md
+                JOIN match_result mr ON md.id = mr.match_id AND mr.result_scope = 'MatchScope_Match'
+                GROUP BY md.deck_id
+            ),
+            games AS (
+                SELECT
+                    md.deck_id,
+                    mr.game_number,
+                    mr.winning_team_id = md.controller_seat_id AS won,
+                    keep.play_draw,
+                    keep.number_to_keep,
+                    (
+                        SELECT MAX((event->'turn'->>'turn_number')::int)
+                        FROM match_event_log log
+                        CROSS JOIN LATERAL jsonb_array_elements(log.events_json::jsonb) AS event
+                        WHERE log.match_id = md.id AND log.game_number = mr.game_number
+                    ) AS turns
+                FROM match_deck md
+                JOIN match_result mr ON md.id = mr.match_id AND mr.result_scope = 'MatchScope_Game'
+                LEFT JOIN mulligan keep ON md.id = keep.match_id AND keep.game_number = mr.game_number
+                    AND keep.decision = 'Keep'
+            ),
+            game_records AS (
+                SELECT
+                    deck_id,
+                    COUNT(*) FILTER (WHERE won) AS game_wins,
+                    COUNT(*) FILTER (WHERE NOT won) AS game_losses,
+                    COUNT(*) FILTER (WHERE won AND game_number = 1) AS game1_wins,
+                    COUNT(*) FILTER (WHERE NOT won AND game_number = 1) AS game1_losses,
+                    COUNT(*) FILTER (WHERE won AND game_number > 1) AS post_board_wins,
+                    COUNT(*) FILTER (WHERE NOT won AND game_number > 1) AS post_board_losses,
+                    COUNT(*) FILTER (WHERE won AND play_draw = 'Play') AS play_wins,
+                    COUNT(*) FILTER (WHERE NOT won AND play_draw = 'Play') AS play_losses,
+                    COUNT(*) FILTER (WHERE won AND play_draw = 'Draw') AS draw_wins,
+                    COUNT(*) FILTER (WHERE NOT won AND play_draw = 'Draw') AS draw_losses,
+                    COUNT(number_to_keep) AS kept_hands,
+                    COUNT(*) FILTER (WHERE number_to_keep < 7) AS mulligans,
+                    AVG(turns)::float8 AS average_turns
+                FROM games
+                GROUP BY deck_id
+            ),
+            names AS (
+                SELECT DISTINCT ON (md.deck_id) md.deck_id, ca.archetype_name
+                FROM match_deck md
+                JOIN effective_match_archetype ca ON md.id = ca.match_id AND ca.side = 'controller'
+                GROUP BY md.deck_id, ca.archetype_name
+                ORDER BY md.deck_id, COUNT(*) DESC, ca.archetype_name
+            )
+            SELECT
+                r.deck_id,
+                n.archetype_name AS name,
+                r.latest_match_id,
+                r.last_played,
+                r.matches,
+                r.match_wins,
+                r.match_losses,
+                COALESCE(g.game_wins, 0) AS game_wins,
+                COALESCE(g.game_losses, 0) AS game_losses,
+                COALESCE(g.game1_wins, 0) AS game1_wins,
+                COALESCE(g.game1_losses, 0) AS game1_losses,
+                COALESCE(g.post_board_wins, 0) AS post_board_wins,
+                COALESCE(g.post_board_losses, 0) AS post_board_losses,
+                COALESCE(g.play_wins, 0) AS play_wins,
+                COALESCE(g.play_losses, 0) AS play_losses,
+                COALESCE(g.draw_wins, 0) AS draw_wins,
+                COALESCE(g.draw_losses, 0) AS draw_losses,
+                COALESCE(g.kept_hands, 0) AS kept_hands,
+                COALESCE(g.mulligans, 0) AS mulligans,
+                g.average_turns
+            FROM match_records r
+            LEFT JOIN game_records g ON r.deck_id = g.deck_id
+            LEFT JOIN names n ON r.deck_id = n.deck_id
+            ORDER BY r.matches DESC, r.last_played DESC",
+        )
+        .bind(user_id)
+        .bind(time_window.cutoff())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DeckStats {
+                deck_id: row.deck_id,
+                name: row.name,
+                latest_match_id: row.latest_match_id.to_string(),
+                last_played: row
+                    .last_played
+                    .map(|naive: NaiveDateTime| naive.and_utc())
+                    .unwrap_or_default(),
+                matches: row.matches,
+                match_wins: row.match_wins,
+                match_losses: row.match_losses,
+                game_wins: row.game_wins,
+                game_losses: row.game_losses,
+                game1_wins: row.game1_wins,
+                game1_losses: row.game1_losses,
+                post_board_wins: row.post_board_wins,
+                post_board_losses: row.post_board_losses,
+                play_wins: row.play_wins,
+                play_losses: row.play_losses,
+                draw_wins: row.draw_wins,
+                draw_losses: row.draw_losses,
+                kept_hands: row.kept_hands,
+                mulligans: row.mulligans,
+                average_turns: row.average_turns,
+            })
+            .collect())
     }
 }
 
@@ -1071,32 +1472,195 @@ impl DraftWriter for PostgresMatchDB {
 #[async_trait::async_trait]
 impl AuthRepository for PostgresMatchDB {
     #[instrument(skip(self, avatar_url))]
-    async fn upsert_user(&self, discord_id: &str, username: &str, avatar_url: Option<&str>) -> Result<Uuid> {
-        let row = sqlx::query!(
-            r#"
-            INSERT INTO app_user (discord_id, username, avatar_url)
-            VALUES ($1, $2, $3)
-            ON CONFLICT (discord_id)
-            DO UPDATE SET username = excluded.username, avatar_url = excluded.avatar_url, updated_at = now()
-            RETURNING id
-            "#,
-            discord_id,
-            username,
-            avatar_url
+    async fn upsert_identity_user(
+        &self,
+        provider: &str,
+        subject: &str,
+        username: &str,
+        avatar_url: Option<&str>,
+    ) -> Result<Uuid> {
+        let mut tx = self.pool.begin().await?;
+
+        let existing: Option<(Uuid,)> =
+            sqlx::query_as("SELECT user_id FROM user_identity WHERE provider = $1 AND subject = $2")
+                .bind(provider)
+                .bind(subject)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        let user_id = if let Some((user_id,)) = existing {
+            sqlx::query("UPDATE app_user SET avatar_url = COALESCE(avatar_url, $2), updated_at = now() WHERE id = $1")
+                .bind(user_id)
+                .bind(avatar_url)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                "UPDATE user_identity SET display_name = $3, last_login_at = now()
+                 WHERE provider = $1 AND subject = $2",
+            )
+            .bind(provider)
+            .bind(subject)
+            .bind(username)
+            .execute(&mut *tx)
+            .await?;
+            user_id
+        } else {
+            // `discord_id` is kept populated for Discord users so older clients still see it.
+            let discord_id = (provider == "discord").then_some(subject);
+            let (user_id,): (Uuid,) = sqlx::query_as(
+                "INSERT INTO app_user (discord_id, username, avatar_url) VALUES ($1, $2, $3) RETURNING id",
+            )
+            .bind(discord_id)
+            .bind(username)
+            .bind(avatar_url)
+            .fetch_one(&mut *tx)
+            .await?;
+            sqlx::query("INSERT INTO user_identity (user_id, provider, subject, display_name) VALUES ($1, $2, $3, $4)")
+                .bind(user_id)
+                .bind(provider)
+                .bind(subject)
+                .bind(username)
+                .execute(&mut *tx)
+                .await?;
+            user_id
+        };
+
+        tx.commit().await?;
+        Ok(user_id)
+    }
+
+    #[instrument(skip(self))]
+    async fn link_identity(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        subject: &str,
+        display_name: Option<&str>,
+    ) -> Result<bool> {
+        let row: (Uuid,) = sqlx::query_as(
+            "INSERT INTO user_identity (user_id, provider, subject, display_name)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (provider, subject) DO UPDATE SET last_login_at = now()
+             RETURNING user_id",
         )
+        .bind(user_id)
+        .bind(provider)
+        .bind(subject)
+        .bind(display_name)
         .fetch_one(&self.pool)
         .await?;
 
-        Ok(row.id)
+        Ok(row.0 == user_id)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_identities(&self, user_id: Uuid) -> Result<Vec<UserIdentity>> {
+        let rows: Vec<UserIdentity> = sqlx::query_as(
+            "SELECT user_id, provider, subject, display_name FROM user_identity
+             WHERE user_id = $1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    #[instrument(skip(self))]
+    async fn unlink_identity(&self, user_id: Uuid, provider: &str, subject: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query("DELETE FROM user_identity WHERE user_id = $1 AND provider = $2 AND subject = $3")
+            .bind(user_id)
+            .bind(provider)
+            .bind(subject)
+            .execute(&mut *tx)
+            .await?;
+        // Without its identity the password could only sign in to a fresh account.
+        if provider == "local" {
+            sqlx::query("DELETE FROM local_credential WHERE user_id = $1 AND username = $2")
+                .bind(user_id)
+                .bind(subject)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[instrument(skip(self, password_hash))]
+    async fn create_local_credential(
+        &self,
+        user_id: Option<Uuid>,
+        username: &str,
+        display_name: &str,
+        password_hash: &str,
+    ) -> Result<Option<Uuid>> {
+        let mut tx = self.pool.begin().await?;
+
+        let taken: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM local_credential WHERE username = $1 OR user_id = $2)")
+                .bind(username)
+                .bind(user_id)
+                .fetch_one(&mut *tx)
+                .await?;
+        if taken {
+            return Ok(None);
+        }
+
+        let user_id = match user_id {
+            Some(user_id) => user_id,
+            None => {
+                sqlx::query_scalar("INSERT INTO app_user (username) VALUES ($1) RETURNING id")
+                    .bind(display_name)
+                    .fetch_one(&mut *tx)
+                    .await?
+            }
+        };
+
+        // A concurrent registration can still claim the username between the check and here.
+        let created = sqlx::query(
+            "INSERT INTO local_credential (user_id, username, password_hash) VALUES ($1, $2, $3)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(username)
+        .bind(password_hash)
+        .execute(&mut *tx)
+        .await?;
+        let linked = sqlx::query(
+            "INSERT INTO user_identity (user_id, provider, subject, display_name) VALUES ($1, 'local', $2, $3)
+             ON CONFLICT (provider, subject) DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(username)
+        .bind(display_name)
+        .execute(&mut *tx)
+        .await?;
+        if created.rows_affected() == 0 || linked.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        tx.commit().await?;
+        Ok(Some(user_id))
+    }
+
+    #[instrument(skip(self))]
+    async fn get_local_password_hash(&self, username: &str) -> Result<Option<String>> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT password_hash FROM local_credential WHERE username = $1")
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| r.0))
     }
 
     #[instrument(skip(self))]
     async fn get_user(&self, user_id: Uuid) -> Result<Option<AppUser>> {
-        let row = sqlx::query_as!(
-            AppUser,
-            r#"SELECT id, discord_id, username, avatar_url FROM app_user WHERE id = $1"#,
-            user_id
+        let row: Option<AppUser> = sqlx::query_as(
+            "SELECT id, discord_id, username, avatar_url, deletion_scheduled_for FROM app_user WHERE id = $1",
         )
+        .bind(user_id)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -1164,24 +1728,284 @@ impl AuthRepository for PostgresMatchDB {
     }
 }
 
-#[async_trait::async_trait]
-impl DebugRepository for PostgresMatchDB {
-    #[instrument(skip(self, raw_json))]
-    async fn insert_parse_error(
-        &self,
-        user_id: Option<Uuid>,
-        raw_json: &str,
-        reported_at: DateTime<Utc>,
-    ) -> Result<()> {
-        sqlx::query!(
-            r#"INSERT INTO parse_error (user_id, raw_json, reported_at) VALUES ($1, $2, $3)"#,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ArenabuddyRepository, AuthRepository, MetagameRepository, db::metagame_models::MatchArchetype, testing::test_db,
+    };
+
+    /// A seeded game: its number, whether we won, and the kept hand's size and play/draw, if any.
+    struct Game {
+        number: i32,
+        won: bool,
+        keep: Option<(i32, &'static str)>,
+    }
+
+    const fn game(number: i32, won: bool, keep: Option<(i32, &'static str)>) -> Game {
+        Game { number, won, keep }
+    }
+
+    /// A match for `user_id` on seat 1 with `deck_cards` as its game 1 deck and the given games;
+    /// the match goes to whoever won more of them.
+    async fn seed_match(db: &PostgresMatchDB, user_id: Uuid, deck_cards: &str, games: &[Game]) -> Uuid {
+        let match_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO match (id, controller_seat_id, controller_player_name, opponent_player_name, user_id)
+             VALUES ($1, 1, 'me', 'them', $2)",
+        )
+        .bind(match_id)
+        .bind(user_id)
+        .execute(db.pool())
+        .await
+        .expect("match");
+        sqlx::query("INSERT INTO deck (match_id, game_number, deck_cards, sideboard_cards) VALUES ($1, 1, $2, '[]')")
+            .bind(match_id)
+            .bind(deck_cards)
+            .execute(db.pool())
+            .await
+            .expect("deck");
+
+        let wins = games.iter().filter(|g| g.won).count();
+        let results = games.iter().map(|g| (g.number, "MatchScope_Game", g.won)).chain([(
+            0,
+            "MatchScope_Match",
+            wins * 2 > games.len(),
+        )]);
+        for (number, scope, won) in results {
+            sqlx::query(
+                "INSERT INTO match_result (match_id, game_number, result_scope, winning_team_id)
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(match_id)
+            .bind(number)
+            .bind(scope)
+            .bind(if won { 1 } else { 2 })
+            .execute(db.pool())
+            .await
+            .expect("result");
+        }
+        for g in games {
+            let Some((number_to_keep, play_draw)) = g.keep else {
+                continue;
+            };
+            sqlx::query(
+                "INSERT INTO mulligan
+                     (match_id, game_number, number_to_keep, hand, play_draw, opponent_identity, decision)
+                 VALUES ($1, $2, $3, '', $4, '', 'Keep')",
+            )
+            .bind(match_id)
+            .bind(g.number)
+            .bind(number_to_keep)
+            .bind(play_draw)
+            .execute(db.pool())
+            .await
+            .expect("mulligan");
+        }
+        match_id
+    }
+
+    #[tokio::test]
+    async fn deck_stats_split_game_1_from_post_board_games() {
+        let Some(db) = test_db().await else { return };
+        let user_id = db.upsert_identity_user("local", "me", "me", None).await.expect("user");
+        let other = db
+            .upsert_identity_user("local", "other", "other", None)
+            .await
+            .expect("other user");
+
+        // The same 60 cards listed in a different order are the same deck.
+        seed_match(
+            &db,
             user_id,
-            raw_json,
-            reported_at
+            "[1, 2, 3]",
+            &[
+                game(1, true, Some((7, "Play"))),
+                game(2, false, Some((6, "Draw"))),
+                game(3, true, Some((7, "Play"))),
+            ],
         )
-        .execute(&self.pool)
-        .await?;
+        .await;
+        seed_match(
+            &db,
+            user_id,
+            "[3, 1, 2]",
+            &[game(1, false, Some((7, "Draw"))), game(2, false, Some((7, "Play")))],
+        )
+        .await;
+        let other_deck = seed_match(&db, user_id, "[4, 5]", &[game(1, true, None), game(2, true, None)]).await;
+        seed_match(&db, other, "[1, 2, 3]", &[game(1, true, Some((7, "Play")))]).await;
+
+        let stats = db
+            .get_deck_stats(Some(user_id), TimeWindow::AllTime)
+            .await
+            .expect("deck stats");
+        assert_eq!(stats.len(), 2);
+
+        let deck = &stats[0];
+        assert_eq!((deck.matches, deck.match_wins, deck.match_losses), (2, 1, 1));
+        assert_eq!((deck.game_wins, deck.game_losses), (2, 3));
+        assert_eq!((deck.game1_wins, deck.game1_losses), (1, 1));
+        assert_eq!((deck.post_board_wins, deck.post_board_losses), (1, 2));
+        assert_eq!((deck.play_wins, deck.play_losses), (2, 1));
+        assert_eq!((deck.draw_wins, deck.draw_losses), (0, 2));
+        assert_eq!((deck.kept_hands, deck.mulligans), (5, 1));
+        assert_eq!(deck.name, None);
+
+        let deck = &stats[1];
+        assert_eq!(deck.latest_match_id, other_deck.to_string());
+        assert_eq!((deck.matches, deck.match_wins), (1, 1));
+        assert_eq!((deck.game1_wins, deck.post_board_wins), (1, 1));
+        assert_eq!((deck.play_wins + deck.draw_wins, deck.kept_hands), (0, 0));
+
+        let everyone = db.get_deck_stats(None, TimeWindow::AllTime).await.expect("deck stats");
+        assert_eq!(everyone[0].matches, 3);
+    }
 
-        Ok(())
+    /// Put a seeded match in `event_id` and classify both sides.
+    async fn classify(db: &PostgresMatchDB, match_id: Uuid, event_id: &str, archetype: &str, opponent: &str) {
+        sqlx::query("UPDATE match SET format = $2 WHERE id = $1")
+            .bind(match_id)
+            .bind(event_id)
+            .execute(db.pool())
+            .await
+            .expect("format");
+        for (side, archetype_name) in [("controller", archetype), ("opponent", opponent)] {
+            db.upsert_match_archetype(&MatchArchetype {
+                match_id: match_id.to_string(),
+                side: side.to_string(),
+                archetype_id: None,
+                archetype_name: archetype_name.to_string(),
+                confidence: 0.9,
+                evidence: None,
+                overridden: false,
+            })
+            .await
+            .expect("classified");
+        }
+    }
+
+    #[tokio::test]
+    async fn archetype_matchups_aggregate_by_archetype_or_deck_within_a_format() {
+        let Some(db) = test_db().await else { return };
+        let user_id = db.upsert_identity_user("local", "me", "me", None).await.expect("user");
+
+        let bo1 = seed_match(&db, user_id, "[1, 2, 3]", &[game(1, true, None)]).await;
+        classify(&db, bo1, "Ladder", "Mono Red", "Azorius Control").await;
+        let bo3 = seed_match(
+            &db,
+            user_id,
+            "[3, 2, 1]",
+            &[game(1, false, None), game(2, true, None), game(3, false, None)],
+        )
+        .await;
+        classify(&db, bo3, "Traditional_Ladder", "Mono Red", "Azorius Control").await;
+        let explorer = seed_match(&db, user_id, "[4, 5]", &[game(1, true, None), game(2, true, None)]).await;
+        classify(&db, explorer, "Explorer_Ladder", "Mono Red", "Azorius Control").await;
+        // Matches without an opponent archetype aren't matchups.
+        seed_match(&db, user_id, "[1, 2, 3]", &[game(1, true, None)]).await;
+
+        let users = [user_id];
+        let all = db
+            .query_archetype_matchups(Some(&users), None, MatchupGrouping::Archetype, None)
+            .await
+            .expect("matchups");
+        assert_eq!(all.len(), 1);
+        assert_eq!((all[0].matches, all[0].wins, all[0].losses), (3, 2, 1));
+        assert_eq!(all[0].deck_id, None);
+
+        let standard = db
+            .query_archetype_matchups(Some(&users), None, MatchupGrouping::Archetype, Some("standard"))
+            .await
+            .expect("matchups");
+        assert_eq!(standard.len(), 1);
+        let matchup = &standard[0];
+        assert_eq!(
+            (matchup.archetype.as_str(), matchup.opponent_archetype.as_str()),
+            ("Mono Red", "Azorius Control")
+        );
+        assert_eq!((matchup.matches, matchup.wins, matchup.losses), (2, 1, 1));
+        assert_eq!((matchup.game1_wins, matchup.game1_losses), (1, 1));
+        assert_eq!((matchup.post_board_wins, matchup.post_board_losses), (1, 1));
+
+        let historic = db
+            .query_archetype_matchups(Some(&users), None, MatchupGrouping::Archetype, Some("historic"))
+            .await
+            .expect("matchups");
+        assert!(historic.is_empty());
+
+        // The same cards in any order are one deck, named after its archetype.
+        let decks = db
+            .query_archetype_matchups(Some(&users), None, MatchupGrouping::Deck, None)
+            .await
+            .expect("matchups");
+        let decks: Vec<_> = decks
+            .iter()
+            .map(|m| (m.archetype.as_str(), m.deck_id.as_deref().map(str::len), m.matches))
+            .collect();
+        assert_eq!(decks, [("Mono Red", Some(32), 2), ("Mono Red", Some(32), 1)]);
+    }
+
+    #[tokio::test]
+    async fn signing_in_through_a_linked_identity_keeps_the_account_profile() {
+        let Some(db) = test_db().await else { return };
+        let user_id = db
+            .upsert_identity_user(
+                "discord",
+                "1234",
+                "discord_name",
+                Some("https://cdn.example/avatar.png"),
+            )
+            .await
+            .expect("discord user");
+        assert!(
+            db.link_identity(user_id, "local", "local_name", Some("local_name"))
+                .await
+                .expect("linked")
+        );
+
+        let local = db
+            .upsert_identity_user("local", "local_name", "local_name", None)
+            .await
+            .expect("local sign-in");
+        assert_eq!(local, user_id);
+        let discord = db
+            .upsert_identity_user("discord", "1234", "renamed", Some("https://cdn.example/new.png"))
+            .await
+            .expect("discord sign-in");
+        assert_eq!(discord, user_id);
+
+        let user = db.get_user(user_id).await.expect("user").expect("user exists");
+        assert_eq!(user.username, "discord_name");
+        assert_eq!(user.avatar_url.as_deref(), Some("https://cdn.example/avatar.png"));
+        let mut names: Vec<_> = db
+            .list_identities(user_id)
+            .await
+            .expect("identities")
+            .into_iter()
+            .map(|identity| (identity.provider, identity.display_name))
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                ("discord".to_string(), Some("renamed".to_string())),
+                ("local".to_string(), Some("local_name".to_string()))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn a_missing_avatar_is_filled_in_on_sign_in() {
+        let Some(db) = test_db().await else { return };
+        let user_id = db
+            .upsert_identity_user("oidc", "subject", "name", None)
+            .await
+            .expect("user");
+        db.upsert_identity_user("oidc", "subject", "name", Some("https://cdn.example/avatar.png"))
+            .await
+            .expect("sign-in");
+        let user = db.get_user(user_id).await.expect("user").expect("user exists");
+        assert_eq!(user.avatar_url.as_deref(), Some("https://cdn.example/avatar.png"));
     }
 }
@@ -1,7 +1,7 @@
 use arenabuddy_core::{
     display::{
         match_summary::MatchSummary,
-        stats::{MatchStats, TimeWindow},
+        stats::{ArchetypeMatchup, DeckStats, LimitedRecord, MatchStats, MatchupGrouping, TimeWindow},
     },
     models::{ArenaId, Deck, Draft, GameEventLog, MTGADraft, MTGAMatch, MatchResult, Mulligan},
     player_log::replay::MatchReplay,
@@ -41,4 +41,21 @@ pub trait ArenabuddyRepository: Send + Sync + 'static {
     async fn delete_match(&self, match_id: &str, user_id: Option<Uuid>) -> Result<()>;
 
     async fn get_match_stats(&self, user_id: Option<Uuid>, time_window: TimeWindow) -> Result<MatchStats>;
+
+    /// Limited match records per set and controller deck label, most played first within a set.
+    async fn get_limited_stats(&self, user_id: Option<Uuid>, time_window: TimeWindow) -> Result<Vec<LimitedRecord>>;
+
+    /// Records of each of the controller's decks, most played first. Matches without a game 1
+    /// decklist are left out, since that list is what identifies the deck.
+    async fn get_deck_stats(&self, user_id: Option<Uuid>, time_window: TimeWindow) -> Result<Vec<DeckStats>>;
+
+    /// Records of the controller's archetypes (or decks) against each opponent archetype, most
+    /// played first. With a `format`, only matches of events of that format are counted.
+    async fn get_archetype_matchups(
+        &self,
+        user_id: Option<Uuid>,
+        time_window: TimeWindow,
+        grouping: MatchupGrouping,
+        format: Option<&str>,
+    ) -> Result<Vec<ArchetypeMatchup>>;
 }
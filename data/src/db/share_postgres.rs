@@ -0,0 +1,157 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Uuid;
+
+use super::{
+    postgres::PostgresMatchDB,
+    share_repository::{MatchShare, ShareRepository},
+};
+use crate::Result;
+
+#[async_trait::async_trait]
+impl ShareRepository for PostgresMatchDB {
+    async fn create_match_share(
+        &self,
+        user_id: Uuid,
+        match_id: &str,
+        token_hash: &[u8],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<MatchShare>> {
+        let match_id = Uuid::parse_str(match_id)?;
+        let share: Option<MatchShare> = sqlx::query_as(
+            "INSERT INTO match_share (match_id, user_id, token_hash, expires_at)
+             SELECT m.id, $2, $3, $4 FROM match m WHERE m.id = $1 AND m.user_id = $2
+             RETURNING id, match_id, user_id, created_at, expires_at, revoked_at",
+        )
+        .bind(match_id)
+        .bind(user_id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(share)
+    }
+
+    async fn list_match_shares(&self, user_id: Uuid, match_id: Option<&str>) -> Result<Vec<MatchShare>> {
+        let match_id = match_id.map(Uuid::parse_str).transpose()?;
+        let shares: Vec<MatchShare> = sqlx::query_as(
+            "SELECT id, match_id, user_id, created_at, expires_at, revoked_at
+             FROM match_share
+             WHERE user_id = $1 AND ($2::uuid IS NULL OR match_id = $2)
+             ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .bind(match_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(shares)
+    }
+
+    async fn revoke_match_share(&self, user_id: Uuid, share_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE match_share SET revoked_at = COALESCE(revoked_at, now())
+             WHERE id = $1 AND user_id = $2",
+        )
+        .bind(share_id)
+        .bind(user_id)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_active_match_share(&self, token_hash: &[u8]) -> Result<Option<MatchShare>> {
+        let share: Option<MatchShare> = sqlx::query_as(
+            "SELECT id, match_id, user_id, created_at, expires_at, revoked_at
+             FROM match_share
+             WHERE token_hash = $1 AND revoked_at IS NULL AND (expires_at IS NULL OR expires_at > now())",
+        )
+        .bind(token_hash)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(share)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::{AuthRepository, testing::test_db};
+
+    async fn seed_match(db: &PostgresMatchDB, user_id: Uuid) -> Uuid {
+        let match_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO match (id, controller_seat_id, controller_player_name, opponent_player_name, user_id)
+             VALUES ($1, 1, 'me', 'them', $2)",
+        )
+        .bind(match_id)
+        .bind(user_id)
+        .execute(db.pool())
+        .await
+        .expect("match");
+        match_id
+    }
+
+    #[tokio::test]
+    async fn only_valid_tokens_resolve_to_a_share() {
+        let Some(db) = test_db().await else { return };
+        let user_id = db
+            .upsert_identity_user("local", "owner", "owner", None)
+            .await
+            .expect("user");
+        let match_id = seed_match(&db, user_id).await.to_string();
+        let now = Utc::now();
+
+        let valid = db
+            .create_match_share(user_id, &match_id, b"valid", Some(now + Duration::hours(1)))
+            .await
+            .expect("create")
+            .expect("owned match");
+        db.create_match_share(user_id, &match_id, b"expired", Some(now - Duration::hours(1)))
+            .await
+            .expect("create")
+            .expect("owned match");
+        let revoked = db
+            .create_match_share(user_id, &match_id, b"revoked", Some(now + Duration::hours(1)))
+            .await
+            .expect("create")
+            .expect("owned match");
+        assert!(db.revoke_match_share(user_id, revoked.id).await.expect("revoke"));
+
+        let found = db.find_active_match_share(b"valid").await.expect("find");
+        assert_eq!(found.map(|share| share.id), Some(valid.id));
+        assert_eq!(db.find_active_match_share(b"expired").await.expect("find"), None);
+        assert_eq!(db.find_active_match_share(b"revoked").await.expect("find"), None);
+        assert_eq!(db.find_active_match_share(b"unknown").await.expect("find"), None);
+    }
+
+    #[tokio::test]
+    async fn shares_are_only_created_and_revoked_by_the_match_owner() {
+        let Some(db) = test_db().await else { return };
+        let owner = db
+            .upsert_identity_user("local", "owner", "owner", None)
+            .await
+            .expect("owner");
+        let other = db
+            .upsert_identity_user("local", "other", "other", None)
+            .await
+            .expect("other");
+        let match_id = seed_match(&db, owner).await.to_string();
+
+        let share = db
+            .create_match_share(other, &match_id, b"token", None)
+            .await
+            .expect("create");
+        assert_eq!(share, None);
+
+        let share = db
+            .create_match_share(owner, &match_id, b"token", None)
+            .await
+            .expect("create")
+            .expect("owned match");
+        assert!(!db.revoke_match_share(other, share.id).await.expect("revoke"));
+        assert!(db.find_active_match_share(b"token").await.expect("find").is_some());
+        assert!(db.revoke_match_share(owner, share.id).await.expect("revoke"));
+        assert!(db.revoke_match_share(owner, share.id).await.expect("revoke again"));
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, types::Uuid};
+
+use crate::Result;
+
+/// A read-only share link for a match.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct MatchShare {
+    pub id: Uuid,
+    pub match_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl MatchShare {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait ShareRepository: Send + Sync + 'static {
+    /// Creates a share for `match_id`. Returns `None` if the match doesn't exist or isn't owned by `user_id`.
+    async fn create_match_share(
+        &self,
+        user_id: Uuid,
+        match_id: &str,
+        token_hash: &[u8],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<MatchShare>>;
+    /// Shares created by `user_id`, newest first, optionally limited to one match.
+    async fn list_match_shares(&self, user_id: Uuid, match_id: Option<&str>) -> Result<Vec<MatchShare>>;
+    /// Revokes a share owned by `user_id`. Returns `false` if no such share exists. Idempotent.
+    async fn revoke_match_share(&self, user_id: Uuid, share_id: Uuid) -> Result<bool>;
+    /// Looks up an unrevoked, unexpired share by token hash.
+    async fn find_active_match_share(&self, token_hash: &[u8]) -> Result<Option<MatchShare>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn share(expires_at: Option<DateTime<Utc>>, revoked_at: Option<DateTime<Utc>>) -> MatchShare {
+        MatchShare {
+            id: Uuid::new_v4(),
+            match_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            created_at: Utc::now(),
+            expires_at,
+            revoked_at,
+        }
+    }
+
+    #[test]
+    fn unexpired_unrevoked_share_is_active() {
+        let now = Utc::now();
+        assert!(share(Some(now + Duration::hours(1)), None).is_active(now));
+        assert!(share(None, None).is_active(now));
+    }
+
+    #[test]
+    fn expired_share_is_inactive() {
+        let now = Utc::now();
+        assert!(!share(Some(now - Duration::hours(1)), None).is_active(now));
+        assert!(!share(Some(now), None).is_active(now));
+    }
+
+    #[test]
+    fn revoked_share_is_inactive() {
+        let now = Utc::now();
+        assert!(!share(Some(now + Duration::hours(1)), Some(now)).is_active(now));
+        assert!(!share(None, Some(now - Duration::hours(1))).is_active(now));
+    }
+}
@@ -0,0 +1,103 @@
+use arenabuddy_core::{
+    display::{
+        match_summary::MatchSummary,
+        stats::{ArchetypeMatchup, MatchStats},
+    },
+    models::Draft,
+};
+use chrono::{DateTime, Utc};
+use sqlx::types::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TeamRole {
+    Owner,
+    Admin,
+    Member,
+}
+
+impl TeamRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TeamRole::Owner => "owner",
+            TeamRole::Admin => "admin",
+            TeamRole::Member => "member",
+        }
+    }
+
+    /// Decode a `team_member.role` value. The column has a CHECK constraint, so anything
+    /// other than `owner`/`admin` is a plain member.
+    pub fn from_db(role: &str) -> Self {
+        match role {
+            "owner" => TeamRole::Owner,
+            "admin" => TeamRole::Admin,
+            _ => TeamRole::Member,
+        }
+    }
+
+    /// Owners and admins can rotate invite codes and remove members.
+    pub fn can_manage(self) -> bool {
+        matches!(self, TeamRole::Owner | TeamRole::Admin)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Team {
+    pub id: Uuid,
+    pub name: String,
+    pub invite_code: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A team as seen by one of its members.
+#[derive(Debug, Clone)]
+pub struct TeamMembership {
+    pub team: Team,
+    pub role: TeamRole,
+    pub share_matches: bool,
+    pub share_drafts: bool,
+    pub member_count: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TeamMember {
+    pub user_id: Uuid,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub role: TeamRole,
+    pub share_matches: bool,
+    pub share_drafts: bool,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MemberMatchRecord {
+    pub user_id: Uuid,
+    pub username: String,
+    pub matches: i64,
+    pub wins: i64,
+    pub losses: i64,
+}
+
+/// Pooled statistics over the matches of members who share them.
+#[derive(Debug, Clone, Default)]
+pub struct TeamStats {
+    pub stats: MatchStats,
+    pub matchups: Vec<ArchetypeMatchup>,
+    pub members: Vec<MemberMatchRecord>,
+}
+
+/// A shared match together with the teammate who played it.
+#[derive(Debug, Clone)]
+pub struct TeamMatch {
+    pub user_id: Uuid,
+    pub username: String,
+    pub summary: MatchSummary,
+}
+
+/// A shared draft together with the teammate who drafted it.
+#[derive(Debug, Clone)]
+pub struct TeamDraft {
+    pub user_id: Uuid,
+    pub username: String,
+    pub draft: Draft,
+}
@@ -0,0 +1,491 @@
+use arenabuddy_core::{
+    display::{
+        match_summary::MatchSummary,
+        stats::{MatchupGrouping, TimeWindow},
+    },
+    models::{Draft, Format},
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::{FromRow, types::Uuid};
+
+use super::{
+    postgres::PostgresMatchDB,
+    team_models::{MemberMatchRecord, Team, TeamDraft, TeamMatch, TeamMember, TeamMembership, TeamRole, TeamStats},
+    team_repository::TeamRepository,
+};
+use crate::Result;
+
+#[derive(FromRow)]
+struct MembershipRow {
+    id: Uuid,
+    name: String,
+    invite_code: String,
+    created_at: DateTime<Utc>,
+    role: String,
+    share_matches: bool,
+    share_drafts: bool,
+    member_count: i64,
+}
+
+impl From<MembershipRow> for TeamMembership {
+    fn from(row: MembershipRow) -> Self {
+        Self {
+            team: Team {
+                id: row.id,
+                name: row.name,
+                invite_code: row.invite_code,
+                created_at: row.created_at,
+            },
+            role: TeamRole::from_db(&row.role),
+            share_matches: row.share_matches,
+            share_drafts: row.share_drafts,
+            member_count: row.member_count,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct MemberRow {
+    user_id: Uuid,
+    username: String,
+    avatar_url: Option<String>,
+    role: String,
+    share_matches: bool,
+    share_drafts: bool,
+    joined_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct MemberRecordRow {
+    user_id: Uuid,
+    username: String,
+    matches: i64,
+    wins: i64,
+    losses: i64,
+}
+
+#[derive(FromRow)]
+struct TeamMatchRow {
+    user_id: Uuid,
+    username: String,
+    id: Uuid,
+    controller_seat_id: i32,
+    controller_player_name: String,
+    opponent_player_name: String,
+    created_at: Option<NaiveDateTime>,
+    format: Option<String>,
+    match_winning_team_id: Option<i32>,
+    game_wins: i64,
+    game_losses: i64,
+    controller_archetype: Option<String>,
+    opponent_archetype: Option<String>,
+}
+
+impl PostgresMatchDB {
+    async fn sharing_member_ids(&self, team_id: Uuid) -> Result<Vec<Uuid>> {
+        let rows: Vec<(Uuid,)> = sqlx::query_as("SELECT user_id FROM team_member WHERE team_id = $1 AND share_matches")
+            .bind(team_id)
+            .fetch_all(self.pool())
+            .await?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl TeamRepository for PostgresMatchDB {
+    async fn create_team(&self, owner_id: Uuid, name: &str, invite_code: &str) -> Result<Option<TeamMembership>> {
+        let mut tx = self.pool().begin().await?;
+
+        let team_id: Option<(Uuid,)> = sqlx::query_as(
+            "INSERT INTO team (name, invite_code, created_by) VALUES ($1, $2, $3)
+             ON CONFLICT (invite_code) DO NOTHING
+             RETURNING id",
+        )
+        .bind(name)
+        .bind(invite_code)
+        .bind(owner_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((team_id,)) = team_id else {
+            return Ok(None);
+        };
+
+        sqlx::query("INSERT INTO team_member (team_id, user_id, role) VALUES ($1, $2, 'owner')")
+            .bind(team_id)
+            .bind(owner_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        self.get_membership(team_id, owner_id).await
+    }
+
+    async fn join_team(&self, user_id: Uuid, invite_code: &str) -> Result<Option<TeamMembership>> {
+        let team_id: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM team WHERE invite_code = $1")
+            .bind(invite_code)
+            .fetch_optional(self.pool())
+            .await?;
+        let Some((team_id,)) = team_id else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            "INSERT INTO team_member (team_id, user_id, role) VALUES ($1, $2, 'member')
+             ON CONFLICT (team_id, user_id) DO NOTHING",
+        )
+        .bind(team_id)
+        .bind(user_id)
+        .execute(self.pool())
+        .await?;
+
+        self.get_membership(team_id, user_id).await
+    }
+
+    async fn list_teams(&self, user_id: Uuid) -> Result<Vec<TeamMembership>> {
+        let rows: Vec<MembershipRow> = sqlx::query_as(
+            "SELECT t.id, t.name, t.invite_code, t.created_at, tm.role, tm.share_matches, tm.share_drafts,
+                (SELECT COUNT(*) FROM team_member c WHERE c.team_id = t.id) AS member_count
+             FROM team t
+             JOIN team_member tm ON tm.team_id = t.id
+             WHERE tm.user_id = $1
+             ORDER BY t.name",
+        )
+        .bind(user_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(rows.into_iter().map(TeamMembership::from).collect())
+    }
+
+    async fn get_membership(&self, team_id: Uuid, user_id: Uuid) -> Result<Option<TeamMembership>> {
+        let row: Option<MembershipRow> = sqlx::query_as(
+            "SELECT t.id, t.name, t.invite_code, t.created_at, tm.role, tm.share_matches, tm.share_drafts,
+                (SELECT COUNT(*) FROM team_member c WHERE c.team_id = t.id) AS member_count
+             FROM team t
+             JOIN team_member tm ON tm.team_id = t.id
+             WHERE t.id = $1 AND tm.user_id = $2",
+        )
+        .bind(team_id)
+        .bind(user_id)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(row.map(TeamMembership::from))
+    }
+
+    async fn list_team_members(&self, team_id: Uuid) -> Result<Vec<TeamMember>> {
+        let rows: Vec<MemberRow> = sqlx::query_as(
+            "SELECT tm.user_id, u.username, u.avatar_url, tm.role, tm.share_matches, tm.share_drafts, tm.joined_at
+             FROM team_member tm
+             JOIN app_user u ON u.id = tm.user_id
+             WHERE tm.team_id = $1
+             ORDER BY tm.joined_at",
+        )
+        .bind(team_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TeamMember {
+                user_id: row.user_id,
+                username: row.username,
+                avatar_url: row.avatar_url,
+                role: TeamRole::from_db(&row.role),
+                share_matches: row.share_matches,
+                share_drafts: row.share_drafts,
+                joined_at: row.joined_at,
+            })
+            .collect())
+    }
+
+    async fn update_sharing(
+        &self,
+        team_id: Uuid,
+        user_id: Uuid,
+        share_matches: bool,
+        share_drafts: bool,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE team_member SET share_matches = $3, share_drafts = $4 WHERE team_id = $1 AND user_id = $2",
+        )
+        .bind(team_id)
+        .bind(user_id)
+        .bind(share_matches)
+        .bind(share_drafts)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn set_member_role(&self, team_id: Uuid, user_id: Uuid, role: TeamRole) -> Result<bool> {
+        let result = sqlx::query("UPDATE team_member SET role = $3 WHERE team_id = $1 AND user_id = $2")
+            .bind(team_id)
+            .bind(user_id)
+            .bind(role.as_str())
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn transfer_ownership(&self, team_id: Uuid, from: Uuid, to: Uuid) -> Result<bool> {
+        let mut tx = self.pool().begin().await?;
+
+        let promoted = sqlx::query("UPDATE team_member SET role = 'owner' WHERE team_id = $1 AND user_id = $2")
+            .bind(team_id)
+            .bind(to)
+            .execute(&mut *tx)
+            .await?;
+        if promoted.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query("UPDATE team_member SET role = 'admin' WHERE team_id = $1 AND user_id = $2")
+            .bind(team_id)
+            .bind(from)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn remove_member(&self, team_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM team_member WHERE team_id = $1 AND user_id = $2")
+            .bind(team_id)
+            .bind(user_id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn rotate_invite_code(&self, team_id: Uuid, invite_code: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE team SET invite_code = $2 WHERE id = $1
+             AND NOT EXISTS (SELECT 1 FROM team WHERE invite_code = $2)",
+        )
+        .bind(team_id)
+        .bind(invite_code)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_team(&self, team_id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM team WHERE id = $1")
+            .bind(team_id)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    async fn get_team_stats(&self, team_id: Uuid, time_window: TimeWindow) -> Result<TeamStats> {
+        let member_ids = self.sharing_member_ids(team_id).await?;
+        let stats = self
+            .match_stats_for_users(Some(member_ids.as_slice()), time_window)
+            .await?;
+        let matchups = self
+            .query_archetype_matchups(
+                Some(member_ids.as_slice()),
+                time_window.cutoff(),
+                MatchupGrouping::Archetype,
+                None,
+            )
+            .await?;
+
+        let members: Vec<MemberRecordRow> = sqlx::query_as(
+            r"SELECT
+                u.id AS user_id,
+                u.username,
+                COUNT(DISTINCT m.id) AS matches,
+                COUNT(DISTINCT CASE WHEN mr.winning_team_id = m.controller_seat_id THEN m.id END) AS wins,
+                COUNT(DISTINCT CASE WHEN mr.winning_team_id != m.controller_seat_id THEN m.id END) AS losses
+            FROM app_user u
+            LEFT JOIN match m ON m.user_id = u.id
+                AND ($2::timestamptz IS NULL OR m.created_at >= $2)
+            LEFT JOIN match_result mr ON m.id = mr.match_id AND mr.result_scope = 'MatchScope_Match'
+            WHERE u.id = ANY($1)
+            GROUP BY u.id, u.username
+            ORDER BY matches DESC, u.username",
+        )
+        .bind(member_ids.as_slice())
+        .bind(time_window.cutoff())
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(TeamStats {
+            stats,
+            matchups,
+            members: members
+                .into_iter()
+                .map(|row| MemberMatchRecord {
+                    user_id: row.user_id,
+                    username: row.username,
+                    matches: row.matches,
+                    wins: row.wins,
+                    losses: row.losses,
+                })
+                .collect(),
+        })
+    }
+
+    async fn list_team_matches(&self, team_id: Uuid, limit: i64) -> Result<Vec<TeamMatch>> {
+        let rows: Vec<TeamMatchRow> = sqlx::query_as(
+            r"SELECT
+                u.id AS user_id,
+                u.username,
+                m.id,
+                m.controller_seat_id,
+                m.controller_player_name,
+                m.opponent_player_name,
+                m.created_at,
+                m.format,
+                match_mr.winning_team_id AS match_winning_team_id,
+                COALESCE(gs.game_wins, 0) AS game_wins,
+                COALESCE(gs.game_losses, 0) AS game_losses,
+                ca.archetype_name AS controller_archetype,
+                oa.archetype_name AS opponent_archetype
+            FROM team_member tm
+            JOIN app_user u ON u.id = tm.user_id
+            JOIN match m ON m.user_id = tm.user_id
+            LEFT JOIN match_result match_mr
+                ON m.id = match_mr.match_id AND match_mr.result_scope = 'MatchScope_Match'
+            LEFT JOIN LATERAL (
+                SELECT
+                    COUNT(CASE WHEN gr.winning_team_id = m.controller_seat_id THEN 1 END) AS game_wins,
+                    COUNT(CASE WHEN gr.winning_team_id != m.controller_seat_id THEN 1 END) AS game_losses
+                FROM match_result gr
+                WHERE gr.match_id = m.id AND gr.result_scope = 'MatchScope_Game'
+            ) gs ON true
+            LEFT JOIN effective_match_archetype ca ON m.id = ca.match_id AND ca.side = 'controller'
+            LEFT JOIN effective_match_archetype oa ON m.id = oa.match_id AND oa.side = 'opponent'
+            WHERE tm.team_id = $1 AND tm.share_matches
+            ORDER BY m.created_at DESC
+            LIMIT $2",
+        )
+        .bind(team_id)
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TeamMatch {
+                user_id: row.user_id,
+                username: row.username,
+                summary: MatchSummary {
+                    id: row.id.to_string(),
+                    controller_player_name: row.controller_player_name,
+                    opponent_player_name: row.opponent_player_name,
+                    created_at: row
+                        .created_at
+                        .map(|naive: NaiveDateTime| naive.and_utc())
+                        .unwrap_or_default(),
+                    format: row.format,
+                    did_controller_win: row.match_winning_team_id.map(|wt| wt == row.controller_seat_id),
+                    game_wins: row.game_wins,
+                    game_losses: row.game_losses,
+                    controller_archetype: row.controller_archetype,
+                    opponent_archetype: row.opponent_archetype,
+                },
+            })
+            .collect())
+    }
+
+    async fn list_team_drafts(&self, team_id: Uuid, limit: i64) -> Result<Vec<TeamDraft>> {
+        #[derive(FromRow)]
+        struct TeamDraftRow {
+            user_id: Uuid,
+            username: String,
+            id: Uuid,
+            set_code: String,
+            draft_format: Option<String>,
+            status: Option<String>,
+            created_at: Option<NaiveDateTime>,
+        }
+
+        let rows: Vec<TeamDraftRow> = sqlx::query_as(
+            r"SELECT
+                u.id AS user_id,
+                u.username,
+                d.id,
+                d.set_code,
+                d.draft_format,
+                d.status,
+                d.created_at
+            FROM team_member tm
+            JOIN app_user u ON u.id = tm.user_id
+            JOIN draft d ON d.user_id = tm.user_id
+            WHERE tm.team_id = $1 AND tm.share_drafts
+            ORDER BY d.created_at DESC
+            LIMIT $2",
+        )
+        .bind(team_id)
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TeamDraft {
+                user_id: row.user_id,
+                username: row.username,
+                draft: Draft::new(
+                    row.id,
+                    row.set_code,
+                    row.draft_format.map(Format::parse_format).unwrap_or_default(),
+                    row.status.unwrap_or_default(),
+                )
+                .with_created_at(row.created_at.unwrap_or_default().and_utc()),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthRepository, testing::test_db};
+
+    async fn seed_draft(db: &PostgresMatchDB, user_id: Uuid, set_code: &str) {
+        sqlx::query("INSERT INTO draft (id, set_code, draft_format, user_id) VALUES ($1, $2, 'PremierDraft', $3)")
+            .bind(Uuid::new_v4())
+            .bind(set_code)
+            .bind(user_id)
+            .execute(db.pool())
+            .await
+            .expect("draft");
+    }
+
+    #[tokio::test]
+    async fn team_drafts_only_include_members_sharing_drafts() {
+        let Some(db) = test_db().await else { return };
+        let owner = db
+            .upsert_identity_user("local", "owner", "owner", None)
+            .await
+            .expect("owner");
+        let member = db
+            .upsert_identity_user("local", "member", "member", None)
+            .await
+            .expect("member");
+        let team = db
+            .create_team(owner, "Team", "ABCDEFGH")
+            .await
+            .expect("team")
+            .expect("created");
+        db.join_team(member, "ABCDEFGH").await.expect("join").expect("joined");
+        seed_draft(&db, owner, "MKM").await;
+        seed_draft(&db, member, "OTJ").await;
+
+        assert!(db.list_team_drafts(team.team.id, 10).await.expect("drafts").is_empty());
+
+        db.update_sharing(team.team.id, member, true, true)
+            .await
+            .expect("sharing");
+        db.update_sharing(team.team.id, owner, true, false)
+            .await
+            .expect("sharing");
+        let drafts = db.list_team_drafts(team.team.id, 10).await.expect("drafts");
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].user_id, member);
+        assert_eq!(drafts[0].draft.set_code(), "OTJ");
+    }
+}
@@ -0,0 +1,36 @@
+use arenabuddy_core::display::stats::TimeWindow;
+use sqlx::types::Uuid;
+
+use super::team_models::{TeamDraft, TeamMatch, TeamMember, TeamMembership, TeamRole, TeamStats};
+use crate::Result;
+
+#[async_trait::async_trait]
+pub trait TeamRepository: Send + Sync + 'static {
+    /// Creates a team with `owner_id` as its owner. Returns `None` if the invite code collides.
+    async fn create_team(&self, owner_id: Uuid, name: &str, invite_code: &str) -> Result<Option<TeamMembership>>;
+    /// Adds `user_id` as a member of the team with `invite_code`. Joining a team twice is a no-op.
+    /// Returns `None` if no team has that code.
+    async fn join_team(&self, user_id: Uuid, invite_code: &str) -> Result<Option<TeamMembership>>;
+    async fn list_teams(&self, user_id: Uuid) -> Result<Vec<TeamMembership>>;
+    async fn get_membership(&self, team_id: Uuid, user_id: Uuid) -> Result<Option<TeamMembership>>;
+    async fn list_team_members(&self, team_id: Uuid) -> Result<Vec<TeamMember>>;
+    async fn update_sharing(
+        &self,
+        team_id: Uuid,
+        user_id: Uuid,
+        share_matches: bool,
+        share_drafts: bool,
+    ) -> Result<bool>;
+    async fn set_member_role(&self, team_id: Uuid, user_id: Uuid, role: TeamRole) -> Result<bool>;
+    /// Makes `to` the owner and demotes `from` to admin, atomically.
+    async fn transfer_ownership(&self, team_id: Uuid, from: Uuid, to: Uuid) -> Result<bool>;
+    async fn remove_member(&self, team_id: Uuid, user_id: Uuid) -> Result<bool>;
+    async fn rotate_invite_code(&self, team_id: Uuid, invite_code: &str) -> Result<bool>;
+    async fn delete_team(&self, team_id: Uuid) -> Result<()>;
+    /// Stats over the matches of members with `share_matches` enabled.
+    async fn get_team_stats(&self, team_id: Uuid, time_window: TimeWindow) -> Result<TeamStats>;
+    /// Most recent matches of members with `share_matches` enabled.
+    async fn list_team_matches(&self, team_id: Uuid, limit: i64) -> Result<Vec<TeamMatch>>;
+    /// Most recent drafts of members with `share_drafts` enabled.
+    async fn list_team_drafts(&self, team_id: Uuid, limit: i64) -> Result<Vec<TeamDraft>>;
+}
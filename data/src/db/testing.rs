@@ -0,0 +1,72 @@
+//! Throwaway databases for tests that exercise the SQL.
+//!
+//! [`test_db`] creates a fresh, migrated database on the server at `TEST_DATABASE_URL` for each
+//! test. When the variable isn't set it returns `None` and the test should return early, so the
+//! suite still passes on machines without Postgres.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use arenabuddy_core::cards::CardsDatabase;
+use sqlx::{
+    PgPool,
+    postgres::{PgConnectOptions, PgPoolOptions},
+};
+
+use super::{postgres::PostgresMatchDB, repository::ArenabuddyRepository};
+
+/// Server URL, e.g. `postgres://postgres@localhost:5432/postgres`. Test databases are created on
+/// it and left behind, so point it at a disposable server.
+pub const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
+
+static NEXT_DATABASE: AtomicU32 = AtomicU32::new(0);
+
+/// A migrated, empty database, or `None` when `TEST_DATABASE_URL` isn't set.
+///
+/// # Panics
+///
+/// Panics if the server can't be reached or the database can't be created.
+pub async fn test_db() -> Option<PostgresMatchDB> {
+    test_db_with_cards(CardsDatabase::default()).await
+}
+
+/// Like [`test_db`], resolving card names through `cards`.
+///
+/// # Panics
+///
+/// Panics if the server can't be reached or the database can't be created.
+pub async fn test_db_with_cards(cards: CardsDatabase) -> Option<PostgresMatchDB> {
+    let url = std::env::var(TEST_DATABASE_URL).ok()?;
+    let options: PgConnectOptions = url.parse().expect("TEST_DATABASE_URL should be a Postgres URL");
+
+    let name = format!(
+        "arenabuddy_test_{}_{}_{}",
+        std::process::id(),
+        chrono::Utc::now().timestamp_micros(),
+        NEXT_DATABASE.fetch_add(1, Ordering::Relaxed)
+    );
+    let admin = PgPoolOptions::new()
+        .max_connections(1)
+        .connect_with(options.clone())
+        .await
+        .expect("TEST_DATABASE_URL should be reachable");
+    // `name` is built above from numbers only.
+    sqlx::query(sqlx::AssertSqlSafe(format!("CREATE DATABASE {name}")))
+        .execute(&admin)
+        .await
+        .expect("test database should be created");
+    admin.close().await;
+
+    let pool = PgPoolOptions::new()
+        .max_connections(4)
+        .connect_with(options.database(&name))
+        .await
+        .expect("test database should be reachable");
+    let db = PostgresMatchDB::with_pool(pool, cards);
+    db.init().await.expect("migrations should apply");
+    Some(db)
+}
+
+/// The pool behind `db`, for seeding rows the repositories have no method to write.
+pub fn pool(db: &PostgresMatchDB) -> &PgPool {
+    db.pool()
+}
@@ -0,0 +1,101 @@
+use sqlx::types::Uuid;
+
+use super::{
+    postgres::PostgresMatchDB,
+    webhook_repository::{DeliveryAttempt, Webhook, WebhookDelivery, WebhookEvent, WebhookRepository},
+};
+use crate::Result;
+
+#[async_trait::async_trait]
+impl WebhookRepository for PostgresMatchDB {
+    async fn create_webhook(&self, user_id: Uuid, url: &str, secret: &str, events: &[WebhookEvent]) -> Result<Webhook> {
+        let events: Vec<&str> = events.iter().map(|event| event.as_str()).collect();
+        let webhook: Webhook = sqlx::query_as(
+            "INSERT INTO webhook (user_id, url, secret, events)
+             VALUES ($1, $2, $3, $4)
+             RETURNING id, user_id, url, secret, events, created_at",
+        )
+        .bind(user_id)
+        .bind(url)
+        .bind(secret)
+        .bind(&events)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(webhook)
+    }
+
+    async fn list_webhooks(&self, user_id: Uuid) -> Result<Vec<Webhook>> {
+        let webhooks: Vec<Webhook> = sqlx::query_as(
+            "SELECT id, user_id, url, secret, events, created_at
+             FROM webhook
+             WHERE user_id = $1
+             ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(webhooks)
+    }
+
+    async fn delete_webhook(&self, user_id: Uuid, webhook_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM webhook WHERE id = $1 AND user_id = $2")
+            .bind(webhook_id)
+            .bind(user_id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn webhooks_for_event(&self, user_id: Uuid, event: WebhookEvent) -> Result<Vec<Webhook>> {
+        let webhooks: Vec<Webhook> = sqlx::query_as(
+            "SELECT id, user_id, url, secret, events, created_at
+             FROM webhook
+             WHERE user_id = $1 AND $2 = ANY(events)",
+        )
+        .bind(user_id)
+        .bind(event.as_str())
+        .fetch_all(self.pool())
+        .await?;
+        Ok(webhooks)
+    }
+
+    async fn record_webhook_delivery(&self, attempt: &DeliveryAttempt<'_>) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO webhook_delivery (webhook_id, delivery_id, event, attempt, status_code, error, succeeded)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(attempt.webhook_id)
+        .bind(attempt.delivery_id)
+        .bind(attempt.event.as_str())
+        .bind(attempt.attempt)
+        .bind(attempt.status_code)
+        .bind(attempt.error)
+        .bind(attempt.succeeded)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        user_id: Uuid,
+        webhook_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let deliveries: Vec<WebhookDelivery> = sqlx::query_as(
+            "SELECT d.id, d.webhook_id, d.delivery_id, d.event, d.attempt, d.status_code, d.error, d.succeeded,
+                    d.created_at
+             FROM webhook_delivery d
+             JOIN webhook w ON w.id = d.webhook_id
+             WHERE d.webhook_id = $1 AND w.user_id = $2
+             ORDER BY d.created_at DESC, d.id DESC
+             LIMIT $3",
+        )
+        .bind(webhook_id)
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(deliveries)
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, types::Uuid};
+
+use crate::Result;
+
+/// Events a webhook can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    MatchCompleted,
+    DraftCompleted,
+    ClassificationUpdated,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 3] = [
+        WebhookEvent::MatchCompleted,
+        WebhookEvent::DraftCompleted,
+        WebhookEvent::ClassificationUpdated,
+    ];
+
+    /// The stable name stored in `webhook.events` and sent in payloads.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::MatchCompleted => "match.completed",
+            WebhookEvent::DraftCompleted => "draft.completed",
+            WebhookEvent::ClassificationUpdated => "classification.updated",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == name)
+    }
+}
+
+/// A user-registered endpoint that receives signed event payloads.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        self.events.iter().any(|name| name == event.as_str())
+    }
+}
+
+/// One attempt at delivering an event to a webhook.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: Uuid,
+    pub delivery_id: Uuid,
+    pub event: String,
+    pub attempt: i32,
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub succeeded: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The outcome of a delivery attempt, as recorded in the delivery log.
+#[derive(Debug, Clone, Copy)]
+pub struct DeliveryAttempt<'a> {
+    pub webhook_id: Uuid,
+    /// Shared by all attempts at delivering the same event to the same webhook.
+    pub delivery_id: Uuid,
+    pub event: WebhookEvent,
+    pub attempt: i32,
+    pub status_code: Option<i32>,
+    pub error: Option<&'a str>,
+    pub succeeded: bool,
+}
+
+#[async_trait::async_trait]
+pub trait WebhookRepository: Send + Sync + 'static {
+    async fn create_webhook(&self, user_id: Uuid, url: &str, secret: &str, events: &[WebhookEvent]) -> Result<Webhook>;
+    /// Webhooks registered by `user_id`, newest first.
+    async fn list_webhooks(&self, user_id: Uuid) -> Result<Vec<Webhook>>;
+    /// Deletes a webhook owned by `user_id` along with its delivery log. Returns `false` if no such webhook exists.
+    async fn delete_webhook(&self, user_id: Uuid, webhook_id: Uuid) -> Result<bool>;
+    /// The webhooks of `user_id` subscribed to `event`.
+    async fn webhooks_for_event(&self, user_id: Uuid, event: WebhookEvent) -> Result<Vec<Webhook>>;
+    async fn record_webhook_delivery(&self, attempt: &DeliveryAttempt<'_>) -> Result<()>;
+    /// The most recent delivery attempts for a webhook owned by `user_id`, newest first.
+    async fn list_webhook_deliveries(
+        &self,
+        user_id: Uuid,
+        webhook_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_names_round_trip() {
+        for event in WebhookEvent::ALL {
+            assert_eq!(WebhookEvent::from_name(event.as_str()), Some(event));
+        }
+        assert_eq!(WebhookEvent::from_name("match.started"), None);
+    }
+}
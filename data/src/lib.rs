@@ -2,9 +2,13 @@ mod db;
 mod errors;
 mod storage;
 
+#[cfg(any(test, feature = "test-util"))]
+pub use db::testing;
 pub use db::{
-    AppUser, ArenabuddyRepository, AuthRepository, CardRepository, DebugRepository, MatchDB, MetagameRepository,
-    RefreshToken, metagame_models, metagame_repository,
+    AccountRepository, AppUser, ArenabuddyRepository, AuthRepository, CardRepository, CommunityRepository,
+    DebugRepository, DraftRepository, ExportRepository, MatchDB, MetagameJobsLock, MetagameRepository, RefreshToken,
+    ShareRepository, TeamRepository, UserIdentity, WebhookRepository, community_repository, debug_repository,
+    export_repository, metagame_models, metagame_repository, share_repository, team_models, webhook_repository,
 };
 pub use errors::{Error, Result};
 pub use storage::DirectoryStorage;
@@ -1,110 +1,482 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
 
 use anyhow::Result;
+use arenabuddy_core::{
+    display::classification::{ArchetypeScore, ClassificationEvidence, MatchedCard},
+    models::{EventKind, EventRules, LimitedArchetypes},
+};
 use arenabuddy_data::{
     MetagameRepository,
-    metagame_models::{MatchArchetype, SignatureCard, SignatureCardRow},
+    metagame_models::{
+        LabelledDeckCardRow, MatchArchetype, MetagameDeck, MetagameDeckCard, SignatureCard, SignatureCardRow,
+    },
 };
 use tracing::info;
+use uuid::Uuid;
+
+/// Minimum fit for a classification to be considered valid.
+const MIN_CLASSIFICATION_FIT: f32 = 0.5;
+
+/// Mainboard size of a constructed deck.
+const CONSTRUCTED_DECK_SIZE: f32 = 60.0;
+
+/// Mainboard size of a Limited deck.
+const LIMITED_DECK_SIZE: f32 = 40.0;
 
-/// Minimum weight threshold for a card to be considered a signature card.
-const SIGNATURE_WEIGHT_THRESHOLD: f32 = 0.15;
+/// Fraction of a deck that must be seen before a classification gets full confidence.
+const CONFIDENT_FRACTION: f32 = 0.25;
 
-/// Minimum total score for a classification to be considered valid.
-const MIN_CLASSIFICATION_SCORE: f32 = 0.5;
+/// Most runner-up archetypes kept as classification evidence.
+const MAX_RUNNERS_UP: usize = 3;
 
-/// Expected number of cards in a standard deck (used for confidence scaling).
-const EXPECTED_DECK_SIZE: f32 = 60.0;
+/// Stale matches whose new classifications are written in one transaction.
+const RECLASSIFY_BATCH_SIZE: usize = 200;
 
-/// Type alias for the card-to-archetype lookup map.
-pub type CardLookup = HashMap<String, Vec<(i32, String, f32)>>;
+/// Source recorded on the decks archetype overrides contribute to the metagame tables.
+pub const OVERRIDE_SOURCE: &str = "override";
 
-/// Build a lookup map from card names to their archetype associations.
-pub fn build_card_lookup(signature_cards: &[SignatureCardRow]) -> CardLookup {
-    let mut lookup: CardLookup = HashMap::new();
-    for sc in signature_cards {
-        lookup
-            .entry(sc.card_name.clone())
-            .or_default()
-            .push((sc.archetype_id, sc.archetype_name.clone(), sc.weight));
+/// Mainboard size of a deck in `format`.
+pub fn deck_size(format: &str) -> f32 {
+    match EventRules::active().format_kind(format) {
+        Some(EventKind::Limited) => LIMITED_DECK_SIZE,
+        _ => CONSTRUCTED_DECK_SIZE,
     }
-    lookup
 }
 
-/// Map an MTGA event ID to the metagame format name used for signature cards.
-pub fn event_id_to_format(event_id: &str) -> Option<&'static str> {
-    match event_id {
-        "Ladder" | "Traditional_Ladder" => Some("standard"),
-        "Explorer_Ladder" | "Traditional_Explorer_Ladder" => Some("explorer"),
-        "Historic_Ladder" | "Traditional_Historic_Ladder" => Some("historic"),
-        "Timeless_Ladder" | "Traditional_Timeless_Ladder" => Some("timeless"),
-        _ => None,
+/// A scraped or hand-labelled deck with its archetype.
+#[derive(Debug, Clone)]
+pub struct LabelledDeck {
+    pub deck_id: i32,
+    pub archetype_id: i32,
+    pub archetype_name: String,
+    /// Mainboard card names and quantities.
+    pub cards: Vec<(String, i32)>,
+}
+
+/// Group labelled deck card rows, ordered by deck, into decks.
+pub fn group_labelled_decks(rows: Vec<LabelledDeckCardRow>) -> Vec<LabelledDeck> {
+    let mut decks: Vec<LabelledDeck> = Vec::new();
+    for row in rows {
+        match decks.last_mut() {
+            Some(deck) if deck.deck_id == row.deck_id => deck.cards.push((row.card_name, row.quantity)),
+            _ => decks.push(LabelledDeck {
+                deck_id: row.deck_id,
+                archetype_id: row.archetype_id,
+                archetype_name: row.archetype_name,
+                cards: vec![(row.card_name, row.quantity)],
+            }),
+        }
     }
+    decks
 }
 
-/// Compute signature cards for all archetypes in a given format.
-///
-/// For each card in each archetype, computes:
-/// - `frequency`: fraction of the archetype's decks containing this card
-/// - `exclusivity`: fraction of all decks with this card that belong to this archetype
-/// - `weight = frequency * exclusivity`
+/// One archetype's typical list.
+#[derive(Debug, Clone)]
+struct ArchetypeProfile {
+    id: i32,
+    name: String,
+    /// Mean copies per deck of every card the archetype plays.
+    copies: HashMap<String, f32>,
+    /// TF-IDF mass of the typical list: the sum of `idf * copies`.
+    mass: f32,
+}
+
+/// Quantity-aware TF-IDF profiles of a format's archetypes.
 ///
-/// Cards above the threshold are saved as signature cards.
-pub async fn compute_signature_cards(repo: &impl MetagameRepository, format: &str) -> Result<u64> {
-    info!("Computing signature cards for format: {format}");
+/// A deck is scored against each archetype by the IDF-weighted copies the two have in common,
+/// counting at most the archetype's mean copies of each card. That overlap is compared with
+/// what was seen (precision) and with the archetype's whole list (recall). Recall is scaled by
+/// the fraction of the deck seen, so an opponent who revealed eight cards isn't penalised for
+/// the fifty-two they didn't, and the fit is the harmonic mean of the two.
+#[derive(Debug, Clone, Default)]
+pub struct ArchetypeModel {
+    deck_size: f32,
+    /// Inverse document frequency of each card across the format's decks.
+    idf: HashMap<String, f32>,
+    archetypes: Vec<ArchetypeProfile>,
+}
+
+impl ArchetypeModel {
+    /// Build profiles from labelled decks. `idf = ln((1 + decks) / (1 + decks with the card))`,
+    /// so cards every deck plays carry no weight.
+    #[expect(clippy::cast_precision_loss)]
+    pub fn train(decks: &[LabelledDeck], deck_size: f32) -> Self {
+        let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+        let mut by_archetype: HashMap<i32, (&str, usize, HashMap<&str, f32>)> = HashMap::new();
+        for deck in decks {
+            let (_, deck_count, copies) = by_archetype
+                .entry(deck.archetype_id)
+                .or_insert_with(|| (&deck.archetype_name, 0, HashMap::new()));
+            *deck_count += 1;
+            for (card, quantity) in &deck.cards {
+                *copies.entry(card).or_default() += *quantity as f32;
+            }
+            let mut distinct: Vec<&str> = deck.cards.iter().map(|(card, _)| card.as_str()).collect();
+            distinct.sort_unstable();
+            distinct.dedup();
+            for card in distinct {
+                *document_frequency.entry(card).or_default() += 1;
+            }
+        }
 
-    let frequencies = repo.get_card_frequencies(format).await?;
-    info!("Fetched {} card-archetype frequency rows", frequencies.len());
+        let total = (decks.len() + 1) as f32;
+        let idf: HashMap<String, f32> = document_frequency
+            .into_iter()
+            .map(|(card, df)| (card.to_string(), (total / (df + 1) as f32).ln()))
+            .collect();
+
+        let mut archetypes: Vec<ArchetypeProfile> = by_archetype
+            .into_iter()
+            .map(|(id, (name, deck_count, copies))| {
+                let copies = copies
+                    .into_iter()
+                    .map(|(card, n)| (card.to_string(), n / deck_count as f32))
+                    .collect();
+                ArchetypeProfile::new(id, name.to_string(), copies, &idf)
+            })
+            .collect();
+        archetypes.sort_by_key(|a| a.id);
+
+        Self {
+            deck_size,
+            idf,
+            archetypes,
+        }
+    }
 
-    let total_decks_in_format: i64 = frequencies.iter().map(|r| r.total_archetype_decks).max().unwrap_or(0);
+    /// Rebuild a model from stored signature cards, skipping any stored without copies and IDF.
+    pub fn from_signatures(signature_cards: &[SignatureCardRow], deck_size: f32) -> Self {
+        let mut idf: HashMap<String, f32> = HashMap::new();
+        let mut by_archetype: HashMap<i32, (&str, HashMap<String, f32>)> = HashMap::new();
+        for sc in signature_cards {
+            let (Some(copies), Some(card_idf)) = (sc.copies, sc.idf) else {
+                continue;
+            };
+            idf.insert(sc.card_name.clone(), card_idf);
+            by_archetype
+                .entry(sc.archetype_id)
+                .or_insert_with(|| (&sc.archetype_name, HashMap::new()))
+                .1
+                .insert(sc.card_name.clone(), copies);
+        }
 
-    if total_decks_in_format == 0 {
-        info!("No decks found for format {format}, skipping");
-        return Ok(0);
+        let mut archetypes: Vec<ArchetypeProfile> = by_archetype
+            .into_iter()
+            .map(|(id, (name, copies))| ArchetypeProfile::new(id, name.to_string(), copies, &idf))
+            .collect();
+        archetypes.sort_by_key(|a| a.id);
+
+        Self {
+            deck_size,
+            idf,
+            archetypes,
+        }
     }
 
-    let mut signature_cards = Vec::new();
+    pub fn is_empty(&self) -> bool {
+        self.archetypes.is_empty()
+    }
 
-    for row in &frequencies {
-        if row.total_archetype_decks == 0 || row.total_decks_with_card == 0 {
-            continue;
+    /// The model's cards as signature card rows. Cards every deck plays are left out.
+    pub fn signature_cards(&self, format: &str) -> Vec<SignatureCard> {
+        let mut cards = Vec::new();
+        for archetype in &self.archetypes {
+            for (card_name, &copies) in &archetype.copies {
+                let idf = self.idf(card_name);
+                if idf <= 0.0 {
+                    continue;
+                }
+                cards.push(SignatureCard {
+                    archetype_id: archetype.id,
+                    archetype_name: archetype.name.clone(),
+                    card_name: card_name.clone(),
+                    weight: copies / self.deck_size * idf,
+                    copies,
+                    idf,
+                    format: format.to_string(),
+                });
+            }
         }
+        cards
+    }
 
-        #[expect(clippy::cast_precision_loss)]
-        let frequency = row.archetype_deck_count as f32 / row.total_archetype_decks as f32;
-        #[expect(clippy::cast_precision_loss)]
-        let exclusivity = row.archetype_deck_count as f32 / row.total_decks_with_card as f32;
-        let weight = frequency * exclusivity;
+    fn idf(&self, card_name: &str) -> f32 {
+        self.idf.get(card_name).copied().unwrap_or_default()
+    }
 
-        if weight >= SIGNATURE_WEIGHT_THRESHOLD {
-            signature_cards.push(SignatureCard {
-                archetype_id: row.archetype_id,
-                archetype_name: row.archetype_name.clone(),
-                card_name: row.card_name.clone(),
-                weight,
-                format: format.to_string(),
-            });
+    /// Score the cards seen of a deck, one entry per copy.
+    ///
+    /// Returns the best fitting archetype, with the cards that matched it weighted by their
+    /// share of the overlap, and the runner-up archetypes. Cards the format's decks don't
+    /// play count towards the deck size but carry no weight. The confidence is the fit, scaled
+    /// down until a quarter of the deck has been seen.
+    #[expect(clippy::cast_precision_loss)]
+    pub fn score(&self, card_names: &[String]) -> Option<DeckScore> {
+        let mut seen: HashMap<&str, f32> = HashMap::new();
+        for card_name in card_names {
+            *seen.entry(card_name).or_default() += 1.0;
         }
+        let observed_mass: f32 = seen.iter().map(|(card, n)| self.idf(card) * n).sum();
+        if observed_mass <= 0.0 {
+            return None;
+        }
+        let completeness = (card_names.len() as f32 / self.deck_size).min(1.0);
+        let observation = (card_names.len() as f32 / (self.deck_size * CONFIDENT_FRACTION)).min(1.0);
+
+        let mut fits: Vec<(&ArchetypeProfile, f32, Vec<MatchedCard>)> = self
+            .archetypes
+            .iter()
+            .filter_map(|archetype| {
+                let mut matched_cards = Vec::new();
+                for (&card_name, &n) in &seen {
+                    let Some(&copies) = archetype.copies.get(card_name) else {
+                        continue;
+                    };
+                    let weight = self.idf(card_name) * n.min(copies);
+                    if weight > 0.0 {
+                        matched_cards.push(MatchedCard {
+                            card_name: card_name.to_string(),
+                            weight,
+                        });
+                    }
+                }
+                let overlap: f32 = matched_cards.iter().map(|c| c.weight).sum();
+                if overlap <= 0.0 {
+                    return None;
+                }
+                let precision = overlap / observed_mass;
+                let recall = (overlap / archetype.mass / completeness).min(1.0);
+                let fit = 2.0 * precision * recall / (precision + recall);
+                Some((archetype, fit, matched_cards))
+            })
+            .collect();
+        fits.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.id.cmp(&b.0.id)));
+
+        let mut ranked = fits.into_iter();
+        let (best, fit, mut matched_cards) = ranked.next().filter(|(_, fit, _)| *fit >= MIN_CLASSIFICATION_FIT)?;
+        matched_cards.sort_by(|a, b| {
+            b.weight
+                .total_cmp(&a.weight)
+                .then_with(|| a.card_name.cmp(&b.card_name))
+        });
+
+        let runners_up = ranked
+            .take(MAX_RUNNERS_UP)
+            .map(|(archetype, fit, _)| ArchetypeScore {
+                archetype_name: archetype.name.clone(),
+                score: fit * observation,
+            })
+            .collect();
+
+        Some(DeckScore {
+            archetype_id: best.id,
+            archetype_name: best.name.clone(),
+            confidence: fit * observation,
+            evidence: ClassificationEvidence {
+                matched_cards,
+                runners_up,
+            },
+        })
+    }
+}
+
+impl ArchetypeProfile {
+    fn new(id: i32, name: String, copies: HashMap<String, f32>, idf: &HashMap<String, f32>) -> Self {
+        let mass = copies
+            .iter()
+            .map(|(card, n)| idf.get(card).copied().unwrap_or_default() * n)
+            .sum();
+        Self { id, name, copies, mass }
+    }
+}
+
+/// The format's labelled decks.
+pub async fn labelled_decks(repo: &impl MetagameRepository, format: &str) -> Result<Vec<LabelledDeck>> {
+    Ok(group_labelled_decks(repo.get_labelled_deck_cards(format).await?))
+}
+
+/// Compute signature cards for all archetypes in a given format.
+///
+/// Trains an [`ArchetypeModel`] on the format's labelled decks and stores every card each
+/// archetype plays with its mean copies and IDF. Returns the number stored, 0 if the cards are
+/// the same as before, which leaves existing classifications current.
+pub async fn compute_signature_cards(repo: &impl MetagameRepository, format: &str) -> Result<u64> {
+    info!("Computing signature cards for format: {format}");
+
+    let decks = labelled_decks(repo, format).await?;
+    info!("Fetched {} labelled decks", decks.len());
+
+    if decks.is_empty() {
+        info!("No decks found for format {format}, skipping");
+        return Ok(0);
     }
 
+    let signature_cards = ArchetypeModel::train(&decks, deck_size(format)).signature_cards(format);
     info!("Found {} signature cards across archetypes", signature_cards.len());
 
     let count = repo.replace_signature_cards(format, &signature_cards).await?;
-    info!("Stored {count} signature cards for {format}");
+    if count == 0 {
+        info!("Signature cards for {format} are unchanged");
+    } else {
+        info!("Stored {count} signature cards for {format}");
+    }
 
     Ok(count)
 }
 
-/// Classify all unclassified matches in a given format using signature cards.
+/// Load the format's classifier from its stored signature cards, retraining it first if they
+/// were stored without copies and IDF.
+async fn load_model(repo: &impl MetagameRepository, format: &str) -> Result<ArchetypeModel> {
+    let mut signature_cards = repo.get_signature_cards(format).await?;
+    if signature_cards.iter().any(|sc| sc.copies.is_none() || sc.idf.is_none()) {
+        info!("Signature cards for {format} predate copies and IDF, retraining");
+        compute_signature_cards(repo, format).await?;
+        signature_cards = repo.get_signature_cards(format).await?;
+    }
+    Ok(ArchetypeModel::from_signatures(&signature_cards, deck_size(format)))
+}
+
+/// Accuracy of the classifier on held-out labelled decks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Evaluation {
+    /// Held-out decks scored.
+    pub decks: usize,
+    /// Decks classified as their labelled archetype.
+    pub correct: usize,
+    /// Decks that fit no archetype well enough to be classified.
+    pub unclassified: usize,
+}
+
+impl Evaluation {
+    #[expect(clippy::cast_precision_loss)]
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.decks > 0).then(|| self.correct as f64 / self.decks as f64 * 100.0)
+    }
+}
+
+/// Hold out every `holdout_every`-th deck, train on the rest and classify the held-out decks.
+///
+/// With `revealed`, only that many of each held-out deck's cards are scored, picked in a
+/// fixed pseudo-random order, to measure how the classifier does on a partly seen opponent.
+pub fn evaluate(decks: &[LabelledDeck], deck_size: f32, holdout_every: usize, revealed: Option<usize>) -> Evaluation {
+    let holdout_every = holdout_every.max(2);
+    let (held_out, training): (Vec<_>, Vec<_>) = decks.iter().enumerate().partition(|(i, _)| i % holdout_every == 0);
+    let training: Vec<LabelledDeck> = training.into_iter().map(|(_, deck)| deck.clone()).collect();
+    let model = ArchetypeModel::train(&training, deck_size);
+
+    let mut evaluation = Evaluation::default();
+    for (_, deck) in held_out {
+        let mut copies: Vec<(u64, &str)> = Vec::new();
+        for (card, quantity) in &deck.cards {
+            for copy in 0..*quantity {
+                let mut hasher = DefaultHasher::new();
+                (deck.deck_id, card, copy).hash(&mut hasher);
+                copies.push((hasher.finish(), card));
+            }
+        }
+        copies.sort_unstable();
+        let seen: Vec<String> = copies
+            .into_iter()
+            .take(revealed.unwrap_or(usize::MAX))
+            .map(|(_, card)| card.to_string())
+            .collect();
+
+        evaluation.decks += 1;
+        match model.score(&seen) {
+            Some(score) if score.archetype_id == deck.archetype_id => evaluation.correct += 1,
+            Some(_) => {}
+            None => evaluation.unclassified += 1,
+        }
+    }
+    evaluation
+}
+
+/// The cards seen for one side of a match, one entry per copy.
+pub(crate) async fn side_cards(repo: &impl MetagameRepository, match_id: &str, side: &str) -> Result<Vec<String>> {
+    Ok(if side == "controller" {
+        repo.get_match_deck_cards(match_id).await?
+    } else {
+        repo.get_match_opponent_cards(match_id).await?
+    })
+}
+
+/// Store one side of a match as a reference deck of `archetype_id`, so signature cards are
+/// computed from it too. Returns false, storing nothing, if no cards are known for the side.
+pub(crate) async fn store_match_deck(
+    repo: &impl MetagameRepository,
+    deck: &MetagameDeck,
+    archetype_id: i32,
+    match_id: &str,
+    side: &str,
+) -> Result<bool> {
+    let mut cards: Vec<MetagameDeckCard> = Vec::new();
+    for card in side_cards(repo, match_id, side).await? {
+        match cards.iter_mut().find(|c| c.card_name == card) {
+            Some(entry) => entry.quantity += 1,
+            None => cards.push(MetagameDeckCard {
+                card_name: card,
+                quantity: 1,
+                is_sideboard: false,
+            }),
+        }
+    }
+    if cards.is_empty() {
+        return Ok(false);
+    }
+    repo.upsert_metagame_deck(deck, None, Some(archetype_id), &cards)
+        .await?;
+    Ok(true)
+}
+
+/// Feed the format's hand-set archetypes back into the metagame tables as labelled decks, so
+/// the next [`compute_signature_cards`] learns from them. Decks whose override was cleared
+/// are removed. Returns the number of labelled decks stored.
+pub async fn sync_override_decks(repo: &impl MetagameRepository, format: &str) -> Result<u64> {
+    let overrides = repo.get_archetype_overrides(format).await?;
+
+    let mut stored = Vec::with_capacity(overrides.len());
+    for o in &overrides {
+        let match_id = o.match_id.to_string();
+        let archetype_id = repo.upsert_metagame_archetype(&o.archetype_name, format, None).await?;
+        let deck = MetagameDeck {
+            source: OVERRIDE_SOURCE.to_string(),
+            source_id: format!("{match_id}/{}", o.side),
+            archetype_name: Some(o.archetype_name.clone()),
+            player_name: None,
+            placement: None,
+            format: format.to_string(),
+            date: None,
+            url: String::new(),
+        };
+        if store_match_deck(repo, &deck, archetype_id, &match_id, &o.side).await? {
+            stored.push(deck.source_id);
+        }
+    }
+
+    let pruned = repo.prune_metagame_decks(OVERRIDE_SOURCE, format, &stored).await?;
+    info!(
+        "Stored {} override decks for {format}, removed {pruned} stale ones",
+        stored.len()
+    );
+    Ok(stored.len() as u64)
+}
+
+/// Classify all unclassified matches in a given format using signature cards. Matches in a
+/// Limited format are labelled by their colors instead.
 pub async fn classify_matches(repo: &impl MetagameRepository, format: &str) -> Result<u64> {
-    let signature_cards = repo.get_signature_cards(format).await?;
-    if signature_cards.is_empty() {
+    if EventRules::active().format_kind(format) == Some(EventKind::Limited) {
+        return label_limited_matches(repo, format).await;
+    }
+
+    let model = load_model(repo, format).await?;
+    if model.is_empty() {
         info!("No signature cards found for {format}. Run compute-signatures first.");
         return Ok(0);
     }
 
-    let card_to_archetypes = build_card_lookup(&signature_cards);
-
     let unclassified = repo.get_unclassified_matches(format).await?;
     info!("Found {} unclassified matches for {format}", unclassified.len());
 
@@ -112,7 +484,7 @@ pub async fn classify_matches(repo: &impl MetagameRepository, format: &str) -> R
 
     for m in &unclassified {
         let match_id = m.match_id.to_string();
-        classify_and_store(repo, &match_id, &card_to_archetypes).await?;
+        classify_and_store(repo, &match_id, &model).await?;
         classified_count += 1;
     }
 
@@ -120,168 +492,410 @@ pub async fn classify_matches(repo: &impl MetagameRepository, format: &str) -> R
     Ok(classified_count)
 }
 
+/// A match [`reclassify_stale_matches`] classified again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReclassifiedMatch {
+    pub match_id: Uuid,
+    pub user_id: Option<Uuid>,
+    /// Whether either side's archetype differs from before.
+    pub changed: bool,
+}
+
+/// Reclassify matches in a given format whose classification is older than the format's
+/// signature cards, writing each batch of matches in one transaction. A match that no longer
+/// scores as any archetype is left unclassified.
+pub async fn reclassify_stale_matches(repo: &impl MetagameRepository, format: &str) -> Result<Vec<ReclassifiedMatch>> {
+    let model = load_model(repo, format).await?;
+    if model.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let stale = repo.get_stale_classified_matches(format).await?;
+    info!("Found {} stale classifications for {format}", stale.len());
+
+    let mut reclassified = Vec::with_capacity(stale.len());
+
+    for batch in stale.chunks(RECLASSIFY_BATCH_SIZE) {
+        let match_ids: Vec<Uuid> = batch.iter().map(|m| m.match_id).collect();
+        let mut before = Vec::with_capacity(batch.len());
+        let mut archetypes = Vec::new();
+        for match_id in &match_ids {
+            let match_id = match_id.to_string();
+            before.push(repo.get_match_archetypes(&match_id).await?);
+            archetypes.extend(score_match(repo, &match_id, &model).await?);
+        }
+        repo.replace_match_archetypes(&match_ids, &archetypes).await?;
+
+        for (m, before) in batch.iter().zip(before) {
+            let after = repo.get_match_archetypes(&m.match_id.to_string()).await?;
+            reclassified.push(ReclassifiedMatch {
+                match_id: m.match_id,
+                user_id: m.user_id,
+                changed: before != after,
+            });
+        }
+    }
+
+    info!("Reclassified {} matches for {format}", reclassified.len());
+    Ok(reclassified)
+}
+
 /// Classify a single match on-the-fly and return the results.
 ///
 /// Loads signature cards for the match's format, scores the controller and opponent
-/// decks, stores results in the database, and returns the classifications.
+/// decks, stores results in the database, and returns the classifications. Limited
+/// matches are labelled by their colors instead.
 /// Returns an empty vec if no signature cards exist or the format is unknown.
 pub async fn classify_single_match(
     repo: &impl MetagameRepository,
     match_id: &str,
     mtga_format: &str,
 ) -> Result<Vec<MatchArchetype>> {
-    let Some(format) = event_id_to_format(mtga_format) else {
+    let Some(event) = EventRules::active().describe(mtga_format) else {
         info!("Unknown MTGA format '{mtga_format}', skipping classification");
         return Ok(Vec::new());
     };
+    if event.is_limited() {
+        return label_limited_match(repo, match_id, event.set_code.as_deref()).await;
+    }
+    let format = event.format.as_str();
 
-    let signature_cards = repo.get_signature_cards(format).await?;
-    if signature_cards.is_empty() {
+    let model = load_model(repo, format).await?;
+    if model.is_empty() {
         info!("No signature cards for {format}, skipping classification");
         return Ok(Vec::new());
     }
 
-    let card_to_archetypes = build_card_lookup(&signature_cards);
-    classify_and_store(repo, match_id, &card_to_archetypes).await
+    classify_and_store(repo, match_id, &model).await
+}
+
+/// Score both sides of a match without storing anything.
+async fn score_match(
+    repo: &impl MetagameRepository,
+    match_id: &str,
+    model: &ArchetypeModel,
+) -> Result<Vec<MatchArchetype>> {
+    let mut results = Vec::new();
+
+    for side in ["controller", "opponent"] {
+        let cards = side_cards(repo, match_id, side).await?;
+        if let Some(score) = model.score(&cards) {
+            results.push(score.into_match_archetype(match_id, side));
+        }
+    }
+
+    Ok(results)
 }
 
 /// Score and store classifications for a single match. Returns the stored archetypes.
 async fn classify_and_store(
     repo: &impl MetagameRepository,
     match_id: &str,
-    card_to_archetypes: &CardLookup,
+    model: &ArchetypeModel,
+) -> Result<Vec<MatchArchetype>> {
+    let results = score_match(repo, match_id, model).await?;
+    for ma in &results {
+        repo.upsert_match_archetype(ma).await?;
+    }
+    Ok(results)
+}
+
+/// Label both sides of a Limited match by their colors, e.g. `UB` or `WR splash g`, using the
+/// name `set_code` gives those colors where it has one. Confidence grows with the cards seen,
+/// as for signature cards. Returns the stored labels.
+#[expect(clippy::cast_precision_loss)]
+pub async fn label_limited_match(
+    repo: &impl MetagameRepository,
+    match_id: &str,
+    set_code: Option<&str>,
 ) -> Result<Vec<MatchArchetype>> {
+    let archetypes = LimitedArchetypes::builtin();
     let mut results = Vec::new();
 
-    // Classify controller deck
-    let controller_cards = repo.get_match_deck_cards(match_id).await?;
-    if let Some(archetype) = score_deck(&controller_cards, card_to_archetypes, 1.0) {
+    for side in ["controller", "opponent"] {
+        let cards = repo.get_match_side_cards(match_id, side).await?;
+        let Some(label) = archetypes.label_deck(&cards, set_code) else {
+            continue;
+        };
         let ma = MatchArchetype {
             match_id: match_id.to_string(),
-            side: "controller".to_string(),
-            archetype_id: Some(archetype.0),
-            archetype_name: archetype.1.clone(),
-            confidence: archetype.2,
+            side: side.to_string(),
+            archetype_id: None,
+            archetype_name: label,
+            confidence: (cards.len() as f32 / (LIMITED_DECK_SIZE * CONFIDENT_FRACTION)).min(1.0),
+            evidence: None,
+            overridden: false,
         };
         repo.upsert_match_archetype(&ma).await?;
         results.push(ma);
     }
 
-    // Classify opponent deck
-    let opponent_cards = repo.get_match_opponent_cards(match_id).await?;
-    if !opponent_cards.is_empty() {
-        #[expect(clippy::cast_precision_loss)]
-        let completeness = (opponent_cards.len() as f32 / EXPECTED_DECK_SIZE).min(1.0);
-        if let Some(archetype) = score_deck(&opponent_cards, card_to_archetypes, completeness) {
-            let ma = MatchArchetype {
-                match_id: match_id.to_string(),
-                side: "opponent".to_string(),
-                archetype_id: Some(archetype.0),
-                archetype_name: archetype.1.clone(),
-                confidence: archetype.2,
-            };
-            repo.upsert_match_archetype(&ma).await?;
-            results.push(ma);
-        }
+    Ok(results)
+}
+
+/// Label every unlabelled match in a Limited format, taking each match's set from its event.
+async fn label_limited_matches(repo: &impl MetagameRepository, format: &str) -> Result<u64> {
+    let rules = EventRules::active();
+    let unlabelled = repo.get_unclassified_matches(format).await?;
+    info!("Found {} unlabelled matches for {format}", unlabelled.len());
+
+    let mut labelled_count = 0u64;
+
+    for m in &unlabelled {
+        let set_code = m
+            .format
+            .as_deref()
+            .and_then(|event_id| rules.describe(event_id))
+            .and_then(|event| event.set_code);
+        label_limited_match(repo, &m.match_id.to_string(), set_code.as_deref()).await?;
+        labelled_count += 1;
     }
 
-    Ok(results)
+    info!("Labelled {labelled_count} matches for {format}");
+    Ok(labelled_count)
 }
 
-/// Score a deck's cards against signature card data.
-/// Returns the best matching archetype as `(archetype_id, archetype_name, confidence)`.
-pub fn score_deck(
-    card_names: &[String],
-    card_to_archetypes: &CardLookup,
-    confidence_scale: f32,
-) -> Option<(i32, String, f32)> {
-    let mut archetype_scores: HashMap<i32, (String, f32)> = HashMap::new();
-
-    for card_name in card_names {
-        if let Some(archetypes) = card_to_archetypes.get(card_name) {
-            for (archetype_id, archetype_name, weight) in archetypes {
-                let entry = archetype_scores
-                    .entry(*archetype_id)
-                    .or_insert_with(|| (archetype_name.clone(), 0.0));
-                entry.1 += weight;
-            }
+/// The archetype a deck scored best for, and why.
+#[derive(Debug, Clone)]
+pub struct DeckScore {
+    pub archetype_id: i32,
+    pub archetype_name: String,
+    pub confidence: f32,
+    pub evidence: ClassificationEvidence,
+}
+
+impl DeckScore {
+    fn into_match_archetype(self, match_id: &str, side: &str) -> MatchArchetype {
+        MatchArchetype {
+            match_id: match_id.to_string(),
+            side: side.to_string(),
+            archetype_id: Some(self.archetype_id),
+            archetype_name: self.archetype_name,
+            confidence: self.confidence,
+            evidence: Some(self.evidence),
+            overridden: false,
         }
     }
-
-    archetype_scores
-        .into_iter()
-        .max_by(|a, b| a.1.1.partial_cmp(&b.1.1).unwrap_or(std::cmp::Ordering::Equal))
-        .filter(|(_, (_, score))| *score >= MIN_CLASSIFICATION_SCORE)
-        .map(|(id, (name, score))| (id, name, score * confidence_scale))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_score_deck_empty() {
-        let card_to_archetypes = HashMap::new();
-        let result = score_deck(&[], &card_to_archetypes, 1.0);
-        assert!(result.is_none());
+    fn deck(deck_id: i32, archetype_id: i32, name: &str, cards: &[(&str, i32)]) -> LabelledDeck {
+        LabelledDeck {
+            deck_id,
+            archetype_id,
+            archetype_name: name.to_string(),
+            cards: cards.iter().map(|(card, n)| ((*card).to_string(), *n)).collect(),
+        }
+    }
+
+    fn mono_red(deck_id: i32) -> LabelledDeck {
+        deck(
+            deck_id,
+            1,
+            "Mono Red",
+            &[
+                ("Lightning Bolt", 4),
+                ("Monastery Swiftspear", 4),
+                ("Kumano Faces Kakkazan", 4),
+                ("Play with Fire", 4),
+                ("Mountain", 20),
+            ],
+        )
+    }
+
+    fn azorius(deck_id: i32) -> LabelledDeck {
+        deck(
+            deck_id,
+            2,
+            "Azorius Control",
+            &[
+                ("Counterspell", 4),
+                ("Memory Deluge", 4),
+                ("Sunfall", 3),
+                ("Play with Fire", 1),
+                ("Island", 10),
+                ("Plains", 10),
+            ],
+        )
+    }
+
+    fn golgari(deck_id: i32) -> LabelledDeck {
+        deck(
+            deck_id,
+            3,
+            "Golgari Midrange",
+            &[
+                ("Llanowar Elves", 4),
+                ("Mosswood Dreadknight", 4),
+                ("Forest", 10),
+                ("Swamp", 10),
+            ],
+        )
+    }
+
+    fn copies(cards: &[(&str, usize)]) -> Vec<String> {
+        cards
+            .iter()
+            .flat_map(|(card, n)| std::iter::repeat_n((*card).to_string(), *n))
+            .collect()
+    }
+
+    fn model() -> ArchetypeModel {
+        ArchetypeModel::train(
+            &[mono_red(1), mono_red(2), azorius(3), azorius(4), golgari(5), golgari(6)],
+            60.0,
+        )
     }
 
     #[test]
-    fn test_score_deck_finds_best_match() {
-        let mut card_to_archetypes: CardLookup = HashMap::new();
-        card_to_archetypes.insert("Lightning Bolt".to_string(), vec![(1, "Mono Red".to_string(), 0.8)]);
-        card_to_archetypes.insert(
-            "Monastery Swiftspear".to_string(),
-            vec![(1, "Mono Red".to_string(), 0.9)],
-        );
-        card_to_archetypes.insert("Counterspell".to_string(), vec![(2, "Blue Control".to_string(), 0.7)]);
-
-        let deck = vec![
-            "Lightning Bolt".to_string(),
-            "Monastery Swiftspear".to_string(),
-            "Mountain".to_string(),
-        ];
+    fn test_score_empty() {
+        assert!(model().score(&[]).is_none());
+        assert!(ArchetypeModel::default().score(&copies(&[("Island", 1)])).is_none());
+    }
 
-        let result = score_deck(&deck, &card_to_archetypes, 1.0);
-        assert!(result.is_some());
-        let (id, name, _score) = result.unwrap();
-        assert_eq!(id, 1);
-        assert_eq!(name, "Mono Red");
+    #[test]
+    fn test_score_finds_best_match() {
+        let deck = copies(&[
+            ("Lightning Bolt", 4),
+            ("Monastery Swiftspear", 4),
+            ("Kumano Faces Kakkazan", 4),
+            ("Mountain", 20),
+        ]);
+        let score = model().score(&deck).unwrap();
+        assert_eq!(score.archetype_id, 1);
+        assert_eq!(score.archetype_name, "Mono Red");
+        assert!(score.confidence > 0.8);
     }
 
     #[test]
-    fn test_score_deck_below_threshold() {
-        let mut card_to_archetypes: CardLookup = HashMap::new();
-        card_to_archetypes.insert("Mountain".to_string(), vec![(1, "Mono Red".to_string(), 0.1)]);
+    fn test_score_unknown_cards_below_threshold() {
+        let deck = copies(&[("Ornithopter", 4), ("Wastes", 20), ("Play with Fire", 1)]);
+        assert!(model().score(&deck).is_none());
+    }
 
-        let deck = vec!["Mountain".to_string()];
+    #[test]
+    fn test_score_partial_observation() {
+        let model = model();
+        let full = model.score(&copies(&[
+            ("Counterspell", 4),
+            ("Memory Deluge", 4),
+            ("Island", 10),
+            ("Plains", 10),
+        ]));
+        let revealed = model.score(&copies(&[("Counterspell", 1), ("Island", 2), ("Plains", 1)]));
+
+        // A handful of cards is enough to classify, at lower confidence.
+        let (full, revealed) = (full.unwrap(), revealed.unwrap());
+        assert_eq!(revealed.archetype_name, "Azorius Control");
+        assert!(revealed.confidence < full.confidence);
+    }
 
-        let result = score_deck(&deck, &card_to_archetypes, 1.0);
-        assert!(result.is_none());
+    #[test]
+    fn test_score_is_quantity_aware() {
+        // Both archetypes play Play with Fire, but only Mono Red plays it as a four-of.
+        let score = model().score(&copies(&[("Play with Fire", 3)])).unwrap();
+        assert_eq!(score.archetype_name, "Mono Red");
+        assert_eq!(score.evidence.runners_up[0].archetype_name, "Azorius Control");
+        assert!(score.evidence.runners_up[0].score < score.confidence);
     }
 
     #[test]
-    fn test_score_deck_confidence_scaling() {
-        let mut card_to_archetypes: CardLookup = HashMap::new();
-        card_to_archetypes.insert("Lightning Bolt".to_string(), vec![(1, "Mono Red".to_string(), 0.8)]);
-        card_to_archetypes.insert(
-            "Monastery Swiftspear".to_string(),
-            vec![(1, "Mono Red".to_string(), 0.9)],
-        );
+    fn test_score_explains_classification() {
+        let deck = copies(&[("Lightning Bolt", 2), ("Monastery Swiftspear", 1), ("Counterspell", 1)]);
+        let score = model().score(&deck).unwrap();
+        assert_eq!(score.archetype_name, "Mono Red");
+
+        let matched: Vec<&str> = score.evidence.top_cards(usize::MAX).collect();
+        assert_eq!(matched, ["Lightning Bolt", "Monastery Swiftspear"]);
+        // Bolt counts once per copy.
+        let bolt = score.evidence.matched_cards[0].weight;
+        let swiftspear = score.evidence.matched_cards[1].weight;
+        assert!((bolt - 2.0 * swiftspear).abs() < 1e-6);
+
+        assert_eq!(score.evidence.runners_up.len(), 1);
+        assert_eq!(score.evidence.runners_up[0].archetype_name, "Azorius Control");
+        assert!(score.evidence.runners_up[0].score < score.confidence);
+    }
 
-        let deck = vec!["Lightning Bolt".to_string(), "Monastery Swiftspear".to_string()];
+    #[test]
+    fn test_limited_deck_size() {
+        assert!((deck_size("limited") - 40.0).abs() < f32::EPSILON);
+        assert!((deck_size("cube") - 40.0).abs() < f32::EPSILON);
+        assert!((deck_size("standard") - 60.0).abs() < f32::EPSILON);
+
+        let decks = [
+            deck(
+                1,
+                1,
+                "Boros",
+                &[("Rally at the Hornburg", 2), ("Mountain", 8), ("Plains", 8)],
+            ),
+            deck(2, 2, "Dimir", &[("Spiteful Banditry", 2), ("Island", 8), ("Swamp", 8)]),
+        ];
+        let limited = ArchetypeModel::train(&decks, 40.0);
+        let constructed = ArchetypeModel::train(&decks, 60.0);
+        let seen = copies(&[("Rally at the Hornburg", 2), ("Mountain", 8)]);
+        let limited = limited.score(&seen).unwrap();
+        let constructed = constructed.score(&seen).unwrap();
+        assert_eq!(limited.archetype_name, "Boros");
+        assert!(limited.confidence > constructed.confidence);
+    }
 
-        let full = score_deck(&deck, &card_to_archetypes, 1.0).unwrap();
-        let partial = score_deck(&deck, &card_to_archetypes, 0.25).unwrap();
+    #[test]
+    fn test_model_survives_signature_round_trip() {
+        let trained = model();
+        let rows: Vec<SignatureCardRow> = trained
+            .signature_cards("standard")
+            .into_iter()
+            .map(|c| SignatureCardRow {
+                archetype_id: c.archetype_id,
+                archetype_name: c.archetype_name,
+                card_name: c.card_name,
+                weight: c.weight,
+                copies: Some(c.copies),
+                idf: Some(c.idf),
+            })
+            .collect();
+        let loaded = ArchetypeModel::from_signatures(&rows, 60.0);
+
+        let deck = copies(&[("Sunfall", 2), ("Plains", 5), ("Play with Fire", 1)]);
+        let (trained, loaded) = (trained.score(&deck).unwrap(), loaded.score(&deck).unwrap());
+        assert_eq!(trained.archetype_id, loaded.archetype_id);
+        assert!((trained.confidence - loaded.confidence).abs() < 1e-6);
+    }
 
-        assert!(partial.2 < full.2);
+    #[test]
+    fn test_group_labelled_decks() {
+        let row = |deck_id, card: &str| LabelledDeckCardRow {
+            deck_id,
+            archetype_id: deck_id,
+            archetype_name: format!("Archetype {deck_id}"),
+            card_name: card.to_string(),
+            quantity: 4,
+        };
+        let decks = group_labelled_decks(vec![row(1, "A"), row(1, "B"), row(2, "C")]);
+        assert_eq!(decks.len(), 2);
+        assert_eq!(decks[0].cards.len(), 2);
+        assert_eq!(decks[1].archetype_name, "Archetype 2");
     }
 
     #[test]
-    fn test_event_id_to_format() {
-        assert_eq!(event_id_to_format("Ladder"), Some("standard"));
-        assert_eq!(event_id_to_format("Traditional_Ladder"), Some("standard"));
-        assert_eq!(event_id_to_format("Explorer_Ladder"), Some("explorer"));
-        assert_eq!(event_id_to_format("SomeRandomEvent"), None);
+    fn test_evaluate_held_out_decks() {
+        let decks: Vec<LabelledDeck> = (0..10)
+            .map(|i| if i % 2 == 0 { mono_red(i) } else { azorius(i) })
+            .collect();
+
+        let full = evaluate(&decks, 60.0, 3, None);
+        assert_eq!(full.decks, 4);
+        assert_eq!(full.correct, 4);
+        assert!((full.accuracy().unwrap() - 100.0).abs() < 1e-9);
+
+        let revealed = evaluate(&decks, 60.0, 3, Some(8));
+        assert_eq!(revealed.decks, 4);
+        assert_eq!(revealed.correct + revealed.unclassified, 4);
+        assert!(Evaluation::default().accuracy().is_none());
     }
 }
@@ -0,0 +1,485 @@
+//! Archetype discovery: cluster decks no archetype claims and propose the clusters as new
+//! archetypes.
+//!
+//! Each deck is a vector of card copies weighted by inverse deck frequency, so cards every deck
+//! plays (basic lands, format staples) count for little. Decks are merged bottom-up with average
+//! linkage on cosine similarity until no two clusters are similar enough.
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::HashMap,
+};
+
+use anyhow::{Result, bail};
+use arenabuddy_data::{
+    MetagameRepository,
+    metagame_models::{
+        ArchetypeClusterCard, ArchetypeClusterMember, ArchetypeClusterRow, MatchArchetype, MetagameDeck,
+        NewArchetypeCluster,
+    },
+};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::classification;
+
+/// Source recorded on the decks an accepted cluster contributes to the metagame tables.
+pub const CLUSTER_SOURCE: &str = "cluster";
+
+/// Most unclassified decks clustered per pass. Clustering is cubic in the deck count.
+const MAX_CLUSTER_DECKS: i64 = 500;
+
+/// Decks with fewer known cards than this are too partial to cluster.
+const MIN_DECK_CARDS: usize = 12;
+
+/// Clusters stop merging once the most similar pair falls below this average similarity.
+const MERGE_THRESHOLD: f32 = 0.6;
+
+/// Smallest cluster worth proposing as an archetype.
+const MIN_CLUSTER_SIZE: usize = 3;
+
+/// A defining card is played by at least this fraction of the cluster's decks.
+const DEFINING_CARD_FREQUENCY: f32 = 0.6;
+
+/// Most defining cards stored per cluster.
+const MAX_DEFINING_CARDS: usize = 15;
+
+/// An unclassified deck: one side of a match and the cards seen for it, one entry per copy.
+#[derive(Debug, Clone)]
+pub struct UnclassifiedDeck {
+    pub match_id: Uuid,
+    pub side: String,
+    pub cards: Vec<String>,
+}
+
+/// Sparse card vector, sorted by card index and L2-normalized.
+type Vector = Vec<(usize, f32)>;
+
+fn dot(a: &[(usize, f32)], b: &[(usize, f32)]) -> f32 {
+    let (mut i, mut j, mut sum) = (0, 0, 0.0);
+    while i < a.len() && j < b.len() {
+        match a[i].0.cmp(&b[j].0) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                sum += a[i].1 * b[j].1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    sum
+}
+
+fn normalize(vector: &mut [(usize, f32)]) {
+    let norm = vector.iter().map(|(_, w)| w * w).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for (_, w) in vector {
+            *w /= norm;
+        }
+    }
+}
+
+/// Decks as idf-weighted card vectors.
+struct Corpus<'a> {
+    names: Vec<&'a str>,
+    idf: Vec<f32>,
+    /// Copies of each card, per deck.
+    copies: Vec<HashMap<usize, u32>>,
+    vectors: Vec<Vector>,
+}
+
+impl<'a> Corpus<'a> {
+    fn new(decks: &[&'a UnclassifiedDeck]) -> Self {
+        let mut vocabulary: HashMap<&str, usize> = HashMap::new();
+        let mut names = Vec::new();
+        let copies: Vec<HashMap<usize, u32>> = decks
+            .iter()
+            .map(|deck| {
+                let mut counts = HashMap::new();
+                for card in &deck.cards {
+                    let index = *vocabulary.entry(card.as_str()).or_insert_with(|| {
+                        names.push(card.as_str());
+                        names.len() - 1
+                    });
+                    *counts.entry(index).or_insert(0) += 1;
+                }
+                counts
+            })
+            .collect();
+
+        let mut deck_frequency = vec![0u32; names.len()];
+        for counts in &copies {
+            for &index in counts.keys() {
+                deck_frequency[index] += 1;
+            }
+        }
+        #[expect(clippy::cast_precision_loss)]
+        let deck_count = decks.len() as f32;
+        #[expect(clippy::cast_precision_loss)]
+        let idf: Vec<f32> = deck_frequency.iter().map(|&df| (deck_count / df as f32).ln()).collect();
+
+        let vectors = copies
+            .iter()
+            .map(|counts| {
+                #[expect(clippy::cast_precision_loss)]
+                let mut vector: Vector = counts
+                    .iter()
+                    .map(|(&index, &n)| (index, n as f32 * idf[index]))
+                    .filter(|(_, weight)| *weight > 0.0)
+                    .collect();
+                vector.sort_unstable_by_key(|(index, _)| *index);
+                normalize(&mut vector);
+                vector
+            })
+            .collect();
+
+        Self {
+            names,
+            idf,
+            copies,
+            vectors,
+        }
+    }
+
+    /// The normalized mean of the members' vectors.
+    fn centroid(&self, members: &[usize]) -> Vector {
+        let mut sums: HashMap<usize, f32> = HashMap::new();
+        for &member in members {
+            for &(index, weight) in &self.vectors[member] {
+                *sums.entry(index).or_insert(0.0) += weight;
+            }
+        }
+        let mut centroid: Vector = sums.into_iter().collect();
+        centroid.sort_unstable_by_key(|(index, _)| *index);
+        normalize(&mut centroid);
+        centroid
+    }
+
+    /// Cards most members play, most characteristic first.
+    fn defining_cards(&self, members: &[usize]) -> Vec<ArchetypeClusterCard> {
+        let mut played: HashMap<usize, u32> = HashMap::new();
+        for &member in members {
+            for &index in self.copies[member].keys() {
+                *played.entry(index).or_insert(0) += 1;
+            }
+        }
+
+        #[expect(clippy::cast_precision_loss)]
+        let size = members.len() as f32;
+        #[expect(clippy::cast_precision_loss)]
+        let mut defining: Vec<(usize, f32)> = played
+            .into_iter()
+            .map(|(index, n)| (index, n as f32 / size))
+            .filter(|&(index, frequency)| frequency >= DEFINING_CARD_FREQUENCY && self.idf[index] > 0.0)
+            .collect();
+        defining.sort_by(|a, b| {
+            (b.1 * self.idf[b.0])
+                .total_cmp(&(a.1 * self.idf[a.0]))
+                .then_with(|| self.names[a.0].cmp(self.names[b.0]))
+        });
+        defining.truncate(MAX_DEFINING_CARDS);
+
+        defining
+            .into_iter()
+            .map(|(index, frequency)| ArchetypeClusterCard {
+                card_name: self.names[index].to_string(),
+                frequency,
+            })
+            .collect()
+    }
+}
+
+/// Group similar decks into proposed archetypes, largest first. Decks too partial to compare
+/// and clusters smaller than [`MIN_CLUSTER_SIZE`] are left out.
+pub fn find_clusters(decks: &[UnclassifiedDeck]) -> Vec<NewArchetypeCluster> {
+    let decks: Vec<&UnclassifiedDeck> = decks.iter().filter(|d| d.cards.len() >= MIN_DECK_CARDS).collect();
+    if decks.len() < MIN_CLUSTER_SIZE {
+        return Vec::new();
+    }
+
+    let corpus = Corpus::new(&decks);
+    let mut clusters: Vec<NewArchetypeCluster> = average_linkage(&corpus.vectors)
+        .into_iter()
+        .filter(|members| members.len() >= MIN_CLUSTER_SIZE)
+        .map(|members| {
+            let cards = corpus.defining_cards(&members);
+            let suggested_name = match cards.as_slice() {
+                [] => "Unnamed cluster".to_string(),
+                [only] => only.card_name.clone(),
+                [first, second, ..] => format!("{} / {}", first.card_name, second.card_name),
+            };
+
+            let centroid = corpus.centroid(&members);
+            let mut members: Vec<ArchetypeClusterMember> = members
+                .iter()
+                .map(|&member| ArchetypeClusterMember {
+                    match_id: decks[member].match_id,
+                    side: decks[member].side.clone(),
+                    similarity: dot(&corpus.vectors[member], &centroid),
+                })
+                .collect();
+            members.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+
+            NewArchetypeCluster {
+                suggested_name,
+                cards,
+                members,
+            }
+        })
+        .collect();
+
+    clusters.sort_by_key(|c| Reverse(c.members.len()));
+    clusters
+}
+
+/// Agglomerative clustering with average linkage. Returns the groups of vector indices.
+fn average_linkage(vectors: &[Vector]) -> Vec<Vec<usize>> {
+    let mut similarity: Vec<Vec<f32>> = vectors
+        .iter()
+        .map(|a| vectors.iter().map(|b| dot(a, b)).collect())
+        .collect();
+
+    let mut groups: Vec<Option<Vec<usize>>> = (0..vectors.len()).map(|i| Some(vec![i])).collect();
+    loop {
+        let active: Vec<usize> = (0..groups.len()).filter(|&i| groups[i].is_some()).collect();
+        let mut best: Option<(usize, usize)> = None;
+        for (position, &i) in active.iter().enumerate() {
+            for &j in &active[position + 1..] {
+                if best.is_none_or(|(bi, bj)| similarity[i][j] > similarity[bi][bj]) {
+                    best = Some((i, j));
+                }
+            }
+        }
+        let Some((keep, absorb)) = best.filter(|&(i, j)| similarity[i][j] >= MERGE_THRESHOLD) else {
+            break;
+        };
+
+        let absorbed = groups[absorb].take().unwrap_or_default();
+        let group = groups[keep].get_or_insert_with(Vec::new);
+        #[expect(clippy::cast_precision_loss)]
+        let (keep_size, absorb_size) = (group.len() as f32, absorbed.len() as f32);
+        group.extend(absorbed);
+
+        // Lance-Williams update: average linkage weights each side by its size.
+        for &other in &active {
+            if other != keep && other != absorb {
+                let merged = (keep_size * similarity[keep][other] + absorb_size * similarity[absorb][other])
+                    / (keep_size + absorb_size);
+                similarity[keep][other] = merged;
+                similarity[other][keep] = merged;
+            }
+        }
+    }
+
+    groups.into_iter().flatten().collect()
+}
+
+/// Cluster the format's unclassified decks and replace its proposed clusters with the result.
+/// Returns the number of clusters proposed.
+pub async fn propose_clusters(repo: &impl MetagameRepository, format: &str) -> Result<u64> {
+    let rows = repo.get_unclassified_decks(format, MAX_CLUSTER_DECKS).await?;
+    info!("Clustering {} unclassified decks for {format}", rows.len());
+
+    let mut decks = Vec::with_capacity(rows.len());
+    for row in rows {
+        let cards = classification::side_cards(repo, &row.match_id.to_string(), &row.side).await?;
+        decks.push(UnclassifiedDeck {
+            match_id: row.match_id,
+            side: row.side,
+            cards,
+        });
+    }
+
+    let clusters = find_clusters(&decks);
+    for cluster in &clusters {
+        info!("  Cluster {}: {} decks", cluster.suggested_name, cluster.members.len());
+    }
+
+    let count = repo.replace_proposed_clusters(format, &clusters).await?;
+    info!("Proposed {count} archetype clusters for {format}");
+    Ok(count)
+}
+
+/// Accept a proposed cluster as the archetype `name`.
+///
+/// The cluster is claimed first, so of two concurrent accepts only one does anything. The
+/// member decks then become the archetype's reference decks, the format's signature cards are
+/// recomputed so future matches classify as it, and the members are classified as it with
+/// their centroid similarity as confidence. If that fails the cluster is proposed again.
+///
+/// Returns the archetype id, or `None` if the cluster was resolved in the meantime.
+pub async fn accept_cluster(
+    repo: &impl MetagameRepository,
+    cluster: &ArchetypeClusterRow,
+    name: &str,
+) -> Result<Option<i32>> {
+    if cluster.status != "proposed" {
+        bail!("cluster {} is already {}", cluster.id, cluster.status);
+    }
+
+    let archetype_id = repo.upsert_metagame_archetype(name, &cluster.format, None).await?;
+    if !repo
+        .resolve_archetype_cluster(cluster.id, "accepted", Some(archetype_id))
+        .await?
+    {
+        return Ok(None);
+    }
+
+    match adopt_members(repo, cluster, name, archetype_id).await {
+        Ok(members) => {
+            info!(
+                "Accepted cluster {} as {name} (archetype {archetype_id}, {members} decks)",
+                cluster.id
+            );
+            Ok(Some(archetype_id))
+        }
+        Err(e) => {
+            if let Err(reopen) = repo.reopen_archetype_cluster(cluster.id).await {
+                warn!("Failed to reopen archetype cluster {}: {reopen}", cluster.id);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Store a claimed cluster's members as reference decks of `archetype_id`, recompute the
+/// format's signature cards and classify the members. Returns the number of members.
+async fn adopt_members(
+    repo: &impl MetagameRepository,
+    cluster: &ArchetypeClusterRow,
+    name: &str,
+    archetype_id: i32,
+) -> Result<usize> {
+    let members = repo.get_archetype_cluster_members(cluster.id).await?;
+
+    for member in &members {
+        let match_id = member.match_id.to_string();
+        let deck = MetagameDeck {
+            source: CLUSTER_SOURCE.to_string(),
+            source_id: format!("{}/{match_id}/{}", cluster.id, member.side),
+            archetype_name: Some(name.to_string()),
+            player_name: None,
+            placement: None,
+            format: cluster.format.clone(),
+            date: None,
+            url: String::new(),
+        };
+        classification::store_match_deck(repo, &deck, archetype_id, &match_id, &member.side).await?;
+    }
+
+    classification::compute_signature_cards(repo, &cluster.format).await?;
+
+    for member in &members {
+        repo.upsert_match_archetype(&MatchArchetype {
+            match_id: member.match_id.to_string(),
+            side: member.side.clone(),
+            archetype_id: Some(archetype_id),
+            archetype_name: name.to_string(),
+            confidence: member.similarity,
+            evidence: None,
+            overridden: false,
+        })
+        .await?;
+    }
+    Ok(members.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deck(cards: &[(&str, usize)]) -> UnclassifiedDeck {
+        UnclassifiedDeck {
+            match_id: Uuid::new_v4(),
+            side: "opponent".to_string(),
+            cards: cards
+                .iter()
+                .flat_map(|&(name, n)| std::iter::repeat_n(name.to_string(), n))
+                .collect(),
+        }
+    }
+
+    fn red(variant: &str) -> UnclassifiedDeck {
+        deck(&[
+            ("Lightning Bolt", 4),
+            ("Monastery Swiftspear", 4),
+            ("Play with Fire", 4),
+            (variant, 2),
+            ("Mountain", 18),
+        ])
+    }
+
+    fn blue(variant: &str) -> UnclassifiedDeck {
+        deck(&[
+            ("Counterspell", 4),
+            ("Memory Deluge", 3),
+            ("Sunfall", 2),
+            (variant, 2),
+            ("Island", 12),
+            ("Plains", 8),
+        ])
+    }
+
+    #[test]
+    fn test_find_clusters_separates_archetypes() {
+        let decks = vec![
+            red("Kumano Faces Kakkazan"),
+            blue("Dennick, Pious Apprentice"),
+            red("Bloodthirsty Adversary"),
+            blue("The Wandering Emperor"),
+            red("Squee, Dubious Monarch"),
+            blue("Shark Typhoon"),
+        ];
+
+        let clusters = find_clusters(&decks);
+        assert_eq!(clusters.len(), 2);
+        for cluster in &clusters {
+            assert_eq!(cluster.members.len(), 3);
+            assert!(cluster.members.iter().all(|m| m.similarity > 0.8));
+        }
+
+        let cards: Vec<Vec<&str>> = clusters
+            .iter()
+            .map(|c| c.cards.iter().map(|card| card.card_name.as_str()).collect())
+            .collect();
+        let red_cluster = cards.iter().find(|c| c.contains(&"Lightning Bolt")).unwrap();
+        assert!(red_cluster.contains(&"Monastery Swiftspear"));
+        assert!(!red_cluster.contains(&"Kumano Faces Kakkazan"));
+        let blue_cluster = cards.iter().find(|c| c.contains(&"Counterspell")).unwrap();
+        assert!(!blue_cluster.contains(&"Lightning Bolt"));
+    }
+
+    #[test]
+    fn test_find_clusters_names_cluster_after_defining_cards() {
+        let decks = vec![red("A"), red("B"), red("C"), blue("D"), blue("E"), blue("F")];
+        let clusters = find_clusters(&decks);
+        let red_cluster = clusters
+            .iter()
+            .find(|c| c.cards.iter().any(|card| card.card_name == "Lightning Bolt"))
+            .unwrap();
+        assert_eq!(red_cluster.suggested_name, "Lightning Bolt / Monastery Swiftspear");
+        assert_eq!(red_cluster.cards.len(), 4);
+        assert!(
+            red_cluster
+                .cards
+                .iter()
+                .all(|c| (c.frequency - 1.0).abs() < f32::EPSILON)
+        );
+    }
+
+    #[test]
+    fn test_find_clusters_skips_small_and_partial_decks() {
+        let mut decks = vec![red("A"), red("B"), blue("C")];
+        decks.push(deck(&[("Lightning Bolt", 4), ("Mountain", 4)]));
+        assert!(find_clusters(&decks).is_empty());
+    }
+
+    #[test]
+    fn test_average_linkage_stops_below_threshold() {
+        let vectors: Vec<Vector> = vec![vec![(0, 1.0)], vec![(0, 1.0)], vec![(1, 1.0)]];
+        let mut groups = average_linkage(&vectors);
+        groups.sort();
+        assert_eq!(groups, [vec![0, 1], vec![2]]);
+    }
+}
@@ -0,0 +1,629 @@
+//! The HTTP layer shared by the scrapers: an on-disk cache that revalidates pages with
+//! `ETag`/`Last-Modified`, exponential backoff on 429 and 5xx responses, a minimum interval
+//! between requests to the same host, and an offline mode that replays the cache without
+//! touching the network.
+//!
+//! The cache is content addressed: each URL has an entry in `entries/` (named by the SHA-256
+//! of the URL) that points at its body in `objects/` (named by the SHA-256 of the body), so
+//! pages with the same content are stored once.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use reqwest::{
+    Client, StatusCode, Url,
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+const DEFAULT_MIN_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_RETRIES: u32 = 4;
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_mins(1);
+
+/// How a [`FetchClient`] talks to the network.
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// `User-Agent` sent with every request.
+    pub user_agent: String,
+    /// Directory of the on-disk cache; `None` disables caching.
+    pub cache_dir: Option<PathBuf>,
+    /// Serve every request from the cache, failing on a miss, without touching the network.
+    pub offline: bool,
+    /// Minimum time between two requests to the same host.
+    pub min_interval: Duration,
+    /// How many times a request is retried after a 429 or 5xx response.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each retry after it.
+    pub initial_backoff: Duration,
+    /// Longest delay between retries, including one asked for by `Retry-After`.
+    pub max_backoff: Duration,
+}
+
+impl FetchOptions {
+    /// Uncached, online options with the default politeness settings.
+    pub fn new(user_agent: impl Into<String>) -> Self {
+        Self {
+            user_agent: user_agent.into(),
+            cache_dir: None,
+            offline: false,
+            min_interval: DEFAULT_MIN_INTERVAL,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+}
+
+/// A response from the network or the cache.
+#[derive(Debug, Clone)]
+pub struct FetchResponse {
+    pub url: String,
+    pub status: StatusCode,
+    pub body: String,
+    /// Whether the body came from the cache, after a 304 or in offline mode.
+    pub from_cache: bool,
+}
+
+impl FetchResponse {
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    /// The response, or an error if its status isn't a success.
+    pub fn error_for_status(self) -> Result<Self> {
+        anyhow::ensure!(self.is_success(), "request failed: {} ({})", self.url, self.status);
+        Ok(self)
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_str(&self.body).with_context(|| format!("invalid JSON from {}", self.url))
+    }
+}
+
+/// HTTP client with caching, retries and per-host rate limiting. See the [module docs](self).
+pub struct FetchClient {
+    client: Client,
+    options: FetchOptions,
+    cache: Option<Cache>,
+    /// Earliest time the next request to each host may start.
+    next_request: Mutex<HashMap<String, Instant>>,
+}
+
+impl FetchClient {
+    /// Build a client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built, or if `offline` is set without a
+    /// cache directory.
+    pub fn new(options: FetchOptions) -> Result<Self> {
+        anyhow::ensure!(
+            !options.offline || options.cache_dir.is_some(),
+            "offline mode needs a cache directory"
+        );
+        let client = Client::builder()
+            .user_agent(&options.user_agent)
+            .build()
+            .context("invalid http client")?;
+        let cache = options.cache_dir.clone().map(|dir| Cache { dir });
+        Ok(Self {
+            client,
+            options,
+            cache,
+            next_request: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.options.offline
+    }
+
+    /// GET `url`. Non-success statuses are returned rather than raised, except for 429 and
+    /// 5xx responses, which are retried first.
+    pub async fn get(&self, url: &str) -> Result<FetchResponse> {
+        let cached = match &self.cache {
+            Some(cache) => cache.load(url).await,
+            None => None,
+        };
+        if self.options.offline {
+            let (entry, body) = cached.with_context(|| format!("not in the cache (offline): {url}"))?;
+            debug!("Replayed {url} from the cache");
+            return Ok(entry.into_response(body));
+        }
+
+        let host = host_key(url)?;
+        let mut attempt = 0;
+        loop {
+            self.wait_turn(&host).await;
+            let mut request = self.client.get(url);
+            if let Some((entry, _)) = &cached {
+                if let Some(etag) = &entry.etag {
+                    request = request.header(IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &entry.last_modified {
+                    request = request.header(IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+            let response = request.send().await.with_context(|| format!("request failed: {url}"))?;
+            let status = response.status();
+
+            if status == StatusCode::NOT_MODIFIED
+                && let Some((entry, body)) = cached
+            {
+                debug!("{url} not modified, using the cache");
+                return Ok(entry.into_response(body));
+            }
+            if is_retryable(status) && attempt < self.options.max_retries {
+                let delay = self.backoff(attempt, retry_after(&response));
+                warn!("{url} returned {status}, retrying in {}ms", delay.as_millis());
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+
+            let etag = header_value(&response, ETAG);
+            let last_modified = header_value(&response, LAST_MODIFIED);
+            let body = response
+                .text()
+                .await
+                .with_context(|| format!("failed to read body: {url}"))?;
+            debug!("Fetched {url} ({status}, {} bytes)", body.len());
+            if let Some(cache) = &self.cache
+                && is_cacheable(status)
+            {
+                let entry = CacheEntry {
+                    url: url.to_string(),
+                    status: status.as_u16(),
+                    etag,
+                    last_modified,
+                    body: String::new(),
+                };
+                if let Err(e) = cache.store(entry, &body).await {
+                    warn!("Failed to cache {url}: {e:#}");
+                }
+            }
+            return Ok(FetchResponse {
+                url: url.to_string(),
+                status,
+                body,
+                from_cache: false,
+            });
+        }
+    }
+
+    /// Wait until `host` may be sent another request, and book the slot after it.
+    async fn wait_turn(&self, host: &str) {
+        let slot = {
+            let mut next_request = self.next_request.lock().expect("rate limit lock poisoned");
+            let now = Instant::now();
+            let slot = next_request.get(host).copied().filter(|t| *t > now).unwrap_or(now);
+            next_request.insert(host.to_string(), slot + self.options.min_interval);
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+
+    /// Delay before retry number `attempt` (from 0), preferring the server's `Retry-After`.
+    fn backoff(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let exponential = self
+            .options
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt));
+        retry_after.unwrap_or(exponential).min(self.options.max_backoff)
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Successes and definitive client errors (e.g. the 404 for an unknown set) are worth
+/// replaying; throttling and server errors aren't.
+fn is_cacheable(status: StatusCode) -> bool {
+    status.is_success()
+        || (status.is_client_error()
+            && status != StatusCode::TOO_MANY_REQUESTS
+            && status != StatusCode::REQUEST_TIMEOUT)
+}
+
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    header_value(response, RETRY_AFTER)?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+fn header_value(response: &reqwest::Response, name: reqwest::header::HeaderName) -> Option<String> {
+    response.headers().get(name)?.to_str().ok().map(ToString::to_string)
+}
+
+/// Rate limiting key of a URL: its host and port.
+fn host_key(url: &str) -> Result<String> {
+    let parsed = Url::parse(url).with_context(|| format!("invalid url: {url}"))?;
+    let host = parsed.host_str().with_context(|| format!("url has no host: {url}"))?;
+    Ok(match parsed.port_or_known_default() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    })
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    status: u16,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// SHA-256 of the body, naming its object.
+    body: String,
+}
+
+impl CacheEntry {
+    fn into_response(self, body: String) -> FetchResponse {
+        FetchResponse {
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK),
+            url: self.url,
+            body,
+            from_cache: true,
+        }
+    }
+}
+
+struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir
+            .join("entries")
+            .join(format!("{}.json", sha256_hex(url.as_bytes())))
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.dir.join("objects").join(hash)
+    }
+
+    /// The cached entry and body for `url`. Unreadable entries count as misses.
+    async fn load(&self, url: &str) -> Option<(CacheEntry, String)> {
+        let path = self.entry_path(url);
+        let json = match tokio::fs::read_to_string(&path).await {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!("Failed to read cache entry {}: {e}", path.display());
+                return None;
+            }
+        };
+        let entry: CacheEntry = match serde_json::from_str(&json) {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!("Ignoring corrupt cache entry {}: {e}", path.display());
+                return None;
+            }
+        };
+        if entry.url != url {
+            return None;
+        }
+        match tokio::fs::read_to_string(self.object_path(&entry.body)).await {
+            Ok(body) => Some((entry, body)),
+            Err(e) => {
+                warn!("Cached body of {url} is missing: {e}");
+                None
+            }
+        }
+    }
+
+    async fn store(&self, mut entry: CacheEntry, body: &str) -> Result<()> {
+        entry.body = sha256_hex(body.as_bytes());
+        let object = self.object_path(&entry.body);
+        if !tokio::fs::try_exists(&object).await? {
+            write_atomic(&object, body.as_bytes()).await?;
+        }
+        let json = serde_json::to_vec(&entry)?;
+        write_atomic(&self.entry_path(&entry.url), &json).await
+    }
+}
+
+/// Write through a temporary file so readers never see a partial file.
+async fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let tmp = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+    tokio::fs::write(&tmp, contents)
+        .await
+        .with_context(|| format!("failed to write {}", tmp.display()))?;
+    tokio::fs::rename(&tmp, path)
+        .await
+        .with_context(|| format!("failed to move {} into place", tmp.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc};
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// A local HTTP server answering each request with `respond(request head, index)`.
+    struct MockServer {
+        addr: SocketAddr,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockServer {
+        async fn start(respond: impl Fn(&str, usize) -> String + Send + Sync + 'static) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let seen = Arc::clone(&requests);
+            tokio::spawn(async move {
+                loop {
+                    let (mut stream, _) = listener.accept().await.unwrap();
+                    let mut head = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = stream.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            break;
+                        }
+                        head.extend_from_slice(&buf[..n]);
+                    }
+                    let head = String::from_utf8_lossy(&head).to_lowercase();
+                    let index = {
+                        let mut seen = seen.lock().unwrap();
+                        seen.push(head.clone());
+                        seen.len() - 1
+                    };
+                    let response = respond(&head, index);
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                    stream.shutdown().await.unwrap();
+                }
+            });
+            Self { addr, requests }
+        }
+
+        fn url(&self, path: &str) -> String {
+            format!("http://{}{path}", self.addr)
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    fn response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}\r\n"))
+            .collect::<Vec<_>>()
+            .concat();
+        format!(
+            "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n{headers}\r\n{body}",
+            body.len()
+        )
+    }
+
+    /// A scratch cache directory, removed on drop.
+    struct CacheDir(PathBuf);
+
+    impl CacheDir {
+        fn new() -> Self {
+            Self(std::env::temp_dir().join(format!("arenabuddy-fetch-{}", Uuid::new_v4())))
+        }
+    }
+
+    impl Drop for CacheDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn options(cache: Option<&CacheDir>) -> FetchOptions {
+        FetchOptions {
+            cache_dir: cache.map(|c| c.0.clone()),
+            min_interval: Duration::ZERO,
+            initial_backoff: Duration::from_millis(1),
+            ..FetchOptions::new("arenabuddy-test")
+        }
+    }
+
+    #[tokio::test]
+    async fn revalidates_with_etag() {
+        let server = MockServer::start(|req, _| {
+            if req.contains("if-none-match: \"v1\"") {
+                response("304 Not Modified", &[], "")
+            } else {
+                response("200 OK", &[("ETag", "\"v1\"")], "page")
+            }
+        })
+        .await;
+        let cache = CacheDir::new();
+        let client = FetchClient::new(options(Some(&cache))).unwrap();
+
+        let first = client.get(&server.url("/page")).await.unwrap();
+        assert_eq!(first.body, "page");
+        assert!(!first.from_cache);
+
+        let second = client.get(&server.url("/page")).await.unwrap();
+        assert_eq!(second.body, "page");
+        assert!(second.from_cache);
+        assert_eq!(second.status, StatusCode::OK);
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn revalidates_with_last_modified() {
+        let stamp = "Wed, 21 Oct 2026 07:28:00 GMT";
+        let server = MockServer::start(move |req, _| {
+            if req.contains(&format!("if-modified-since: {}", stamp.to_lowercase())) {
+                response("304 Not Modified", &[], "")
+            } else {
+                response("200 OK", &[("Last-Modified", stamp)], "cards")
+            }
+        })
+        .await;
+        let cache = CacheDir::new();
+        let client = FetchClient::new(options(Some(&cache))).unwrap();
+
+        client.get(&server.url("/cards")).await.unwrap();
+        let second = client.get(&server.url("/cards")).await.unwrap();
+        assert!(second.from_cache);
+        assert_eq!(second.body, "cards");
+    }
+
+    #[tokio::test]
+    async fn changed_pages_replace_the_cache() {
+        let server = MockServer::start(|_, index| {
+            response("200 OK", &[("ETag", &format!("\"v{index}\""))], &format!("v{index}"))
+        })
+        .await;
+        let cache = CacheDir::new();
+        let client = FetchClient::new(options(Some(&cache))).unwrap();
+
+        client.get(&server.url("/page")).await.unwrap();
+        let second = client.get(&server.url("/page")).await.unwrap();
+        assert_eq!(second.body, "v1");
+        assert!(server.requests()[1].contains("if-none-match: \"v0\""));
+
+        let offline = FetchClient::new(FetchOptions {
+            offline: true,
+            ..options(Some(&cache))
+        })
+        .unwrap();
+        assert_eq!(offline.get(&server.url("/page")).await.unwrap().body, "v1");
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_with_backoff() {
+        let server = MockServer::start(|_, index| match index {
+            0 => response("503 Service Unavailable", &[], ""),
+            1 => response("429 Too Many Requests", &[("Retry-After", "0")], ""),
+            _ => response("200 OK", &[], "ok"),
+        })
+        .await;
+        let client = FetchClient::new(options(None)).unwrap();
+
+        let response = client.get(&server.url("/busy")).await.unwrap();
+        assert_eq!(response.body, "ok");
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let server = MockServer::start(|_, _| response("500 Internal Server Error", &[], "")).await;
+        let cache = CacheDir::new();
+        let client = FetchClient::new(FetchOptions {
+            max_retries: 2,
+            ..options(Some(&cache))
+        })
+        .unwrap();
+
+        let response = client.get(&server.url("/down")).await.unwrap();
+        assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.error_for_status().is_err());
+        assert_eq!(server.requests().len(), 3);
+        assert!(!cache.0.join("entries").exists(), "server errors aren't cached");
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let client = FetchClient::new(FetchOptions {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            ..FetchOptions::new("arenabuddy-test")
+        })
+        .unwrap();
+        assert_eq!(client.backoff(0, None), Duration::from_secs(1));
+        assert_eq!(client.backoff(2, None), Duration::from_secs(4));
+        assert_eq!(client.backoff(3, None), Duration::from_secs(5));
+        assert_eq!(client.backoff(0, Some(Duration::from_secs(3))), Duration::from_secs(3));
+        assert_eq!(client.backoff(0, Some(Duration::from_secs(90))), Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn spaces_out_requests_to_the_same_host() {
+        let server = MockServer::start(|_, _| response("200 OK", &[], "ok")).await;
+        let client = FetchClient::new(FetchOptions {
+            min_interval: Duration::from_millis(100),
+            ..options(None)
+        })
+        .unwrap();
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            client.get(&server.url("/page")).await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn offline_replays_the_cache() {
+        let server = MockServer::start(|req, _| {
+            if req.starts_with("get /missing") {
+                response("404 Not Found", &[], "no such set")
+            } else {
+                response("200 OK", &[], "page")
+            }
+        })
+        .await;
+        let cache = CacheDir::new();
+        let online = FetchClient::new(options(Some(&cache))).unwrap();
+        online.get(&server.url("/page")).await.unwrap();
+        online.get(&server.url("/missing")).await.unwrap();
+
+        let offline = FetchClient::new(FetchOptions {
+            offline: true,
+            ..options(Some(&cache))
+        })
+        .unwrap();
+        let page = offline.get(&server.url("/page")).await.unwrap();
+        assert_eq!(page.body, "page");
+        assert!(page.from_cache);
+        let missing = offline.get(&server.url("/missing")).await.unwrap();
+        assert_eq!(missing.status, StatusCode::NOT_FOUND);
+        assert!(offline.get(&server.url("/never-fetched")).await.is_err());
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn offline_needs_a_cache() {
+        let options = FetchOptions {
+            offline: true,
+            ..FetchOptions::new("arenabuddy-test")
+        };
+        assert!(FetchClient::new(options).is_err());
+    }
+
+    #[tokio::test]
+    async fn identical_bodies_share_an_object() {
+        let server = MockServer::start(|_, _| response("200 OK", &[], "same")).await;
+        let cache = CacheDir::new();
+        let client = FetchClient::new(options(Some(&cache))).unwrap();
+
+        client.get(&server.url("/a")).await.unwrap();
+        client.get(&server.url("/b")).await.unwrap();
+        assert_eq!(std::fs::read_dir(cache.0.join("entries")).unwrap().count(), 2);
+        assert_eq!(std::fs::read_dir(cache.0.join("objects")).unwrap().count(), 1);
+    }
+}
@@ -1,10 +1,15 @@
 pub mod classification;
+pub mod clustering;
+pub mod fetch;
+pub mod scheduler;
 pub mod scraper;
+pub mod source;
 
 use std::path::PathBuf;
 
+use arenabuddy_core::display::metagame::{MetagameTrends, TrendInterval};
 use arenabuddy_data::{ArenabuddyRepository, MatchDB, MetagameRepository, metagame_repository::MetagameStatsResult};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use tracing::info;
 
 #[derive(Parser)]
@@ -22,9 +27,30 @@ enum Commands {
         #[arg(long)]
         local_dir: Option<PathBuf>,
 
+        /// Cache fetched pages in this directory, revalidating them on later runs.
+        #[arg(long, env = "METAGAME_CACHE_DIR")]
+        cache_dir: Option<PathBuf>,
+
+        /// Replay pages from the cache directory without fetching from the web.
+        #[arg(long, requires = "cache_dir")]
+        offline: bool,
+
         #[command(subcommand)]
         target: ScrapeTarget,
     },
+    /// Import decklists from a local folder of `.txt`/`.dek` lists or JSON/CSV tournament dumps
+    Import {
+        /// Folder of decklists
+        dir: PathBuf,
+
+        /// MTG format the lists belong to
+        #[arg(long, default_value = "standard")]
+        format: String,
+
+        /// Database URL
+        #[arg(long, env = "DATABASE_URL")]
+        db: String,
+    },
     /// Show database statistics
     Stats {
         /// MTG format (standard, pioneer, explorer, historic)
@@ -35,6 +61,39 @@ enum Commands {
         #[arg(long, env = "DATABASE_URL")]
         db: String,
     },
+    /// Show archetype share over time, with emerging and declining archetypes
+    Trends {
+        /// MTG format (standard, pioneer, explorer, historic)
+        #[arg(long, default_value = "standard")]
+        format: String,
+
+        /// Bucket tournament decks by day or by week
+        #[arg(long, value_enum, default_value_t = Interval::Weekly)]
+        interval: Interval,
+
+        /// How many days of tournaments to include
+        #[arg(long, default_value_t = 56)]
+        days: u64,
+
+        /// Database URL
+        #[arg(long, env = "DATABASE_URL")]
+        db: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Interval {
+    Daily,
+    Weekly,
+}
+
+impl From<Interval> for TrendInterval {
+    fn from(interval: Interval) -> Self {
+        match interval {
+            Interval::Daily => TrendInterval::Daily,
+            Interval::Weekly => TrendInterval::Weekly,
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -120,13 +179,66 @@ fn print_stats(stats: &MetagameStatsResult, format: &str) {
     info!("Card entries: {}", stats.card_count);
 }
 
+fn print_trends(trends: &MetagameTrends, format: &str) {
+    info!(
+        "=== {format} {} metagame share ===",
+        trends.interval.label().to_lowercase()
+    );
+    if trends.periods.is_empty() {
+        info!("No dated tournament decks");
+        return;
+    }
+
+    let header = trends
+        .periods
+        .iter()
+        .map(|p| format!("{:>7}", p.format("%m-%d")))
+        .collect::<Vec<_>>()
+        .concat();
+    info!("{:<30}{header}{:>8}", "Archetype", "Δ");
+    for trend in &trends.archetypes {
+        let shares = trend
+            .shares
+            .iter()
+            .map(|s| format!("{s:>6.1}%"))
+            .collect::<Vec<_>>()
+            .concat();
+        let delta = trend.delta().map_or_else(String::new, |d| format!("{d:+.1}"));
+        info!("{:<30}{shares}{delta:>8}", trend.archetype);
+    }
+    let totals = trends
+        .deck_totals
+        .iter()
+        .map(|n| format!("{n:>7}"))
+        .collect::<Vec<_>>()
+        .concat();
+    info!("{:<30}{totals}", "Decks");
+
+    for trend in trends.emerging() {
+        info!("Emerging:  {} ({:.1}%)", trend.archetype, trend.current());
+    }
+    for trend in trends.declining() {
+        info!(
+            "Declining: {} ({:.1}%, {:+.1})",
+            trend.archetype,
+            trend.current(),
+            trend.delta().unwrap_or_default()
+        );
+    }
+}
+
 async fn run_command(command: Commands) -> anyhow::Result<()> {
     match command {
-        Commands::Scrape { local_dir, target } => {
-            let fetcher = match local_dir {
+        Commands::Scrape {
+            local_dir,
+            cache_dir,
+            offline,
+            target,
+        } => {
+            let goldfish = scraper::MtgGoldfish::new(match local_dir {
                 Some(dir) => scraper::Fetcher::local(&dir)?,
-                None => scraper::Fetcher::http(),
-            };
+                None => scraper::Fetcher::cached(cache_dir, offline)?,
+            });
             match target {
                 ScrapeTarget::Tournaments { format, from, to, db } => {
                     let today = chrono::Utc::now().date_naive();
@@ -140,25 +252,45 @@ async fn run_command(command: Commands) -> anyhow::Result<()> {
                         None => today,
                     };
                     let repo = connect_and_init(&db).await?;
-                    scraper::tournament::scrape_tournaments(&repo, &fetcher, &format, from_date, to_date).await?;
+                    let count = source::import_tournaments(&repo, &goldfish, &format, from_date, to_date).await?;
+                    info!("Imported {count} decks for {format}");
                 }
                 ScrapeTarget::Tournament { ids, format, db } => {
                     let repo = connect_and_init(&db).await?;
                     for id in ids {
-                        scraper::tournament::scrape_single_tournament(&repo, &fetcher, id, &format).await?;
+                        let tournament = goldfish.tournament(id, &format).await?;
+                        source::import_tournament(&repo, &goldfish, &tournament).await?;
                     }
                 }
                 ScrapeTarget::Metagame { format, db } => {
                     let repo = connect_and_init(&db).await?;
-                    scraper::metagame::scrape_metagame(&repo, &fetcher, &format).await?;
+                    source::import_archetypes(&repo, &goldfish, &format).await?;
                 }
             }
         }
+        Commands::Import { dir, format, db } => {
+            let decklists = source::LocalDecklists::new(&dir)?;
+            let repo = connect_and_init(&db).await?;
+            let today = chrono::Utc::now().date_naive();
+            let count = source::import_tournaments(&repo, &decklists, &format, today, today).await?;
+            info!("Imported {count} decks for {format}");
+        }
         Commands::Stats { format, db } => {
             let repo = connect_and_init(&db).await?;
             let stats = repo.metagame_stats(&format).await?;
             print_stats(&stats, &format);
         }
+        Commands::Trends {
+            format,
+            interval,
+            days,
+            db,
+        } => {
+            let repo = connect_and_init(&db).await?;
+            let since = chrono::Utc::now().date_naive() - chrono::Days::new(days);
+            let trends = repo.metagame_trends(&format, interval.into(), since).await?;
+            print_trends(&trends, &format);
+        }
     }
     Ok(())
 }
@@ -0,0 +1,884 @@
+//! Periodic metagame refresh: import recent tournaments, recompute signature cards, reclassify
+//! matches against them, then cluster what's still unclassified into proposed archetypes. Every
+//! job run is recorded in the repository.
+
+use anyhow::Result;
+use arenabuddy_data::MetagameRepository;
+use chrono::NaiveDate;
+use tracing::{info, warn};
+
+use crate::{
+    classification::{self, ReclassifiedMatch},
+    clustering,
+    source::{self, MetagameSource},
+};
+
+/// Days of tournaments each scrape covers when not configured.
+pub const DEFAULT_LOOKBACK_DAYS: u64 = 7;
+
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    /// Metagame formats to refresh, e.g. `standard`.
+    pub formats: Vec<String>,
+    /// Days of tournaments each scrape covers, ending today.
+    pub lookback_days: u64,
+    /// Add hand-labelled match decks to the scraped ones before computing signature cards.
+    pub learn_from_overrides: bool,
+}
+
+/// The jobs run for each format, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Job {
+    /// Import tournaments from the lookback window.
+    Scrape,
+    /// Recompute the format's signature cards, optionally learning from overrides first.
+    Signatures,
+    /// Classify new matches and reclassify those older than the signature cards.
+    Reclassify,
+    /// Propose new archetypes from the decks that are still unclassified.
+    Cluster,
+}
+
+impl Job {
+    pub const ALL: [Self; 4] = [Self::Scrape, Self::Signatures, Self::Reclassify, Self::Cluster];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Scrape => "scrape",
+            Self::Signatures => "signatures",
+            Self::Reclassify => "reclassify",
+            Self::Cluster => "cluster",
+        }
+    }
+}
+
+/// Run every job once for each configured format, as of `today`, and return the matches whose
+/// archetypes the reclassification changed so their owners can be notified.
+///
+/// A failing job is recorded as failed and the remaining jobs still run, since signature cards
+/// can be recomputed from previously scraped decks. Only failures to record a run are returned.
+pub async fn run_once(
+    repo: &impl MetagameRepository,
+    source: &dyn MetagameSource,
+    config: &SchedulerConfig,
+    today: NaiveDate,
+) -> Result<Vec<ReclassifiedMatch>> {
+    let from = today - chrono::Days::new(config.lookback_days);
+    let mut changed = Vec::new();
+    for format in &config.formats {
+        for job in Job::ALL {
+            let outcome = match job {
+                Job::Scrape => {
+                    let import = source::import_tournaments(repo, source, format, from, today);
+                    record(repo, job, format, import).await?
+                }
+                Job::Signatures => {
+                    record(repo, job, format, async {
+                        if config.learn_from_overrides {
+                            classification::sync_override_decks(repo, format).await?;
+                        }
+                        classification::compute_signature_cards(repo, format).await
+                    })
+                    .await?
+                }
+                Job::Reclassify => {
+                    record(repo, job, format, async {
+                        let classified = classification::classify_matches(repo, format).await?;
+                        let reclassified = classification::reclassify_stale_matches(repo, format).await?;
+                        let count = classified + reclassified.len() as u64;
+                        changed.extend(reclassified.into_iter().filter(|m| m.changed));
+                        Ok(count)
+                    })
+                    .await?
+                }
+                Job::Cluster => record(repo, job, format, clustering::propose_clusters(repo, format)).await?,
+            };
+            match outcome {
+                Ok(items) => info!("Metagame job {} for {format} finished: {items} item(s)", job.as_str()),
+                Err(e) => warn!("Metagame job {} for {format} failed: {e:#}", job.as_str()),
+            }
+        }
+    }
+    Ok(changed)
+}
+
+/// Run `work` as a recorded job run. The outer result is the bookkeeping, the inner the job's.
+async fn record(
+    repo: &impl MetagameRepository,
+    job: Job,
+    format: &str,
+    work: impl Future<Output = Result<u64>>,
+) -> Result<Result<u64>> {
+    let run_id = repo.start_metagame_job_run(job.as_str(), format).await?;
+    let outcome = work.await;
+    match &outcome {
+        Ok(items) => {
+            let items = i64::try_from(*items).unwrap_or(i64::MAX);
+            repo.finish_metagame_job_run(run_id, items, None).await?;
+        }
+        Err(e) => {
+            repo.finish_metagame_job_run(run_id, 0, Some(&format!("{e:#}"))).await?;
+        }
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::{Path, PathBuf},
+        sync::Mutex,
+    };
+
+    use arenabuddy_core::display::metagame::{MetagameTrends, TrendInterval};
+    use arenabuddy_data::{
+        metagame_models::{
+            ArchetypeClusterCard, ArchetypeClusterMember, ArchetypeClusterRow, ArchetypeOverrideRow,
+            LabelledDeckCardRow, MatchArchetype, MetagameDeck, MetagameDeckCard, MetagameJobRun, MetagameTournament,
+            NewArchetypeCluster, SignatureCard, SignatureCardRow, UnclassifiedDeckRow, UnclassifiedMatchRow,
+        },
+        metagame_repository::MetagameStatsResult,
+    };
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::scraper::{Fetcher, MtgGoldfish, fetcher::path_to_filename, tournament};
+
+    /// A user's match: the controller's deck and its classification, stamped with the clock.
+    struct FakeMatch {
+        id: Uuid,
+        cards: Vec<String>,
+        controller: Option<(String, u64)>,
+    }
+
+    /// A stored reference deck: its source and id there, archetype and mainboard.
+    struct FakeDeck {
+        source: String,
+        source_id: String,
+        archetype_id: Option<i32>,
+        cards: Vec<(String, i32)>,
+    }
+
+    /// In-memory repository. Time is a counter bumped by every write that stamps a row. Only
+    /// the controller side of matches is classified.
+    #[derive(Default)]
+    struct FakeRepo {
+        state: Mutex<FakeState>,
+    }
+
+    #[derive(Default)]
+    struct FakeState {
+        clock: u64,
+        archetypes: Vec<String>,
+        /// Deck ids are positions in this list, plus one.
+        decks: Vec<FakeDeck>,
+        signatures: Vec<SignatureCardRow>,
+        signatures_at: Option<u64>,
+        matches: Vec<FakeMatch>,
+        overrides: Vec<ArchetypeOverrideRow>,
+        runs: Vec<MetagameJobRun>,
+        clusters: Vec<NewArchetypeCluster>,
+    }
+
+    impl FakeState {
+        fn tick(&mut self) -> u64 {
+            self.clock += 1;
+            self.clock
+        }
+    }
+
+    fn count<T>(items: impl Iterator<Item = T>) -> i64 {
+        i64::try_from(items.count()).unwrap()
+    }
+
+    /// Signature cards in a comparable, order-independent form.
+    fn signature_keys(rows: &[SignatureCardRow]) -> Vec<(i32, String, [Option<u32>; 3])> {
+        let mut keys: Vec<_> = rows
+            .iter()
+            .map(|r| {
+                let values = [Some(r.weight), r.copies, r.idf].map(|v| v.map(f32::to_bits));
+                (r.archetype_id, r.card_name.clone(), values)
+            })
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[async_trait::async_trait]
+    impl MetagameRepository for FakeRepo {
+        async fn upsert_metagame_tournament(&self, tournament: &MetagameTournament) -> arenabuddy_data::Result<i32> {
+            Ok(tournament.source_id.parse().unwrap())
+        }
+
+        async fn upsert_metagame_archetype(
+            &self,
+            name: &str,
+            _format: &str,
+            _url: Option<&str>,
+        ) -> arenabuddy_data::Result<i32> {
+            let mut state = self.state.lock().unwrap();
+            let index = if let Some(index) = state.archetypes.iter().position(|a| a == name) {
+                index
+            } else {
+                state.archetypes.push(name.to_string());
+                state.archetypes.len() - 1
+            };
+            Ok(i32::try_from(index).unwrap() + 1)
+        }
+
+        async fn upsert_metagame_deck(
+            &self,
+            deck: &MetagameDeck,
+            _tournament_id: Option<i32>,
+            archetype_id: Option<i32>,
+            cards: &[MetagameDeckCard],
+        ) -> arenabuddy_data::Result<i32> {
+            let stored = FakeDeck {
+                source: deck.source.clone(),
+                source_id: deck.source_id.clone(),
+                archetype_id,
+                cards: cards
+                    .iter()
+                    .filter(|c| !c.is_sideboard)
+                    .map(|c| (c.card_name.clone(), c.quantity))
+                    .collect(),
+            };
+            let mut state = self.state.lock().unwrap();
+            let existing = state
+                .decks
+                .iter()
+                .position(|d| d.source == stored.source && d.source_id == stored.source_id);
+            let index = if let Some(index) = existing {
+                state.decks[index] = stored;
+                index
+            } else {
+                state.decks.push(stored);
+                state.decks.len() - 1
+            };
+            Ok(i32::try_from(index).unwrap() + 1)
+        }
+
+        async fn metagame_stats(&self, _format: &str) -> arenabuddy_data::Result<MetagameStatsResult> {
+            let state = self.state.lock().unwrap();
+            Ok(MetagameStatsResult {
+                tournament_count: 0,
+                archetype_count: count(state.archetypes.iter()),
+                deck_count: count(state.decks.iter()),
+                card_count: count(state.decks.iter().flat_map(|d| &d.cards)),
+            })
+        }
+
+        async fn metagame_trends(
+            &self,
+            _format: &str,
+            interval: TrendInterval,
+            _since: NaiveDate,
+        ) -> arenabuddy_data::Result<MetagameTrends> {
+            Ok(MetagameTrends {
+                interval,
+                ..MetagameTrends::default()
+            })
+        }
+
+        async fn get_labelled_deck_cards(&self, _format: &str) -> arenabuddy_data::Result<Vec<LabelledDeckCardRow>> {
+            let state = self.state.lock().unwrap();
+            let mut rows = Vec::new();
+            for (deck_id, deck) in (1..).zip(&state.decks) {
+                let Some(archetype_id) = deck.archetype_id else {
+                    continue;
+                };
+                let name = &state.archetypes[usize::try_from(archetype_id - 1).unwrap()];
+                for (card_name, quantity) in &deck.cards {
+                    rows.push(LabelledDeckCardRow {
+                        deck_id,
+                        archetype_id,
+                        archetype_name: name.clone(),
+                        card_name: card_name.clone(),
+                        quantity: *quantity,
+                    });
+                }
+            }
+            Ok(rows)
+        }
+
+        async fn get_stock_list(
+            &self,
+            _format: &str,
+            _archetype_name: &str,
+        ) -> arenabuddy_data::Result<Option<arenabuddy_core::display::deck::StockList>> {
+            Ok(None)
+        }
+
+        async fn replace_signature_cards(
+            &self,
+            _format: &str,
+            cards: &[SignatureCard],
+        ) -> arenabuddy_data::Result<u64> {
+            let rows: Vec<SignatureCardRow> = cards
+                .iter()
+                .map(|c| SignatureCardRow {
+                    archetype_id: c.archetype_id,
+                    archetype_name: c.archetype_name.clone(),
+                    card_name: c.card_name.clone(),
+                    weight: c.weight,
+                    copies: Some(c.copies),
+                    idf: Some(c.idf),
+                })
+                .collect();
+            let mut state = self.state.lock().unwrap();
+            if signature_keys(&rows) == signature_keys(&state.signatures) {
+                return Ok(0);
+            }
+            state.signatures = rows;
+            state.signatures_at = Some(state.tick());
+            Ok(u64::try_from(cards.len()).unwrap())
+        }
+
+        async fn get_signature_cards(&self, _format: &str) -> arenabuddy_data::Result<Vec<SignatureCardRow>> {
+            Ok(self.state.lock().unwrap().signatures.clone())
+        }
+
+        async fn get_unclassified_matches(&self, _format: &str) -> arenabuddy_data::Result<Vec<UnclassifiedMatchRow>> {
+            let state = self.state.lock().unwrap();
+            Ok(state
+                .matches
+                .iter()
+                .filter(|m| m.controller.is_none())
+                .map(|m| UnclassifiedMatchRow {
+                    match_id: m.id,
+                    format: Some("Ladder".to_string()),
+                    user_id: None,
+                })
+                .collect())
+        }
+
+        async fn get_stale_classified_matches(
+            &self,
+            _format: &str,
+        ) -> arenabuddy_data::Result<Vec<UnclassifiedMatchRow>> {
+            let state = self.state.lock().unwrap();
+            let Some(signatures_at) = state.signatures_at else {
+                return Ok(Vec::new());
+            };
+            Ok(state
+                .matches
+                .iter()
+                .filter(|m| m.controller.as_ref().is_some_and(|(_, at)| *at < signatures_at))
+                .map(|m| UnclassifiedMatchRow {
+                    match_id: m.id,
+                    format: Some("Ladder".to_string()),
+                    user_id: None,
+                })
+                .collect())
+        }
+
+        async fn replace_match_archetypes(
+            &self,
+            match_ids: &[Uuid],
+            archetypes: &[MatchArchetype],
+        ) -> arenabuddy_data::Result<()> {
+            let mut state = self.state.lock().unwrap();
+            let at = state.tick();
+            for m in state.matches.iter_mut().filter(|m| match_ids.contains(&m.id)) {
+                m.controller = archetypes
+                    .iter()
+                    .find(|a| a.side == "controller" && a.match_id == m.id.to_string())
+                    .map(|a| (a.archetype_name.clone(), at));
+            }
+            Ok(())
+        }
+
+        async fn upsert_match_archetype(&self, archetype: &MatchArchetype) -> arenabuddy_data::Result<()> {
+            if archetype.side != "controller" {
+                return Ok(());
+            }
+            let mut state = self.state.lock().unwrap();
+            let at = state.tick();
+            for m in state
+                .matches
+                .iter_mut()
+                .filter(|m| m.id.to_string() == archetype.match_id)
+            {
+                m.controller = Some((archetype.archetype_name.clone(), at));
+            }
+            Ok(())
+        }
+
+        async fn get_match_deck_cards(&self, match_id: &str) -> arenabuddy_data::Result<Vec<String>> {
+            let state = self.state.lock().unwrap();
+            Ok(state
+                .matches
+                .iter()
+                .find(|m| m.id.to_string() == match_id)
+                .map(|m| m.cards.clone())
+                .unwrap_or_default())
+        }
+
+        async fn get_match_opponent_cards(&self, _match_id: &str) -> arenabuddy_data::Result<Vec<String>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_match_side_cards(
+            &self,
+            _match_id: &str,
+            _side: &str,
+        ) -> arenabuddy_data::Result<Vec<arenabuddy_core::models::Card>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_match_archetypes(
+            &self,
+            match_id: &str,
+        ) -> arenabuddy_data::Result<(Option<String>, Option<String>)> {
+            let classifications = self.get_match_classifications(match_id).await?;
+            let side = |side: &str| {
+                classifications
+                    .iter()
+                    .find(|c| c.side == side)
+                    .map(|c| c.archetype_name.clone())
+            };
+            Ok((side("controller"), side("opponent")))
+        }
+
+        async fn get_match_classifications(&self, match_id: &str) -> arenabuddy_data::Result<Vec<MatchArchetype>> {
+            let state = self.state.lock().unwrap();
+            let classification = |side: &str, archetype_name: &str, overridden| MatchArchetype {
+                match_id: match_id.to_string(),
+                side: side.to_string(),
+                archetype_id: None,
+                archetype_name: archetype_name.to_string(),
+                confidence: 1.0,
+                evidence: None,
+                overridden,
+            };
+            let mut classifications: Vec<MatchArchetype> = state
+                .overrides
+                .iter()
+                .filter(|o| o.match_id.to_string() == match_id)
+                .map(|o| classification(&o.side, &o.archetype_name, true))
+                .collect();
+            let controller = state
+                .matches
+                .iter()
+                .find(|m| m.id.to_string() == match_id)
+                .and_then(|m| m.controller.as_ref());
+            if let Some((name, _)) = controller
+                && !classifications.iter().any(|c| c.side == "controller")
+            {
+                classifications.push(classification("controller", name, false));
+            }
+            classifications.sort_by(|a, b| a.side.cmp(&b.side));
+            Ok(classifications)
+        }
+
+        async fn set_match_archetype_override(
+            &self,
+            match_id: &str,
+            side: &str,
+            archetype_name: &str,
+            _set_by: Option<Uuid>,
+        ) -> arenabuddy_data::Result<()> {
+            self.clear_match_archetype_override(match_id, side).await?;
+            self.state.lock().unwrap().overrides.push(ArchetypeOverrideRow {
+                match_id: Uuid::parse_str(match_id)?,
+                side: side.to_string(),
+                archetype_name: archetype_name.to_string(),
+            });
+            Ok(())
+        }
+
+        async fn clear_match_archetype_override(&self, match_id: &str, side: &str) -> arenabuddy_data::Result<bool> {
+            let mut state = self.state.lock().unwrap();
+            let before = state.overrides.len();
+            state
+                .overrides
+                .retain(|o| o.match_id.to_string() != match_id || o.side != side);
+            Ok(state.overrides.len() < before)
+        }
+
+        async fn get_archetype_overrides(&self, _format: &str) -> arenabuddy_data::Result<Vec<ArchetypeOverrideRow>> {
+            Ok(self.state.lock().unwrap().overrides.clone())
+        }
+
+        async fn prune_metagame_decks(
+            &self,
+            source: &str,
+            _format: &str,
+            keep: &[String],
+        ) -> arenabuddy_data::Result<u64> {
+            let mut state = self.state.lock().unwrap();
+            let before = state.decks.len();
+            state
+                .decks
+                .retain(|d| d.source != source || keep.contains(&d.source_id));
+            Ok(u64::try_from(before - state.decks.len()).unwrap())
+        }
+
+        async fn get_unclassified_decks(
+            &self,
+            _format: &str,
+            limit: i64,
+        ) -> arenabuddy_data::Result<Vec<UnclassifiedDeckRow>> {
+            let state = self.state.lock().unwrap();
+            Ok(state
+                .matches
+                .iter()
+                .filter(|m| m.controller.is_none())
+                .take(usize::try_from(limit).unwrap())
+                .map(|m| UnclassifiedDeckRow {
+                    match_id: m.id,
+                    side: "controller".to_string(),
+                })
+                .collect())
+        }
+
+        async fn replace_proposed_clusters(
+            &self,
+            _format: &str,
+            clusters: &[NewArchetypeCluster],
+        ) -> arenabuddy_data::Result<u64> {
+            self.state.lock().unwrap().clusters = clusters.to_vec();
+            Ok(u64::try_from(clusters.len()).unwrap())
+        }
+
+        async fn list_archetype_clusters(
+            &self,
+            _format: Option<&str>,
+            _include_resolved: bool,
+        ) -> arenabuddy_data::Result<Vec<ArchetypeClusterRow>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_archetype_cluster(
+            &self,
+            _cluster_id: i32,
+        ) -> arenabuddy_data::Result<Option<ArchetypeClusterRow>> {
+            Ok(None)
+        }
+
+        async fn get_archetype_cluster_cards(
+            &self,
+            _cluster_id: i32,
+        ) -> arenabuddy_data::Result<Vec<ArchetypeClusterCard>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_archetype_cluster_members(
+            &self,
+            _cluster_id: i32,
+        ) -> arenabuddy_data::Result<Vec<ArchetypeClusterMember>> {
+            Ok(Vec::new())
+        }
+
+        async fn resolve_archetype_cluster(
+            &self,
+            _cluster_id: i32,
+            _status: &str,
+            _archetype_id: Option<i32>,
+        ) -> arenabuddy_data::Result<bool> {
+            Ok(false)
+        }
+
+        async fn reopen_archetype_cluster(&self, _cluster_id: i32) -> arenabuddy_data::Result<bool> {
+            Ok(false)
+        }
+
+        async fn start_metagame_job_run(&self, job: &str, format: &str) -> arenabuddy_data::Result<i64> {
+            let mut state = self.state.lock().unwrap();
+            let id = count(state.runs.iter()) + 1;
+            state.runs.push(MetagameJobRun {
+                id,
+                job: job.to_string(),
+                format: format.to_string(),
+                status: "running".to_string(),
+                items: 0,
+                error: None,
+                started_at: chrono::Utc::now(),
+                finished_at: None,
+            });
+            Ok(id)
+        }
+
+        async fn fail_interrupted_metagame_job_runs(&self) -> arenabuddy_data::Result<u64> {
+            let mut state = self.state.lock().unwrap();
+            let mut failed = 0;
+            for run in state.runs.iter_mut().filter(|r| r.status == "running") {
+                run.status = "failed".to_string();
+                run.error = Some("interrupted".to_string());
+                failed += 1;
+            }
+            Ok(failed)
+        }
+
+        async fn finish_metagame_job_run(
+            &self,
+            id: i64,
+            items: i64,
+            error: Option<&str>,
+        ) -> arenabuddy_data::Result<()> {
+            let mut state = self.state.lock().unwrap();
+            let run = state.runs.iter_mut().find(|r| r.id == id).unwrap();
+            run.status = if error.is_some() { "failed" } else { "succeeded" }.to_string();
+            run.items = items;
+            run.error = error.map(str::to_string);
+            run.finished_at = Some(chrono::Utc::now());
+            Ok(())
+        }
+
+        async fn list_metagame_job_runs(&self, limit: i64) -> arenabuddy_data::Result<Vec<MetagameJobRun>> {
+            let state = self.state.lock().unwrap();
+            Ok(state
+                .runs
+                .iter()
+                .rev()
+                .take(usize::try_from(limit).unwrap())
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 14).unwrap()
+    }
+
+    fn config() -> SchedulerConfig {
+        SchedulerConfig {
+            formats: vec!["standard".to_string()],
+            lookback_days: DEFAULT_LOOKBACK_DAYS,
+            learn_from_overrides: false,
+        }
+    }
+
+    /// A scratch directory of pages for [`Fetcher::local`], removed on drop.
+    struct Pages(PathBuf);
+
+    impl Pages {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("arenabuddy-scheduler-{}", Uuid::new_v4()));
+            std::fs::create_dir(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn write(&self, path: &str, content: &str) {
+            std::fs::write(self.0.join(path_to_filename(path)), content).unwrap();
+        }
+
+        fn dir(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for Pages {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// One search page listing tournament 100, whose two decks are Mono Red and Azorius Control.
+    fn write_tournament(pages: &Pages) {
+        let from = today() - chrono::Days::new(DEFAULT_LOOKBACK_DAYS);
+        let range = tournament::date_range(from, today());
+        pages.write(
+            &tournament::search_path("standard", &range, 1),
+            r#"<table><tr><td>2026-03-10</td><td><a href="/tournament/100">Standard Challenge</a></td><td>32</td></tr></table>"#,
+        );
+        pages.write(
+            "/tournament/100",
+            r#"<table>
+                <tr><td><a href="/deck/1">Mono Red</a></td><td><a href="/player/a">a</a></td></tr>
+                <tr><td><a href="/deck/2">Azorius Control</a></td><td><a href="/player/b">b</a></td></tr>
+            </table>"#,
+        );
+        pages.write(
+            "/deck/download/1",
+            "4 Lightning Bolt\n4 Monastery Swiftspear\n20 Mountain\n",
+        );
+        pages.write("/deck/download/2", "4 Counterspell\n4 Memory Deluge\n20 Island\n");
+    }
+
+    fn runs(repo: &FakeRepo) -> Vec<(String, String, i64)> {
+        let state = repo.state.lock().unwrap();
+        state
+            .runs
+            .iter()
+            .map(|r| (r.job.clone(), r.status.clone(), r.items))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_run_once_refreshes_and_reclassifies_stale_matches() {
+        let pages = Pages::new();
+        write_tournament(&pages);
+        let source = MtgGoldfish::new(Fetcher::local(pages.dir()).unwrap());
+
+        let repo = FakeRepo::default();
+        let outdated = Uuid::new_v4();
+        let new = Uuid::new_v4();
+        {
+            let mut state = repo.state.lock().unwrap();
+            let red = ["Lightning Bolt", "Monastery Swiftspear", "Mountain"].map(String::from);
+            let blue = ["Counterspell", "Memory Deluge", "Island"].map(String::from);
+            state.matches.push(FakeMatch {
+                id: outdated,
+                cards: red.to_vec(),
+                controller: Some(("Rakdos Aggro".to_string(), 0)),
+            });
+            state.matches.push(FakeMatch {
+                id: new,
+                cards: blue.to_vec(),
+                controller: None,
+            });
+        }
+
+        let changed = run_once(&repo, &source, &config(), today()).await.unwrap();
+        assert_eq!(
+            changed,
+            [ReclassifiedMatch {
+                match_id: outdated,
+                user_id: None,
+                changed: true,
+            }]
+        );
+
+        let recorded = runs(&repo);
+        assert_eq!(recorded.len(), 4);
+        assert_eq!(recorded[0], ("scrape".to_string(), "succeeded".to_string(), 2));
+        assert_eq!(recorded[1].0, "signatures");
+        assert_eq!(recorded[1].1, "succeeded");
+        assert!(recorded[1].2 > 0);
+        assert_eq!(recorded[2], ("reclassify".to_string(), "succeeded".to_string(), 2));
+        assert_eq!(recorded[3], ("cluster".to_string(), "succeeded".to_string(), 0));
+
+        let (controller, _) = repo.get_match_archetypes(&outdated.to_string()).await.unwrap();
+        assert_eq!(controller.as_deref(), Some("Mono Red"));
+        let (controller, _) = repo.get_match_archetypes(&new.to_string()).await.unwrap();
+        assert_eq!(controller.as_deref(), Some("Azorius Control"));
+
+        // The same tournaments give the same signature cards, so nothing turns stale.
+        let changed = run_once(&repo, &source, &config(), today()).await.unwrap();
+        assert!(changed.is_empty());
+        let reruns = &runs(&repo)[4..];
+        assert_eq!(reruns[1], ("signatures".to_string(), "succeeded".to_string(), 0));
+        assert_eq!(reruns[2], ("reclassify".to_string(), "succeeded".to_string(), 0));
+    }
+
+    #[tokio::test]
+    async fn test_sync_override_decks_stores_overridden_matches_and_prunes_the_rest() {
+        let repo = FakeRepo::default();
+        let labelled = Uuid::new_v4();
+        let unseen = Uuid::new_v4();
+        {
+            let mut state = repo.state.lock().unwrap();
+            state.matches.push(FakeMatch {
+                id: labelled,
+                cards: ["Lightning Bolt", "Lightning Bolt", "Mountain"]
+                    .map(String::from)
+                    .to_vec(),
+                controller: None,
+            });
+            state.matches.push(FakeMatch {
+                id: unseen,
+                cards: Vec::new(),
+                controller: None,
+            });
+            state.decks.push(FakeDeck {
+                source: classification::OVERRIDE_SOURCE.to_string(),
+                source_id: "cleared/controller".to_string(),
+                archetype_id: None,
+                cards: Vec::new(),
+            });
+        }
+        repo.set_match_archetype_override(&labelled.to_string(), "controller", "Mono Red", None)
+            .await
+            .unwrap();
+        repo.set_match_archetype_override(&unseen.to_string(), "controller", "Azorius Control", None)
+            .await
+            .unwrap();
+
+        let stored = classification::sync_override_decks(&repo, "standard").await.unwrap();
+        assert_eq!(stored, 1, "a match without cards adds no deck");
+
+        let decks = classification::labelled_decks(&repo, "standard").await.unwrap();
+        assert_eq!(decks.len(), 1);
+        assert_eq!(decks[0].archetype_name, "Mono Red");
+        assert_eq!(
+            decks[0].cards,
+            [("Lightning Bolt".to_string(), 2), ("Mountain".to_string(), 1)]
+        );
+        let state = repo.state.lock().unwrap();
+        let source_ids: Vec<&str> = state.decks.iter().map(|d| d.source_id.as_str()).collect();
+        assert_eq!(
+            source_ids,
+            [format!("{labelled}/controller")],
+            "the cleared override's deck is pruned"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_signature_cards_without_copies_are_retrained_before_classifying() {
+        let repo = FakeRepo::default();
+        let red = Uuid::new_v4();
+        {
+            let mut state = repo.state.lock().unwrap();
+            state.archetypes = vec!["Mono Red".to_string(), "Azorius Control".to_string()];
+            for (archetype_id, cards) in [(1, ["Lightning Bolt", "Mountain"]), (2, ["Counterspell", "Island"])] {
+                state.decks.push(FakeDeck {
+                    source: "mtggoldfish".to_string(),
+                    source_id: archetype_id.to_string(),
+                    archetype_id: Some(archetype_id),
+                    cards: cards.map(|card| (card.to_string(), 4)).to_vec(),
+                });
+            }
+            state.signatures = vec![SignatureCardRow {
+                archetype_id: 2,
+                archetype_name: "Azorius Control".to_string(),
+                card_name: "Lightning Bolt".to_string(),
+                weight: 1.0,
+                copies: None,
+                idf: None,
+            }];
+            state.matches.push(FakeMatch {
+                id: red,
+                cards: ["Lightning Bolt", "Mountain"].map(String::from).to_vec(),
+                controller: None,
+            });
+        }
+
+        let classified = classification::classify_matches(&repo, "standard").await.unwrap();
+        assert_eq!(classified, 1);
+        let (controller, _) = repo.get_match_archetypes(&red.to_string()).await.unwrap();
+        assert_eq!(controller.as_deref(), Some("Mono Red"));
+        let state = repo.state.lock().unwrap();
+        assert!(
+            state
+                .signatures
+                .iter()
+                .all(|sc| sc.copies.is_some() && sc.idf.is_some())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_once_records_failed_job_and_continues() {
+        let pages = Pages::new();
+        write_tournament(&pages);
+        std::fs::remove_file(pages.dir().join(path_to_filename("/tournament/100"))).unwrap();
+        let source = MtgGoldfish::new(Fetcher::local(pages.dir()).unwrap());
+
+        let repo = FakeRepo::default();
+        run_once(&repo, &source, &config(), today()).await.unwrap();
+
+        let state = repo.state.lock().unwrap();
+        let statuses: Vec<_> = state.runs.iter().map(|r| (r.job.as_str(), r.status.as_str())).collect();
+        assert_eq!(
+            statuses,
+            [
+                ("scrape", "failed"),
+                ("signatures", "succeeded"),
+                ("reclassify", "succeeded"),
+                ("cluster", "succeeded")
+            ]
+        );
+        assert!(state.runs[0].error.as_deref().unwrap().contains("tournament_100"));
+        assert!(state.runs.iter().all(|r| r.finished_at.is_some()));
+    }
+}
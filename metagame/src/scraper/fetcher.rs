@@ -1,34 +1,47 @@
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use anyhow::{Context, Result};
-use reqwest::Client;
+use reqwest::StatusCode;
 use tracing::debug;
 
+use crate::fetch::{FetchClient, FetchOptions};
+
 const USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36";
 const BASE_URL: &str = "https://www.mtggoldfish.com";
 const RATE_LIMIT_MS: u64 = 500;
 
 /// Abstraction over fetching page content — either from HTTP or local files.
 pub enum Fetcher {
-    Http { client: Client, base_url: String },
+    Http { client: FetchClient, base_url: String },
     Local { dir: PathBuf },
 }
 
 impl Fetcher {
-    /// Creates a new HTTP fetcher with default settings.
+    /// Creates a new HTTP fetcher with default settings and no cache.
     ///
     /// # Panics
     ///
     /// Panics if the HTTP client cannot be built (e.g., due to invalid TLS configuration).
     pub fn http() -> Self {
-        let client = Client::builder()
-            .user_agent(USER_AGENT)
-            .build()
-            .expect("invalid http client");
-        Self::Http {
+        Self::cached(None, false).expect("invalid http client")
+    }
+
+    /// Creates an HTTP fetcher that caches pages in `cache_dir` and, when `offline`, only
+    /// replays them from there.
+    pub fn cached(cache_dir: Option<PathBuf>, offline: bool) -> Result<Self> {
+        let client = FetchClient::new(FetchOptions {
+            cache_dir,
+            offline,
+            min_interval: Duration::from_millis(RATE_LIMIT_MS),
+            ..FetchOptions::new(USER_AGENT)
+        })?;
+        Ok(Self::Http {
             client,
             base_url: BASE_URL.to_string(),
-        }
+        })
     }
 
     pub fn local(dir: &Path) -> Result<Self> {
@@ -47,21 +60,14 @@ impl Fetcher {
     pub async fn fetch(&self, path: &str) -> Result<String> {
         match self {
             Self::Http { client, base_url } => {
-                tokio::time::sleep(std::time::Duration::from_millis(RATE_LIMIT_MS)).await;
                 let url = format!("{base_url}{path}");
-                let resp = client
-                    .get(&url)
-                    .send()
-                    .await?
-                    .error_for_status()
-                    .with_context(|| format!("request failed: {url}"))?;
-                let final_url = resp.url().to_string();
-                let text = resp
-                    .text()
-                    .await
-                    .with_context(|| format!("failed to read body: {url}"))?;
-                debug!("Fetched {url} -> {final_url} ({} bytes)", text.len());
-                Ok(text)
+                let response = client.get(&url).await?.error_for_status()?;
+                debug!(
+                    "Fetched {url} ({} bytes{})",
+                    response.body.len(),
+                    if response.from_cache { ", cached" } else { "" }
+                );
+                Ok(response.body)
             }
             Self::Local { dir } => {
                 let filename = path_to_filename(path);
@@ -76,23 +82,12 @@ impl Fetcher {
     pub async fn fetch_optional(&self, path: &str) -> Result<Option<String>> {
         match self {
             Self::Http { client, base_url } => {
-                tokio::time::sleep(std::time::Duration::from_millis(RATE_LIMIT_MS)).await;
                 let url = format!("{base_url}{path}");
-                let resp = client
-                    .get(&url)
-                    .send()
-                    .await
-                    .with_context(|| format!("request failed: {url}"))?;
-                if resp.status() == reqwest::StatusCode::BAD_REQUEST {
+                let response = client.get(&url).await?;
+                if response.status == StatusCode::BAD_REQUEST {
                     return Ok(None);
                 }
-                let text = resp
-                    .error_for_status()
-                    .with_context(|| format!("request failed: {url}"))?
-                    .text()
-                    .await
-                    .with_context(|| format!("failed to read body: {url}"))?;
-                Ok(Some(text))
+                Ok(Some(response.error_for_status()?.body))
             }
             Self::Local { dir } => {
                 let filename = path_to_filename(path);
@@ -114,7 +109,7 @@ impl Fetcher {
 /// - `/tournament/62266` -> `tournament_62266`
 /// - `/deck/download/7677856` -> `deck_download_7677856`
 /// - `/metagame/standard/full` -> `metagame_standard_full`
-fn path_to_filename(path: &str) -> String {
+pub(crate) fn path_to_filename(path: &str) -> String {
     path.trim_start_matches('/')
         .replace(['/', '?', '=', '&', '%', '[', ']', '+'], "_")
 }
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use arenabuddy_data::metagame_models::{MetagameArchetype, MetagameTournament};
+use chrono::NaiveDate;
+use tracing::warn;
+
+use super::{Fetcher, deck, metagame, tournament};
+use crate::source::{Decklist, MetagameSource};
+
+/// Source name recorded for `MTGGoldfish` tournaments and decks.
+pub const SOURCE: &str = "mtggoldfish";
+
+/// Tournaments, decklists and archetypes scraped from `MTGGoldfish`, or from pages saved with
+/// [`Fetcher::local`].
+pub struct MtgGoldfish {
+    fetcher: Fetcher,
+}
+
+impl MtgGoldfish {
+    pub fn new(fetcher: Fetcher) -> Self {
+        Self { fetcher }
+    }
+
+    /// Look up a tournament by its `MTGGoldfish` ID, e.g. `62212`.
+    pub async fn tournament(&self, goldfish_id: i32, format: &str) -> Result<MetagameTournament> {
+        tournament::fetch_tournament(&self.fetcher, goldfish_id, format).await
+    }
+}
+
+#[async_trait::async_trait]
+impl MetagameSource for MtgGoldfish {
+    async fn tournaments(&self, format: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<MetagameTournament>> {
+        tournament::search_all_tournaments(&self.fetcher, format, from, to).await
+    }
+
+    async fn decklists(&self, tournament: &MetagameTournament) -> Result<Vec<Decklist>> {
+        let decks =
+            tournament::fetch_tournament_decks(&self.fetcher, &tournament.source_id, &tournament.format).await?;
+        let mut decklists = Vec::with_capacity(decks.len());
+        for deck in decks {
+            match deck::fetch_deck_cards(&self.fetcher, &deck.source_id).await {
+                Ok(cards) => decklists.push(Decklist { deck, cards }),
+                Err(e) => warn!("    Failed to fetch deck {}: {e:#}", deck.source_id),
+            }
+        }
+        Ok(decklists)
+    }
+
+    async fn archetypes(&self, format: &str) -> Result<Vec<MetagameArchetype>> {
+        metagame::fetch_archetypes(&self.fetcher, format).await
+    }
+}
@@ -1,24 +1,21 @@
 use anyhow::Result;
-use arenabuddy_data::MetagameRepository;
+use arenabuddy_data::metagame_models::MetagameArchetype;
 use scraper::{Html, Selector};
-use tracing::info;
 
 use super::Fetcher;
 
-/// Scrape the metagame index page and upsert archetypes.
-pub async fn scrape_metagame(repo: &impl MetagameRepository, fetcher: &Fetcher, format: &str) -> Result<()> {
+/// Fetch the metagame index page's archetypes.
+pub(crate) async fn fetch_archetypes(fetcher: &Fetcher, format: &str) -> Result<Vec<MetagameArchetype>> {
     let html = fetcher.fetch(&format!("/metagame/{format}/full")).await?;
     let document = Html::parse_document(&html);
-    let archetypes = parse_metagame_page(&document, fetcher.base_url());
-
-    info!("Found {} archetypes for {format}", archetypes.len());
-
-    for (name, url) in &archetypes {
-        let id = repo.upsert_metagame_archetype(name, format, Some(url.as_str())).await?;
-        info!("  Archetype: {name} (id={id})");
-    }
-
-    Ok(())
+    Ok(parse_metagame_page(&document, fetcher.base_url())
+        .into_iter()
+        .map(|(name, url)| MetagameArchetype {
+            name,
+            format: format.to_string(),
+            url: Some(url),
+        })
+        .collect())
 }
 
 /// Parse archetype names and URLs from the metagame page HTML.
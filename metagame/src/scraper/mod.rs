@@ -1,6 +1,11 @@
-pub mod deck;
+//! The `MTGGoldfish` [`MetagameSource`](crate::source::MetagameSource): HTML scraping of the
+//! tournament search, tournament and deck pages, and the metagame index.
+
+mod deck;
 pub mod fetcher;
-pub mod metagame;
-pub mod tournament;
+mod goldfish;
+mod metagame;
+pub(crate) mod tournament;
 
 pub use fetcher::Fetcher;
+pub use goldfish::{MtgGoldfish, SOURCE};
@@ -1,31 +1,20 @@
 use std::collections::HashSet;
 
 use anyhow::Result;
-use arenabuddy_data::{
-    MetagameRepository,
-    metagame_models::{MetagameDeck, MetagameTournament},
-};
+use arenabuddy_data::metagame_models::{MetagameDeck, MetagameTournament};
 use chrono::NaiveDate;
 use scraper::{Html, Selector};
 use tracing::info;
 
-use super::{Fetcher, deck};
+use super::{Fetcher, SOURCE};
 
-/// Scrape a single tournament by its `MTGGoldfish` ID, importing all decklists.
+/// Fetch a tournament page by its `MTGGoldfish` ID, taking the name from the page title.
 ///
 /// # Panics
 ///
 /// Panics if hardcoded CSS selectors are invalid (should never happen).
-pub async fn scrape_single_tournament(
-    repo: &impl MetagameRepository,
-    fetcher: &Fetcher,
-    goldfish_id: i32,
-    format: &str,
-) -> Result<()> {
+pub(crate) async fn fetch_tournament(fetcher: &Fetcher, goldfish_id: i32, format: &str) -> Result<MetagameTournament> {
     let base_url = fetcher.base_url();
-    let url = format!("{base_url}/tournament/{goldfish_id}");
-    info!("Scraping tournament {goldfish_id}: {url}");
-
     let html = fetcher.fetch(&format!("/tournament/{goldfish_id}")).await?;
     let document = Html::parse_document(&html);
 
@@ -35,73 +24,28 @@ pub async fn scrape_single_tournament(
         |el| el.text().collect::<String>().trim().to_string(),
     );
 
-    let tournament = MetagameTournament {
-        goldfish_id,
-        name: name.clone(),
+    Ok(MetagameTournament {
+        source: SOURCE.to_string(),
+        source_id: goldfish_id.to_string(),
+        name,
         format: format.to_string(),
         date: chrono::Utc::now().date_naive(),
-        url: url.clone(),
-    };
-    let tournament_db_id = repo.upsert_metagame_tournament(&tournament).await?;
-    info!("Tournament: {name} - id={tournament_db_id} url={url}");
-
-    let decks = parse_tournament_decks(&document, base_url, format);
-    info!("  Found {} decks", decks.len());
-
-    import_decks(repo, fetcher, &decks, tournament_db_id, format).await?;
-
-    Ok(())
-}
-
-/// Resolve archetypes and import each deck's cards for a tournament.
-async fn import_decks(
-    repo: &impl MetagameRepository,
-    fetcher: &Fetcher,
-    decks: &[(MetagameDeck, Option<String>)],
-    tournament_db_id: i32,
-    format: &str,
-) -> Result<()> {
-    for (deck_info, archetype_name) in decks {
-        let archetype_id = if let Some(name) = archetype_name {
-            Some(repo.upsert_metagame_archetype(name, format, None).await?)
-        } else {
-            None
-        };
-
-        match deck::fetch_deck_cards(fetcher, deck_info.goldfish_id).await {
-            Ok(cards) => {
-                let deck_db_id = repo
-                    .upsert_metagame_deck(deck_info, Some(tournament_db_id), archetype_id, &cards)
-                    .await?;
-                info!(
-                    "    Deck {} ({}): {} cards - db_id={}",
-                    deck_info.goldfish_id,
-                    deck_info.player_name.as_deref().unwrap_or("unknown"),
-                    cards.len(),
-                    deck_db_id,
-                );
-            }
-            Err(e) => {
-                tracing::warn!("    Failed to fetch deck {}: {e:#}", deck_info.goldfish_id);
-            }
-        }
-    }
-
-    Ok(())
+        url: format!("{base_url}/tournament/{goldfish_id}"),
+    })
 }
 
-/// Scrape tournaments matching a format and date range, including all decklists.
-pub async fn scrape_tournaments(
-    repo: &impl MetagameRepository,
+/// Search tournaments matching a format and date range, following pagination to the end.
+pub(crate) async fn search_all_tournaments(
     fetcher: &Fetcher,
     format: &str,
     from: NaiveDate,
     to: NaiveDate,
-) -> Result<()> {
+) -> Result<Vec<MetagameTournament>> {
     let base_url = fetcher.base_url();
-    let date_range = format!("{} - {}", from.format("%m/%d/%Y"), to.format("%m/%d/%Y"));
+    let date_range = date_range(from, to);
     info!("Searching {format} tournaments from {date_range}");
 
+    let mut all = Vec::new();
     let mut page = 1u32;
     loop {
         info!("Fetching search results page {page}");
@@ -118,24 +62,11 @@ pub async fn scrape_tournaments(
         }
 
         info!("Found {} tournaments on page {page}", tournaments.len());
-
-        for tournament in &tournaments {
-            let tournament_db_id = repo.upsert_metagame_tournament(tournament).await?;
-            info!(
-                "Tournament: {} ({}) - id={} url={}",
-                tournament.name, tournament.date, tournament_db_id, tournament.url
-            );
-
-            let decks = scrape_tournament_decks(fetcher, base_url, tournament.goldfish_id, format).await?;
-            info!("  Found {} decks", decks.len());
-
-            import_decks(repo, fetcher, &decks, tournament_db_id, format).await?;
-        }
-
+        all.extend(tournaments);
         page += 1;
     }
 
-    Ok(())
+    Ok(all)
 }
 
 /// Search for tournaments using the tournament search endpoint.
@@ -147,10 +78,7 @@ async fn search_tournaments(
     date_range: &str,
     page: u32,
 ) -> Result<Option<Vec<MetagameTournament>>> {
-    let encoded_range = urlencoding::encode(date_range);
-    let path = format!(
-        "/tournament_searches/create?tournament_search%5Bname%5D=&tournament_search%5Bformat%5D={format}&tournament_search%5Bdate_range%5D={encoded_range}&commit=Search&page={page}"
-    );
+    let path = search_path(format, date_range, page);
     let Some(html) = fetcher.fetch_optional(&path).await? else {
         return Ok(None);
     };
@@ -158,6 +86,19 @@ async fn search_tournaments(
     Ok(Some(parse_tournament_search_results(&document, base_url, format)))
 }
 
+/// The search form's date range, e.g. `01/01/2026 - 01/14/2026`.
+pub(crate) fn date_range(from: NaiveDate, to: NaiveDate) -> String {
+    format!("{} - {}", from.format("%m/%d/%Y"), to.format("%m/%d/%Y"))
+}
+
+/// Path of one page of tournament search results.
+pub(crate) fn search_path(format: &str, date_range: &str, page: u32) -> String {
+    let encoded_range = urlencoding::encode(date_range);
+    format!(
+        "/tournament_searches/create?tournament_search%5Bname%5D=&tournament_search%5Bformat%5D={format}&tournament_search%5Bdate_range%5D={encoded_range}&commit=Search&page={page}"
+    )
+}
+
 /// Parse tournament search results table.
 fn parse_tournament_search_results(document: &Html, base_url: &str, format: &str) -> Vec<MetagameTournament> {
     let mut tournaments = Vec::new();
@@ -194,7 +135,8 @@ fn parse_tournament_search_results(document: &Html, base_url: &str, format: &str
             NaiveDate::parse_from_str(&date_text, "%Y-%m-%d").unwrap_or_else(|_| chrono::Utc::now().date_naive());
 
         tournaments.push(MetagameTournament {
-            goldfish_id,
+            source: SOURCE.to_string(),
+            source_id: goldfish_id.to_string(),
             name,
             format: format.to_string(),
             date,
@@ -205,25 +147,23 @@ fn parse_tournament_search_results(document: &Html, base_url: &str, format: &str
     tournaments
 }
 
-/// Parse a single tournament page to extract deck entries.
-/// Returns `(MetagameDeck, Option<archetype_name>)` pairs.
-async fn scrape_tournament_decks(
+/// Fetch a tournament page and extract its deck entries.
+pub(crate) async fn fetch_tournament_decks(
     fetcher: &Fetcher,
-    base_url: &str,
-    tournament_goldfish_id: i32,
+    tournament_source_id: &str,
     format: &str,
-) -> Result<Vec<(MetagameDeck, Option<String>)>> {
-    let html = fetcher.fetch(&format!("/tournament/{tournament_goldfish_id}")).await?;
+) -> Result<Vec<MetagameDeck>> {
+    let html = fetcher.fetch(&format!("/tournament/{tournament_source_id}")).await?;
     tracing::debug!(
-        "Tournament {tournament_goldfish_id} page: {} bytes, contains '/deck/': {}",
+        "Tournament {tournament_source_id} page: {} bytes, contains '/deck/': {}",
         html.len(),
         html.contains("/deck/")
     );
     let document = Html::parse_document(&html);
-    Ok(parse_tournament_decks(&document, base_url, format))
+    Ok(parse_tournament_decks(&document, fetcher.base_url(), format))
 }
 
-fn parse_tournament_decks(document: &Html, base_url: &str, format: &str) -> Vec<(MetagameDeck, Option<String>)> {
+fn parse_tournament_decks(document: &Html, base_url: &str, format: &str) -> Vec<MetagameDeck> {
     let mut decks = Vec::new();
     let mut seen_ids = HashSet::new();
 
@@ -251,18 +191,16 @@ fn parse_tournament_decks(document: &Html, base_url: &str, format: &str) -> Vec<
 
         let player_name = find_sibling_link(&deck_link, "/player/");
 
-        decks.push((
-            MetagameDeck {
-                goldfish_id: goldfish_deck_id,
-                archetype_name: archetype.clone(),
-                player_name,
-                placement: None,
-                format: format.to_string(),
-                date: None,
-                url: format!("{base_url}{href}"),
-            },
-            archetype,
-        ));
+        decks.push(MetagameDeck {
+            source: SOURCE.to_string(),
+            source_id: goldfish_deck_id.to_string(),
+            archetype_name: archetype,
+            player_name,
+            placement: None,
+            format: format.to_string(),
+            date: None,
+            url: format!("{base_url}{href}"),
+        });
     }
 
     decks
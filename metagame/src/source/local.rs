@@ -0,0 +1,492 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result};
+use arenabuddy_data::metagame_models::{MetagameArchetype, MetagameDeck, MetagameDeckCard, MetagameTournament};
+use chrono::{DateTime, NaiveDate, Utc};
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use tracing::warn;
+
+use super::{Decklist, MetagameSource};
+
+/// Source name recorded for decklists imported from a local folder.
+pub const SOURCE: &str = "local";
+
+/// Decklists from a local folder, such as a team's gauntlet:
+///
+/// - `.txt` (MTGA or `MTGGoldfish` export) and `.dek` (MTGO) files directly in the folder form
+///   one tournament named after the folder, and each subfolder of such files another;
+/// - a `.json` or `.csv` file is a tournament dump of its own.
+///
+/// A list file's name is its deck's archetype. Everything in the folder belongs to whichever
+/// format is imported, unless a JSON dump names a different one, and the date range is ignored:
+/// the folder is a fixed set of lists, dated by when their files last changed.
+///
+/// JSON dumps look like
+/// `{"name", "date", "format", "decks": [{"archetype", "player", "placement", "mainboard": [{"quantity", "name"}], "sideboard"}]}`,
+/// with everything but `decks` optional. CSV dumps have one card per row, with columns
+/// `archetype`, `player`, `placement`, `quantity`, `card` and `sideboard`, and optionally a `deck`
+/// column to tell apart decks sharing a player and archetype.
+pub struct LocalDecklists {
+    dir: PathBuf,
+}
+
+impl LocalDecklists {
+    pub fn new(dir: &Path) -> Result<Self> {
+        anyhow::ensure!(dir.is_dir(), "{} is not a directory", dir.display());
+        Ok(Self { dir: dir.to_path_buf() })
+    }
+
+    /// A list file's or tournament's name: its file or folder name, without the extension.
+    fn display_name(&self, path: &Path) -> String {
+        path.file_stem()
+            .or_else(|| self.dir.file_name())
+            .map_or_else(|| "Local decklists".to_string(), |n| n.to_string_lossy().into_owned())
+    }
+
+    fn tournament(&self, format: &str, path: &Path, date: NaiveDate) -> MetagameTournament {
+        let relative = path.strip_prefix(&self.dir).unwrap_or(path);
+        MetagameTournament {
+            source: SOURCE.to_string(),
+            source_id: format!("{format}/{}", relative.display()),
+            name: self.display_name(path),
+            format: format.to_string(),
+            date,
+            url: file_url(path),
+        }
+    }
+
+    /// The path a tournament was listed from.
+    fn tournament_path(&self, tournament: &MetagameTournament) -> PathBuf {
+        let relative = tournament
+            .source_id
+            .strip_prefix(&format!("{}/", tournament.format))
+            .unwrap_or(&tournament.source_id);
+        self.dir.join(relative)
+    }
+}
+
+#[async_trait::async_trait]
+impl MetagameSource for LocalDecklists {
+    async fn tournaments(&self, format: &str, _from: NaiveDate, _to: NaiveDate) -> Result<Vec<MetagameTournament>> {
+        let mut tournaments = Vec::new();
+        if let Some(date) = newest_list(&self.dir)? {
+            tournaments.push(self.tournament(format, &self.dir, date));
+        }
+
+        for path in sorted_entries(&self.dir)? {
+            if path.is_dir() {
+                if let Some(date) = newest_list(&path)? {
+                    tournaments.push(self.tournament(format, &path, date));
+                }
+                continue;
+            }
+            match extension(&path).as_str() {
+                "json" => {
+                    let dump = read_json_dump(&path)?;
+                    if dump.format.as_ref().is_some_and(|f| !f.eq_ignore_ascii_case(format)) {
+                        continue;
+                    }
+                    let mut tournament = self.tournament(format, &path, dump.date.unwrap_or(modified(&path)?));
+                    if let Some(name) = dump.name {
+                        tournament.name = name;
+                    }
+                    if let Some(url) = dump.url {
+                        tournament.url = url;
+                    }
+                    tournaments.push(tournament);
+                }
+                "csv" => tournaments.push(self.tournament(format, &path, modified(&path)?)),
+                _ => {}
+            }
+        }
+        Ok(tournaments)
+    }
+
+    async fn decklists(&self, tournament: &MetagameTournament) -> Result<Vec<Decklist>> {
+        let path = self.tournament_path(tournament);
+        let make_deck =
+            |index: String, archetype: Option<String>, player: Option<String>, placement: Option<String>| {
+                MetagameDeck {
+                    source: SOURCE.to_string(),
+                    source_id: format!("{}#{index}", tournament.source_id),
+                    archetype_name: archetype.filter(|a| !a.trim().is_empty()),
+                    player_name: player.filter(|p| !p.trim().is_empty()),
+                    placement: placement.filter(|p| !p.trim().is_empty()),
+                    format: tournament.format.clone(),
+                    date: Some(tournament.date),
+                    url: tournament.url.clone(),
+                }
+            };
+
+        if path.is_dir() {
+            let mut decklists = Vec::new();
+            for file in sorted_entries(&path)? {
+                let cards = match extension(&file).as_str() {
+                    "txt" => parse_text_decklist(&read(&file)?),
+                    "dek" => parse_dek(&read(&file)?),
+                    _ => continue,
+                };
+                if cards.is_empty() {
+                    warn!("    No cards in {}", file.display());
+                    continue;
+                }
+                let name = self.display_name(&file);
+                let mut deck = make_deck(name.clone(), Some(name), None, None);
+                deck.url = file_url(&file);
+                decklists.push(Decklist { deck, cards });
+            }
+            return Ok(decklists);
+        }
+
+        let decklists = match extension(&path).as_str() {
+            "json" => read_json_dump(&path)?
+                .decks
+                .into_iter()
+                .enumerate()
+                .map(|(i, d)| {
+                    let cards = d
+                        .mainboard
+                        .into_iter()
+                        .map(|c| c.into_card(false))
+                        .chain(d.sideboard.into_iter().map(|c| c.into_card(true)))
+                        .collect();
+                    Decklist {
+                        deck: make_deck((i + 1).to_string(), d.archetype, d.player, d.placement),
+                        cards,
+                    }
+                })
+                .collect(),
+            "csv" => read_csv_dump(&path)?
+                .into_iter()
+                .map(|(key, d)| Decklist {
+                    deck: make_deck(key, d.archetype, d.player, d.placement),
+                    cards: d.cards,
+                })
+                .collect(),
+            _ => anyhow::bail!("{} is not a decklist folder or tournament dump", path.display()),
+        };
+        Ok(decklists)
+    }
+
+    /// Archetypes are named by the lists themselves, so there is no separate index.
+    async fn archetypes(&self, _format: &str) -> Result<Vec<MetagameArchetype>> {
+        Ok(Vec::new())
+    }
+}
+
+#[derive(Deserialize)]
+struct TournamentDump {
+    name: Option<String>,
+    date: Option<NaiveDate>,
+    format: Option<String>,
+    url: Option<String>,
+    decks: Vec<DeckDump>,
+}
+
+#[derive(Deserialize)]
+struct DeckDump {
+    archetype: Option<String>,
+    player: Option<String>,
+    placement: Option<String>,
+    #[serde(default)]
+    mainboard: Vec<CardDump>,
+    #[serde(default)]
+    sideboard: Vec<CardDump>,
+}
+
+#[derive(Deserialize)]
+struct CardDump {
+    quantity: i32,
+    name: String,
+}
+
+impl CardDump {
+    fn into_card(self, is_sideboard: bool) -> MetagameDeckCard {
+        MetagameDeckCard {
+            card_name: self.name,
+            quantity: self.quantity,
+            is_sideboard,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CsvRow {
+    deck: Option<String>,
+    archetype: Option<String>,
+    player: Option<String>,
+    placement: Option<String>,
+    quantity: i32,
+    card: String,
+    #[serde(default)]
+    sideboard: bool,
+}
+
+struct CsvDeck {
+    archetype: Option<String>,
+    player: Option<String>,
+    placement: Option<String>,
+    cards: Vec<MetagameDeckCard>,
+}
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+fn read_json_dump(path: &Path) -> Result<TournamentDump> {
+    serde_json::from_str(&read(path)?).with_context(|| format!("invalid tournament dump {}", path.display()))
+}
+
+/// A CSV dump's decks in the order they first appear, keyed by their `deck` column, or by
+/// player and archetype when it's missing.
+fn read_csv_dump(path: &Path) -> Result<Vec<(String, CsvDeck)>> {
+    let mut reader = csv::Reader::from_path(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mut decks: Vec<(String, CsvDeck)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for row in reader.deserialize() {
+        let row: CsvRow = row.with_context(|| format!("invalid tournament dump {}", path.display()))?;
+        let key = row.deck.clone().unwrap_or_else(|| {
+            format!(
+                "{}/{}",
+                row.player.as_deref().unwrap_or_default(),
+                row.archetype.as_deref().unwrap_or_default()
+            )
+        });
+        let i = *index.entry(key.clone()).or_insert_with(|| {
+            decks.push((
+                key,
+                CsvDeck {
+                    archetype: row.archetype.clone(),
+                    player: row.player.clone(),
+                    placement: row.placement.clone(),
+                    cards: Vec::new(),
+                },
+            ));
+            decks.len() - 1
+        });
+        decks[i].1.cards.push(MetagameDeckCard {
+            card_name: row.card,
+            quantity: row.quantity,
+            is_sideboard: row.sideboard,
+        });
+    }
+    Ok(decks)
+}
+
+/// Parse an MTGA or `MTGGoldfish` text export: `4 Lightning Bolt` lines, optionally with a set
+/// and collector number (`4 Lightning Bolt (STA) 42`). The sideboard follows a `Sideboard` header,
+/// a blank line, or is marked line by line with MTGO's `SB:` prefix.
+fn parse_text_decklist(text: &str) -> Vec<MetagameDeckCard> {
+    let mut cards = Vec::new();
+    let mut is_sideboard = false;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            if !cards.is_empty() {
+                is_sideboard = true;
+            }
+            continue;
+        }
+        if line.starts_with("//") || line.starts_with('#') {
+            continue;
+        }
+        match line.to_ascii_lowercase().as_str() {
+            "deck" | "main" | "mainboard" | "commander" => {
+                is_sideboard = false;
+                continue;
+            }
+            "sideboard" | "companion" => {
+                is_sideboard = true;
+                continue;
+            }
+            _ => {}
+        }
+
+        let (line, sideboard_line) = match line.strip_prefix("SB:") {
+            Some(rest) => (rest.trim(), true),
+            None => (line, is_sideboard),
+        };
+        let Some((qty_str, card_name)) = line.split_once(' ') else {
+            continue;
+        };
+        let Ok(quantity) = qty_str.trim_end_matches('x').parse::<i32>() else {
+            continue;
+        };
+
+        cards.push(MetagameDeckCard {
+            card_name: strip_printing(card_name.trim()).to_string(),
+            quantity,
+            is_sideboard: sideboard_line,
+        });
+    }
+
+    cards
+}
+
+/// Drop an MTGA export's ` (SET) 123` suffix from a card name.
+fn strip_printing(card_name: &str) -> &str {
+    let Some((name, printing)) = card_name.rsplit_once(" (") else {
+        return card_name;
+    };
+    match printing.split_once(')') {
+        Some((set, number))
+            if !set.is_empty()
+                && set.chars().all(|c| c.is_ascii_alphanumeric())
+                && !number.trim().is_empty()
+                && number.trim().chars().all(|c| c.is_ascii_alphanumeric() || c == '-') =>
+        {
+            name
+        }
+        _ => card_name,
+    }
+}
+
+/// Parse an MTGO `.dek` file: `<Cards Quantity="4" Sideboard="false" Name="Lightning Bolt"/>`.
+///
+/// # Panics
+///
+/// Panics if the hardcoded CSS selector is invalid (should never happen).
+fn parse_dek(xml: &str) -> Vec<MetagameDeckCard> {
+    let document = Html::parse_fragment(xml);
+    // The HTML parser lowercases element and attribute names.
+    let cards_sel = Selector::parse("cards").expect("valid selector");
+    document
+        .select(&cards_sel)
+        .filter_map(|el| {
+            let el = el.value();
+            Some(MetagameDeckCard {
+                card_name: el.attr("name")?.to_string(),
+                quantity: el.attr("quantity")?.parse().ok()?,
+                is_sideboard: el.attr("sideboard").is_some_and(|s| s.eq_ignore_ascii_case("true")),
+            })
+        })
+        .collect()
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+    Ok(entries)
+}
+
+fn modified(path: &Path) -> Result<NaiveDate> {
+    let modified = std::fs::metadata(path)?.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    Ok(DateTime::<Utc>::from(modified).date_naive())
+}
+
+/// When the newest `.txt` or `.dek` list directly in `dir` changed, if it has any.
+fn newest_list(dir: &Path) -> Result<Option<NaiveDate>> {
+    let mut newest = None;
+    for path in sorted_entries(dir)? {
+        if path.is_file() && matches!(extension(&path).as_str(), "txt" | "dek") {
+            newest = newest.max(Some(modified(&path)?));
+        }
+    }
+    Ok(newest)
+}
+
+fn file_url(path: &Path) -> String {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    format!("file://{}", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text_decklist_mtga_export() {
+        let text = "Deck\n4 Lightning Bolt (STA) 42\n20 Mountain (FDN) 279\n\nSideboard\n2 Negate (FDN) 47\n";
+        let cards = parse_text_decklist(text);
+        assert_eq!(cards.len(), 3);
+        assert_eq!(cards[0].card_name, "Lightning Bolt");
+        assert_eq!(cards[0].quantity, 4);
+        assert!(!cards[1].is_sideboard);
+        assert_eq!(cards[2].card_name, "Negate");
+        assert!(cards[2].is_sideboard);
+    }
+
+    #[test]
+    fn test_parse_text_decklist_keeps_parentheses_in_names() {
+        let cards = parse_text_decklist("1 Borrowing 100,000 Arrows (Alchemy)\nSB: 2x Duress\n");
+        assert_eq!(cards[0].card_name, "Borrowing 100,000 Arrows (Alchemy)");
+        assert_eq!(cards[1].card_name, "Duress");
+        assert_eq!(cards[1].quantity, 2);
+        assert!(cards[1].is_sideboard);
+    }
+
+    #[test]
+    fn test_parse_dek() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<Deck xmlns:xsd="http://www.w3.org/2001/XMLSchema">
+  <NetDeckID>0</NetDeckID>
+  <Cards CatID="1" Quantity="4" Sideboard="false" Name="Lightning Bolt" Annotation="0" />
+  <Cards CatID="2" Quantity="2" Sideboard="true" Name="Negate" Annotation="0" />
+</Deck>"#;
+        let cards = parse_dek(xml);
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[0].card_name, "Lightning Bolt");
+        assert_eq!(cards[0].quantity, 4);
+        assert!(!cards[0].is_sideboard);
+        assert!(cards[1].is_sideboard);
+    }
+
+    #[tokio::test]
+    async fn test_local_folder_tournaments() {
+        let dir = std::env::temp_dir().join(format!("arenabuddy-local-source-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("week-12")).unwrap();
+        std::fs::write(dir.join("Mono Red.txt"), "20 Mountain\n4 Lightning Bolt\n").unwrap();
+        std::fs::write(dir.join("week-12/Azorius Control.txt"), "4 Counterspell\n20 Island\n").unwrap();
+        std::fs::write(
+            dir.join("league.csv"),
+            "player,archetype,quantity,card,sideboard\na,Mono Red,4,Lightning Bolt,false\nb,Domain,4,Leyline Binding,false\na,Mono Red,2,Duress,true\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("explorer.json"),
+            r#"{"format": "explorer", "decks": [{"archetype": "Spirits", "mainboard": [{"quantity": 4, "name": "Mausoleum Wanderer"}]}]}"#,
+        )
+        .unwrap();
+
+        let source = LocalDecklists::new(&dir).unwrap();
+        let today = chrono::Utc::now().date_naive();
+        let tournaments = source.tournaments("standard", today, today).await.unwrap();
+        let ids: Vec<_> = tournaments.iter().map(|t| t.source_id.as_str()).collect();
+        assert_eq!(ids, ["standard/", "standard/league.csv", "standard/week-12"]);
+
+        let loose = source.decklists(&tournaments[0]).await.unwrap();
+        assert_eq!(loose.len(), 1);
+        assert_eq!(loose[0].deck.archetype_name.as_deref(), Some("Mono Red"));
+
+        let league = source.decklists(&tournaments[1]).await.unwrap();
+        assert_eq!(league.len(), 2);
+        assert_eq!(league[0].deck.player_name.as_deref(), Some("a"));
+        assert_eq!(league[0].cards.len(), 2);
+        assert!(league[0].cards[1].is_sideboard);
+
+        let explorer = source.tournaments("explorer", today, today).await.unwrap();
+        let dump = explorer
+            .iter()
+            .find(|t| t.source_id == "explorer/explorer.json")
+            .unwrap();
+        let decks = source.decklists(dump).await.unwrap();
+        assert_eq!(decks[0].deck.archetype_name.as_deref(), Some("Spirits"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,104 @@
+//! Where metagame tournaments, decklists and archetypes come from.
+//!
+//! [`MtgGoldfish`](crate::scraper::MtgGoldfish) scrapes the web; [`LocalDecklists`] reads a
+//! folder of a team's own lists. Both feed the same import into the metagame tables.
+
+mod local;
+
+use anyhow::Result;
+use arenabuddy_data::{
+    MetagameRepository,
+    metagame_models::{MetagameArchetype, MetagameDeck, MetagameDeckCard, MetagameTournament},
+};
+use chrono::NaiveDate;
+pub use local::LocalDecklists;
+use tracing::info;
+
+/// A deck with its card list.
+#[derive(Debug, Clone)]
+pub struct Decklist {
+    pub deck: MetagameDeck,
+    pub cards: Vec<MetagameDeckCard>,
+}
+
+#[async_trait::async_trait]
+pub trait MetagameSource: Send + Sync {
+    /// Tournaments in `format` held between `from` and `to`, inclusive.
+    async fn tournaments(&self, format: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<MetagameTournament>>;
+    /// A tournament's decklists. Decks whose list can't be loaded are skipped.
+    async fn decklists(&self, tournament: &MetagameTournament) -> Result<Vec<Decklist>>;
+    /// The format's archetype index.
+    async fn archetypes(&self, format: &str) -> Result<Vec<MetagameArchetype>>;
+}
+
+/// Import every tournament `source` lists for a format and date range, including all
+/// decklists. Returns the number of decks imported.
+pub async fn import_tournaments(
+    repo: &impl MetagameRepository,
+    source: &dyn MetagameSource,
+    format: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<u64> {
+    let mut imported = 0u64;
+    for tournament in source.tournaments(format, from, to).await? {
+        imported += import_tournament(repo, source, &tournament).await?;
+    }
+    Ok(imported)
+}
+
+/// Import one tournament and its decklists. Returns the number of decks imported.
+pub async fn import_tournament(
+    repo: &impl MetagameRepository,
+    source: &dyn MetagameSource,
+    tournament: &MetagameTournament,
+) -> Result<u64> {
+    let tournament_db_id = repo.upsert_metagame_tournament(tournament).await?;
+    info!(
+        "Tournament: {} ({}) - id={} url={}",
+        tournament.name, tournament.date, tournament_db_id, tournament.url
+    );
+
+    let decklists = source.decklists(tournament).await?;
+    info!("  Found {} decks", decklists.len());
+
+    let mut imported = 0u64;
+    for Decklist { deck, cards } in &decklists {
+        let archetype_id = match &deck.archetype_name {
+            Some(name) => Some(repo.upsert_metagame_archetype(name, &tournament.format, None).await?),
+            None => None,
+        };
+        let deck_db_id = repo
+            .upsert_metagame_deck(deck, Some(tournament_db_id), archetype_id, cards)
+            .await?;
+        info!(
+            "    Deck {} ({}): {} cards - db_id={}",
+            deck.source_id,
+            deck.player_name.as_deref().unwrap_or("unknown"),
+            cards.len(),
+            deck_db_id,
+        );
+        imported += 1;
+    }
+
+    Ok(imported)
+}
+
+/// Import the format's archetype index. Returns the number of archetypes.
+pub async fn import_archetypes(
+    repo: &impl MetagameRepository,
+    source: &dyn MetagameSource,
+    format: &str,
+) -> Result<u64> {
+    let archetypes = source.archetypes(format).await?;
+    info!("Found {} archetypes for {format}", archetypes.len());
+
+    for archetype in &archetypes {
+        let id = repo
+            .upsert_metagame_archetype(&archetype.name, &archetype.format, archetype.url.as_deref())
+            .await?;
+        info!("  Archetype: {} (id={id})", archetype.name);
+    }
+
+    Ok(archetypes.len() as u64)
+}
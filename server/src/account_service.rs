@@ -0,0 +1,347 @@
+use std::{pin::Pin, time::Duration};
+
+use arenabuddy_core::services::account_service::{
+    CancelAccountDeletionRequest, CancelAccountDeletionResponse, DeleteMyAccountRequest, DeleteMyAccountResponse,
+    ExportMyDataRequest, ExportMyDataResponse, Record, account_service_server::AccountService,
+};
+use arenabuddy_data::{AccountRepository, DraftRepository, MatchDB};
+use tokio::sync::mpsc;
+use tokio_stream::{Stream, wrappers::ReceiverStream};
+use tonic::{Request, Response, Status};
+use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    auth::{load_user, require_user_id},
+    debug_service::sample_to_proto,
+    export::Exporter,
+    match_service::load_match_data,
+};
+
+/// Days between a deletion request and the deletion, unless `ACCOUNT_DELETION_GRACE_DAYS` is set.
+const DEFAULT_DELETION_GRACE_DAYS: i64 = 7;
+
+/// The deletion grace period from `ACCOUNT_DELETION_GRACE_DAYS`, or the default when it's unset.
+/// Fails on values that aren't a number of days or that would schedule deletions out of range.
+pub(crate) fn deletion_grace_from_env() -> Result<chrono::Duration, String> {
+    match std::env::var("ACCOUNT_DELETION_GRACE_DAYS") {
+        Ok(value) => parse_deletion_grace(&value),
+        Err(_) => Ok(chrono::Duration::days(DEFAULT_DELETION_GRACE_DAYS)),
+    }
+}
+
+fn parse_deletion_grace(value: &str) -> Result<chrono::Duration, String> {
+    let invalid = || format!("ACCOUNT_DELETION_GRACE_DAYS must be a number of days, got {value:?}");
+    let days: i64 = value.trim().parse().map_err(|_| invalid())?;
+    if days < 0 {
+        return Err(invalid());
+    }
+    chrono::Duration::try_days(days)
+        .filter(|grace| chrono::Utc::now().checked_add_signed(*grace).is_some())
+        .ok_or_else(|| format!("ACCOUNT_DELETION_GRACE_DAYS is too large: {days}"))
+}
+
+/// How often accounts whose grace period has passed are deleted.
+const PURGE_INTERVAL: Duration = Duration::from_hours(1);
+
+/// Records buffered ahead of a slow export reader.
+const EXPORT_BUFFER: usize = 16;
+
+#[derive(Clone)]
+pub(crate) struct AccountServiceImpl {
+    pub(crate) db: MatchDB,
+    pub(crate) exporter: Exporter,
+    /// Delay between a deletion request and the deletion. Zero deletes immediately.
+    pub(crate) deletion_grace: chrono::Duration,
+}
+
+/// Delete an account and the CSV exports written for it.
+async fn delete_account(db: &MatchDB, exporter: &Exporter, user_id: Uuid) -> Result<bool, Status> {
+    let deleted = db.delete_account(user_id).await.map_err(|e| {
+        error!("Failed to delete account {user_id}: {e}");
+        Status::internal("failed to delete account")
+    })?;
+    exporter.remove_user_files(user_id).await;
+    Ok(deleted)
+}
+
+/// Send every record of `user_id` to `tx`, stopping early if the client goes away.
+async fn export_records(db: &MatchDB, user_id: Uuid, tx: &mpsc::Sender<Result<ExportMyDataResponse, Status>>) {
+    let send = |record| async move { tx.send(Ok(ExportMyDataResponse { record: Some(record) })).await.is_ok() };
+    let fail = |what: &str, e: &dyn std::fmt::Display| {
+        error!("Failed to export {what} of user {user_id}: {e}");
+        Status::internal(format!("failed to export {what}"))
+    };
+
+    let result: Result<(), Status> = async {
+        if !send(Record::Profile(load_user(db, user_id).await?)).await {
+            return Ok(());
+        }
+
+        let match_ids = db.list_user_match_ids(user_id).await.map_err(|e| fail("matches", &e))?;
+        for match_id in &match_ids {
+            let match_data = load_match_data(db, match_id, Some(user_id)).await?;
+            if !send(Record::MatchData((&match_data).into())).await {
+                return Ok(());
+            }
+        }
+
+        let drafts = db.list_user_drafts(user_id).await.map_err(|e| fail("drafts", &e))?;
+        for draft in drafts.iter().rev() {
+            let Some(draft) = db
+                .get_user_draft(user_id, &draft.id().to_string())
+                .await
+                .map_err(|e| fail("drafts", &e))?
+            else {
+                continue;
+            };
+            if !send(Record::Draft((&draft).into())).await {
+                return Ok(());
+            }
+        }
+
+        let parse_errors = db
+            .list_user_parse_errors(user_id)
+            .await
+            .map_err(|e| fail("parse errors", &e))?;
+        for report in &parse_errors {
+            if !send(Record::ParseError(sample_to_proto(report))).await {
+                return Ok(());
+            }
+        }
+
+        info!(
+            "Exported {} match(es), {} draft(s) and {} parse error(s) for user {user_id}",
+            match_ids.len(),
+            drafts.len(),
+            parse_errors.len()
+        );
+        Ok(())
+    }
+    .await;
+
+    if let Err(status) = result {
+        let _ = tx.send(Err(status)).await;
+    }
+}
+
+/// Delete accounts whose deletion grace period has passed, once an hour.
+pub(crate) fn spawn_deletion_purge(db: MatchDB, exporter: Exporter) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let due = match db.accounts_due_for_deletion(chrono::Utc::now()).await {
+                Ok(due) => due,
+                Err(e) => {
+                    error!("Failed to list accounts due for deletion: {e}");
+                    continue;
+                }
+            };
+            for user_id in due {
+                match delete_account(&db, &exporter, user_id).await {
+                    Ok(_) => info!("Deleted account {user_id} after its grace period"),
+                    Err(status) => warn!("Scheduled deletion of {user_id} failed: {}", status.message()),
+                }
+            }
+        }
+    });
+}
+
+#[tonic::async_trait]
+impl AccountService for AccountServiceImpl {
+    type ExportMyDataStream = Pin<Box<dyn Stream<Item = Result<ExportMyDataResponse, Status>> + Send>>;
+
+    #[instrument(skip(self, request))]
+    async fn export_my_data(
+        &self,
+        request: Request<ExportMyDataRequest>,
+    ) -> Result<Response<Self::ExportMyDataStream>, Status> {
+        let user_id = require_user_id(&request)?;
+        let (tx, rx) = mpsc::channel(EXPORT_BUFFER);
+        let db = self.db.clone();
+        tokio::spawn(async move { export_records(&db, user_id, &tx).await });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn delete_my_account(
+        &self,
+        request: Request<DeleteMyAccountRequest>,
+    ) -> Result<Response<DeleteMyAccountResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let confirm_username = request.into_inner().confirm_username;
+        let user = load_user(&self.db, user_id).await?;
+        if confirm_username != user.username {
+            return Err(Status::failed_precondition(
+                "confirm_username does not match the account's username",
+            ));
+        }
+
+        if self.deletion_grace <= chrono::Duration::zero() {
+            delete_account(&self.db, &self.exporter, user_id).await?;
+            info!("Deleted account {user_id} at its owner's request");
+            return Ok(Response::new(DeleteMyAccountResponse::default()));
+        }
+
+        let scheduled_for = chrono::Utc::now()
+            .checked_add_signed(self.deletion_grace)
+            .ok_or_else(|| Status::internal("account deletion grace period is out of range"))?;
+        self.db
+            .schedule_account_deletion(user_id, scheduled_for)
+            .await
+            .map_err(|e| {
+                error!("Failed to schedule account deletion: {e}");
+                Status::internal("failed to schedule account deletion")
+            })?;
+        info!("Account {user_id} scheduled for deletion at {scheduled_for}");
+        Ok(Response::new(DeleteMyAccountResponse {
+            scheduled_for: scheduled_for.to_rfc3339(),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn cancel_account_deletion(
+        &self,
+        request: Request<CancelAccountDeletionRequest>,
+    ) -> Result<Response<CancelAccountDeletionResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let cancelled = self.db.cancel_account_deletion(user_id).await.map_err(|e| {
+            error!("Failed to cancel account deletion: {e}");
+            Status::internal("failed to cancel account deletion")
+        })?;
+        if !cancelled {
+            return Err(Status::failed_precondition("no account deletion is pending"));
+        }
+
+        info!("Account {user_id} deletion cancelled");
+        Ok(Response::new(CancelAccountDeletionResponse {}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arenabuddy_core::cards::CardsDatabase;
+    use arenabuddy_data::{AuthRepository, testing::test_db};
+    use tokio_stream::StreamExt;
+
+    use super::*;
+    use crate::auth::UserId;
+
+    fn authed<T>(user_id: Uuid, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request.extensions_mut().insert(UserId(user_id));
+        request
+    }
+
+    async fn service(deletion_grace: chrono::Duration) -> Option<(AccountServiceImpl, Uuid)> {
+        let db = test_db().await?;
+        let user_id = db
+            .upsert_identity_user("local", "alice", "alice", None)
+            .await
+            .expect("user");
+        let exporter = Exporter::new(db.clone(), CardsDatabase::default(), std::env::temp_dir(), None);
+        Some((
+            AccountServiceImpl {
+                db,
+                exporter,
+                deletion_grace,
+            },
+            user_id,
+        ))
+    }
+
+    fn delete_request(user_id: Uuid, confirm_username: &str) -> Request<DeleteMyAccountRequest> {
+        authed(
+            user_id,
+            DeleteMyAccountRequest {
+                confirm_username: confirm_username.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn grace_days_must_be_a_representable_number_of_days() {
+        assert_eq!(parse_deletion_grace("0"), Ok(chrono::Duration::zero()));
+        assert_eq!(parse_deletion_grace(" 30 "), Ok(chrono::Duration::days(30)));
+        assert!(parse_deletion_grace("-1").is_err());
+        assert!(parse_deletion_grace("a week").is_err());
+        assert!(parse_deletion_grace("999999999999").is_err());
+        assert!(parse_deletion_grace(&i64::MAX.to_string()).is_err());
+    }
+
+    #[tokio::test]
+    async fn deletion_waits_for_the_grace_period_and_can_be_cancelled() {
+        let Some((service, user_id)) = service(chrono::Duration::days(7)).await else {
+            return;
+        };
+
+        let status = service
+            .delete_my_account(delete_request(user_id, "bob"))
+            .await
+            .expect_err("wrong username");
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+        let scheduled = service
+            .delete_my_account(delete_request(user_id, "alice"))
+            .await
+            .expect("scheduled")
+            .into_inner();
+        let scheduled_for = chrono::DateTime::parse_from_rfc3339(&scheduled.scheduled_for).expect("timestamp");
+        assert!(scheduled_for > chrono::Utc::now() + chrono::Duration::days(6));
+        assert!(load_user(&service.db, user_id).await.is_ok());
+
+        service
+            .cancel_account_deletion(authed(user_id, CancelAccountDeletionRequest {}))
+            .await
+            .expect("cancelled");
+        let status = service
+            .cancel_account_deletion(authed(user_id, CancelAccountDeletionRequest {}))
+            .await
+            .expect_err("nothing pending");
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+    }
+
+    #[tokio::test]
+    async fn deletion_without_a_grace_period_is_immediate() {
+        let Some((service, user_id)) = service(chrono::Duration::zero()).await else {
+            return;
+        };
+
+        let response = service
+            .delete_my_account(delete_request(user_id, "alice"))
+            .await
+            .expect("deleted")
+            .into_inner();
+        assert!(response.scheduled_for.is_empty());
+        let status = load_user(&service.db, user_id).await.expect_err("user deleted");
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn export_streams_the_callers_profile() {
+        let Some((service, user_id)) = service(chrono::Duration::zero()).await else {
+            return;
+        };
+
+        let stream = service
+            .export_my_data(authed(user_id, ExportMyDataRequest {}))
+            .await
+            .expect("export")
+            .into_inner();
+        let records: Vec<_> = stream.collect::<Result<_, _>>().await.expect("records");
+        assert_eq!(records.len(), 1);
+        match &records[0].record {
+            Some(Record::Profile(user)) => assert_eq!(user.username, "alice"),
+            other => panic!("expected the profile, got {other:?}"),
+        }
+
+        let status = service
+            .export_my_data(Request::new(ExportMyDataRequest {}))
+            .await
+            .err()
+            .expect("unauthenticated");
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+    }
+}
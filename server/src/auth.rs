@@ -1,8 +1,11 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use arenabuddy_core::services::auth_service::{
-    ExchangeTokenRequest, ExchangeTokenResponse, GetCurrentUserRequest, GetCurrentUserResponse, LogoutRequest,
-    LogoutResponse, RefreshTokenRequest, RefreshTokenResponse, User, auth_service_server::AuthService,
+    ExchangeTokenRequest, ExchangeTokenResponse, GetCurrentUserRequest, GetCurrentUserResponse, Identity,
+    IdentityProviderInfo, LinkIdentityRequest, LinkIdentityResponse, ListIdentityProvidersRequest,
+    ListIdentityProvidersResponse, LogoutRequest, LogoutResponse, RefreshTokenRequest, RefreshTokenResponse,
+    RegisterLocalUserRequest, RegisterLocalUserResponse, UnlinkIdentityRequest, UnlinkIdentityResponse, User,
+    auth_service_server::AuthService,
 };
 use arenabuddy_data::{AuthRepository, MatchDB};
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
@@ -13,122 +16,43 @@ use tonic::{Request, Response, Status};
 use tracing::{debug, error, info, instrument};
 use uuid::Uuid;
 
+use crate::{
+    identity::{Credentials, ExternalIdentity, IdentityProviders},
+    rate_limit::RateLimiter,
+};
+
 const ACCESS_TOKEN_LIFETIME_MINUTES: i64 = 15;
 const REFRESH_TOKEN_LIFETIME_DAYS: i64 = 30;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct DiscordTokenResponse {
-    access_token: String,
-    token_type: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct DiscordUser {
-    id: String,
-    username: String,
-    avatar: Option<String>,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // user UUID
-    pub discord_id: String,
     pub exp: usize,
 }
 
 pub struct AuthConfig {
-    pub discord_client_id: String,
-    pub discord_client_secret: String,
     pub jwt_secret: String,
 }
 
+#[derive(Clone)]
 pub struct AuthServiceImpl {
     db: MatchDB,
     config: Arc<AuthConfig>,
-    http: reqwest::Client,
+    providers: IdentityProviders,
 }
 
 impl AuthServiceImpl {
-    pub fn new(db: MatchDB, config: Arc<AuthConfig>) -> Self {
-        Self {
-            db,
-            config,
-            http: reqwest::Client::new(),
-        }
-    }
-
-    async fn exchange_discord_code(
-        &self,
-        code: &str,
-        code_verifier: &str,
-        redirect_uri: &str,
-    ) -> Result<DiscordTokenResponse, Status> {
-        let params = [
-            ("client_id", self.config.discord_client_id.as_str()),
-            ("client_secret", self.config.discord_client_secret.as_str()),
-            ("grant_type", "authorization_code"),
-            ("code", code),
-            ("redirect_uri", redirect_uri),
-            ("code_verifier", code_verifier),
-        ];
-
-        let resp = self
-            .http
-            .post("https://discord.com/api/v10/oauth2/token")
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| {
-                error!("Discord token exchange HTTP error: {e}");
-                Status::internal("failed to contact Discord")
-            })?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            error!("Discord token exchange failed: {status} {body}");
-            return Err(Status::unauthenticated("Discord authentication failed"));
-        }
-
-        resp.json::<DiscordTokenResponse>().await.map_err(|e| {
-            error!("Failed to parse Discord token response: {e}");
-            Status::internal("failed to parse Discord response")
-        })
-    }
-
-    async fn get_discord_user(&self, access_token: &str) -> Result<DiscordUser, Status> {
-        let resp = self
-            .http
-            .get("https://discord.com/api/v10/users/@me")
-            .bearer_auth(access_token)
-            .send()
-            .await
-            .map_err(|e| {
-                error!("Discord user fetch HTTP error: {e}");
-                Status::internal("failed to contact Discord")
-            })?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            error!("Discord user fetch failed: {status} {body}");
-            return Err(Status::internal("failed to fetch Discord user"));
-        }
-
-        resp.json::<DiscordUser>().await.map_err(|e| {
-            error!("Failed to parse Discord user response: {e}");
-            Status::internal("failed to parse Discord user")
-        })
+    pub fn new(db: MatchDB, config: Arc<AuthConfig>, providers: IdentityProviders) -> Self {
+        Self { db, config, providers }
     }
 
-    fn mint_jwt(&self, user_id: &Uuid, discord_id: &str) -> Result<(String, i64), Status> {
+    fn mint_jwt(&self, user_id: &Uuid) -> Result<(String, i64), Status> {
         let exp = chrono::Utc::now()
             .checked_add_signed(chrono::Duration::minutes(ACCESS_TOKEN_LIFETIME_MINUTES))
             .ok_or_else(|| Status::internal("failed to compute expiry"))?;
 
         let claims = Claims {
             sub: user_id.to_string(),
-            discord_id: discord_id.to_string(),
             #[expect(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
             exp: exp.timestamp() as usize,
         };
@@ -146,8 +70,65 @@ impl AuthServiceImpl {
         Ok((token, exp.timestamp()))
     }
 
+    /// Resolve the account for an external identity (creating it on first login) and issue
+    /// an access/refresh token pair.
+    async fn sign_in(&self, provider: &str, identity: &ExternalIdentity) -> Result<SignedIn, Status> {
+        let user_id = self
+            .db
+            .upsert_identity_user(
+                provider,
+                &identity.subject,
+                &identity.username,
+                identity.avatar_url.as_deref(),
+            )
+            .await
+            .map_err(|e| {
+                error!("Failed to upsert user: {e}");
+                Status::internal("failed to create user")
+            })?;
+
+        self.issue_tokens(user_id).await
+    }
+
+    /// Issue an access/refresh token pair for `user_id`.
+    async fn issue_tokens(&self, user_id: Uuid) -> Result<SignedIn, Status> {
+        let (access_token, expires_at) = self.mint_jwt(&user_id)?;
+        let (refresh_token, refresh_expires_at) = self.create_refresh_token(&user_id).await?;
+        let user = load_user(&self.db, user_id).await?;
+
+        Ok(SignedIn {
+            access_token,
+            expires_at,
+            user,
+            refresh_token,
+            refresh_expires_at,
+        })
+    }
+
+    /// `AuthService` is not behind the auth interceptor (because `exchange_token`,
+    /// `refresh_token`, and `logout` must be callable without a JWT), so RPCs that need
+    /// the caller validate the JWT manually here.
+    fn authenticated_user<T>(&self, request: &Request<T>) -> Result<Uuid, Status> {
+        let token = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("missing authorization token"))?;
+
+        let claims = validate_jwt(token, &self.config.jwt_secret).map_err(|e| {
+            error!("JWT validation failed: {e}");
+            Status::unauthenticated("invalid token")
+        })?;
+
+        claims
+            .sub
+            .parse()
+            .map_err(|_| Status::unauthenticated("invalid token claims"))
+    }
+
     async fn create_refresh_token(&self, user_id: &Uuid) -> Result<(String, i64), Status> {
-        let raw_token = generate_refresh_token();
+        let raw_token = generate_opaque_token();
         let token_hash = hash_token(&raw_token);
         let expires_at = chrono::Utc::now()
             .checked_add_signed(chrono::Duration::days(REFRESH_TOKEN_LIFETIME_DAYS))
@@ -203,13 +184,55 @@ impl AuthServiceImpl {
     }
 }
 
-fn generate_refresh_token() -> String {
+/// Load a user together with its linked identities.
+pub(crate) async fn load_user(db: &MatchDB, user_id: Uuid) -> Result<User, Status> {
+    let user = db.get_user(user_id).await.map_err(|e| {
+        error!("Failed to fetch user: {e}");
+        Status::internal("failed to fetch user")
+    })?;
+    let user = user.ok_or_else(|| Status::not_found("user not found"))?;
+
+    let identities = db.list_identities(user_id).await.map_err(|e| {
+        error!("Failed to list identities: {e}");
+        Status::internal("failed to fetch user")
+    })?;
+
+    Ok(User {
+        id: user.id.to_string(),
+        discord_id: user.discord_id.unwrap_or_default(),
+        username: user.username,
+        avatar_url: user.avatar_url.unwrap_or_default(),
+        deletion_scheduled_for: user
+            .deletion_scheduled_for
+            .map(|at| at.to_rfc3339())
+            .unwrap_or_default(),
+        identities: identities
+            .into_iter()
+            .map(|identity| Identity {
+                provider: identity.provider,
+                subject: identity.subject,
+                display_name: identity.display_name.unwrap_or_default(),
+            })
+            .collect(),
+    })
+}
+
+struct SignedIn {
+    access_token: String,
+    expires_at: i64,
+    user: User,
+    refresh_token: String,
+    refresh_expires_at: i64,
+}
+
+/// 256 bits of randomness, URL-safe base64. Used for refresh and share tokens.
+pub(crate) fn generate_opaque_token() -> String {
     let mut bytes = [0u8; 32];
     rand::fill(&mut bytes);
     URL_SAFE_NO_PAD.encode(bytes)
 }
 
-fn hash_token(token: &str) -> Vec<u8> {
+pub(crate) fn hash_token(token: &str) -> Vec<u8> {
     Sha256::digest(token.as_bytes()).to_vec()
 }
 
@@ -222,34 +245,46 @@ pub fn validate_jwt(token: &str, jwt_secret: &str) -> Result<Claims, jsonwebtoke
     Ok(token_data.claims)
 }
 
-pub fn auth_interceptor(jwt_secret: String) -> impl Fn(Request<()>) -> Result<Request<()>, Status> + Clone {
-    move |mut req: Request<()>| {
-        debug!("Incoming authenticated request");
-
-        let token = req
-            .metadata()
-            .get("authorization")
-            .and_then(|v| v.to_str().ok())
-            .and_then(|v| v.strip_prefix("Bearer "))
-            .ok_or_else(|| {
-                info!("Rejected: missing authorization token");
-                Status::unauthenticated("missing authorization token")
-            })?;
+/// Authenticates the caller, then applies their rate limit.
+pub(crate) fn auth_interceptor(
+    jwt_secret: String,
+    limiter: RateLimiter,
+) -> impl Fn(Request<()>) -> Result<Request<()>, Status> + Clone {
+    move |req: Request<()>| {
+        let req = authenticate(req, &jwt_secret)?;
+        limiter.check_request(&req)?;
+        Ok(req)
+    }
+}
 
-        let claims = validate_jwt(token, &jwt_secret).map_err(|e| {
-            error!("Rejected: JWT validation failed: {e}");
-            Status::unauthenticated("invalid token")
+/// Validate the request's `authorization: Bearer` token and attach the caller as a [`UserId`]
+/// extension. Shared by [`auth_interceptor`] and the JSON gateway so both accept the same tokens.
+pub(crate) fn authenticate<T>(mut req: Request<T>, jwt_secret: &str) -> Result<Request<T>, Status> {
+    debug!("Incoming authenticated request");
+
+    let token = req
+        .metadata()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| {
+            info!("Rejected: missing authorization token");
+            Status::unauthenticated("missing authorization token")
         })?;
 
-        let user_id = claims
-            .sub
-            .parse::<Uuid>()
-            .map_err(|_| Status::unauthenticated("invalid token claims"))?;
+    let claims = validate_jwt(token, jwt_secret).map_err(|e| {
+        error!("Rejected: JWT validation failed: {e}");
+        Status::unauthenticated("invalid token")
+    })?;
 
-        debug!("Authorized user {user_id}");
-        req.extensions_mut().insert(UserId(user_id));
-        Ok(req)
-    }
+    let user_id = claims
+        .sub
+        .parse::<Uuid>()
+        .map_err(|_| Status::unauthenticated("invalid token claims"))?;
+
+    debug!("Authorized user {user_id}");
+    req.extensions_mut().insert(UserId(user_id));
+    Ok(req)
 }
 
 #[tonic::async_trait]
@@ -262,54 +297,23 @@ impl AuthService for AuthServiceImpl {
         info!("ExchangeToken request received");
         let req = request.into_inner();
 
-        if req.authorization_code.is_empty() {
-            return Err(Status::invalid_argument("authorization_code is required"));
-        }
-        if req.code_verifier.is_empty() {
-            return Err(Status::invalid_argument("code_verifier is required"));
-        }
-        if req.redirect_uri.is_empty() {
-            return Err(Status::invalid_argument("redirect_uri is required"));
-        }
-
-        let discord_token = self
-            .exchange_discord_code(&req.authorization_code, &req.code_verifier, &req.redirect_uri)
-            .await?;
-
-        let discord_user = self.get_discord_user(&discord_token.access_token).await?;
-        info!(
-            "Discord user authenticated: {} ({})",
-            discord_user.username, discord_user.id
+        let provider = self.providers.get(&req.provider)?;
+        let credentials = Credentials::from_request_fields(
+            req.authorization_code,
+            req.code_verifier,
+            req.redirect_uri,
+            req.username,
+            req.password,
         );
-
-        let avatar_url = discord_user
-            .avatar
-            .as_ref()
-            .map(|hash| format!("https://cdn.discordapp.com/avatars/{}/{hash}.png", discord_user.id));
-
-        let user_id = self
-            .db
-            .upsert_user(&discord_user.id, &discord_user.username, avatar_url.as_deref())
-            .await
-            .map_err(|e| {
-                error!("Failed to upsert user: {e}");
-                Status::internal("failed to create user")
-            })?;
-
-        let (jwt, expires_at) = self.mint_jwt(&user_id, &discord_user.id)?;
-        let (refresh_token, refresh_expires_at) = self.create_refresh_token(&user_id).await?;
+        let identity = provider.authenticate(&credentials).await?;
+        let signed_in = self.sign_in(provider.name(), &identity).await?;
 
         Ok(Response::new(ExchangeTokenResponse {
-            access_token: jwt,
-            expires_at,
-            user: Some(User {
-                id: user_id.to_string(),
-                discord_id: discord_user.id,
-                username: discord_user.username,
-                avatar_url: avatar_url.unwrap_or_default(),
-            }),
-            refresh_token,
-            refresh_expires_at,
+            access_token: signed_in.access_token,
+            expires_at: signed_in.expires_at,
+            user: Some(signed_in.user),
+            refresh_token: signed_in.refresh_token,
+            refresh_expires_at: signed_in.refresh_expires_at,
         }))
     }
 
@@ -328,15 +332,16 @@ impl AuthService for AuthServiceImpl {
         let (user_id, new_refresh_token, new_refresh_expires_at) =
             self.validate_and_rotate_refresh_token(&req.refresh_token).await?;
 
-        // Fetch user to get discord_id for JWT claims
+        // Make sure the account still exists before minting a new access token
         let user = self.db.get_user(user_id).await.map_err(|e| {
             error!("Failed to fetch user for refresh: {e}");
             Status::internal("failed to fetch user")
         })?;
+        if user.is_none() {
+            return Err(Status::not_found("user not found"));
+        }
 
-        let user = user.ok_or_else(|| Status::not_found("user not found"))?;
-
-        let (jwt, expires_at) = self.mint_jwt(&user_id, &user.discord_id)?;
+        let (jwt, expires_at) = self.mint_jwt(&user_id)?;
 
         info!("Refreshed tokens for user {user_id}");
         Ok(Response::new(RefreshTokenResponse {
@@ -372,43 +377,189 @@ impl AuthService for AuthServiceImpl {
         &self,
         request: Request<GetCurrentUserRequest>,
     ) -> Result<Response<GetCurrentUserResponse>, Status> {
-        // AuthService is not behind the auth interceptor (because exchange_token,
-        // refresh_token, and logout must be callable without a JWT), so we validate
-        // the JWT manually here.
-        let token = request
-            .metadata()
-            .get("authorization")
-            .and_then(|v| v.to_str().ok())
-            .and_then(|v| v.strip_prefix("Bearer "))
-            .ok_or_else(|| Status::unauthenticated("missing authorization token"))?;
+        let user_id = self.authenticated_user(&request)?;
+        let user = load_user(&self.db, user_id).await?;
 
-        let claims = validate_jwt(token, &self.config.jwt_secret).map_err(|e| {
-            error!("get_current_user: JWT validation failed: {e}");
-            Status::unauthenticated("invalid token")
-        })?;
+        Ok(Response::new(GetCurrentUserResponse { user: Some(user) }))
+    }
 
-        let user_id: Uuid = claims
-            .sub
-            .parse()
-            .map_err(|_| Status::unauthenticated("invalid token claims"))?;
+    #[instrument(skip(self, request))]
+    async fn register_local_user(
+        &self,
+        request: Request<RegisterLocalUserRequest>,
+    ) -> Result<Response<RegisterLocalUserResponse>, Status> {
+        info!("RegisterLocalUser request received");
+        // Signed-in callers add a local login to their own account instead of creating one.
+        let link_to = if request.metadata().contains_key("authorization") {
+            Some(self.authenticated_user(&request)?)
+        } else {
+            None
+        };
+        let req = request.into_inner();
 
-        let user = self.db.get_user(user_id).await.map_err(|e| {
-            error!("Failed to fetch user: {e}");
-            Status::internal("failed to fetch user")
+        let local = self
+            .providers
+            .local()
+            .ok_or_else(|| Status::failed_precondition("local accounts are not enabled"))?;
+        let user_id = local.register(&req.username, &req.password, link_to).await?;
+        let signed_in = self.issue_tokens(user_id).await?;
+
+        Ok(Response::new(RegisterLocalUserResponse {
+            access_token: signed_in.access_token,
+            expires_at: signed_in.expires_at,
+            user: Some(signed_in.user),
+            refresh_token: signed_in.refresh_token,
+            refresh_expires_at: signed_in.refresh_expires_at,
+        }))
+    }
+
+    #[instrument(skip(self, _request))]
+    async fn list_identity_providers(
+        &self,
+        _request: Request<ListIdentityProvidersRequest>,
+    ) -> Result<Response<ListIdentityProvidersResponse>, Status> {
+        let details = self
+            .providers
+            .iter()
+            .map(|provider| {
+                let endpoint = provider.authorization_endpoint();
+                IdentityProviderInfo {
+                    name: provider.name().to_string(),
+                    authorization_endpoint: endpoint.map(|e| e.url.to_string()).unwrap_or_default(),
+                    client_id: endpoint.map(|e| e.client_id.to_string()).unwrap_or_default(),
+                    scope: endpoint.map(|e| e.scope.to_string()).unwrap_or_default(),
+                }
+            })
+            .collect();
+        Ok(Response::new(ListIdentityProvidersResponse {
+            providers: self.providers.names().map(str::to_string).collect(),
+            details,
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn link_identity(
+        &self,
+        request: Request<LinkIdentityRequest>,
+    ) -> Result<Response<LinkIdentityResponse>, Status> {
+        let user_id = self.authenticated_user(&request)?;
+        let req = request.into_inner();
+        info!("LinkIdentity request for user {user_id} via '{}'", req.provider);
+
+        let provider = self.providers.get(&req.provider)?;
+        let credentials = Credentials::from_request_fields(
+            req.authorization_code,
+            req.code_verifier,
+            req.redirect_uri,
+            req.username,
+            req.password,
+        );
+        let identity = provider.authenticate(&credentials).await?;
+
+        let linked = self
+            .db
+            .link_identity(user_id, provider.name(), &identity.subject, Some(&identity.username))
+            .await
+            .map_err(|e| {
+                error!("Failed to link identity: {e}");
+                Status::internal("failed to link identity")
+            })?;
+        if !linked {
+            return Err(Status::already_exists("identity is already linked to another account"));
+        }
+
+        let user = load_user(&self.db, user_id).await?;
+        Ok(Response::new(LinkIdentityResponse { user: Some(user) }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn unlink_identity(
+        &self,
+        request: Request<UnlinkIdentityRequest>,
+    ) -> Result<Response<UnlinkIdentityResponse>, Status> {
+        let user_id = self.authenticated_user(&request)?;
+        let req = request.into_inner();
+        info!("UnlinkIdentity request for user {user_id} via '{}'", req.provider);
+
+        if req.provider.is_empty() || req.subject.is_empty() {
+            return Err(Status::invalid_argument("provider and subject are required"));
+        }
+
+        let identities = self.db.list_identities(user_id).await.map_err(|e| {
+            error!("Failed to list identities: {e}");
+            Status::internal("failed to unlink identity")
         })?;
+        // Never leave an account without a way to sign in.
+        if identities.len() <= 1 {
+            return Err(Status::failed_precondition(
+                "cannot unlink the last identity of an account",
+            ));
+        }
 
-        let user = user.ok_or_else(|| Status::not_found("user not found"))?;
+        let removed = self
+            .db
+            .unlink_identity(user_id, &req.provider, &req.subject)
+            .await
+            .map_err(|e| {
+                error!("Failed to unlink identity: {e}");
+                Status::internal("failed to unlink identity")
+            })?;
+        if !removed {
+            return Err(Status::not_found("identity not linked to this account"));
+        }
 
-        Ok(Response::new(GetCurrentUserResponse {
-            user: Some(User {
-                id: user.id.to_string(),
-                discord_id: user.discord_id,
-                username: user.username,
-                avatar_url: user.avatar_url.unwrap_or_default(),
-            }),
-        }))
+        let user = load_user(&self.db, user_id).await?;
+        Ok(Response::new(UnlinkIdentityResponse { user: Some(user) }))
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct UserId(pub Uuid);
+
+/// The authenticated caller of an intercepted service. Every service behind
+/// [`auth_interceptor`] has this extension, so a miss means the service was wired without it.
+pub(crate) fn require_user_id<T>(request: &Request<T>) -> Result<Uuid, Status> {
+    request
+        .extensions()
+        .get::<UserId>()
+        .map(|u| u.0)
+        .ok_or_else(|| Status::unauthenticated("authentication required"))
+}
+
+/// Operators allowed to call admin RPCs, configured as a comma-separated list of user ids in
+/// `ADMIN_USER_IDS`.
+#[derive(Debug, Clone)]
+pub(crate) struct Admins(Arc<HashSet<Uuid>>);
+
+impl Admins {
+    pub(crate) fn from_env() -> Result<Self, uuid::Error> {
+        let ids = std::env::var("ADMIN_USER_IDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(str::parse)
+            .collect::<Result<HashSet<Uuid>, _>>()?;
+        Ok(Self(Arc::new(ids)))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The authenticated caller, if they are an admin.
+    pub(crate) fn require<T>(&self, request: &Request<T>) -> Result<Uuid, Status> {
+        let user_id = require_user_id(request)?;
+        if !self.0.contains(&user_id) {
+            info!("Rejected admin request from user {user_id}");
+            return Err(Status::permission_denied("admin access required"));
+        }
+        Ok(user_id)
+    }
+}
+
+impl FromIterator<Uuid> for Admins {
+    fn from_iter<I: IntoIterator<Item = Uuid>>(ids: I) -> Self {
+        Self(Arc::new(ids.into_iter().collect()))
+    }
+}
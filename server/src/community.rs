@@ -0,0 +1,138 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    time::Duration,
+};
+
+use arenabuddy_core::{display::stats::TimeWindow, models::EventRules};
+use arenabuddy_data::{
+    CommunityRepository, MatchDB,
+    community_repository::{CommunityMatchup, RawCommunityMatchup},
+};
+use tracing::{error, info};
+use uuid::Uuid;
+
+const DEFAULT_INTERVAL_SECS: u64 = 3600;
+
+#[derive(Default)]
+struct CellAccumulator {
+    matches: i64,
+    wins: i64,
+    losses: i64,
+    players: HashSet<Uuid>,
+}
+
+/// Merge per-event-id rows into per-format cells. Event ids no event rule describes are
+/// dropped.
+fn merge_by_format(rows: Vec<RawCommunityMatchup>) -> Vec<CommunityMatchup> {
+    let mut cells: BTreeMap<(String, String, String), CellAccumulator> = BTreeMap::new();
+    for row in rows {
+        let Some(event) = EventRules::active().describe(&row.event_id) else {
+            continue;
+        };
+        let cell = cells
+            .entry((event.format, row.archetype, row.opponent_archetype))
+            .or_default();
+        cell.matches += row.matches;
+        cell.wins += row.wins;
+        cell.losses += row.losses;
+        cell.players.extend(row.user_ids);
+    }
+
+    cells
+        .into_iter()
+        .map(|((format, archetype, opponent_archetype), cell)| CommunityMatchup {
+            format,
+            archetype,
+            opponent_archetype,
+            matches: cell.matches,
+            wins: cell.wins,
+            losses: cell.losses,
+            players: i64::try_from(cell.players.len()).unwrap_or(i64::MAX),
+        })
+        .collect()
+}
+
+/// Recompute the community matchup table for every time window.
+async fn aggregate(db: &MatchDB) -> arenabuddy_data::Result<()> {
+    for window in TimeWindow::ALL {
+        let rows = db.aggregate_community_matchups(window.cutoff()).await?;
+        let cells = merge_by_format(rows);
+        db.replace_community_matchups(window, &cells).await?;
+        info!("Community matchups for {window}: {} cells", cells.len());
+    }
+    Ok(())
+}
+
+/// Spawn the background job that periodically rebuilds community matchups.
+///
+/// The interval is read from `COMMUNITY_AGGREGATION_INTERVAL_SECS` (default: one hour).
+pub(crate) fn spawn_aggregation(db: MatchDB) {
+    let secs = std::env::var("COMMUNITY_AGGREGATION_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+    info!("Community matchup aggregation every {secs}s");
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(e) = aggregate(&db).await {
+                error!("Community matchup aggregation failed: {e}");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(event_id: &str, archetype: &str, record: (i64, i64), user_ids: &[Uuid]) -> RawCommunityMatchup {
+        RawCommunityMatchup {
+            event_id: event_id.to_string(),
+            archetype: archetype.to_string(),
+            opponent_archetype: "Azorius Control".to_string(),
+            matches: record.0 + record.1,
+            wins: record.0,
+            losses: record.1,
+            user_ids: user_ids.to_vec(),
+        }
+    }
+
+    #[test]
+    fn event_ids_merge_into_formats_counting_each_player_once() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let cells = merge_by_format(vec![
+            raw("Ladder", "Mono Red", (3, 1), &[a, b]),
+            raw("Traditional_Ladder", "Mono Red", (1, 2), &[b, c]),
+            raw("Explorer_Ladder", "Mono Red", (5, 0), &[a]),
+            raw("Play", "Gruul", (1, 1), &[c]),
+            raw("SomeRandomEvent", "Mono Red", (9, 9), &[a, b, c]),
+        ]);
+
+        let summary: Vec<_> = cells
+            .iter()
+            .map(|cell| {
+                (
+                    cell.format.as_str(),
+                    cell.archetype.as_str(),
+                    cell.matches,
+                    cell.wins,
+                    cell.losses,
+                    cell.players,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("explorer", "Mono Red", 5, 5, 0, 1),
+                ("standard", "Gruul", 2, 1, 1, 1),
+                ("standard", "Mono Red", 7, 4, 3, 3),
+            ]
+        );
+    }
+}
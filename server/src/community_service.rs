@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use arenabuddy_core::{
+    display::stats::{ArchetypeMatchup, TimeWindow},
+    services::{
+        community_service::{
+            GetCommunitySettingsRequest, GetCommunitySettingsResponse, GetMatchupMatrixRequest,
+            GetMatchupMatrixResponse, MatchupCell, SetCommunityOptOutRequest, SetCommunityOptOutResponse,
+            community_service_server::CommunityService,
+        },
+        team_service::StatsTimeWindow,
+    },
+};
+use arenabuddy_data::{CommunityRepository, MatchDB, community_repository::CommunityMatchup};
+use tonic::{Request, Response, Status};
+use tracing::{error, info, instrument};
+
+use crate::auth::require_user_id;
+
+/// Default minimum number of matches before a cell is reported.
+pub(crate) const DEFAULT_MIN_MATCHES: i64 = 20;
+/// Minimum distinct contributors per cell, so no single player's record can be read off the matrix.
+const MIN_PLAYERS: i64 = 3;
+
+pub(crate) struct CommunityServiceImpl {
+    pub(crate) db: MatchDB,
+    /// Server-wide floor for the per-cell sample size; requests may only raise it.
+    pub(crate) min_matches: i64,
+}
+
+fn matchup_cell(cell: CommunityMatchup) -> MatchupCell {
+    let matchup = ArchetypeMatchup {
+        archetype: cell.archetype,
+        opponent_archetype: cell.opponent_archetype,
+        matches: cell.matches,
+        wins: cell.wins,
+        losses: cell.losses,
+        ..Default::default()
+    };
+    let win_rate = matchup.win_rate().unwrap_or_default();
+    let (ci_low, ci_high) = matchup.confidence_interval().unwrap_or_default();
+    MatchupCell {
+        archetype: matchup.archetype,
+        opponent_archetype: matchup.opponent_archetype,
+        matches: matchup.matches,
+        wins: matchup.wins,
+        losses: matchup.losses,
+        players: cell.players,
+        win_rate,
+        ci_low,
+        ci_high,
+    }
+}
+
+/// Archetypes that appear in `cells`, most played first.
+fn archetypes_by_matches(cells: &[CommunityMatchup]) -> Vec<String> {
+    let mut totals: HashMap<&str, i64> = HashMap::new();
+    for cell in cells {
+        *totals.entry(&cell.archetype).or_default() += cell.matches;
+        totals.entry(&cell.opponent_archetype).or_default();
+    }
+    let mut archetypes: Vec<_> = totals.into_iter().collect();
+    archetypes.sort_by(|(a, a_total), (b, b_total)| b_total.cmp(a_total).then_with(|| a.cmp(b)));
+    archetypes.into_iter().map(|(name, _)| name.to_string()).collect()
+}
+
+#[tonic::async_trait]
+impl CommunityService for CommunityServiceImpl {
+    #[instrument(skip(self, request))]
+    async fn get_matchup_matrix(
+        &self,
+        request: Request<GetMatchupMatrixRequest>,
+    ) -> Result<Response<GetMatchupMatrixResponse>, Status> {
+        require_user_id(&request)?;
+        let req = request.into_inner();
+        let window = TimeWindow::from(req.time_window());
+        let min_matches = req.min_matches.max(self.min_matches);
+
+        let formats = self.db.list_community_formats().await.map_err(|e| {
+            error!("Failed to list community formats: {e}");
+            Status::internal("failed to load community matchups")
+        })?;
+        let format = if req.format.is_empty() {
+            formats.first().cloned().unwrap_or_default()
+        } else {
+            req.format.trim().to_lowercase()
+        };
+
+        let (cells, computed_at) = self
+            .db
+            .get_community_matchups(&format, window, min_matches, MIN_PLAYERS)
+            .await
+            .map_err(|e| {
+                error!("Failed to load community matchups: {e}");
+                Status::internal("failed to load community matchups")
+            })?;
+
+        Ok(Response::new(GetMatchupMatrixResponse {
+            format,
+            time_window: StatsTimeWindow::from(window).into(),
+            archetypes: archetypes_by_matches(&cells),
+            cells: cells.into_iter().map(matchup_cell).collect(),
+            min_matches,
+            formats,
+            computed_at: computed_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn get_community_settings(
+        &self,
+        request: Request<GetCommunitySettingsRequest>,
+    ) -> Result<Response<GetCommunitySettingsResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let opt_out = self.db.get_community_opt_out(user_id).await.map_err(|e| {
+            error!("Failed to load community settings: {e}");
+            Status::internal("failed to load community settings")
+        })?;
+        Ok(Response::new(GetCommunitySettingsResponse { opt_out }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn set_community_opt_out(
+        &self,
+        request: Request<SetCommunityOptOutRequest>,
+    ) -> Result<Response<SetCommunityOptOutResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let opt_out = request.into_inner().opt_out;
+        self.db.set_community_opt_out(user_id, opt_out).await.map_err(|e| {
+            error!("Failed to update community opt-out: {e}");
+            Status::internal("failed to update community settings")
+        })?;
+        info!("User {user_id} set community opt-out to {opt_out}");
+        Ok(Response::new(SetCommunityOptOutResponse { opt_out }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arenabuddy_data::{AuthRepository, testing::test_db};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::auth::UserId;
+
+    fn authed<T>(user_id: Uuid, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request.extensions_mut().insert(UserId(user_id));
+        request
+    }
+
+    fn cell(archetype: &str, matches: i64, players: i64) -> CommunityMatchup {
+        CommunityMatchup {
+            format: "standard".to_string(),
+            archetype: archetype.to_string(),
+            opponent_archetype: "Azorius Control".to_string(),
+            matches,
+            wins: matches / 2,
+            losses: matches - matches / 2,
+            players,
+        }
+    }
+
+    #[tokio::test]
+    async fn small_cells_are_hidden_and_requests_cannot_lower_the_floor() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let user_id = db
+            .upsert_identity_user("local", "alice", "alice", None)
+            .await
+            .expect("user");
+        db.replace_community_matchups(
+            TimeWindow::AllTime,
+            &[
+                cell("Mono Red", DEFAULT_MIN_MATCHES, MIN_PLAYERS),
+                cell("Gruul", DEFAULT_MIN_MATCHES * 2, MIN_PLAYERS - 1),
+                cell("Domain", DEFAULT_MIN_MATCHES - 1, MIN_PLAYERS * 2),
+            ],
+        )
+        .await
+        .expect("cells");
+        let service = CommunityServiceImpl {
+            db,
+            min_matches: DEFAULT_MIN_MATCHES,
+        };
+        let matrix = |min_matches| {
+            authed(
+                user_id,
+                GetMatchupMatrixRequest {
+                    min_matches,
+                    ..Default::default()
+                },
+            )
+        };
+
+        let response = service
+            .get_matchup_matrix(matrix(1))
+            .await
+            .expect("matrix")
+            .into_inner();
+        assert_eq!(response.format, "standard");
+        assert_eq!(response.min_matches, DEFAULT_MIN_MATCHES);
+        let archetypes: Vec<_> = response.cells.iter().map(|c| c.archetype.as_str()).collect();
+        assert_eq!(archetypes, ["Mono Red"]);
+
+        let response = service
+            .get_matchup_matrix(matrix(DEFAULT_MIN_MATCHES + 1))
+            .await
+            .expect("matrix")
+            .into_inner();
+        assert_eq!(response.min_matches, DEFAULT_MIN_MATCHES + 1);
+        assert!(response.cells.is_empty());
+
+        let status = service
+            .get_matchup_matrix(Request::new(GetMatchupMatrixRequest::default()))
+            .await
+            .expect_err("signed out");
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+    }
+}
@@ -1,14 +1,56 @@
-use arenabuddy_core::services::debug_service::{
-    ReportParseErrorsRequest, ReportParseErrorsResponse, debug_service_server::DebugService,
+use arenabuddy_core::{
+    player_log::triage::signature,
+    services::debug_service::{
+        ListParseErrorGroupsRequest, ListParseErrorGroupsResponse, ParseErrorGroup as ParseErrorGroupProto,
+        ParseErrorSample, ReportParseErrorsRequest, ReportParseErrorsResponse, ResolveParseErrorGroupRequest,
+        ResolveParseErrorGroupResponse, debug_service_server::DebugService,
+    },
+};
+use arenabuddy_data::{
+    DebugRepository, MatchDB,
+    debug_repository::{ParseErrorGroup, ParseErrorReport},
 };
-use arenabuddy_data::{DebugRepository, MatchDB};
 use tonic::{Request, Response, Status};
 use tracing::{error, info, instrument};
 
-use crate::auth::UserId;
+use crate::{
+    auth::{Admins, UserId},
+    rate_limit::{Class, PayloadLimits, check_count, check_payload},
+};
+
+const DEFAULT_SAMPLES_PER_GROUP: i32 = 3;
+const MAX_SAMPLES_PER_GROUP: i32 = 20;
 
+#[derive(Clone)]
 pub(crate) struct DebugServiceImpl {
     pub(crate) db: MatchDB,
+    pub(crate) admins: Admins,
+    pub(crate) payload_limits: PayloadLimits,
+}
+
+pub(crate) fn sample_to_proto(report: &ParseErrorReport) -> ParseErrorSample {
+    ParseErrorSample {
+        id: report.id.to_string(),
+        user_id: report.user_id.map(|id| id.to_string()).unwrap_or_default(),
+        raw_json: report.raw_json.clone(),
+        reported_at: report.reported_at.to_rfc3339(),
+    }
+}
+
+fn group_to_proto(group: &ParseErrorGroup, samples: &[ParseErrorReport]) -> ParseErrorGroupProto {
+    ParseErrorGroupProto {
+        fingerprint: group.fingerprint.clone(),
+        message_type: group.message_type.clone(),
+        serde_path: group.serde_path.clone(),
+        reason: group.reason.clone().unwrap_or_default(),
+        count: group.count,
+        affected_users: group.affected_users,
+        first_seen: group.first_seen.to_rfc3339(),
+        last_seen: group.last_seen.to_rfc3339(),
+        resolved_at: group.resolved_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+        reports_since_resolved: group.reports_since_resolved,
+        samples: samples.iter().map(sample_to_proto).collect(),
+    }
 }
 
 #[tonic::async_trait]
@@ -20,13 +62,28 @@ impl DebugService for DebugServiceImpl {
     ) -> Result<Response<ReportParseErrorsResponse>, Status> {
         let user_id = request.extensions().get::<UserId>().map(|u| u.0);
         let errors = request.into_inner().errors;
+        check_count(
+            Class::Upload,
+            "parse errors",
+            errors.len(),
+            self.payload_limits.parse_errors_per_request,
+        )?;
+        for err in &errors {
+            check_payload(
+                Class::Upload,
+                "parse error report",
+                err.raw_json.len(),
+                self.payload_limits.parse_error_bytes,
+            )?;
+        }
 
         let mut accepted = 0i32;
         for err in &errors {
             let reported_at = chrono::DateTime::from_timestamp(err.timestamp, 0).unwrap_or_else(chrono::Utc::now);
+            let signature = signature(&err.raw_json);
 
             self.db
-                .insert_parse_error(user_id, &err.raw_json, reported_at)
+                .insert_parse_error(user_id, &err.raw_json, reported_at, &signature)
                 .await
                 .map_err(|e| {
                     error!("Failed to insert parse error: {e}");
@@ -38,4 +95,192 @@ impl DebugService for DebugServiceImpl {
         info!("Stored {accepted} parse error(s) from user {user_id:?}");
         Ok(Response::new(ReportParseErrorsResponse { accepted }))
     }
+
+    #[instrument(skip(self, request))]
+    async fn list_parse_error_groups(
+        &self,
+        request: Request<ListParseErrorGroupsRequest>,
+    ) -> Result<Response<ListParseErrorGroupsResponse>, Status> {
+        self.admins.require(&request)?;
+        let req = request.into_inner();
+        let samples_per_group = match req.samples_per_group {
+            n if n <= 0 => DEFAULT_SAMPLES_PER_GROUP,
+            n => n.min(MAX_SAMPLES_PER_GROUP),
+        };
+
+        let groups = self
+            .db
+            .list_parse_error_groups(req.include_resolved)
+            .await
+            .map_err(|e| {
+                error!("Failed to list parse error groups: {e}");
+                Status::internal("failed to list parse error groups")
+            })?;
+
+        let mut protos = Vec::with_capacity(groups.len());
+        for group in &groups {
+            let samples = self
+                .db
+                .parse_error_samples(&group.fingerprint, i64::from(samples_per_group))
+                .await
+                .map_err(|e| {
+                    error!("Failed to load parse error samples: {e}");
+                    Status::internal("failed to list parse error groups")
+                })?;
+            protos.push(group_to_proto(group, &samples));
+        }
+
+        Ok(Response::new(ListParseErrorGroupsResponse { groups: protos }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn resolve_parse_error_group(
+        &self,
+        request: Request<ResolveParseErrorGroupRequest>,
+    ) -> Result<Response<ResolveParseErrorGroupResponse>, Status> {
+        let user_id = self.admins.require(&request)?;
+        let req = request.into_inner();
+
+        let found = self
+            .db
+            .set_parse_error_group_resolved(&req.fingerprint, user_id, !req.reopen)
+            .await
+            .map_err(|e| {
+                error!("Failed to resolve parse error group: {e}");
+                Status::internal("failed to resolve parse error group")
+            })?;
+        if !found {
+            return Err(Status::not_found("parse error group not found"));
+        }
+
+        let action = if req.reopen { "reopened" } else { "resolved" };
+        info!("User {user_id} {action} parse error group {}", req.fingerprint);
+        Ok(Response::new(ResolveParseErrorGroupResponse {}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arenabuddy_core::services::debug_service::ParseErrorReport as ParseErrorReportProto;
+    use arenabuddy_data::{AuthRepository, testing::test_db};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::rate_limit::RateLimiter;
+
+    fn authed<T>(user_id: Uuid, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request.extensions_mut().insert(UserId(user_id));
+        request
+    }
+
+    fn report(raw_json: &str) -> ParseErrorReportProto {
+        ParseErrorReportProto {
+            raw_json: raw_json.to_string(),
+            timestamp: 1_700_000_000,
+        }
+    }
+
+    async fn groups(service: &DebugServiceImpl, admin: Uuid, include_resolved: bool) -> Vec<ParseErrorGroupProto> {
+        service
+            .list_parse_error_groups(authed(
+                admin,
+                ListParseErrorGroupsRequest {
+                    include_resolved,
+                    samples_per_group: 0,
+                },
+            ))
+            .await
+            .expect("groups")
+            .into_inner()
+            .groups
+    }
+
+    #[tokio::test]
+    async fn matching_reports_group_and_only_admins_triage_them() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let admin = db
+            .upsert_identity_user("local", "admin", "admin", None)
+            .await
+            .expect("admin");
+        let player = db
+            .upsert_identity_user("local", "player", "player", None)
+            .await
+            .expect("player");
+        let service = DebugServiceImpl {
+            db,
+            admins: [admin].into_iter().collect(),
+            payload_limits: RateLimiter::from_env(false).payload_limits(),
+        };
+
+        // The same decoding failure, formatted differently, plus an unrelated event.
+        let reported = service
+            .report_parse_errors(authed(
+                player,
+                ReportParseErrorsRequest {
+                    errors: vec![
+                        report(r#"{"greToClientEvent":{"greToClientMessages":[]},"timestamp":5}"#),
+                        report(r#"{"greToClientEvent": {"greToClientMessages": []}, "timestamp": 12345}"#),
+                        report(r#"{"foo":1}"#),
+                    ],
+                },
+            ))
+            .await
+            .expect("reported")
+            .into_inner();
+        assert_eq!(reported.accepted, 3);
+
+        let status = service
+            .list_parse_error_groups(authed(player, ListParseErrorGroupsRequest::default()))
+            .await
+            .expect_err("players can't list groups");
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        let listed = groups(&service, admin, false).await;
+        assert_eq!(listed.len(), 2);
+        let group = listed
+            .iter()
+            .find(|g| g.message_type.starts_with("greToClientEvent"))
+            .expect("grouped");
+        assert_eq!((group.count, group.affected_users), (2, 1));
+        assert_eq!(group.samples.len(), 2);
+        assert!(group.samples.iter().all(|s| s.user_id == player.to_string()));
+
+        let resolve = |caller| {
+            service.resolve_parse_error_group(authed(
+                caller,
+                ResolveParseErrorGroupRequest {
+                    fingerprint: group.fingerprint.clone(),
+                    reopen: false,
+                },
+            ))
+        };
+        let status = resolve(player).await.expect_err("players can't resolve groups");
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        resolve(admin).await.expect("resolved");
+
+        let open = groups(&service, admin, false).await;
+        assert_eq!(open.len(), 1);
+        assert_ne!(open[0].fingerprint, group.fingerprint);
+        let all = groups(&service, admin, true).await;
+        let resolved = all
+            .iter()
+            .find(|g| g.fingerprint == group.fingerprint)
+            .expect("resolved groups can still be listed");
+        assert!(!resolved.resolved_at.is_empty());
+
+        let status = service
+            .resolve_parse_error_group(authed(
+                admin,
+                ResolveParseErrorGroupRequest {
+                    fingerprint: "unknown".to_string(),
+                    reopen: false,
+                },
+            ))
+            .await
+            .expect_err("unknown group");
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
 }
@@ -0,0 +1,90 @@
+use arenabuddy_core::{
+    cards::CardsDatabase,
+    models::MTGADraft,
+    services::draft_service::{
+        GetDraftRequest, GetDraftResponse, ListDraftsRequest, ListDraftsResponse, UpsertDraftRequest,
+        UpsertDraftResponse, draft_service_server::DraftService,
+    },
+};
+use arenabuddy_data::{DraftRepository, MatchDB};
+use tonic::{Request, Response, Status};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+use crate::{auth::require_user_id, webhooks::Webhooks};
+
+pub(crate) struct DraftServiceImpl {
+    pub(crate) db: MatchDB,
+    pub(crate) cards: CardsDatabase,
+    pub(crate) webhooks: Webhooks,
+}
+
+#[tonic::async_trait]
+impl DraftService for DraftServiceImpl {
+    #[instrument(skip(self, request))]
+    async fn upsert_draft(
+        &self,
+        request: Request<UpsertDraftRequest>,
+    ) -> Result<Response<UpsertDraftResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let draft_proto = request
+            .into_inner()
+            .draft
+            .ok_or_else(|| Status::invalid_argument("draft is required"))?;
+
+        let draft: MTGADraft = (&draft_proto)
+            .try_into()
+            .map_err(|_| Status::invalid_argument("draft is missing required fields"))?;
+        let draft_id = draft.draft().id();
+
+        let stored = self.db.upsert_user_draft(user_id, &draft).await.map_err(|e| {
+            error!("Failed to upsert draft: {e}");
+            Status::internal("failed to upsert draft")
+        })?;
+        if !stored {
+            return Err(Status::permission_denied("draft belongs to another user"));
+        }
+
+        info!("Upserted draft {draft_id} with {} picks", draft.packs().len());
+        // Drafts are only uploaded once the draft is over.
+        self.webhooks.draft_completed(user_id, &draft);
+        Ok(Response::new(UpsertDraftResponse {}))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn list_drafts(&self, request: Request<ListDraftsRequest>) -> Result<Response<ListDraftsResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let drafts = self.db.list_user_drafts(user_id).await.map_err(|e| {
+            error!("Failed to list drafts: {e}");
+            Status::internal("failed to list drafts")
+        })?;
+
+        Ok(Response::new(ListDraftsResponse {
+            drafts: drafts.iter().map(Into::into).collect(),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn get_draft(&self, request: Request<GetDraftRequest>) -> Result<Response<GetDraftResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let draft_id = request.into_inner().draft_id;
+        if draft_id.parse::<Uuid>().is_err() {
+            return Err(Status::invalid_argument("draft_id must be a valid UUID"));
+        }
+
+        let draft = self
+            .db
+            .get_user_draft(user_id, &draft_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to get draft: {e}");
+                Status::internal("failed to get draft")
+            })?
+            .ok_or_else(|| Status::not_found(format!("draft not found: {draft_id}")))?;
+
+        Ok(Response::new(GetDraftResponse {
+            cards: Some(self.cards.collection_for(draft.cards())),
+            draft: Some((&draft).into()),
+        }))
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tokio::sync::Mutex;
+use tracing::info;
+
+use super::{BoxError, ExportSink};
+
+/// Exports to a local CSV file. The whole file is rewritten on each export, so this is meant
+/// for self-hosting and offline testing rather than large histories.
+pub(crate) struct CsvSink {
+    path: PathBuf,
+    /// Serializes writers; concurrent uploads would otherwise drop each other's rows.
+    lock: Arc<Mutex<()>>,
+}
+
+impl CsvSink {
+    pub(crate) fn new(path: PathBuf, lock: Arc<Mutex<()>>) -> Self {
+        Self { path, lock }
+    }
+}
+
+/// Read the rows of an existing export, rearranged to `headers` by header name so that a
+/// changed column mapping keeps the values it still has columns for.
+fn read_rows(path: &Path, headers: &[String]) -> Result<Vec<Vec<String>>, BoxError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut reader = csv::Reader::from_path(path)?;
+    let old_headers = reader.headers()?.clone();
+    let positions: Vec<Option<usize>> = headers
+        .iter()
+        .map(|header| old_headers.iter().position(|old| old == header))
+        .collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        rows.push(
+            positions
+                .iter()
+                .map(|position| position.and_then(|i| record.get(i)).unwrap_or_default().to_string())
+                .collect(),
+        );
+    }
+    Ok(rows)
+}
+
+fn upsert_file(path: &Path, headers: &[String], new_rows: &[Vec<String>]) -> Result<(), BoxError> {
+    let mut rows = read_rows(path, headers)?;
+    let mut index: HashMap<String, usize> = rows
+        .iter()
+        .enumerate()
+        .filter_map(|(i, row)| Some((row.first()?.clone(), i)))
+        .collect();
+
+    for row in new_rows {
+        let Some(match_id) = row.first() else { continue };
+        if let Some(&i) = index.get(match_id) {
+            rows[i].clone_from(row);
+        } else {
+            index.insert(match_id.clone(), rows.len());
+            rows.push(row.clone());
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Write to a temporary file first so a failed export never leaves a truncated file.
+    let tmp = path.with_extension("csv.tmp");
+    {
+        let mut writer = csv::Writer::from_path(&tmp)?;
+        writer.write_record(headers)?;
+        for row in &rows {
+            writer.write_record(row)?;
+        }
+        writer.flush()?;
+    }
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl ExportSink for CsvSink {
+    async fn upsert_rows(&self, headers: &[String], rows: &[Vec<String>]) -> Result<(), BoxError> {
+        let _guard = self.lock.lock().await;
+        let path = self.path.clone();
+        let headers = headers.to_vec();
+        let rows = rows.to_vec();
+        let count = rows.len();
+        tokio::task::spawn_blocking(move || upsert_file(&path, &headers, &rows)).await??;
+        info!("Exported {count} row(s) to {}", self.path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    #[tokio::test]
+    async fn rows_are_replaced_by_match_id_and_appended_otherwise() {
+        let dir = std::env::temp_dir().join(format!("arenabuddy-csv-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("matches.csv");
+        let sink = CsvSink::new(path.clone(), Arc::new(Mutex::new(())));
+
+        let headers = strings(&["Match ID", "Result"]);
+        sink.upsert_rows(&headers, &[strings(&["m1", "Win"]), strings(&["m2", "Loss"])])
+            .await
+            .expect("first export");
+        sink.upsert_rows(&headers, &[strings(&["m2", "Win"]), strings(&["m3", "Loss"])])
+            .await
+            .expect("second export");
+        assert_eq!(
+            read_rows(&path, &headers).expect("rows"),
+            vec![
+                strings(&["m1", "Win"]),
+                strings(&["m2", "Win"]),
+                strings(&["m3", "Loss"])
+            ]
+        );
+
+        // A new column mapping keeps the values of columns it still has, by header name.
+        let remapped = strings(&["Match ID", "Opponent", "Result"]);
+        sink.upsert_rows(&remapped, &[strings(&["m3", "Bob", "Win"])])
+            .await
+            .expect("remapped export");
+        assert_eq!(
+            read_rows(&path, &remapped).expect("rows"),
+            vec![
+                strings(&["m1", "", "Win"]),
+                strings(&["m2", "", "Win"]),
+                strings(&["m3", "Bob", "Win"])
+            ]
+        );
+        assert!(!path.with_extension("csv.tmp").exists());
+
+        std::fs::remove_dir_all(dir).expect("cleanup");
+    }
+}
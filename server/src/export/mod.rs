@@ -0,0 +1,287 @@
+//! Match export to spreadsheets and files.
+//!
+//! Each user can configure one [`ExportDestination`] with a column mapping. Matches are
+//! exported as they are uploaded, and a resync rewrites all of them; rows are keyed on match
+//! id, so exporting the same match twice replaces its row.
+//!
+//! Spreadsheets are written with the server's service account, which can edit every spreadsheet
+//! shared with it, so a user's spreadsheet is only written once they've proven they can edit it
+//! too, by putting the destination's verification code in it.
+
+mod csv_file;
+mod sheets;
+
+use std::{path::PathBuf, sync::Arc};
+
+use arenabuddy_core::{
+    cards::CardsDatabase,
+    display::export::{ExportColumn, export_headers, export_row, parse_columns},
+};
+use arenabuddy_data::{
+    ArenabuddyRepository, ExportRepository, MatchDB,
+    export_repository::{ExportDestination, ExportSinkKind},
+};
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use self::{csv_file::CsvSink, sheets::SheetsSink};
+use crate::{match_service::load_match_details, metrics::EXPORT_FAILURES};
+
+pub(crate) type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Matches written per [`ExportSink::upsert_rows`] call during a resync.
+const RESYNC_BATCH_SIZE: usize = 100;
+
+/// A place exported matches are written to.
+#[async_trait::async_trait]
+pub(crate) trait ExportSink: Send + Sync {
+    /// Write `headers` and `rows`, replacing existing rows with the same first cell (the match id)
+    /// and appending the rest.
+    async fn upsert_rows(&self, headers: &[String], rows: &[Vec<String>]) -> Result<(), BoxError>;
+}
+
+/// A resolved destination: which sink to open and which columns to write.
+struct ExportTarget {
+    kind: ExportSinkKind,
+    target: String,
+    sheet_name: String,
+    columns: Vec<ExportColumn>,
+}
+
+impl TryFrom<&ExportDestination> for ExportTarget {
+    type Error = BoxError;
+
+    fn try_from(destination: &ExportDestination) -> Result<Self, Self::Error> {
+        let columns = parse_columns(&destination.columns).map_err(|key| format!("unknown export column: {key}"))?;
+        Ok(Self {
+            kind: destination.kind,
+            target: destination.target.clone(),
+            sheet_name: destination.sheet_name.clone(),
+            columns,
+        })
+    }
+}
+
+/// CSV file names become paths under the export directory, so only allow plain names.
+pub(crate) fn is_valid_csv_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= 64 && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[derive(Clone)]
+pub(crate) struct Exporter {
+    db: MatchDB,
+    cards: CardsDatabase,
+    csv_dir: PathBuf,
+    csv_lock: Arc<Mutex<()>>,
+    /// Operator-wide spreadsheet that receives every uploaded match with the default columns.
+    operator_spreadsheet_id: Option<String>,
+}
+
+impl Exporter {
+    pub(crate) fn new(
+        db: MatchDB,
+        cards: CardsDatabase,
+        csv_dir: PathBuf,
+        operator_spreadsheet_id: Option<String>,
+    ) -> Self {
+        Self {
+            db,
+            cards,
+            csv_dir,
+            csv_lock: Arc::new(Mutex::new(())),
+            operator_spreadsheet_id,
+        }
+    }
+
+    async fn open(&self, user_id: Option<Uuid>, target: &ExportTarget) -> Result<Box<dyn ExportSink>, BoxError> {
+        match target.kind {
+            ExportSinkKind::GoogleSheets => Ok(Box::new(
+                SheetsSink::new(target.target.clone(), target.sheet_name.clone()).await?,
+            )),
+            ExportSinkKind::Csv => {
+                if !is_valid_csv_name(&target.target) {
+                    return Err(format!("invalid CSV file name: {}", target.target).into());
+                }
+                let dir = user_id.map_or_else(|| self.csv_dir.clone(), |id| self.csv_dir.join(id.to_string()));
+                let path = dir.join(format!("{}.csv", target.target));
+                Ok(Box::new(CsvSink::new(path, self.csv_lock.clone())))
+            }
+        }
+    }
+
+    async fn export(
+        &self,
+        user_id: Option<Uuid>,
+        target: &ExportTarget,
+        match_ids: &[String],
+    ) -> Result<usize, BoxError> {
+        self.write(user_id, target, match_ids).await.inspect_err(|_| {
+            EXPORT_FAILURES.with_label_values(&[target.kind.as_str()]).inc();
+        })
+    }
+
+    async fn write(
+        &self,
+        user_id: Option<Uuid>,
+        target: &ExportTarget,
+        match_ids: &[String],
+    ) -> Result<usize, BoxError> {
+        let sink = self.open(user_id, target).await?;
+        let headers = export_headers(&target.columns);
+
+        let mut exported = 0;
+        for batch in match_ids.chunks(RESYNC_BATCH_SIZE) {
+            let mut rows = Vec::with_capacity(batch.len());
+            for match_id in batch {
+                match load_match_details(&self.db, &self.cards, match_id, user_id).await {
+                    Ok(details) => rows.push(export_row(&details, &target.columns)),
+                    Err(status) => warn!("Skipping export of match {match_id}: {}", status.message()),
+                }
+            }
+            sink.upsert_rows(&headers, &rows).await?;
+            exported += rows.len();
+        }
+        Ok(exported)
+    }
+
+    /// The user's destination, if it has been verified.
+    async fn user_target(&self, user_id: Uuid) -> Result<Option<ExportTarget>, BoxError> {
+        self.db
+            .get_export_destination(user_id)
+            .await?
+            .as_ref()
+            .filter(|destination| destination.is_verified())
+            .map(ExportTarget::try_from)
+            .transpose()
+    }
+
+    /// Whether the destination's spreadsheet holds its verification code.
+    pub(crate) async fn spreadsheet_has_code(&self, destination: &ExportDestination) -> Result<bool, BoxError> {
+        let Some(code) = destination.verification_code.as_deref() else {
+            return Ok(false);
+        };
+        SheetsSink::new(destination.target.clone(), destination.sheet_name.clone())
+            .await?
+            .has_verification_code(code)
+            .await
+    }
+
+    /// Export a newly uploaded match to the uploader's destination and the operator
+    /// spreadsheet, if configured. Runs in the background.
+    pub(crate) fn match_uploaded(&self, user_id: Option<Uuid>, match_id: String) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            let match_ids = [match_id];
+
+            if let Some(spreadsheet_id) = this.operator_spreadsheet_id.clone() {
+                let target = ExportTarget {
+                    kind: ExportSinkKind::GoogleSheets,
+                    target: spreadsheet_id,
+                    sheet_name: "Matches".to_string(),
+                    columns: ExportColumn::DEFAULT.to_vec(),
+                };
+                if let Err(e) = this.export(user_id, &target, &match_ids).await {
+                    error!("Operator sheet export failed for match {}: {e}", match_ids[0]);
+                }
+            }
+
+            let Some(user_id) = user_id else { return };
+            let result = match this.user_target(user_id).await {
+                Ok(Some(target)) => this.export(Some(user_id), &target, &match_ids).await.map(|_| ()),
+                Ok(None) => Ok(()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                error!("Export failed for match {} of user {user_id}: {e}", match_ids[0]);
+            }
+        });
+    }
+
+    /// Remove the CSV files exported for `user_id`, after its account is deleted.
+    pub(crate) async fn remove_user_files(&self, user_id: Uuid) {
+        let dir = self.csv_dir.join(user_id.to_string());
+        match tokio::fs::remove_dir_all(&dir).await {
+            Ok(()) => info!("Removed exports of user {user_id}"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => error!("Failed to remove {}: {e}", dir.display()),
+        }
+    }
+
+    /// Re-export every match of `user_id`, replacing existing rows. Returns the number of
+    /// matches written, or `None` if the user has no verified destination.
+    pub(crate) async fn resync(&self, user_id: Uuid) -> Result<Option<usize>, BoxError> {
+        let Some(target) = self.user_target(user_id).await? else {
+            return Ok(None);
+        };
+
+        let match_ids: Vec<String> = self
+            .db
+            .list_matches(Some(user_id))
+            .await?
+            .iter()
+            .map(|m| m.id().to_string())
+            .collect();
+        let exported = self.export(Some(user_id), &target, &match_ids).await?;
+        info!("Resynced {exported} match(es) for user {user_id}");
+        Ok(Some(exported))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn destination(columns: &[&str]) -> ExportDestination {
+        ExportDestination {
+            user_id: Uuid::new_v4(),
+            kind: ExportSinkKind::Csv,
+            target: "matches".to_string(),
+            sheet_name: "Matches".to_string(),
+            columns: columns.iter().map(ToString::to_string).collect(),
+            updated_at: chrono::Utc::now(),
+            verification_code: None,
+            verified_at: None,
+        }
+    }
+
+    fn columns(keys: &[&str]) -> Result<Vec<ExportColumn>, String> {
+        ExportTarget::try_from(&destination(keys))
+            .map(|target| target.columns)
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn columns_keep_their_order_after_the_match_id() {
+        assert_eq!(columns(&[]), Ok(ExportColumn::DEFAULT.to_vec()));
+        assert_eq!(
+            columns(&["opponent_name", "format", "match_id", "won_match"]),
+            Ok(vec![
+                ExportColumn::MatchId,
+                ExportColumn::OpponentName,
+                ExportColumn::Format,
+                ExportColumn::WonMatch,
+            ])
+        );
+    }
+
+    #[test]
+    fn repeated_columns_are_written_once() {
+        assert_eq!(
+            columns(&["format", "won_match", "format", "match_id", "match_id"]),
+            Ok(vec![
+                ExportColumn::MatchId,
+                ExportColumn::Format,
+                ExportColumn::WonMatch
+            ])
+        );
+    }
+
+    #[test]
+    fn unknown_columns_are_rejected() {
+        assert_eq!(
+            columns(&["format", "deck_colour", "nope"]),
+            Err("unknown export column: deck_colour".to_string())
+        );
+    }
+}
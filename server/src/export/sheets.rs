@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use google_sheets4::{
+    Sheets,
+    api::{BatchUpdateValuesRequest, ValueRange},
+    hyper_rustls,
+    hyper_util::{
+        client::legacy::{Client, connect::HttpConnector},
+        rt::TokioExecutor,
+    },
+};
+use serde_json::json;
+use tracing::info;
+
+use super::{BoxError, ExportSink};
+
+/// Exports to a worksheet of a Google spreadsheet shared with the server's service account.
+pub(crate) struct SheetsSink {
+    sheets: Sheets<hyper_rustls::HttpsConnector<HttpConnector>>,
+    spreadsheet_id: String,
+    sheet_name: String,
+}
+
+/// An A1-notation range on `sheet_name`, e.g. `'My Matches'!A:A`. The name is always quoted, with
+/// embedded quotes doubled, so names with spaces, `!` or `'` address the right worksheet.
+fn a1_range(sheet_name: &str, cells: &str) -> String {
+    format!("'{}'!{cells}", sheet_name.replace('\'', "''"))
+}
+
+fn to_values(row: &[String]) -> Vec<serde_json::Value> {
+    row.iter().map(|cell| json!(cell)).collect()
+}
+
+impl SheetsSink {
+    pub(crate) async fn new(spreadsheet_id: String, sheet_name: String) -> Result<Self, BoxError> {
+        let key_path = std::env::var("GOOGLE_SERVICE_ACCOUNT_KEY")
+            .unwrap_or_else(|_| "./keys/arenabuddy-7632406ac057.json".to_string());
+        let key = yup_oauth2::read_service_account_key(key_path).await?;
+        let authenticator = yup_oauth2::ServiceAccountAuthenticator::builder(key).build().await?;
+        let client = Client::builder(TokioExecutor::new()).build(
+            hyper_rustls::HttpsConnectorBuilder::new()
+                .with_native_roots()?
+                .https_only()
+                .enable_http1()
+                .enable_http2()
+                .build(),
+        );
+
+        let sheets = Sheets::new(client, authenticator);
+        Ok(SheetsSink {
+            sheets,
+            spreadsheet_id,
+            sheet_name,
+        })
+    }
+
+    /// Whether cell A1 of the worksheet holds `code`, proving whoever set it can edit the
+    /// spreadsheet.
+    pub(crate) async fn has_verification_code(&self, code: &str) -> Result<bool, BoxError> {
+        let (_, value_range) = self
+            .sheets
+            .spreadsheets()
+            .values_get(&self.spreadsheet_id, &a1_range(&self.sheet_name, "A1"))
+            .doit()
+            .await?;
+
+        let cell = value_range
+            .values
+            .as_ref()
+            .and_then(|rows| rows.first()?.first()?.as_str().map(|cell| cell.trim().to_string()));
+        Ok(cell.as_deref() == Some(code))
+    }
+
+    /// Row numbers (1-based) of the match ids already in column A.
+    async fn existing_rows(&self) -> Result<HashMap<String, usize>, BoxError> {
+        let range = a1_range(&self.sheet_name, "A:A");
+        let (_, value_range) = self
+            .sheets
+            .spreadsheets()
+            .values_get(&self.spreadsheet_id, &range)
+            .doit()
+            .await?;
+
+        Ok(value_range
+            .values
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(index, row)| Some((row.first()?.as_str()?.to_string(), index + 1)))
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl ExportSink for SheetsSink {
+    async fn upsert_rows(&self, headers: &[String], rows: &[Vec<String>]) -> Result<(), BoxError> {
+        let existing = self.existing_rows().await?;
+
+        // Headers and rows that already exist are rewritten in place; new rows are appended.
+        let mut updates = vec![ValueRange {
+            range: Some(a1_range(&self.sheet_name, "A1")),
+            major_dimension: Some("ROWS".to_string()),
+            values: Some(vec![to_values(headers)]),
+        }];
+        let mut appends = Vec::new();
+        for row in rows {
+            match row.first().and_then(|match_id| existing.get(match_id)) {
+                Some(row_number) => updates.push(ValueRange {
+                    range: Some(a1_range(&self.sheet_name, &format!("A{row_number}"))),
+                    major_dimension: Some("ROWS".to_string()),
+                    values: Some(vec![to_values(row)]),
+                }),
+                None => appends.push(to_values(row)),
+            }
+        }
+
+        let replaced = updates.len() - 1;
+        self.sheets
+            .spreadsheets()
+            .values_batch_update(
+                BatchUpdateValuesRequest {
+                    data: Some(updates),
+                    value_input_option: Some("USER_ENTERED".to_string()),
+                    ..Default::default()
+                },
+                &self.spreadsheet_id,
+            )
+            .doit()
+            .await?;
+
+        let appended = appends.len();
+        if !appends.is_empty() {
+            let range = a1_range(&self.sheet_name, "A:A");
+            let value_range = ValueRange {
+                range: Some(range.clone()),
+                major_dimension: Some("ROWS".to_string()),
+                values: Some(appends),
+            };
+            self.sheets
+                .spreadsheets()
+                .values_append(value_range, &self.spreadsheet_id, &range)
+                .value_input_option("USER_ENTERED")
+                .insert_data_option("INSERT_ROWS")
+                .doit()
+                .await?;
+        }
+
+        info!(
+            "Exported to sheet {}: {replaced} row(s) replaced, {appended} appended",
+            self.sheet_name
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::a1_range;
+
+    #[test]
+    fn sheet_names_are_quoted_in_ranges() {
+        assert_eq!(a1_range("Matches", "A:A"), "'Matches'!A:A");
+        assert_eq!(a1_range("My Matches!", "A1"), "'My Matches!'!A1");
+        assert_eq!(a1_range("Bob's 'Deck'", "A2"), "'Bob''s ''Deck'''!A2");
+    }
+}
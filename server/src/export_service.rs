@@ -0,0 +1,246 @@
+use arenabuddy_core::{
+    display::export::{ExportColumn, parse_columns},
+    services::export_service::{
+        DeleteExportDestinationRequest, DeleteExportDestinationResponse, ExportColumnInfo,
+        ExportDestination as ExportDestinationProto, ExportSinkKind as ExportSinkKindProto,
+        GetExportDestinationRequest, GetExportDestinationResponse, ListExportColumnsRequest, ListExportColumnsResponse,
+        ResyncExportRequest, ResyncExportResponse, SetExportDestinationRequest, SetExportDestinationResponse,
+        VerifyExportDestinationRequest, VerifyExportDestinationResponse, export_service_server::ExportService,
+    },
+};
+use arenabuddy_data::{
+    ExportRepository, MatchDB,
+    export_repository::{ExportDestination, ExportSinkKind},
+};
+use tonic::{Request, Response, Status};
+use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    auth::{generate_opaque_token, require_user_id},
+    export::{Exporter, is_valid_csv_name},
+};
+
+const DEFAULT_SHEET_NAME: &str = "Matches";
+
+pub(crate) struct ExportServiceImpl {
+    pub(crate) db: MatchDB,
+    pub(crate) exporter: Exporter,
+}
+
+fn destination_to_proto(destination: &ExportDestination) -> ExportDestinationProto {
+    let kind = match destination.kind {
+        ExportSinkKind::GoogleSheets => ExportSinkKindProto::GoogleSheets,
+        ExportSinkKind::Csv => ExportSinkKindProto::Csv,
+    };
+    ExportDestinationProto {
+        kind: kind as i32,
+        target: destination.target.clone(),
+        sheet_name: destination.sheet_name.clone(),
+        columns: destination.columns.clone(),
+        updated_at: destination.updated_at.to_rfc3339(),
+        verified: destination.is_verified(),
+        verification_code: destination.verification_code.clone().unwrap_or_default(),
+    }
+}
+
+impl ExportServiceImpl {
+    async fn destination(&self, user_id: Uuid) -> Result<ExportDestination, Status> {
+        self.db
+            .get_export_destination(user_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to get export destination: {e}");
+                Status::internal("failed to get export destination")
+            })?
+            .ok_or_else(|| Status::failed_precondition("no export destination configured"))
+    }
+}
+
+#[tonic::async_trait]
+impl ExportService for ExportServiceImpl {
+    #[instrument(skip(self, request))]
+    async fn set_export_destination(
+        &self,
+        request: Request<SetExportDestinationRequest>,
+    ) -> Result<Response<SetExportDestinationResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let proto = request
+            .into_inner()
+            .destination
+            .ok_or_else(|| Status::invalid_argument("destination is required"))?;
+
+        let kind = match proto.kind() {
+            ExportSinkKindProto::GoogleSheets => ExportSinkKind::GoogleSheets,
+            ExportSinkKindProto::Csv => ExportSinkKind::Csv,
+            ExportSinkKindProto::Unspecified => return Err(Status::invalid_argument("kind is required")),
+        };
+        let target = proto.target.trim().to_string();
+        if target.is_empty() {
+            return Err(Status::invalid_argument("target is required"));
+        }
+        if kind == ExportSinkKind::Csv && !is_valid_csv_name(&target) {
+            return Err(Status::invalid_argument(
+                "CSV file names may only contain letters, digits, '-' and '_'",
+            ));
+        }
+        // Store the normalized mapping so what's saved is what gets written.
+        let columns = parse_columns(&proto.columns)
+            .map_err(|key| Status::invalid_argument(format!("unknown export column: {key}")))?;
+        let sheet_name = match proto.sheet_name.trim() {
+            "" => DEFAULT_SHEET_NAME.to_string(),
+            name => name.to_string(),
+        };
+
+        // A new spreadsheet must be proven editable before anything is written to it; the
+        // repository keeps an existing verification while the spreadsheet stays the same.
+        let (verification_code, verified_at) = match kind {
+            ExportSinkKind::GoogleSheets => (Some(generate_opaque_token()), None),
+            ExportSinkKind::Csv => (None, Some(chrono::Utc::now())),
+        };
+        let destination = self
+            .db
+            .set_export_destination(&ExportDestination {
+                user_id,
+                kind,
+                target,
+                sheet_name,
+                columns: columns.iter().map(|column| column.key().to_string()).collect(),
+                updated_at: chrono::Utc::now(),
+                verification_code,
+                verified_at,
+            })
+            .await
+            .map_err(|e| {
+                error!("Failed to set export destination: {e}");
+                Status::internal("failed to set export destination")
+            })?;
+
+        info!("User {user_id} set export destination ({})", kind.as_str());
+        Ok(Response::new(SetExportDestinationResponse {
+            destination: Some(destination_to_proto(&destination)),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn get_export_destination(
+        &self,
+        request: Request<GetExportDestinationRequest>,
+    ) -> Result<Response<GetExportDestinationResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let destination = self.db.get_export_destination(user_id).await.map_err(|e| {
+            error!("Failed to get export destination: {e}");
+            Status::internal("failed to get export destination")
+        })?;
+
+        Ok(Response::new(GetExportDestinationResponse {
+            destination: destination.as_ref().map(destination_to_proto),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn delete_export_destination(
+        &self,
+        request: Request<DeleteExportDestinationRequest>,
+    ) -> Result<Response<DeleteExportDestinationResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let deleted = self.db.delete_export_destination(user_id).await.map_err(|e| {
+            error!("Failed to delete export destination: {e}");
+            Status::internal("failed to delete export destination")
+        })?;
+        if !deleted {
+            return Err(Status::not_found("no export destination configured"));
+        }
+
+        Ok(Response::new(DeleteExportDestinationResponse {}))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn verify_export_destination(
+        &self,
+        request: Request<VerifyExportDestinationRequest>,
+    ) -> Result<Response<VerifyExportDestinationResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let destination = self.destination(user_id).await?;
+        if destination.is_verified() {
+            return Ok(Response::new(VerifyExportDestinationResponse {
+                destination: Some(destination_to_proto(&destination)),
+            }));
+        }
+
+        let found = self.exporter.spreadsheet_has_code(&destination).await.map_err(|e| {
+            warn!("Could not read spreadsheet of user {user_id}: {e}");
+            Status::failed_precondition("could not read the spreadsheet; share it with the server's service account")
+        })?;
+        if !found {
+            return Err(Status::failed_precondition(format!(
+                "put the verification code in cell A1 of the '{}' worksheet and try again",
+                destination.sheet_name
+            )));
+        }
+
+        let code = destination.verification_code.as_deref().unwrap_or_default();
+        let verified = self
+            .db
+            .mark_export_destination_verified(user_id, code)
+            .await
+            .map_err(|e| {
+                error!("Failed to verify export destination: {e}");
+                Status::internal("failed to verify export destination")
+            })?;
+        if !verified {
+            return Err(Status::aborted(
+                "the export destination changed while it was being verified",
+            ));
+        }
+
+        info!("User {user_id} verified their export spreadsheet");
+        let destination = self.destination(user_id).await?;
+        Ok(Response::new(VerifyExportDestinationResponse {
+            destination: Some(destination_to_proto(&destination)),
+        }))
+    }
+
+    #[instrument(skip(self, _request))]
+    async fn list_export_columns(
+        &self,
+        _request: Request<ListExportColumnsRequest>,
+    ) -> Result<Response<ListExportColumnsResponse>, Status> {
+        let columns = ExportColumn::ALL
+            .into_iter()
+            .map(|column| ExportColumnInfo {
+                key: column.key().to_string(),
+                header: column.header().to_string(),
+                is_default: ExportColumn::DEFAULT.contains(&column),
+            })
+            .collect();
+
+        Ok(Response::new(ListExportColumnsResponse { columns }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn resync_export(
+        &self,
+        request: Request<ResyncExportRequest>,
+    ) -> Result<Response<ResyncExportResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        if !self.destination(user_id).await?.is_verified() {
+            return Err(Status::failed_precondition(
+                "verify the export spreadsheet before exporting to it",
+            ));
+        }
+        let exported = self
+            .exporter
+            .resync(user_id)
+            .await
+            .map_err(|e| {
+                error!("Export resync failed for user {user_id}: {e}");
+                Status::internal("export resync failed")
+            })?
+            .ok_or_else(|| Status::failed_precondition("no export destination configured"))?;
+
+        Ok(Response::new(ResyncExportResponse {
+            exported: i32::try_from(exported).unwrap_or(i32::MAX),
+        }))
+    }
+}
@@ -0,0 +1,43 @@
+//! The standard `grpc.health.v1.Health` service, backed by a database readiness check.
+
+use std::time::Duration;
+
+use arenabuddy_data::MatchDB;
+use tonic_health::{ServingStatus, server::HealthReporter};
+use tracing::{info, warn};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Ping the database every [`CHECK_INTERVAL`] and report the server (the empty service name)
+/// as serving only while it answers. Every service needs the database, so none are reported
+/// separately.
+pub(crate) fn spawn_readiness_check(db: MatchDB, reporter: HealthReporter) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut serving = true;
+        loop {
+            interval.tick().await;
+            let ready = match db.ping().await {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("Database readiness check failed: {e}");
+                    false
+                }
+            };
+            if ready != serving {
+                info!(
+                    "Health status changed to {}",
+                    if ready { "serving" } else { "not serving" }
+                );
+                serving = ready;
+            }
+            let status = if ready {
+                ServingStatus::Serving
+            } else {
+                ServingStatus::NotServing
+            };
+            reporter.set_service_status("", status).await;
+        }
+    });
+}
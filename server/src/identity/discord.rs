@@ -0,0 +1,131 @@
+use serde::Deserialize;
+use tonic::Status;
+use tracing::{error, info};
+
+use super::{AuthorizationEndpoint, Credentials, ExternalIdentity, IdentityProvider};
+
+#[derive(Debug, Deserialize)]
+struct DiscordTokenResponse {
+    access_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscordUser {
+    id: String,
+    username: String,
+    avatar: Option<String>,
+}
+
+/// Discord `OAuth2` (authorization code + PKCE).
+pub struct DiscordProvider {
+    client_id: String,
+    client_secret: String,
+    http: reqwest::Client,
+}
+
+impl DiscordProvider {
+    pub fn new(client_id: String, client_secret: String, http: reqwest::Client) -> Self {
+        Self {
+            client_id,
+            client_secret,
+            http,
+        }
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        redirect_uri: &str,
+    ) -> Result<DiscordTokenResponse, Status> {
+        let params = [
+            ("client_id", self.client_id.as_str()),
+            ("client_secret", self.client_secret.as_str()),
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", code_verifier),
+        ];
+
+        let resp = self
+            .http
+            .post("https://discord.com/api/v10/oauth2/token")
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Discord token exchange HTTP error: {e}");
+                Status::internal("failed to contact Discord")
+            })?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            error!("Discord token exchange failed: {status} {body}");
+            return Err(Status::unauthenticated("Discord authentication failed"));
+        }
+
+        resp.json::<DiscordTokenResponse>().await.map_err(|e| {
+            error!("Failed to parse Discord token response: {e}");
+            Status::internal("failed to parse Discord response")
+        })
+    }
+
+    async fn get_user(&self, access_token: &str) -> Result<DiscordUser, Status> {
+        let resp = self
+            .http
+            .get("https://discord.com/api/v10/users/@me")
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Discord user fetch HTTP error: {e}");
+                Status::internal("failed to contact Discord")
+            })?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            error!("Discord user fetch failed: {status} {body}");
+            return Err(Status::internal("failed to fetch Discord user"));
+        }
+
+        resp.json::<DiscordUser>().await.map_err(|e| {
+            error!("Failed to parse Discord user response: {e}");
+            Status::internal("failed to parse Discord user")
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl IdentityProvider for DiscordProvider {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    fn authorization_endpoint(&self) -> Option<AuthorizationEndpoint<'_>> {
+        Some(AuthorizationEndpoint {
+            url: "https://discord.com/oauth2/authorize",
+            client_id: &self.client_id,
+            scope: "identify",
+        })
+    }
+
+    async fn authenticate(&self, credentials: &Credentials) -> Result<ExternalIdentity, Status> {
+        let (code, code_verifier, redirect_uri) = credentials.authorization_code()?;
+        let token = self.exchange_code(code, code_verifier, redirect_uri).await?;
+        let user = self.get_user(&token.access_token).await?;
+        info!("Discord user authenticated: {} ({})", user.username, user.id);
+
+        let avatar_url = user
+            .avatar
+            .as_ref()
+            .map(|hash| format!("https://cdn.discordapp.com/avatars/{}/{hash}.png", user.id));
+
+        Ok(ExternalIdentity {
+            subject: user.id,
+            username: user.username,
+            avatar_url,
+        })
+    }
+}
@@ -0,0 +1,232 @@
+use arenabuddy_data::{AuthRepository, MatchDB};
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::{self, SaltString},
+};
+use tonic::Status;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use super::{Credentials, ExternalIdentity, IdentityProvider};
+
+const MIN_USERNAME_LEN: usize = 3;
+const MAX_USERNAME_LEN: usize = 32;
+const MIN_PASSWORD_LEN: usize = 8;
+const MAX_PASSWORD_LEN: usize = 128;
+
+/// Username/password accounts stored in `local_credential`, hashed with Argon2id.
+///
+/// The normalised (lowercase) username doubles as the identity subject.
+pub struct LocalProvider {
+    db: MatchDB,
+    allow_registration: bool,
+}
+
+impl LocalProvider {
+    pub fn new(db: MatchDB, allow_registration: bool) -> Self {
+        Self { db, allow_registration }
+    }
+
+    /// Create a local login and return the user it belongs to: `link_to` when set, otherwise a new
+    /// account. Linking is allowed even when open registration is disabled.
+    pub async fn register(&self, username: &str, password: &str, link_to: Option<Uuid>) -> Result<Uuid, Status> {
+        if link_to.is_none() && !self.allow_registration {
+            return Err(Status::permission_denied("local account registration is disabled"));
+        }
+
+        let subject = normalize_username(username)?;
+        validate_password(password)?;
+
+        let password = password.to_string();
+        let hash = tokio::task::spawn_blocking(move || hash_password(&password))
+            .await
+            .map_err(|e| {
+                error!("Password hashing task failed: {e}");
+                Status::internal("failed to hash password")
+            })?
+            .map_err(|e| {
+                error!("Failed to hash password: {e}");
+                Status::internal("failed to hash password")
+            })?;
+
+        let user_id = self
+            .db
+            .create_local_credential(link_to, &subject, username.trim(), &hash)
+            .await
+            .map_err(|e| {
+                error!("Failed to store local credential: {e}");
+                Status::internal("failed to create account")
+            })?
+            .ok_or_else(|| match link_to {
+                Some(_) => Status::already_exists("username is taken or the account already has a local login"),
+                None => Status::already_exists("username is already taken"),
+            })?;
+
+        info!("Registered local account {subject}");
+        Ok(user_id)
+    }
+}
+
+#[tonic::async_trait]
+impl IdentityProvider for LocalProvider {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn authenticate(&self, credentials: &Credentials) -> Result<ExternalIdentity, Status> {
+        let Credentials::Password { username, password } = credentials else {
+            return Err(Status::invalid_argument(
+                "local provider expects a username and password",
+            ));
+        };
+        if username.is_empty() || password.is_empty() {
+            return Err(Status::invalid_argument("username and password are required"));
+        }
+
+        let invalid = || Status::unauthenticated("invalid username or password");
+        let subject = normalize_username(username).map_err(|_| invalid())?;
+
+        let hash = self
+            .db
+            .get_local_password_hash(&subject)
+            .await
+            .map_err(|e| {
+                error!("Failed to look up local credential: {e}");
+                Status::internal("failed to authenticate")
+            })?
+            .ok_or_else(invalid)?;
+
+        let password = password.clone();
+        let verified = tokio::task::spawn_blocking(move || verify_password(&password, &hash))
+            .await
+            .map_err(|e| {
+                error!("Password verification task failed: {e}");
+                Status::internal("failed to authenticate")
+            })?;
+        if !verified {
+            info!("Rejected local login for {subject}");
+            return Err(invalid());
+        }
+
+        Ok(ExternalIdentity {
+            username: username.trim().to_string(),
+            subject,
+            avatar_url: None,
+        })
+    }
+}
+
+fn normalize_username(username: &str) -> Result<String, Status> {
+    let username = username.trim();
+    if !(MIN_USERNAME_LEN..=MAX_USERNAME_LEN).contains(&username.len()) {
+        return Err(Status::invalid_argument(format!(
+            "username must be {MIN_USERNAME_LEN}-{MAX_USERNAME_LEN} characters"
+        )));
+    }
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(Status::invalid_argument(
+            "username may only contain letters, digits, '_', '-' and '.'",
+        ));
+    }
+    Ok(username.to_ascii_lowercase())
+}
+
+fn validate_password(password: &str) -> Result<(), Status> {
+    if !(MIN_PASSWORD_LEN..=MAX_PASSWORD_LEN).contains(&password.chars().count()) {
+        return Err(Status::invalid_argument(format!(
+            "password must be {MIN_PASSWORD_LEN}-{MAX_PASSWORD_LEN} characters"
+        )));
+    }
+    Ok(())
+}
+
+fn hash_password(password: &str) -> Result<String, password_hash::Error> {
+    let mut salt = [0u8; 16];
+    rand::fill(&mut salt);
+    let salt = SaltString::encode_b64(&salt)?;
+    Ok(Argon2::default().hash_password(password.as_bytes(), &salt)?.to_string())
+}
+
+fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use arenabuddy_data::testing::test_db;
+    use tonic::Code;
+
+    use super::*;
+
+    fn password(username: &str, password: &str) -> Credentials {
+        Credentials::Password {
+            username: username.to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn registers_and_logs_in() {
+        let Some(db) = test_db().await else { return };
+        let local = LocalProvider::new(db, true);
+
+        local.register("Alice", "correct horse", None).await.expect("registers");
+
+        let identity = local
+            .authenticate(&password("alice", "correct horse"))
+            .await
+            .expect("logs in");
+        assert_eq!(identity.subject, "alice");
+        let err = local
+            .authenticate(&password("alice", "wrong horse"))
+            .await
+            .expect_err("wrong password");
+        assert_eq!(err.code(), Code::Unauthenticated);
+    }
+
+    #[tokio::test]
+    async fn rejects_duplicate_usernames() {
+        let Some(db) = test_db().await else { return };
+        let local = LocalProvider::new(db, true);
+
+        local.register("bob", "correct horse", None).await.expect("registers");
+        let err = local
+            .register("BOB", "another horse", None)
+            .await
+            .expect_err("duplicate");
+        assert_eq!(err.code(), Code::AlreadyExists);
+    }
+
+    #[tokio::test]
+    async fn links_to_an_existing_account() {
+        let Some(db) = test_db().await else { return };
+        let user_id = db
+            .upsert_identity_user("discord", "1234", "Carol", None)
+            .await
+            .expect("discord user");
+        // Linking works even where open registration is disabled.
+        let local = LocalProvider::new(db.clone(), false);
+
+        let linked = local
+            .register("carol", "correct horse", Some(user_id))
+            .await
+            .expect("links");
+        assert_eq!(linked, user_id);
+        let identities = db.list_identities(user_id).await.expect("identities");
+        assert_eq!(identities.len(), 2);
+
+        let err = local
+            .register("carol2", "correct horse", Some(user_id))
+            .await
+            .expect_err("one local login per account");
+        assert_eq!(err.code(), Code::AlreadyExists);
+        let err = local
+            .register("dave", "correct horse", None)
+            .await
+            .expect_err("registration disabled");
+        assert_eq!(err.code(), Code::PermissionDenied);
+    }
+}
@@ -0,0 +1,154 @@
+//! Pluggable identity providers.
+//!
+//! [`crate::auth::AuthServiceImpl`] no longer talks to Discord directly; it resolves a provider by
+//! name from [`IdentityProviders`] and asks it to turn client credentials into an
+//! [`ExternalIdentity`]. The identity is then mapped to an `app_user` through the
+//! `user_identity` table, so one account can have several linked logins.
+
+mod discord;
+mod local;
+mod oidc;
+
+use std::{collections::BTreeMap, sync::Arc};
+
+pub use discord::DiscordProvider;
+pub use local::LocalProvider;
+pub use oidc::{OidcConfig, OidcProvider};
+use tonic::Status;
+
+/// Provider used when a client does not name one (pre-multi-provider desktop builds).
+pub const DEFAULT_PROVIDER: &str = "discord";
+
+/// Credentials presented by a client during login or identity linking.
+pub enum Credentials {
+    /// `OAuth2` authorization code obtained with PKCE.
+    AuthorizationCode {
+        code: String,
+        code_verifier: String,
+        redirect_uri: String,
+    },
+    /// Username and password for the local provider.
+    Password { username: String, password: String },
+}
+
+impl Credentials {
+    /// Build credentials from the flat request fields shared by `ExchangeToken` and `LinkIdentity`.
+    /// A username or password selects password credentials; otherwise an authorization code is assumed.
+    pub fn from_request_fields(
+        authorization_code: String,
+        code_verifier: String,
+        redirect_uri: String,
+        username: String,
+        password: String,
+    ) -> Self {
+        if username.is_empty() && password.is_empty() {
+            Self::AuthorizationCode {
+                code: authorization_code,
+                code_verifier,
+                redirect_uri,
+            }
+        } else {
+            Self::Password { username, password }
+        }
+    }
+
+    /// Borrow the authorization code fields, rejecting password credentials and empty fields.
+    fn authorization_code(&self) -> Result<(&str, &str, &str), Status> {
+        let Self::AuthorizationCode {
+            code,
+            code_verifier,
+            redirect_uri,
+        } = self
+        else {
+            return Err(Status::invalid_argument("provider expects an authorization code"));
+        };
+        if code.is_empty() {
+            return Err(Status::invalid_argument("authorization_code is required"));
+        }
+        if code_verifier.is_empty() {
+            return Err(Status::invalid_argument("code_verifier is required"));
+        }
+        if redirect_uri.is_empty() {
+            return Err(Status::invalid_argument("redirect_uri is required"));
+        }
+        Ok((code, code_verifier, redirect_uri))
+    }
+}
+
+/// A user as reported by an identity provider.
+#[derive(Debug, Clone)]
+pub struct ExternalIdentity {
+    /// Stable, provider-scoped identifier (Discord snowflake, OIDC `sub`, local username).
+    pub subject: String,
+    pub username: String,
+    pub avatar_url: Option<String>,
+}
+
+/// Where a browser client sends the user to obtain an authorization code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthorizationEndpoint<'a> {
+    pub url: &'a str,
+    pub client_id: &'a str,
+    pub scope: &'a str,
+}
+
+#[tonic::async_trait]
+pub trait IdentityProvider: Send + Sync {
+    /// Name clients use to select this provider, stored as `user_identity.provider`.
+    fn name(&self) -> &str;
+
+    /// The authorization endpoint for redirect-based providers; `None` for password providers.
+    fn authorization_endpoint(&self) -> Option<AuthorizationEndpoint<'_>> {
+        None
+    }
+
+    /// Verify `credentials` with the provider and return the identity they belong to.
+    async fn authenticate(&self, credentials: &Credentials) -> Result<ExternalIdentity, Status>;
+}
+
+/// Registry of the identity providers enabled on this server.
+#[derive(Clone, Default)]
+pub struct IdentityProviders {
+    providers: BTreeMap<String, Arc<dyn IdentityProvider>>,
+    local: Option<Arc<LocalProvider>>,
+}
+
+impl IdentityProviders {
+    pub fn register(&mut self, provider: impl IdentityProvider + 'static) {
+        self.providers.insert(provider.name().to_string(), Arc::new(provider));
+    }
+
+    /// Register the local username/password provider. It is kept separately as well so
+    /// `RegisterLocalUser` can create accounts through it.
+    pub fn register_local(&mut self, provider: LocalProvider) {
+        let provider = Arc::new(provider);
+        self.providers.insert(
+            provider.name().to_string(),
+            provider.clone() as Arc<dyn IdentityProvider>,
+        );
+        self.local = Some(provider);
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Arc<dyn IdentityProvider>, Status> {
+        let name = if name.is_empty() { DEFAULT_PROVIDER } else { name };
+        self.providers
+            .get(name)
+            .ok_or_else(|| Status::invalid_argument(format!("identity provider '{name}' is not enabled")))
+    }
+
+    pub fn local(&self) -> Option<&LocalProvider> {
+        self.local.as_deref()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.providers.keys().map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn IdentityProvider> {
+        self.providers.values().map(AsRef::as_ref)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
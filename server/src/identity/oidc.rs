@@ -0,0 +1,175 @@
+use serde::Deserialize;
+use tonic::Status;
+use tracing::{error, info};
+
+use super::{AuthorizationEndpoint, Credentials, ExternalIdentity, IdentityProvider};
+
+/// Settings for a generic `OpenID` Connect provider (Keycloak, Authentik, Google, ...).
+pub struct OidcConfig {
+    /// Name clients use to select this provider. Defaults to `oidc`.
+    pub name: String,
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Omit for public clients that rely on PKCE alone.
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[expect(clippy::struct_field_names)]
+struct Discovery {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserInfo {
+    sub: String,
+    preferred_username: Option<String>,
+    name: Option<String>,
+    email: Option<String>,
+    picture: Option<String>,
+}
+
+/// Generic OIDC provider using the authorization code flow with PKCE. Endpoints are
+/// resolved once at startup from the issuer's discovery document, and the user is read
+/// from the userinfo endpoint with the access token obtained from the exchange.
+pub struct OidcProvider {
+    name: String,
+    client_id: String,
+    client_secret: Option<String>,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+    http: reqwest::Client,
+}
+
+impl OidcProvider {
+    /// Fetch the issuer's discovery document and build the provider.
+    pub async fn discover(config: OidcConfig, http: reqwest::Client) -> Result<Self, reqwest::Error> {
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            config.issuer_url.trim_end_matches('/')
+        );
+        let discovery: Discovery = http.get(&url).send().await?.error_for_status()?.json().await?;
+        info!(
+            "OIDC provider '{}' discovered: token={}, userinfo={}",
+            config.name, discovery.token_endpoint, discovery.userinfo_endpoint
+        );
+
+        Ok(Self {
+            name: config.name,
+            client_id: config.client_id,
+            client_secret: config.client_secret,
+            authorization_endpoint: discovery.authorization_endpoint,
+            token_endpoint: discovery.token_endpoint,
+            userinfo_endpoint: discovery.userinfo_endpoint,
+            http,
+        })
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        redirect_uri: &str,
+    ) -> Result<TokenResponse, Status> {
+        let mut params = vec![
+            ("client_id", self.client_id.as_str()),
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(secret) = &self.client_secret {
+            params.push(("client_secret", secret.as_str()));
+        }
+
+        let resp = self
+            .http
+            .post(&self.token_endpoint)
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("OIDC token exchange HTTP error: {e}");
+                Status::internal("failed to contact identity provider")
+            })?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            error!("OIDC token exchange failed: {status} {body}");
+            return Err(Status::unauthenticated("identity provider authentication failed"));
+        }
+
+        resp.json::<TokenResponse>().await.map_err(|e| {
+            error!("Failed to parse OIDC token response: {e}");
+            Status::internal("failed to parse identity provider response")
+        })
+    }
+
+    async fn get_userinfo(&self, access_token: &str) -> Result<UserInfo, Status> {
+        let resp = self
+            .http
+            .get(&self.userinfo_endpoint)
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("OIDC userinfo HTTP error: {e}");
+                Status::internal("failed to contact identity provider")
+            })?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            error!("OIDC userinfo fetch failed: {status} {body}");
+            return Err(Status::internal("failed to fetch user info"));
+        }
+
+        resp.json::<UserInfo>().await.map_err(|e| {
+            error!("Failed to parse OIDC userinfo response: {e}");
+            Status::internal("failed to parse user info")
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl IdentityProvider for OidcProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn authorization_endpoint(&self) -> Option<AuthorizationEndpoint<'_>> {
+        Some(AuthorizationEndpoint {
+            url: &self.authorization_endpoint,
+            client_id: &self.client_id,
+            scope: "openid profile email",
+        })
+    }
+
+    async fn authenticate(&self, credentials: &Credentials) -> Result<ExternalIdentity, Status> {
+        let (code, code_verifier, redirect_uri) = credentials.authorization_code()?;
+        let token = self.exchange_code(code, code_verifier, redirect_uri).await?;
+        let info = self.get_userinfo(&token.access_token).await?;
+        info!("OIDC user authenticated via '{}': {}", self.name, info.sub);
+
+        let username = info
+            .preferred_username
+            .or(info.name)
+            .or(info.email)
+            .unwrap_or_else(|| info.sub.clone());
+
+        Ok(ExternalIdentity {
+            subject: info.sub,
+            username,
+            avatar_url: info.picture,
+        })
+    }
+}
@@ -2,27 +2,76 @@ use std::sync::Arc;
 
 use arenabuddy_core::{
     cards::CardsDatabase,
+    models::EventRules,
     services::{
-        auth_service::auth_service_server::AuthServiceServer, debug_service::debug_service_server::DebugServiceServer,
+        FILE_DESCRIPTOR_SET,
+        account_service::account_service_server::AccountServiceServer,
+        auth_service::auth_service_server::AuthServiceServer,
+        community_service::community_service_server::CommunityServiceServer,
+        debug_service::debug_service_server::DebugServiceServer,
+        draft_service::draft_service_server::DraftServiceServer,
+        export_service::export_service_server::ExportServiceServer,
         match_service::match_service_server::MatchServiceServer,
+        metagame_service::metagame_service_server::MetagameServiceServer,
+        share_service::{
+            share_service_server::ShareServiceServer, shared_match_service_server::SharedMatchServiceServer,
+        },
+        team_service::team_service_server::TeamServiceServer,
+        webhook_service::webhook_service_server::WebhookServiceServer,
     },
 };
 use arenabuddy_data::{ArenabuddyRepository, CardRepository, MatchDB};
+use axum::http::{HeaderName, HeaderValue};
 use tonic::transport::Server;
-use tracing::info;
+use tonic_web::GrpcWebLayer;
+use tower::util::MapRequestLayer;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tracing::{info, warn};
 
 use crate::{
-    auth::{AuthConfig, AuthServiceImpl, auth_interceptor},
+    account_service::AccountServiceImpl,
+    auth::{Admins, AuthConfig, AuthServiceImpl, auth_interceptor},
+    community_service::{CommunityServiceImpl, DEFAULT_MIN_MATCHES},
     debug_service::DebugServiceImpl,
+    draft_service::DraftServiceImpl,
+    export::Exporter,
+    export_service::ExportServiceImpl,
+    identity::{DiscordProvider, IdentityProviders, LocalProvider, OidcConfig, OidcProvider},
     match_service::MatchServiceImpl,
+    metagame_jobs::MetagameJobs,
+    metagame_service::MetagameServiceImpl,
+    metrics::RpcMetricsLayer,
+    rate_limit::{RateLimiter, rate_limit_interceptor, tag_rpc_path},
+    rest::Gateway,
+    share_service::{ShareServiceImpl, SharedMatchServiceImpl},
+    team_service::TeamServiceImpl,
+    webhook_service::WebhookServiceImpl,
+    webhooks::Webhooks,
 };
 
+mod account_service;
 pub mod auth;
+mod community;
+mod community_service;
 mod debug_service;
+mod draft_service;
+mod export;
+mod export_service;
+mod health;
+pub mod identity;
 mod match_service;
+mod metagame_jobs;
+mod metagame_service;
+mod metrics;
 #[cfg(feature = "otel")]
 mod otel;
-mod sheets_sync;
+mod public_http;
+mod rate_limit;
+mod rest;
+mod share_service;
+mod team_service;
+mod webhook_service;
+mod webhooks;
 
 /// Start the gRPC server with all services.
 ///
@@ -31,8 +80,21 @@ mod sheets_sync;
 /// bind to the listen address.
 ///
 /// # Panics
-/// Panics if required environment variables are missing: `DATABASE_URL`,
-/// `DISCORD_CLIENT_ID`, `DISCORD_CLIENT_SECRET`, or `JWT_SECRET`.
+/// Panics if required environment variables are missing: `DATABASE_URL` or
+/// `JWT_SECRET`. Identity providers are optional; see [`identity_providers_from_env`].
+/// Per-user CSV exports are written under `EXPORT_DIR` (default `./exports`), and
+/// `GOOGLE_SHEETS_SPREADSHEET_ID` additionally exports every uploaded match to one operator sheet.
+/// Set `PUBLIC_HTTP_ADDR` to also serve shared matches and the JSON API gateway over plain HTTP.
+/// `CORS_ALLOWED_ORIGINS` (comma-separated) limits which browser origins may call gRPC-Web and the
+/// gateway; any origin is allowed when unset.
+/// Account deletions wait `ACCOUNT_DELETION_GRACE_DAYS` (default 7) before running; 0 deletes
+/// immediately.
+/// Set `METAGAME_FORMATS` (e.g. `standard,explorer`) to scrape tournaments, recompute signature
+/// cards and reclassify matches every `METAGAME_REFRESH_INTERVAL_SECS` (default one day).
+/// Set `METRICS_ADDR` to serve Prometheus metrics at `/metrics`; keep it off the public network.
+/// The gRPC port also serves `grpc.health.v1.Health`, reporting not serving while the database is
+/// unreachable, and server reflection for tools like `grpcurl`.
+#[expect(clippy::too_many_lines)]
 pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(feature = "otel")]
     let otel_guard = otel::init_compact_with_otel("arenabuddy-server");
@@ -66,13 +128,11 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
         .parse()?;
 
     let auth_config = Arc::new(AuthConfig {
-        discord_client_id: std::env::var("DISCORD_CLIENT_ID")
-            .expect("DISCORD_CLIENT_ID environment variable must be set"),
-        discord_client_secret: std::env::var("DISCORD_CLIENT_SECRET")
-            .expect("DISCORD_CLIENT_SECRET environment variable must be set"),
         jwt_secret: std::env::var("JWT_SECRET").expect("JWT_SECRET environment variable must be set"),
     });
 
+    load_event_rules();
+
     info!("Connecting to database...");
     let cards = CardsDatabase::default();
     let db = MatchDB::new(Some(&database_url), cards.clone()).await?;
@@ -81,26 +141,155 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     load_cards_on_startup(&db, &cards).await?;
 
+    let providers = identity_providers_from_env(&db).await?;
+    let admins = Admins::from_env()?;
+    if admins.is_empty() {
+        info!("ADMIN_USER_IDS not set; admin RPCs are disabled");
+    }
+
+    let limiter = RateLimiter::from_env(env_flag("TRUST_FORWARDED_FOR"));
+    let payload_limits = limiter.payload_limits();
+
     let spreadsheet_id = std::env::var("GOOGLE_SHEETS_SPREADSHEET_ID").ok();
     if spreadsheet_id.is_some() {
-        info!("Google Sheets sync enabled");
+        info!("Operator Google Sheets export enabled");
     }
+    let export_dir = std::env::var("EXPORT_DIR").unwrap_or_else(|_| "./exports".to_string());
+    let exporter = Exporter::new(db.clone(), cards.clone(), export_dir.into(), spreadsheet_id);
 
+    let webhooks = Webhooks::new(db.clone(), cards.clone());
     let match_service = MatchServiceImpl {
         db: db.clone(),
         cards: cards.clone(),
-        spreadsheet_id,
+        exporter: exporter.clone(),
+        webhooks: webhooks.clone(),
+        payload_limits,
+    };
+    let debug_service = DebugServiceImpl {
+        db: db.clone(),
+        admins: admins.clone(),
+        payload_limits,
+    };
+    let team_service = TeamServiceImpl { db: db.clone() };
+    let draft_service = DraftServiceImpl {
+        db: db.clone(),
+        cards: cards.clone(),
+        webhooks: webhooks.clone(),
+    };
+    let webhook_service = WebhookServiceImpl { db: db.clone() };
+    let account_service = AccountServiceImpl {
+        db: db.clone(),
+        exporter: exporter.clone(),
+        deletion_grace: account_service::deletion_grace_from_env()?,
+    };
+    account_service::spawn_deletion_purge(db.clone(), exporter.clone());
+    let export_service = ExportServiceImpl {
+        db: db.clone(),
+        exporter,
     };
-    let debug_service = DebugServiceImpl { db: db.clone() };
-    let auth_service = AuthServiceImpl::new(db, auth_config.clone());
+    let share_service = ShareServiceImpl { db: db.clone() };
+    let shared_match_service = SharedMatchServiceImpl {
+        db: db.clone(),
+        cards: cards.clone(),
+    };
+    let community_service = CommunityServiceImpl {
+        db: db.clone(),
+        min_matches: std::env::var("COMMUNITY_MIN_MATCHES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_MIN_MATCHES),
+    };
+    community::spawn_aggregation(db.clone());
+    let metagame_jobs = MetagameJobs::from_env()?;
+    if let Some(jobs) = &metagame_jobs {
+        jobs.spawn(db.clone(), webhooks);
+    } else {
+        info!("METAGAME_FORMATS not set; scheduled metagame jobs are disabled");
+    }
+    let metagame_service = MetagameServiceImpl {
+        db: db.clone(),
+        admins,
+        jobs: metagame_jobs,
+    };
+
+    if let Ok(metrics_addr) = std::env::var("METRICS_ADDR") {
+        metrics::spawn(metrics_addr.parse()?, db.clone()).await?;
+    }
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    health::spawn_readiness_check(db.clone(), health_reporter);
+    let reflection = || {
+        tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+    };
+    let reflection_v1 = reflection().build_v1()?;
+    // Older grpcurl releases only speak v1alpha.
+    let reflection_v1alpha = reflection().build_v1alpha()?;
+
+    let auth_service = AuthServiceImpl::new(db, auth_config.clone(), providers);
 
-    let interceptor = auth_interceptor(auth_config.jwt_secret.clone());
+    let cors = cors_layer()?;
+    if let Ok(public_addr) = std::env::var("PUBLIC_HTTP_ADDR") {
+        let gateway = Gateway {
+            matches: match_service.clone(),
+            debug: debug_service.clone(),
+            auth: auth_service.clone(),
+            jwt_secret: auth_config.jwt_secret.as_str().into(),
+            limiter: limiter.clone(),
+        };
+        public_http::spawn(
+            public_addr.parse()?,
+            shared_match_service.clone(),
+            gateway,
+            cors.clone(),
+        )
+        .await?;
+    }
 
-    info!("Starting gRPC server on {addr}");
+    let interceptor = auth_interceptor(auth_config.jwt_secret.clone(), limiter.clone());
+
+    info!("Starting gRPC server on {addr} (gRPC and gRPC-Web)");
     Server::builder()
+        // Browsers reach the services over gRPC-Web, which runs over HTTP/1.1.
+        .accept_http1(true)
+        .layer(cors)
+        .layer(GrpcWebLayer::new())
+        .layer(MapRequestLayer::new(tag_rpc_path))
+        .layer(RpcMetricsLayer)
+        .add_service(health_service)
+        .add_service(reflection_v1)
+        .add_service(reflection_v1alpha)
         .add_service(MatchServiceServer::with_interceptor(match_service, interceptor.clone()))
-        .add_service(DebugServiceServer::with_interceptor(debug_service, interceptor))
-        .add_service(AuthServiceServer::new(auth_service))
+        .add_service(DebugServiceServer::with_interceptor(debug_service, interceptor.clone()))
+        .add_service(TeamServiceServer::with_interceptor(team_service, interceptor.clone()))
+        .add_service(DraftServiceServer::with_interceptor(draft_service, interceptor.clone()))
+        .add_service(CommunityServiceServer::with_interceptor(
+            community_service,
+            interceptor.clone(),
+        ))
+        .add_service(ShareServiceServer::with_interceptor(share_service, interceptor.clone()))
+        .add_service(WebhookServiceServer::with_interceptor(
+            webhook_service,
+            interceptor.clone(),
+        ))
+        .add_service(ExportServiceServer::with_interceptor(
+            export_service,
+            interceptor.clone(),
+        ))
+        .add_service(AccountServiceServer::with_interceptor(
+            account_service,
+            interceptor.clone(),
+        ))
+        .add_service(MetagameServiceServer::with_interceptor(metagame_service, interceptor))
+        .add_service(AuthServiceServer::with_interceptor(
+            auth_service,
+            rate_limit_interceptor(limiter.clone()),
+        ))
+        .add_service(SharedMatchServiceServer::with_interceptor(
+            shared_match_service,
+            rate_limit_interceptor(limiter),
+        ))
         .serve_with_shutdown(addr, async {
             tokio::signal::ctrl_c().await.ok();
             info!("Received shutdown signal");
@@ -116,6 +305,21 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Use the event rules file at `EVENT_RULES_PATH`, if set, in place of the built-in rules.
+/// A file that can't be read or parsed is logged and the built-in rules are kept.
+fn load_event_rules() {
+    let Ok(path) = std::env::var("EVENT_RULES_PATH") else {
+        return;
+    };
+    match EventRules::from_path(std::path::Path::new(&path)) {
+        Ok(rules) => {
+            info!("Loaded event rules version {} from {path}", rules.version());
+            EventRules::install(rules);
+        }
+        Err(e) => warn!("Failed to load event rules from {path}, using the built-in rules: {e}"),
+    }
+}
+
 /// Populate the `card` table from the embedded cards database.
 ///
 /// By default this only loads when the table is empty, so normal restarts are
@@ -123,8 +327,7 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
 /// (TRUNCATE + reinsert) on startup, e.g. after shipping an updated
 /// `cards-full.pb`.
 async fn load_cards_on_startup(db: &MatchDB, cards: &CardsDatabase) -> Result<(), Box<dyn std::error::Error>> {
-    let force_reload =
-        std::env::var("ARENABUDDY_RELOAD_CARDS").is_ok_and(|v| matches!(v.trim(), "1" | "true" | "TRUE" | "yes"));
+    let force_reload = env_flag("ARENABUDDY_RELOAD_CARDS");
 
     let existing = db.card_count().await?;
     if existing > 0 && !force_reload {
@@ -143,3 +346,77 @@ async fn load_cards_on_startup(db: &MatchDB, cards: &CardsDatabase) -> Result<()
     info!("Card load complete");
     Ok(())
 }
+
+/// Build the set of enabled identity providers from the environment.
+///
+/// - Discord: `DISCORD_CLIENT_ID` and `DISCORD_CLIENT_SECRET`.
+/// - Generic OIDC: `OIDC_ISSUER_URL` and `OIDC_CLIENT_ID`, plus optional
+///   `OIDC_CLIENT_SECRET` and `OIDC_PROVIDER_NAME` (defaults to `oidc`).
+/// - Local username/password: `LOCAL_AUTH_ENABLED=1`; new accounts additionally
+///   require `LOCAL_AUTH_ALLOW_REGISTRATION=1`.
+async fn identity_providers_from_env(db: &MatchDB) -> Result<IdentityProviders, Box<dyn std::error::Error>> {
+    let http = reqwest::Client::new();
+    let mut providers = IdentityProviders::default();
+
+    if let (Ok(client_id), Ok(client_secret)) = (
+        std::env::var("DISCORD_CLIENT_ID"),
+        std::env::var("DISCORD_CLIENT_SECRET"),
+    ) {
+        providers.register(DiscordProvider::new(client_id, client_secret, http.clone()));
+    }
+
+    if let (Ok(issuer_url), Ok(client_id)) = (std::env::var("OIDC_ISSUER_URL"), std::env::var("OIDC_CLIENT_ID")) {
+        let config = OidcConfig {
+            name: std::env::var("OIDC_PROVIDER_NAME").unwrap_or_else(|_| "oidc".to_string()),
+            issuer_url,
+            client_id,
+            client_secret: std::env::var("OIDC_CLIENT_SECRET").ok(),
+        };
+        providers.register(OidcProvider::discover(config, http.clone()).await?);
+    }
+
+    if env_flag("LOCAL_AUTH_ENABLED") {
+        providers.register_local(LocalProvider::new(
+            db.clone(),
+            env_flag("LOCAL_AUTH_ALLOW_REGISTRATION"),
+        ));
+    }
+
+    if providers.is_empty() {
+        warn!("No identity providers configured; users will not be able to sign in");
+    } else {
+        info!(
+            "Identity providers enabled: {}",
+            providers.names().collect::<Vec<_>>().join(", ")
+        );
+    }
+    Ok(providers)
+}
+
+/// CORS for browser clients of gRPC-Web and the JSON gateway. Credentials travel in the
+/// `authorization` header rather than cookies, so allowing any origin is the default.
+fn cors_layer() -> Result<CorsLayer, Box<dyn std::error::Error>> {
+    let origins = match std::env::var("CORS_ALLOWED_ORIGINS") {
+        Ok(list) => AllowOrigin::list(
+            list.split(',')
+                .map(str::trim)
+                .filter(|o| !o.is_empty())
+                .map(HeaderValue::from_str)
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Err(_) => AllowOrigin::any(),
+    };
+    Ok(CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(Any)
+        .allow_headers(Any)
+        .expose_headers([
+            HeaderName::from_static("grpc-status"),
+            HeaderName::from_static("grpc-message"),
+            HeaderName::from_static("grpc-status-details-bin"),
+        ]))
+}
+
+pub(crate) fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|v| matches!(v.trim(), "1" | "true" | "TRUE" | "yes"))
+}
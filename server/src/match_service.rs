@@ -1,22 +1,132 @@
 use arenabuddy_core::{
     cards::CardsDatabase,
-    models::{ArenaId, MatchData, OpponentDeck},
-    services::match_service::{
-        ArchetypeClassification, ClassifyMatchRequest, ClassifyMatchResponse, DeleteMatchRequest, DeleteMatchResponse,
-        GetMatchDataRequest, GetMatchDataResponse, ListMatchesRequest, ListMatchesResponse, UpsertMatchDataRequest,
-        UpsertMatchDataResponse, match_service_server::MatchService,
+    display::{
+        match_details::MatchDetails,
+        stats::{MatchupGrouping, TimeWindow},
+    },
+    models::{ArenaId, MatchData, MatchResult, OpponentDeck},
+    services::{
+        match_service::{
+            ArchetypeClassification, ClassifyMatchRequest, ClassifyMatchResponse, DeleteMatchRequest,
+            DeleteMatchResponse, GetMatchDataRequest, GetMatchDataResponse, GetMatchStatsRequest,
+            GetMatchStatsResponse, ListMatchSummariesRequest, ListMatchSummariesResponse, ListMatchesRequest,
+            ListMatchesResponse, SetArchetypeOverrideRequest, SetArchetypeOverrideResponse, UpsertMatchDataRequest,
+            UpsertMatchDataResponse, match_service_server::MatchService,
+        },
+        team_service::{ArchetypeMatchupRecord, TeamStats},
     },
 };
-use arenabuddy_data::{ArenabuddyRepository, MatchDB};
+use arenabuddy_data::{ArenabuddyRepository, MatchDB, MetagameRepository, metagame_models::MatchArchetype};
 use tonic::{Request, Response, Status};
 use tracing::{debug, error, info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    auth::UserId,
+    export::Exporter,
+    metrics::{CLASSIFICATIONS, MATCHES_INGESTED},
+    rate_limit::{Class, PayloadLimits, check_payload},
+    webhooks::Webhooks,
+};
+
+/// Load everything stored for a match. Restricted to `user_id`'s matches when set.
+pub(crate) async fn load_match_data(db: &MatchDB, match_id: &str, user_id: Option<Uuid>) -> Result<MatchData, Status> {
+    let (mtga_match, _match_result) = db.get_match(match_id, user_id).await.map_err(|e| {
+        error!("Failed to get match: {e}");
+        Status::internal("failed to get match")
+    })?;
+
+    if mtga_match.id().is_empty() {
+        return Err(Status::not_found(format!("match not found: {match_id}")));
+    }
+
+    let decks = db.list_decklists(match_id).await.map_err(|e| {
+        error!("Failed to list decklists: {e}");
+        Status::internal("failed to list decklists")
+    })?;
 
-use crate::auth::UserId;
+    let mulligans = db.list_mulligans(match_id).await.map_err(|e| {
+        error!("Failed to list mulligans: {e}");
+        Status::internal("failed to list mulligans")
+    })?;
 
+    let results = db.list_match_results(match_id).await.map_err(|e| {
+        error!("Failed to list match results: {e}");
+        Status::internal("failed to list match results")
+    })?;
+
+    let opponent_deck = db
+        .get_opponent_deck(match_id)
+        .await
+        .inspect_err(|e| debug!("No opponent deck for match {match_id}: {e}"))
+        .ok()
+        .map_or_else(OpponentDeck::empty, |d| {
+            OpponentDeck::new(d.mainboard().iter().map(|&id| ArenaId::from(id)).collect())
+        });
+
+    let event_logs = db.list_event_logs(match_id).await.map_err(|e| {
+        error!("Failed to list event logs: {e}");
+        Status::internal("failed to list event logs")
+    })?;
+
+    Ok(MatchData {
+        mtga_match,
+        decks,
+        mulligans,
+        results,
+        opponent_deck,
+        event_logs,
+    })
+}
+
+/// Load a match in display form, including its archetype classifications.
+pub(crate) async fn load_match_details(
+    db: &MatchDB,
+    cards: &CardsDatabase,
+    match_id: &str,
+    user_id: Option<Uuid>,
+) -> Result<MatchDetails, Status> {
+    let data = load_match_data(db, match_id, user_id).await?;
+    let mut details = MatchDetails::from_match_data(&data, cards);
+    let classifications = db
+        .get_match_classifications(match_id)
+        .await
+        .inspect_err(|e| debug!("No archetypes for match {match_id}: {e}"))
+        .unwrap_or_default();
+    for c in classifications {
+        details.set_classification(&c.side, c.archetype_name, c.evidence);
+    }
+    Ok(details)
+}
+
+pub(crate) fn classification_to_proto(archetype: &MatchArchetype) -> ArchetypeClassification {
+    ArchetypeClassification {
+        side: archetype.side.clone(),
+        archetype_name: archetype.archetype_name.clone(),
+        confidence: archetype.confidence,
+        evidence: archetype.evidence.as_ref().map(Into::into),
+        overridden: archetype.overridden,
+    }
+}
+
+#[derive(Clone)]
 pub(crate) struct MatchServiceImpl {
     pub(crate) db: MatchDB,
     pub(crate) cards: CardsDatabase,
-    pub(crate) spreadsheet_id: Option<String>,
+    pub(crate) exporter: Exporter,
+    pub(crate) webhooks: Webhooks,
+    pub(crate) payload_limits: PayloadLimits,
+}
+
+impl MatchServiceImpl {
+    /// The match's classifications, overrides taking precedence.
+    async fn match_classifications(&self, match_id: &str) -> Result<Vec<ArchetypeClassification>, Status> {
+        let classifications = self.db.get_match_classifications(match_id).await.map_err(|e| {
+            error!("Failed to load classifications for match {match_id}: {e}");
+            Status::internal("failed to load classifications")
+        })?;
+        Ok(classifications.iter().map(classification_to_proto).collect())
+    }
 }
 
 #[tonic::async_trait]
@@ -31,6 +141,14 @@ impl MatchService for MatchServiceImpl {
             .into_inner()
             .match_data
             .ok_or_else(|| Status::invalid_argument("match_data is required"))?;
+        for event_log in &match_data_proto.event_logs {
+            check_payload(
+                Class::Upload,
+                &format!("event log for game {}", event_log.game_number),
+                event_log.events_json.len(),
+                self.payload_limits.event_log_bytes,
+            )?;
+        }
 
         let match_data: MatchData = (&match_data_proto)
             .try_into()
@@ -38,6 +156,7 @@ impl MatchService for MatchServiceImpl {
         let match_id = match_data.mtga_match.id().to_string();
 
         let opponent_cards: Vec<ArenaId> = match_data.opponent_deck.cards.clone();
+        let completed = match_data.results.iter().any(MatchResult::is_match_result);
 
         self.db
             .upsert_match_data(
@@ -55,18 +174,17 @@ impl MatchService for MatchServiceImpl {
                 Status::internal("failed to upsert match data")
             })?;
 
+        MATCHES_INGESTED.inc();
         info!("Upserted match data for match_id: {match_id}");
 
-        if let Some(ref spreadsheet_id) = self.spreadsheet_id {
-            crate::sheets_sync::spawn_sheets_sync(
-                self.db.clone(),
-                self.cards.clone(),
-                match_id,
-                user_id,
-                spreadsheet_id.clone(),
-            );
+        if let Some(user_id) = user_id
+            && completed
+        {
+            self.webhooks.match_completed(user_id, match_id.clone());
         }
 
+        self.exporter.match_uploaded(user_id, match_id);
+
         Ok(Response::new(UpsertMatchDataResponse {}))
     }
 
@@ -76,61 +194,40 @@ impl MatchService for MatchServiceImpl {
         request: Request<GetMatchDataRequest>,
     ) -> Result<Response<GetMatchDataResponse>, Status> {
         let user_id = request.extensions().get::<UserId>().map(|u| u.0);
-        let match_id = request.into_inner().match_id;
+        let req = request.into_inner();
+        let match_id = req.match_id;
         if match_id.is_empty() {
             return Err(Status::invalid_argument("match_id is required"));
         }
 
-        let (mtga_match, _match_result) = self.db.get_match(&match_id, user_id).await.map_err(|e| {
-            error!("Failed to get match: {e}");
-            Status::internal("failed to get match")
-        })?;
-
-        if mtga_match.id().is_empty() {
-            return Err(Status::not_found(format!("match not found: {match_id}")));
-        }
-
-        let decks = self.db.list_decklists(&match_id).await.map_err(|e| {
-            error!("Failed to list decklists: {e}");
-            Status::internal("failed to list decklists")
-        })?;
-
-        let mulligans = self.db.list_mulligans(&match_id).await.map_err(|e| {
-            error!("Failed to list mulligans: {e}");
-            Status::internal("failed to list mulligans")
-        })?;
+        let match_data_model = load_match_data(&self.db, &match_id, user_id).await?;
 
-        let results = self.db.list_match_results(&match_id).await.map_err(|e| {
-            error!("Failed to list match results: {e}");
-            Status::internal("failed to list match results")
-        })?;
-
-        let opponent_deck = self
+        let classifications: Vec<ArchetypeClassification> = self
             .db
-            .get_opponent_deck(&match_id)
+            .get_match_classifications(&match_id)
             .await
-            .inspect_err(|e| debug!("No opponent deck for match {match_id}: {e}"))
-            .ok()
-            .map_or_else(OpponentDeck::empty, |d| {
-                OpponentDeck::new(d.mainboard().iter().map(|&id| ArenaId::from(id)).collect())
-            });
-
-        let event_logs = self.db.list_event_logs(&match_id).await.map_err(|e| {
-            error!("Failed to list event logs: {e}");
-            Status::internal("failed to list event logs")
-        })?;
-
-        let match_data_model = MatchData {
-            mtga_match,
-            decks,
-            mulligans,
-            results,
-            opponent_deck,
-            event_logs,
+            .inspect_err(|e| debug!("No archetypes for match {match_id}: {e}"))
+            .unwrap_or_default()
+            .iter()
+            .map(classification_to_proto)
+            .collect();
+        let archetype = |side: &str| {
+            classifications
+                .iter()
+                .find(|c| c.side == side)
+                .map(|c| c.archetype_name.clone())
         };
 
+        let cards = req
+            .include_cards
+            .then(|| self.cards.collection_for(match_data_model.card_ids()));
+
         Ok(Response::new(GetMatchDataResponse {
             match_data: Some((&match_data_model).into()),
+            cards,
+            controller_archetype: archetype("controller"),
+            opponent_archetype: archetype("opponent"),
+            classifications,
         }))
     }
 
@@ -150,6 +247,53 @@ impl MatchService for MatchServiceImpl {
         }))
     }
 
+    #[instrument(skip(self, request))]
+    async fn list_match_summaries(
+        &self,
+        request: Request<ListMatchSummariesRequest>,
+    ) -> Result<Response<ListMatchSummariesResponse>, Status> {
+        let user_id = request.extensions().get::<UserId>().map(|u| u.0);
+        let summaries = self.db.list_match_summaries(user_id).await.map_err(|e| {
+            error!("Failed to list match summaries: {e}");
+            Status::internal("failed to list match summaries")
+        })?;
+
+        Ok(Response::new(ListMatchSummariesResponse {
+            matches: summaries.iter().map(Into::into).collect(),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn get_match_stats(
+        &self,
+        request: Request<GetMatchStatsRequest>,
+    ) -> Result<Response<GetMatchStatsResponse>, Status> {
+        let user_id = request.extensions().get::<UserId>().map(|u| u.0);
+        let request = request.into_inner();
+        let time_window = TimeWindow::from(request.time_window());
+        let grouping = MatchupGrouping::from(request.grouping());
+        let format = request.format.as_deref().filter(|format| !format.is_empty());
+        let stats = self.db.get_match_stats(user_id, time_window).await.map_err(|e| {
+            error!("Failed to compute match stats: {e}");
+            Status::internal("failed to compute match stats")
+        })?;
+        let matchups = self
+            .db
+            .get_archetype_matchups(user_id, time_window, grouping, format)
+            .await
+            .map_err(|e| {
+                error!("Failed to compute archetype matchups: {e}");
+                Status::internal("failed to compute match stats")
+            })?;
+
+        let mut team_stats = TeamStats::from(&stats);
+        team_stats.matchups = matchups.iter().map(ArchetypeMatchupRecord::from).collect();
+        Ok(Response::new(GetMatchStatsResponse {
+            stats: Some(team_stats),
+            opponents: stats.opponents.iter().map(Into::into).collect(),
+        }))
+    }
+
     #[instrument(skip(self, request))]
     async fn delete_match(
         &self,
@@ -192,6 +336,7 @@ impl MatchService for MatchServiceImpl {
         }
 
         let Some(format) = mtga_match.format() else {
+            CLASSIFICATIONS.with_label_values(&["unsupported_format"]).inc();
             return Ok(Response::new(ClassifyMatchResponse {
                 classifications: vec![],
             }));
@@ -200,19 +345,177 @@ impl MatchService for MatchServiceImpl {
         let archetypes = arenabuddy_metagame::classification::classify_single_match(&self.db, &match_id, format)
             .await
             .map_err(|e| {
+                CLASSIFICATIONS.with_label_values(&["error"]).inc();
                 error!("Classification failed for match {match_id}: {e}");
                 Status::internal("classification failed")
             })?;
+        let outcome = if archetypes.is_empty() {
+            "unclassified"
+        } else {
+            "classified"
+        };
+        CLASSIFICATIONS.with_label_values(&[outcome]).inc();
 
-        let classifications = archetypes
-            .into_iter()
-            .map(|a| ArchetypeClassification {
-                side: a.side,
-                archetype_name: a.archetype_name,
-                confidence: a.confidence,
-            })
-            .collect();
+        // Overrides take precedence over what the classifier just stored.
+        let classifications = self.match_classifications(&match_id).await?;
+
+        if let Some(user_id) = user_id
+            && !classifications.is_empty()
+        {
+            self.webhooks
+                .classification_updated(user_id, match_id, classifications.clone());
+        }
 
         Ok(Response::new(ClassifyMatchResponse { classifications }))
     }
+
+    #[instrument(skip(self, request))]
+    async fn set_archetype_override(
+        &self,
+        request: Request<SetArchetypeOverrideRequest>,
+    ) -> Result<Response<SetArchetypeOverrideResponse>, Status> {
+        let user_id = request.extensions().get::<UserId>().map(|u| u.0);
+        let req = request.into_inner();
+        if req.match_id.is_empty() {
+            return Err(Status::invalid_argument("match_id is required"));
+        }
+        if !matches!(req.side.as_str(), "controller" | "opponent") {
+            return Err(Status::invalid_argument("side must be \"controller\" or \"opponent\""));
+        }
+
+        // Only the match's owner can relabel it.
+        let (mtga_match, _) = self.db.get_match(&req.match_id, user_id).await.map_err(|e| {
+            error!("Failed to get match for archetype override: {e}");
+            Status::internal("failed to get match")
+        })?;
+        if mtga_match.id().is_empty() {
+            return Err(Status::not_found(format!("match not found: {}", req.match_id)));
+        }
+
+        let archetype_name = req.archetype_name.trim();
+        if archetype_name.is_empty() {
+            self.db
+                .clear_match_archetype_override(&req.match_id, &req.side)
+                .await
+                .map_err(|e| {
+                    error!("Failed to clear archetype override for match {}: {e}", req.match_id);
+                    Status::internal("failed to clear archetype override")
+                })?;
+            info!("Cleared {} archetype override for match {}", req.side, req.match_id);
+        } else {
+            self.db
+                .set_match_archetype_override(&req.match_id, &req.side, archetype_name, user_id)
+                .await
+                .map_err(|e| {
+                    error!("Failed to set archetype override for match {}: {e}", req.match_id);
+                    Status::internal("failed to set archetype override")
+                })?;
+            info!(
+                "Set {} archetype of match {} to {archetype_name}",
+                req.side, req.match_id
+            );
+        }
+
+        let classifications = self.match_classifications(&req.match_id).await?;
+        if let Some(user_id) = user_id {
+            self.webhooks
+                .classification_updated(user_id, req.match_id, classifications.clone());
+        }
+
+        Ok(Response::new(SetArchetypeOverrideResponse { classifications }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arenabuddy_core::models::MTGAMatch;
+    use arenabuddy_data::{AuthRepository, testing::test_db};
+
+    use super::*;
+
+    fn authed<T>(user_id: Uuid, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request.extensions_mut().insert(UserId(user_id));
+        request
+    }
+
+    #[tokio::test]
+    async fn only_the_owner_can_override_a_match_archetype() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let cards = CardsDatabase::default();
+        let service = MatchServiceImpl {
+            db: db.clone(),
+            cards: cards.clone(),
+            exporter: Exporter::new(db.clone(), cards.clone(), std::env::temp_dir(), None),
+            webhooks: Webhooks::new(db.clone(), cards),
+            payload_limits: PayloadLimits {
+                event_log_bytes: 1024,
+                parse_error_bytes: 1024,
+                parse_errors_per_request: 1,
+            },
+        };
+        let alice = db
+            .upsert_identity_user("local", "alice", "alice", None)
+            .await
+            .expect("alice");
+        let bob = db.upsert_identity_user("local", "bob", "bob", None).await.expect("bob");
+        let match_id = Uuid::new_v4().to_string();
+        // Matches are only looked up through their match-level result.
+        db.upsert_match_data(
+            &MTGAMatch::new(&match_id, 1, "alice", "them"),
+            &[],
+            &[],
+            &[MatchResult::new(&match_id, 0, 1, "MatchScope_Match")],
+            &[],
+            &[],
+            Some(alice),
+        )
+        .await
+        .expect("match");
+
+        let request = |user_id, archetype_name: &str| {
+            authed(
+                user_id,
+                SetArchetypeOverrideRequest {
+                    match_id: match_id.clone(),
+                    side: "opponent".to_string(),
+                    archetype_name: archetype_name.to_string(),
+                },
+            )
+        };
+
+        let status = service
+            .set_archetype_override(request(bob, "Mono Red"))
+            .await
+            .expect_err("not bob's match");
+        assert_eq!(status.code(), tonic::Code::NotFound);
+        assert!(
+            db.get_match_classifications(&match_id)
+                .await
+                .expect("classifications")
+                .is_empty()
+        );
+
+        let classifications = service
+            .set_archetype_override(request(alice, "Mono Red"))
+            .await
+            .expect("alice's match")
+            .into_inner()
+            .classifications;
+        assert_eq!(classifications.len(), 1);
+        assert_eq!(classifications[0].archetype_name, "Mono Red");
+        assert!(classifications[0].overridden);
+
+        let status = service
+            .set_archetype_override(request(bob, ""))
+            .await
+            .expect_err("not bob's match");
+        assert_eq!(status.code(), tonic::Code::NotFound);
+        assert_eq!(
+            db.get_match_archetypes(&match_id).await.expect("archetypes"),
+            (None, Some("Mono Red".to_string()))
+        );
+    }
 }
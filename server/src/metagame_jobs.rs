@@ -0,0 +1,136 @@
+//! Scheduled metagame refresh: tournament import, signature cards and reclassification.
+//!
+//! Every replica schedules the jobs, but a run only goes ahead on the replica holding the
+//! metagame jobs advisory lock; the others skip it.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use arenabuddy_data::{MatchDB, MetagameRepository};
+use arenabuddy_metagame::{
+    scheduler::{self, DEFAULT_LOOKBACK_DAYS, SchedulerConfig},
+    scraper::{Fetcher, MtgGoldfish},
+    source::{LocalDecklists, MetagameSource},
+};
+use tracing::{error, info, warn};
+
+use crate::webhooks::Webhooks;
+
+const DEFAULT_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// The refresh schedule, configured from the environment.
+#[derive(Clone)]
+pub(crate) struct MetagameJobs {
+    pub(crate) config: SchedulerConfig,
+    pub(crate) interval: Duration,
+    source: Arc<dyn MetagameSource>,
+}
+
+impl MetagameJobs {
+    /// `METAGAME_FORMATS` (comma-separated, e.g. `standard,explorer`) enables the jobs. They run
+    /// every `METAGAME_REFRESH_INTERVAL_SECS` (default one day) and scrape the last
+    /// `METAGAME_LOOKBACK_DAYS` (default 7) of tournaments from `MTGGoldfish`, or from pages
+    /// saved in `METAGAME_LOCAL_DIR`; `METAGAME_CACHE_DIR` caches fetched pages between runs.
+    /// Set `METAGAME_DECKLIST_DIR` to import a folder of the team's own decklists instead, and
+    /// `METAGAME_LEARN_FROM_OVERRIDES` to count hand-labelled matches as reference decks for
+    /// signature cards.
+    pub(crate) fn from_env() -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let formats: Vec<String> = std::env::var("METAGAME_FORMATS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|f| f.trim().to_lowercase())
+            .filter(|f| !f.is_empty())
+            .collect();
+        if formats.is_empty() {
+            return Ok(None);
+        }
+
+        let secs = std::env::var("METAGAME_REFRESH_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_INTERVAL_SECS);
+        let lookback_days = std::env::var("METAGAME_LOOKBACK_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_LOOKBACK_DAYS);
+        let source: Arc<dyn MetagameSource> = if let Ok(dir) = std::env::var("METAGAME_DECKLIST_DIR") {
+            Arc::new(LocalDecklists::new(Path::new(&dir))?)
+        } else if let Ok(dir) = std::env::var("METAGAME_LOCAL_DIR") {
+            Arc::new(MtgGoldfish::new(Fetcher::local(Path::new(&dir))?))
+        } else {
+            let cache_dir = std::env::var("METAGAME_CACHE_DIR").ok().map(PathBuf::from);
+            Arc::new(MtgGoldfish::new(Fetcher::cached(cache_dir, false)?))
+        };
+
+        Ok(Some(Self {
+            config: SchedulerConfig {
+                formats,
+                lookback_days,
+                learn_from_overrides: crate::env_flag("METAGAME_LEARN_FROM_OVERRIDES"),
+            },
+            interval: Duration::from_secs(secs),
+            source,
+        }))
+    }
+
+    /// Run the jobs now and then every `interval`, notifying webhooks of matches whose
+    /// archetypes changed.
+    pub(crate) fn spawn(&self, db: MatchDB, webhooks: Webhooks) {
+        info!(
+            "Metagame refresh for {} every {}s",
+            self.config.formats.join(", "),
+            self.interval.as_secs()
+        );
+        let jobs = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(jobs.interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                jobs.run_locked(&db, &webhooks).await;
+            }
+        });
+    }
+
+    /// Run the jobs once if no other replica is running them. Runs another replica left
+    /// `running` when it died are marked failed first.
+    async fn run_locked(&self, db: &MatchDB, webhooks: &Webhooks) {
+        let lock = match db.try_lock_metagame_jobs().await {
+            Ok(Some(lock)) => lock,
+            Ok(None) => {
+                info!("Another replica is running the metagame jobs; skipping this run");
+                return;
+            }
+            Err(e) => {
+                error!("Failed to take the metagame jobs lock: {e}");
+                return;
+            }
+        };
+
+        match db.fail_interrupted_metagame_job_runs().await {
+            Ok(0) => {}
+            Ok(failed) => warn!("Marked {failed} interrupted metagame job run(s) as failed"),
+            Err(e) => error!("Failed to mark interrupted metagame job runs: {e}"),
+        }
+
+        let today = chrono::Utc::now().date_naive();
+        match scheduler::run_once(db, self.source.as_ref(), &self.config, today).await {
+            Ok(changed) => {
+                for reclassified in changed {
+                    if let Some(user_id) = reclassified.user_id {
+                        webhooks.match_reclassified(user_id, reclassified.match_id.to_string());
+                    }
+                }
+            }
+            Err(e) => error!("Failed to record metagame job runs: {e:#}"),
+        }
+
+        if let Err(e) = lock.release().await {
+            warn!("Failed to release the metagame jobs lock: {e}");
+        }
+    }
+}
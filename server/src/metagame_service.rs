@@ -0,0 +1,259 @@
+use arenabuddy_core::services::metagame_service::{
+    AcceptArchetypeClusterRequest, AcceptArchetypeClusterResponse, ArchetypeCluster,
+    ArchetypeClusterCard as ArchetypeClusterCardProto, ArchetypeClusterMember as ArchetypeClusterMemberProto,
+    DiscoverArchetypeClustersRequest, DiscoverArchetypeClustersResponse, DismissArchetypeClusterRequest,
+    DismissArchetypeClusterResponse, GetMetagameJobStatusRequest, GetMetagameJobStatusResponse,
+    ListArchetypeClustersRequest, ListArchetypeClustersResponse, MetagameJobRun as MetagameJobRunProto,
+    metagame_service_server::MetagameService,
+};
+use arenabuddy_data::{
+    MatchDB, MetagameRepository,
+    metagame_models::{ArchetypeClusterRow, MetagameJobRun},
+};
+use arenabuddy_metagame::clustering;
+use tonic::{Request, Response, Status};
+use tracing::{error, instrument};
+
+use crate::{auth::Admins, metagame_jobs::MetagameJobs};
+
+const DEFAULT_RUN_LIMIT: i32 = 20;
+const MAX_RUN_LIMIT: i32 = 200;
+
+#[derive(Clone)]
+pub(crate) struct MetagameServiceImpl {
+    pub(crate) db: MatchDB,
+    pub(crate) admins: Admins,
+    /// `None` when the scheduled jobs are disabled.
+    pub(crate) jobs: Option<MetagameJobs>,
+}
+
+fn run_to_proto(run: &MetagameJobRun) -> MetagameJobRunProto {
+    MetagameJobRunProto {
+        id: run.id,
+        job: run.job.clone(),
+        format: run.format.clone(),
+        status: run.status.clone(),
+        items: run.items,
+        error: run.error.clone().unwrap_or_default(),
+        started_at: run.started_at.to_rfc3339(),
+        finished_at: run.finished_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+        duration_ms: run
+            .finished_at
+            .map(|at| (at - run.started_at).num_milliseconds())
+            .unwrap_or_default(),
+    }
+}
+
+impl MetagameServiceImpl {
+    /// Load a cluster's defining cards, and its members if asked, into its proto.
+    async fn cluster_to_proto(
+        &self,
+        cluster: &ArchetypeClusterRow,
+        include_members: bool,
+    ) -> Result<ArchetypeCluster, Status> {
+        let cards = self.db.get_archetype_cluster_cards(cluster.id).await.map_err(|e| {
+            error!("Failed to load cards of archetype cluster {}: {e}", cluster.id);
+            Status::internal("failed to load archetype cluster")
+        })?;
+        let members = if include_members {
+            self.db.get_archetype_cluster_members(cluster.id).await.map_err(|e| {
+                error!("Failed to load members of archetype cluster {}: {e}", cluster.id);
+                Status::internal("failed to load archetype cluster")
+            })?
+        } else {
+            Vec::new()
+        };
+
+        Ok(ArchetypeCluster {
+            id: cluster.id,
+            format: cluster.format.clone(),
+            suggested_name: cluster.suggested_name.clone(),
+            status: cluster.status.clone(),
+            archetype_id: cluster.archetype_id.unwrap_or_default(),
+            member_count: cluster.member_count,
+            cards: cards
+                .into_iter()
+                .map(|card| ArchetypeClusterCardProto {
+                    card_name: card.card_name,
+                    frequency: card.frequency,
+                })
+                .collect(),
+            members: members
+                .into_iter()
+                .map(|member| ArchetypeClusterMemberProto {
+                    match_id: member.match_id.to_string(),
+                    side: member.side,
+                    similarity: member.similarity,
+                })
+                .collect(),
+            created_at: cluster.created_at.to_rfc3339(),
+        })
+    }
+
+    async fn load_cluster(&self, cluster_id: i32) -> Result<ArchetypeClusterRow, Status> {
+        self.db
+            .get_archetype_cluster(cluster_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to load archetype cluster {cluster_id}: {e}");
+                Status::internal("failed to load archetype cluster")
+            })?
+            .ok_or_else(|| Status::not_found(format!("archetype cluster {cluster_id} not found")))
+    }
+}
+
+#[tonic::async_trait]
+impl MetagameService for MetagameServiceImpl {
+    #[instrument(skip(self, request))]
+    async fn get_metagame_job_status(
+        &self,
+        request: Request<GetMetagameJobStatusRequest>,
+    ) -> Result<Response<GetMetagameJobStatusResponse>, Status> {
+        self.admins.require(&request)?;
+        let limit = match request.into_inner().limit {
+            n if n <= 0 => DEFAULT_RUN_LIMIT,
+            n => n.min(MAX_RUN_LIMIT),
+        };
+
+        let runs = self.db.list_metagame_job_runs(i64::from(limit)).await.map_err(|e| {
+            error!("Failed to list metagame job runs: {e}");
+            Status::internal("failed to list metagame job runs")
+        })?;
+
+        Ok(Response::new(GetMetagameJobStatusResponse {
+            enabled: self.jobs.is_some(),
+            formats: self
+                .jobs
+                .as_ref()
+                .map(|jobs| jobs.config.formats.clone())
+                .unwrap_or_default(),
+            interval_secs: self
+                .jobs
+                .as_ref()
+                .map(|jobs| i64::try_from(jobs.interval.as_secs()).unwrap_or(i64::MAX))
+                .unwrap_or_default(),
+            runs: runs.iter().map(run_to_proto).collect(),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn list_archetype_clusters(
+        &self,
+        request: Request<ListArchetypeClustersRequest>,
+    ) -> Result<Response<ListArchetypeClustersResponse>, Status> {
+        self.admins.require(&request)?;
+        let request = request.into_inner();
+        let format = Some(request.format.as_str()).filter(|format| !format.is_empty());
+
+        let rows = self
+            .db
+            .list_archetype_clusters(format, request.include_resolved)
+            .await
+            .map_err(|e| {
+                error!("Failed to list archetype clusters: {e}");
+                Status::internal("failed to list archetype clusters")
+            })?;
+
+        let mut clusters = Vec::with_capacity(rows.len());
+        for row in &rows {
+            clusters.push(self.cluster_to_proto(row, request.include_members).await?);
+        }
+        Ok(Response::new(ListArchetypeClustersResponse { clusters }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn discover_archetype_clusters(
+        &self,
+        request: Request<DiscoverArchetypeClustersRequest>,
+    ) -> Result<Response<DiscoverArchetypeClustersResponse>, Status> {
+        self.admins.require(&request)?;
+        let format = request.into_inner().format;
+        if format.is_empty() {
+            return Err(Status::invalid_argument("format is required"));
+        }
+
+        clustering::propose_clusters(&self.db, &format).await.map_err(|e| {
+            error!("Failed to cluster unclassified decks for {format}: {e:#}");
+            Status::internal("failed to cluster unclassified decks")
+        })?;
+
+        let rows = self
+            .db
+            .list_archetype_clusters(Some(&format), false)
+            .await
+            .map_err(|e| {
+                error!("Failed to list archetype clusters: {e}");
+                Status::internal("failed to list archetype clusters")
+            })?;
+
+        let mut clusters = Vec::with_capacity(rows.len());
+        for row in &rows {
+            clusters.push(self.cluster_to_proto(row, false).await?);
+        }
+        Ok(Response::new(DiscoverArchetypeClustersResponse { clusters }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn accept_archetype_cluster(
+        &self,
+        request: Request<AcceptArchetypeClusterRequest>,
+    ) -> Result<Response<AcceptArchetypeClusterResponse>, Status> {
+        self.admins.require(&request)?;
+        let request = request.into_inner();
+        let cluster = self.load_cluster(request.cluster_id).await?;
+        if cluster.status != "proposed" {
+            return Err(Status::failed_precondition(format!(
+                "archetype cluster {} is already {}",
+                cluster.id, cluster.status
+            )));
+        }
+
+        let name = request.name.trim();
+        let name = if name.is_empty() {
+            cluster.suggested_name.as_str()
+        } else {
+            name
+        };
+        let accepted = clustering::accept_cluster(&self.db, &cluster, name)
+            .await
+            .map_err(|e| {
+                error!("Failed to accept archetype cluster {}: {e:#}", cluster.id);
+                Status::internal("failed to accept archetype cluster")
+            })?;
+        if accepted.is_none() {
+            return Err(Status::failed_precondition(format!(
+                "archetype cluster {} was resolved concurrently",
+                cluster.id
+            )));
+        }
+
+        let cluster = self.load_cluster(cluster.id).await?;
+        Ok(Response::new(AcceptArchetypeClusterResponse {
+            cluster: Some(self.cluster_to_proto(&cluster, true).await?),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn dismiss_archetype_cluster(
+        &self,
+        request: Request<DismissArchetypeClusterRequest>,
+    ) -> Result<Response<DismissArchetypeClusterResponse>, Status> {
+        self.admins.require(&request)?;
+        let cluster_id = request.into_inner().cluster_id;
+
+        let dismissed = self
+            .db
+            .resolve_archetype_cluster(cluster_id, "dismissed", None)
+            .await
+            .map_err(|e| {
+                error!("Failed to dismiss archetype cluster {cluster_id}: {e}");
+                Status::internal("failed to dismiss archetype cluster")
+            })?;
+        if !dismissed {
+            return Err(Status::failed_precondition(format!(
+                "archetype cluster {cluster_id} is not a proposed cluster"
+            )));
+        }
+        Ok(Response::new(DismissArchetypeClusterResponse {}))
+    }
+}
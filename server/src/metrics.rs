@@ -0,0 +1,270 @@
+//! Prometheus metrics, served on `METRICS_ADDR` at `/metrics`.
+
+use std::{
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::LazyLock,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use arenabuddy_data::MatchDB;
+use axum::{Router, extract::State, http::header, response::IntoResponse, routing::get};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+    core::Collector,
+};
+use tonic::codegen::http;
+use tower::{Layer, Service};
+use tracing::{error, info};
+
+pub(crate) static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+fn register<C: Collector + Clone + 'static>(collector: C) -> C {
+    REGISTRY
+        .register(Box::new(collector.clone()))
+        .expect("metric registered twice");
+    collector
+}
+
+/// Requests rejected by a rate limit or payload limit, by rate-limit class and reason.
+pub(crate) static REQUESTS_REJECTED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new(
+                "arenabuddy_requests_rejected_total",
+                "Requests rejected by rate or payload limits",
+            ),
+            &["class", "reason"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// Finished gRPC requests, by method and status code.
+static RPC_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new(
+                "arenabuddy_rpc_requests_total",
+                "gRPC requests by method and status code",
+            ),
+            &["method", "code"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// Time until response headers, by method. Streaming responses aren't timed to the last message.
+static RPC_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new("arenabuddy_rpc_duration_seconds", "gRPC request latency by method"),
+            &["method"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// Matches stored by `UpsertMatchData`, including re-uploads of a known match.
+pub(crate) static MATCHES_INGESTED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register(IntCounter::new("arenabuddy_matches_ingested_total", "Matches uploaded by clients").expect("valid metric"))
+});
+
+/// `ClassifyMatch` results: `classified`, `unclassified`, `unsupported_format` or `error`.
+pub(crate) static CLASSIFICATIONS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new("arenabuddy_classifications_total", "Match classifications by outcome"),
+            &["outcome"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// Failed match exports, by sink kind (`google_sheets` or `csv`).
+pub(crate) static EXPORT_FAILURES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new("arenabuddy_export_failures_total", "Failed match exports by sink"),
+            &["sink"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+static DB_POOL_CONNECTIONS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register(IntGauge::new("arenabuddy_db_pool_connections", "Open database connections").expect("valid metric"))
+});
+
+static DB_POOL_IDLE: LazyLock<IntGauge> = LazyLock::new(|| {
+    register(IntGauge::new("arenabuddy_db_pool_idle", "Idle database connections").expect("valid metric"))
+});
+
+/// The gRPC status code of a response. Unary errors are trailers-only responses, so the status
+/// is in the headers; a response without one succeeded, or failed mid-stream.
+fn grpc_code<B>(response: &http::Response<B>) -> tonic::Code {
+    response
+        .headers()
+        .get("grpc-status")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i32>().ok())
+        .map_or(tonic::Code::Ok, tonic::Code::from_i32)
+}
+
+/// Records [`RPC_REQUESTS`] and [`RPC_DURATION`] for every request to the gRPC server.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RpcMetricsLayer;
+
+impl<S> Layer<S> for RpcMetricsLayer {
+    type Service = RpcMetrics<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcMetrics { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RpcMetrics<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for RpcMetrics<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+    ResBody: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        // gRPC and gRPC-Web calls are POSTs; skip CORS preflights and other stray requests.
+        let path = (request.method() == http::Method::POST).then(|| request.uri().path().to_string());
+        let start = Instant::now();
+        let future = self.inner.call(request);
+        Box::pin(async move {
+            let result = future.await;
+            let Some(path) = path else {
+                return result;
+            };
+            let code = result.as_ref().map_or(tonic::Code::Internal, grpc_code);
+            // Unknown paths come back Unimplemented; don't let them mint new label values.
+            let method = if code == tonic::Code::Unimplemented {
+                "other"
+            } else {
+                path.as_str()
+            };
+            RPC_REQUESTS.with_label_values(&[method, &format!("{code:?}")]).inc();
+            RPC_DURATION
+                .with_label_values(&[method])
+                .observe(start.elapsed().as_secs_f64());
+            result
+        })
+    }
+}
+
+async fn render(State(db): State<MatchDB>) -> impl IntoResponse {
+    let (size, idle) = db.pool_stats();
+    DB_POOL_CONNECTIONS.set(i64::from(size));
+    DB_POOL_IDLE.set(i64::try_from(idle).unwrap_or(i64::MAX));
+
+    let encoder = TextEncoder::new();
+    let mut body = Vec::new();
+    if let Err(e) = encoder.encode(&REGISTRY.gather(), &mut body) {
+        error!("Failed to encode metrics: {e}");
+    }
+    ([(header::CONTENT_TYPE, encoder.format_type().to_string())], body)
+}
+
+/// Serve `/metrics` on `addr` in the background. Keep this address off the public network.
+pub(crate) async fn spawn(addr: SocketAddr, db: MatchDB) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("Serving metrics on {addr}/metrics");
+    let router = Router::new().route("/metrics", get(render)).with_state(db);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router).await {
+            error!("Metrics server failed: {e}");
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{ServiceExt, service_fn};
+
+    use super::*;
+
+    fn response(status: Option<&str>) -> http::Response<()> {
+        let mut builder = http::Response::builder();
+        if let Some(status) = status {
+            builder = builder.header("grpc-status", status);
+        }
+        builder.body(()).expect("valid response")
+    }
+
+    #[test]
+    fn grpc_code_reads_the_status_header() {
+        assert_eq!(grpc_code(&response(None)), tonic::Code::Ok);
+        assert_eq!(grpc_code(&response(Some("0"))), tonic::Code::Ok);
+        assert_eq!(grpc_code(&response(Some("5"))), tonic::Code::NotFound);
+        assert_eq!(grpc_code(&response(Some("16"))), tonic::Code::Unauthenticated);
+        assert_eq!(grpc_code(&response(Some("99"))), tonic::Code::Unknown);
+        assert_eq!(grpc_code(&response(Some("not a code"))), tonic::Code::Ok);
+    }
+
+    async fn call(method: http::Method, path: &str, status: &'static str) {
+        let service = RpcMetricsLayer.layer(service_fn(move |_request: http::Request<()>| async move {
+            Ok::<_, Infallible>(response(Some(status)))
+        }));
+        let request = http::Request::builder()
+            .method(method)
+            .uri(path)
+            .body(())
+            .expect("valid request");
+        service.oneshot(request).await.expect("infallible");
+    }
+
+    fn requests(method: &str, code: &str) -> u64 {
+        RPC_REQUESTS.with_label_values(&[method, code]).get()
+    }
+
+    fn timed(method: &str) -> u64 {
+        RPC_DURATION.with_label_values(&[method]).get_sample_count()
+    }
+
+    #[tokio::test]
+    async fn requests_are_counted_and_timed_by_method_and_code() {
+        let path = "/arenabuddy.api.v1.MetricsTest/Succeeds";
+        call(http::Method::POST, path, "0").await;
+        call(http::Method::POST, path, "0").await;
+        call(http::Method::POST, path, "7").await;
+        assert_eq!(requests(path, "Ok"), 2);
+        assert_eq!(requests(path, "PermissionDenied"), 1);
+        assert_eq!(timed(path), 3);
+    }
+
+    #[tokio::test]
+    async fn unknown_methods_and_non_posts_mint_no_labels() {
+        let unknown = "/arenabuddy.api.v1.MetricsTest/Unknown";
+        let before = requests("other", "Unimplemented");
+        call(http::Method::POST, unknown, "12").await;
+        assert_eq!(requests(unknown, "Unimplemented"), 0);
+        assert!(requests("other", "Unimplemented") > before);
+
+        let preflight = "/arenabuddy.api.v1.MetricsTest/Preflight";
+        call(http::Method::OPTIONS, preflight, "0").await;
+        assert_eq!(requests(preflight, "Ok"), 0);
+        assert_eq!(timed(preflight), 0);
+    }
+}
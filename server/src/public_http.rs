@@ -0,0 +1,78 @@
+//! Plain HTTP endpoints: public shared match links plus the JSON gateway in [`crate::rest`].
+//!
+//! Scripts and browsers that don't speak native gRPC fetch data as JSON here.
+
+use std::net::SocketAddr;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use tower_http::cors::CorsLayer;
+use tracing::{error, info};
+
+use crate::{rest::Gateway, share_service::SharedMatchServiceImpl};
+
+pub(crate) fn status_code(status: &tonic::Status) -> StatusCode {
+    match status.code() {
+        tonic::Code::InvalidArgument | tonic::Code::FailedPrecondition | tonic::Code::OutOfRange => {
+            StatusCode::BAD_REQUEST
+        }
+        tonic::Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        tonic::Code::PermissionDenied => StatusCode::FORBIDDEN,
+        tonic::Code::NotFound => StatusCode::NOT_FOUND,
+        tonic::Code::AlreadyExists | tonic::Code::Aborted => StatusCode::CONFLICT,
+        tonic::Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        tonic::Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        tonic::Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+async fn get_shared_match(State(shares): State<SharedMatchServiceImpl>, Path(token): Path<String>) -> Response {
+    let mut response = match shares.shared_match(&token).await {
+        Ok(details) => Json(details).into_response(),
+        Err(status) => (status_code(&status), status.message().to_string()).into_response(),
+    };
+    // Share links are public and hold no cookies, so any origin may read them.
+    response
+        .headers_mut()
+        .insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+    response
+}
+
+fn router(shares: SharedMatchServiceImpl, gateway: Gateway, cors: CorsLayer) -> Router {
+    Router::new()
+        .route("/api/v1/shares/{token}", get(get_shared_match))
+        // Share links are unauthenticated, so they're limited by client IP like the gateway.
+        .route_layer(middleware::from_fn_with_state(gateway.clone(), crate::rest::rate_limit))
+        .with_state(shares)
+        .merge(crate::rest::router(gateway).layer(cors))
+}
+
+/// Serve the public HTTP endpoints on `addr` in the background.
+pub(crate) async fn spawn(
+    addr: SocketAddr,
+    shares: SharedMatchServiceImpl,
+    gateway: Gateway,
+    cors: CorsLayer,
+) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("Starting public HTTP server on {addr}");
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(
+            listener,
+            // Peer addresses key rate limits for unauthenticated gateway callers.
+            router(shares, gateway, cors).into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        {
+            error!("Public HTTP server failed: {e}");
+        }
+    });
+    Ok(())
+}
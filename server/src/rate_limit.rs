@@ -0,0 +1,510 @@
+//! Per-caller token-bucket rate limits and payload size limits.
+//!
+//! Every request takes a token from its caller's bucket for the request's [`Class`]. Callers are
+//! keyed by user id once authenticated and by client IP otherwise, which covers the auth RPCs
+//! that run before the caller has a token. Rejections are `ResourceExhausted` with a
+//! `retry-after` hint in seconds.
+//!
+//! Buckets live in two generations that rotate once every bucket could have refilled, so idle
+//! callers are forgotten without scanning the map and memory stays bounded under a flood of new
+//! callers.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tonic::{Request, Status, codegen::http, metadata::MetadataValue};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{auth::UserId, metrics::REQUESTS_REJECTED};
+
+/// Buckets per generation; a full generation rotates early, so at most twice this many are kept.
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+/// Rate-limit classes, each with its own quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Class {
+    Api,
+    /// Client uploads: match data, drafts, and parse error reports.
+    Upload,
+    /// Unauthenticated auth RPCs, keyed by IP.
+    Auth,
+}
+
+impl Class {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Class::Api => "api",
+            Class::Upload => "upload",
+            Class::Auth => "auth",
+        }
+    }
+
+    /// Classify a gRPC method path such as `/arenabuddy.api.v1.MatchService/UpsertMatchData`.
+    fn for_rpc(path: &str) -> Self {
+        match path {
+            "/arenabuddy.api.v1.MatchService/UpsertMatchData"
+            | "/arenabuddy.api.v1.DraftService/UpsertDraft"
+            | "/arenabuddy.api.v1.DebugService/ReportParseErrors" => Class::Upload,
+            _ if path.starts_with("/arenabuddy.api.v1.AuthService/") => Class::Auth,
+            _ => Class::Api,
+        }
+    }
+
+    /// Classify a JSON gateway route.
+    pub(crate) fn for_rest(method: &http::Method, path: &str) -> Self {
+        match (method, path) {
+            (&http::Method::POST, "/api/v1/matches" | "/api/v1/debug/parse-errors") => Class::Upload,
+            _ if path.starts_with("/api/v1/auth/") => Class::Auth,
+            _ => Class::Api,
+        }
+    }
+}
+
+/// A bucket holding up to `burst` tokens, refilled at `per_second` tokens a second.
+#[derive(Debug, Clone, Copy)]
+struct Quota {
+    burst: f64,
+    per_second: f64,
+}
+
+impl Quota {
+    /// Read `RATE_LIMIT_{name}_PER_MINUTE` and `RATE_LIMIT_{name}_BURST`. A rate of 0 disables
+    /// the limit.
+    fn from_env(name: &str, per_minute: u32, burst: u32) -> Option<Self> {
+        let read = |suffix: &str, default: u32| {
+            std::env::var(format!("RATE_LIMIT_{name}_{suffix}"))
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(default)
+        };
+        let per_minute = read("PER_MINUTE", per_minute);
+        let burst = read("BURST", burst).max(1);
+        (per_minute > 0).then(|| Quota {
+            burst: f64::from(burst),
+            per_second: f64::from(per_minute) / 60.0,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, quota: Quota, now: Instant) {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * quota.per_second).min(quota.burst);
+        self.updated = now;
+    }
+
+    /// Take a token, or return how long until one is available.
+    fn take(&mut self, quota: Quota, now: Instant) -> Result<(), Duration> {
+        self.refill(quota, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / quota.per_second))
+        }
+    }
+}
+
+/// Who a bucket belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Caller {
+    User(Uuid),
+    Ip(IpAddr),
+}
+
+/// Size limits for uploaded payloads, in bytes of JSON.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PayloadLimits {
+    /// Per game event log in `UpsertMatchData`.
+    pub(crate) event_log_bytes: usize,
+    /// Per reported event in `ReportParseErrors`.
+    pub(crate) parse_error_bytes: usize,
+    pub(crate) parse_errors_per_request: usize,
+}
+
+impl PayloadLimits {
+    fn from_env() -> Self {
+        let read =
+            |name: &str, default: usize| std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+        Self {
+            event_log_bytes: read("MAX_EVENT_LOG_BYTES", 2 * 1024 * 1024),
+            parse_error_bytes: read("MAX_PARSE_ERROR_BYTES", 256 * 1024),
+            parse_errors_per_request: read("MAX_PARSE_ERRORS_PER_REQUEST", 100),
+        }
+    }
+
+    /// Largest request body the REST gateway accepts: room for a best-of-three match with every
+    /// event log at its limit, or a full parse error report, doubled for JSON string escaping.
+    /// The per-payload limits above still apply once the body is parsed.
+    pub(crate) fn max_request_bytes(&self) -> usize {
+        let match_upload = self.event_log_bytes.saturating_mul(3);
+        let parse_errors = self.parse_errors_per_request.saturating_mul(self.parse_error_bytes);
+        match_upload.max(parse_errors).saturating_mul(2)
+    }
+}
+
+/// Reject a payload of `size` over `limit`, counting the rejection under `class`.
+pub(crate) fn check_payload(class: Class, what: &str, size: usize, limit: usize) -> Result<(), Status> {
+    if size <= limit {
+        return Ok(());
+    }
+    REQUESTS_REJECTED
+        .with_label_values(&[class.as_str(), "payload_too_large"])
+        .inc();
+    Err(Status::resource_exhausted(format!(
+        "{what} is {size} bytes, over the limit of {limit}"
+    )))
+}
+
+/// Reject a request carrying `count` items over `limit`, counting the rejection under `class`.
+pub(crate) fn check_count(class: Class, what: &str, count: usize, limit: usize) -> Result<(), Status> {
+    if count <= limit {
+        return Ok(());
+    }
+    REQUESTS_REJECTED
+        .with_label_values(&[class.as_str(), "too_many_items"])
+        .inc();
+    Err(Status::invalid_argument(format!(
+        "at most {limit} {what} may be sent at once"
+    )))
+}
+
+/// Buckets in two generations. A lookup moves its bucket into the current generation, and
+/// rotating drops the previous one whole. A generation lasts as long as the slowest quota takes
+/// to refill, so a dropped bucket was full and a new one behaves the same. When a generation
+/// fills up before then it rotates early, which may forget callers that were still limited.
+struct Buckets {
+    current: HashMap<(Caller, Class), Bucket>,
+    previous: HashMap<(Caller, Class), Bucket>,
+    rotated: Instant,
+    lifetime: Duration,
+    capacity: usize,
+}
+
+impl Buckets {
+    fn new(lifetime: Duration, capacity: usize, now: Instant) -> Self {
+        Self {
+            current: HashMap::new(),
+            previous: HashMap::new(),
+            rotated: now,
+            lifetime,
+            capacity,
+        }
+    }
+
+    /// The bucket for `key`, starting with `burst` tokens if it's new or was forgotten.
+    fn get(&mut self, key: (Caller, Class), burst: f64, now: Instant) -> &mut Bucket {
+        if self.current.len() >= self.capacity || now.duration_since(self.rotated) >= self.lifetime {
+            self.previous = std::mem::take(&mut self.current);
+            self.rotated = now;
+        }
+        let previous = self.previous.remove(&key);
+        self.current.entry(key).or_insert_with(|| {
+            previous.unwrap_or(Bucket {
+                tokens: burst,
+                updated: now,
+            })
+        })
+    }
+}
+
+struct Inner {
+    quotas: HashMap<Class, Quota>,
+    buckets: Mutex<Buckets>,
+    /// Key unauthenticated callers by the first `X-Forwarded-For` address, for deployments
+    /// behind a reverse proxy.
+    trust_forwarded_for: bool,
+    payload: PayloadLimits,
+}
+
+#[derive(Clone)]
+pub(crate) struct RateLimiter(Arc<Inner>);
+
+impl RateLimiter {
+    /// Quotas come from `RATE_LIMIT_{API,UPLOAD,AUTH}_{PER_MINUTE,BURST}`, payload limits from
+    /// `MAX_EVENT_LOG_BYTES`, `MAX_PARSE_ERROR_BYTES` and `MAX_PARSE_ERRORS_PER_REQUEST`.
+    pub(crate) fn from_env(trust_forwarded_for: bool) -> Self {
+        let quotas = [
+            (Class::Api, Quota::from_env("API", 600, 120)),
+            (Class::Upload, Quota::from_env("UPLOAD", 120, 60)),
+            (Class::Auth, Quota::from_env("AUTH", 30, 10)),
+        ]
+        .into_iter()
+        .filter_map(|(class, quota)| Some((class, quota?)))
+        .collect();
+        Self::new(
+            quotas,
+            trust_forwarded_for,
+            PayloadLimits::from_env(),
+            MAX_TRACKED_BUCKETS,
+        )
+    }
+
+    fn new(quotas: HashMap<Class, Quota>, trust_forwarded_for: bool, payload: PayloadLimits, capacity: usize) -> Self {
+        let lifetime = quotas
+            .values()
+            .map(|quota| Duration::from_secs_f64(quota.burst / quota.per_second))
+            .max()
+            .unwrap_or_default();
+        Self(Arc::new(Inner {
+            quotas,
+            buckets: Mutex::new(Buckets::new(lifetime, capacity, Instant::now())),
+            trust_forwarded_for,
+            payload,
+        }))
+    }
+
+    pub(crate) fn payload_limits(&self) -> PayloadLimits {
+        self.0.payload
+    }
+
+    /// Take a token for `caller`, or fail with `ResourceExhausted` and a `retry-after` hint.
+    pub(crate) fn check(&self, caller: Caller, class: Class) -> Result<(), Status> {
+        let Some(&quota) = self.0.quotas.get(&class) else {
+            return Ok(());
+        };
+        let now = Instant::now();
+
+        let result = {
+            let mut buckets = self.0.buckets.lock().expect("rate limit buckets poisoned");
+            buckets.get((caller, class), quota.burst, now).take(quota, now)
+        };
+
+        result.map_err(|wait| {
+            REQUESTS_REJECTED
+                .with_label_values(&[class.as_str(), "rate_limited"])
+                .inc();
+            let retry_after = (wait.as_secs() + u64::from(wait.subsec_nanos() > 0)).max(1);
+            info!(
+                "Rate limited {caller:?} ({}), retry after {retry_after}s",
+                class.as_str()
+            );
+            let mut status = Status::resource_exhausted(format!("rate limit exceeded, retry after {retry_after}s"));
+            status
+                .metadata_mut()
+                .insert("retry-after", MetadataValue::from(retry_after));
+            status
+        })
+    }
+
+    /// The address of an unauthenticated caller, given its `X-Forwarded-For` header and the
+    /// connection's peer address.
+    pub(crate) fn client_ip(&self, forwarded_for: Option<&str>, peer: Option<SocketAddr>) -> Option<IpAddr> {
+        let forwarded = forwarded_for
+            .filter(|_| self.0.trust_forwarded_for)
+            .and_then(|list| list.split(',').next()?.trim().parse().ok());
+        forwarded.or_else(|| peer.map(|addr| addr.ip()))
+    }
+
+    /// Rate-limit a gRPC request by its authenticated user, or by client IP before
+    /// authentication. The method comes from the [`RpcPath`] extension.
+    pub(crate) fn check_request<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let class = request
+            .extensions()
+            .get::<RpcPath>()
+            .map_or(Class::Api, |path| Class::for_rpc(&path.0));
+        let user = request.extensions().get::<UserId>().filter(|_| class != Class::Auth);
+        let caller = if let Some(user) = user {
+            Caller::User(user.0)
+        } else if let Some(ip) = self.client_ip(
+            request.metadata().get("x-forwarded-for").and_then(|v| v.to_str().ok()),
+            request.remote_addr(),
+        ) {
+            Caller::Ip(ip)
+        } else {
+            warn!("No client address for rate limiting; allowing request");
+            return Ok(());
+        };
+        self.check(caller, class)
+    }
+}
+
+/// The gRPC method of a request, e.g. `/arenabuddy.api.v1.MatchService/UpsertMatchData`.
+/// Interceptors don't see the request URI, so [`tag_rpc_path`] copies it into the extensions.
+#[derive(Debug, Clone)]
+pub(crate) struct RpcPath(String);
+
+pub(crate) fn tag_rpc_path(mut request: http::Request<tonic::body::Body>) -> http::Request<tonic::body::Body> {
+    let path = RpcPath(request.uri().path().to_string());
+    request.extensions_mut().insert(path);
+    request
+}
+
+/// Interceptor for services that authenticate callers themselves, such as the auth service.
+pub(crate) fn rate_limit_interceptor(
+    limiter: RateLimiter,
+) -> impl Fn(Request<()>) -> Result<Request<()>, Status> + Clone {
+    move |request: Request<()>| {
+        limiter.check_request(&request)?;
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: PayloadLimits = PayloadLimits {
+        event_log_bytes: 1024,
+        parse_error_bytes: 256,
+        parse_errors_per_request: 10,
+    };
+
+    /// A limiter allowing bursts of `burst` API requests, refilled at one token a second.
+    fn limiter(burst: f64, trust_forwarded_for: bool) -> RateLimiter {
+        let quotas = HashMap::from([(Class::Api, Quota { burst, per_second: 1.0 })]);
+        RateLimiter::new(quotas, trust_forwarded_for, LIMITS, MAX_TRACKED_BUCKETS)
+    }
+
+    fn tracked(buckets: &Buckets) -> usize {
+        buckets.current.len() + buckets.previous.len()
+    }
+
+    fn ip(last: u8) -> Caller {
+        Caller::Ip(IpAddr::from([192, 0, 2, last]))
+    }
+
+    #[test]
+    fn methods_are_classified() {
+        assert_eq!(
+            Class::for_rpc("/arenabuddy.api.v1.MatchService/UpsertMatchData"),
+            Class::Upload
+        );
+        assert_eq!(Class::for_rpc("/arenabuddy.api.v1.AuthService/Login"), Class::Auth);
+        assert_eq!(
+            Class::for_rpc("/arenabuddy.api.v1.MatchService/ListMatches"),
+            Class::Api
+        );
+        assert_eq!(Class::for_rest(&http::Method::POST, "/api/v1/matches"), Class::Upload);
+        assert_eq!(Class::for_rest(&http::Method::GET, "/api/v1/matches"), Class::Api);
+        assert_eq!(
+            Class::for_rest(&http::Method::POST, "/api/v1/auth/refresh"),
+            Class::Auth
+        );
+    }
+
+    #[test]
+    fn buckets_refill_over_time() {
+        let quota = Quota {
+            burst: 2.0,
+            per_second: 1.0,
+        };
+        let start = Instant::now();
+        let mut bucket = Bucket {
+            tokens: 2.0,
+            updated: start,
+        };
+        assert!(bucket.take(quota, start).is_ok());
+        assert!(bucket.take(quota, start).is_ok());
+        assert_eq!(bucket.take(quota, start), Err(Duration::from_secs(1)));
+        assert!(bucket.take(quota, start + Duration::from_secs(1)).is_ok());
+
+        bucket.refill(quota, start + Duration::from_mins(1));
+        assert!(
+            (bucket.tokens - quota.burst).abs() < f64::EPSILON,
+            "refill is capped at the burst"
+        );
+    }
+
+    #[test]
+    fn callers_are_limited_separately_with_a_retry_hint() {
+        let limiter = limiter(2.0, false);
+        let rejected = || REQUESTS_REJECTED.with_label_values(&["api", "rate_limited"]).get();
+        let before = rejected();
+
+        assert!(limiter.check(ip(1), Class::Api).is_ok());
+        assert!(limiter.check(ip(1), Class::Api).is_ok());
+        let status = limiter.check(ip(1), Class::Api).expect_err("burst is spent");
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(status.metadata().get("retry-after").expect("retry hint"), "1");
+        assert!(rejected() > before);
+
+        assert!(limiter.check(ip(2), Class::Api).is_ok());
+        assert!(
+            limiter.check(ip(1), Class::Upload).is_ok(),
+            "classes without a quota are unlimited"
+        );
+    }
+
+    #[test]
+    fn idle_buckets_are_forgotten_when_generations_rotate() {
+        let start = Instant::now();
+        let lifetime = Duration::from_secs(10);
+        let mut buckets = Buckets::new(lifetime, 100, start);
+        buckets.get((ip(1), Class::Api), 5.0, start).tokens = 0.0;
+        buckets.get((ip(2), Class::Api), 5.0, start).tokens = 0.0;
+
+        // One rotation keeps every bucket; a caller seen since survives the next.
+        let later = start + lifetime;
+        assert!(buckets.get((ip(1), Class::Api), 5.0, later).tokens < 1.0);
+        assert_eq!(tracked(&buckets), 2);
+        let much_later = later + lifetime;
+        assert!(buckets.get((ip(1), Class::Api), 5.0, much_later).tokens < 1.0);
+        assert_eq!(tracked(&buckets), 1);
+        assert!((buckets.get((ip(2), Class::Api), 5.0, much_later).tokens - 5.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn tracked_buckets_are_bounded() {
+        let start = Instant::now();
+        let mut buckets = Buckets::new(Duration::from_mins(1), 4, start);
+        for last in 0..100 {
+            buckets.get((ip(last), Class::Api), 5.0, start);
+            assert!(tracked(&buckets) <= 8);
+        }
+    }
+
+    #[test]
+    fn forwarded_for_is_only_trusted_when_configured() {
+        let peer = Some(SocketAddr::from(([203, 0, 113, 7], 443)));
+        let forwarded = Some("198.51.100.1, 10.0.0.1");
+
+        let direct = limiter(1.0, false);
+        assert_eq!(direct.client_ip(forwarded, peer), Some(IpAddr::from([203, 0, 113, 7])));
+
+        let proxied = limiter(1.0, true);
+        assert_eq!(
+            proxied.client_ip(forwarded, peer),
+            Some(IpAddr::from([198, 51, 100, 1]))
+        );
+        assert_eq!(
+            proxied.client_ip(Some("garbage"), peer),
+            Some(IpAddr::from([203, 0, 113, 7]))
+        );
+        assert_eq!(proxied.client_ip(None, None), None);
+    }
+
+    #[test]
+    fn oversized_payloads_and_counts_are_rejected_and_counted() {
+        let rejected = |reason: &str| REQUESTS_REJECTED.with_label_values(&["upload", reason]).get();
+        let (payloads, counts) = (rejected("payload_too_large"), rejected("too_many_items"));
+
+        assert!(check_payload(Class::Upload, "event log", 1024, LIMITS.event_log_bytes).is_ok());
+        let status = check_payload(Class::Upload, "event log", 1025, LIMITS.event_log_bytes).expect_err("too large");
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+
+        assert!(check_count(Class::Upload, "parse errors", 10, LIMITS.parse_errors_per_request).is_ok());
+        let status =
+            check_count(Class::Upload, "parse errors", 11, LIMITS.parse_errors_per_request).expect_err("too many");
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        assert!(rejected("payload_too_large") > payloads);
+        assert!(rejected("too_many_items") > counts);
+    }
+
+    #[test]
+    fn request_limit_fits_a_best_of_three_upload() {
+        assert_eq!(LIMITS.max_request_bytes(), 3 * 1024 * 2);
+    }
+}
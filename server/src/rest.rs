@@ -0,0 +1,520 @@
+//! JSON gateway over the Match, Auth, and Debug gRPC services.
+//!
+//! Each route takes the protobuf request message as JSON (or query parameters for reads), calls
+//! the same service implementation tonic serves, and returns the response message as JSON. Routes
+//! behind the auth interceptor on the gRPC side require the same `Authorization: Bearer` header.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use arenabuddy_core::services::{
+    auth_service::{
+        ExchangeTokenRequest, ExchangeTokenResponse, GetCurrentUserRequest, GetCurrentUserResponse,
+        LinkIdentityRequest, LinkIdentityResponse, ListIdentityProvidersRequest, ListIdentityProvidersResponse,
+        LogoutRequest, LogoutResponse, RefreshTokenRequest, RefreshTokenResponse, RegisterLocalUserRequest,
+        RegisterLocalUserResponse, UnlinkIdentityRequest, UnlinkIdentityResponse, auth_service_server::AuthService,
+    },
+    debug_service::{
+        ListParseErrorGroupsRequest, ListParseErrorGroupsResponse, ReportParseErrorsRequest, ReportParseErrorsResponse,
+        ResolveParseErrorGroupRequest, ResolveParseErrorGroupResponse, debug_service_server::DebugService,
+    },
+    match_service::{
+        ClassifyMatchRequest, ClassifyMatchResponse, DeleteMatchRequest, DeleteMatchResponse, GetMatchDataRequest,
+        GetMatchDataResponse, GetMatchStatsRequest, GetMatchStatsResponse, ListMatchSummariesRequest,
+        ListMatchSummariesResponse, ListMatchesRequest, ListMatchesResponse, SetArchetypeOverrideRequest,
+        SetArchetypeOverrideResponse, UpsertMatchDataRequest, UpsertMatchDataResponse,
+        match_service_server::MatchService,
+    },
+};
+use axum::{
+    Json, Router,
+    extract::{ConnectInfo, DefaultBodyLimit, Path, Query, State},
+    http::{HeaderMap, HeaderValue, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+};
+use tonic::{Extensions, Request, Status, metadata::MetadataMap};
+
+use crate::{
+    auth::{AuthServiceImpl, authenticate, validate_jwt},
+    debug_service::DebugServiceImpl,
+    match_service::MatchServiceImpl,
+    public_http::status_code,
+    rate_limit::{Caller, Class, RateLimiter},
+};
+
+#[derive(Clone)]
+pub(crate) struct Gateway {
+    pub(crate) matches: MatchServiceImpl,
+    pub(crate) debug: DebugServiceImpl,
+    pub(crate) auth: AuthServiceImpl,
+    pub(crate) jwt_secret: Arc<str>,
+    pub(crate) limiter: RateLimiter,
+}
+
+impl Gateway {
+    /// Wrap `message` in a tonic request carrying the HTTP headers as metadata.
+    fn request<T>(headers: &HeaderMap, message: T) -> Request<T> {
+        Request::from_parts(MetadataMap::from_headers(headers.clone()), Extensions::new(), message)
+    }
+
+    /// Like [`Self::request`], but rejected unless the bearer token is valid.
+    fn authenticated<T>(&self, headers: &HeaderMap, message: T) -> Result<Request<T>, Status> {
+        authenticate(Self::request(headers, message), &self.jwt_secret)
+    }
+}
+
+/// A gRPC status rendered as `{"code": <grpc code>, "message": ...}` with the matching HTTP status.
+struct ApiError(Status);
+
+impl From<Status> for ApiError {
+    fn from(status: Status) -> Self {
+        Self(status)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "code": self.0.code() as i32,
+            "message": self.0.message(),
+        });
+        let mut response = (status_code(&self.0), Json(body)).into_response();
+        if let Some(retry_after) = self.0.metadata().get("retry-after")
+            && let Ok(value) = HeaderValue::from_bytes(retry_after.as_bytes())
+        {
+            response.headers_mut().insert(header::RETRY_AFTER, value);
+        }
+        response
+    }
+}
+
+/// Rate-limit HTTP requests by the bearer token's user, or by client IP for auth routes and
+/// unauthenticated callers.
+pub(crate) async fn rate_limit(State(gw): State<Gateway>, request: axum::extract::Request, next: Next) -> Response {
+    let class = Class::for_rest(request.method(), request.uri().path());
+    let headers = request.headers();
+    let user_id = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok()?.strip_prefix("Bearer "))
+        .filter(|_| class != Class::Auth)
+        .and_then(|token| validate_jwt(token, &gw.jwt_secret).ok()?.sub.parse().ok());
+
+    let caller = match user_id {
+        Some(user_id) => Some(Caller::User(user_id)),
+        None => gw
+            .limiter
+            .client_ip(
+                headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()),
+                request.extensions().get::<ConnectInfo<SocketAddr>>().map(|info| info.0),
+            )
+            .map(Caller::Ip),
+    };
+    if let Some(caller) = caller
+        && let Err(status) = gw.limiter.check(caller, class)
+    {
+        return ApiError(status).into_response();
+    }
+    next.run(request).await
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+// Auth. The auth service checks bearer tokens itself on the routes that need them.
+
+async fn exchange_token(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Json(body): Json<ExchangeTokenRequest>,
+) -> ApiResult<ExchangeTokenResponse> {
+    let response = gw.auth.exchange_token(Gateway::request(&headers, body)).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn refresh_token(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Json(body): Json<RefreshTokenRequest>,
+) -> ApiResult<RefreshTokenResponse> {
+    let response = gw.auth.refresh_token(Gateway::request(&headers, body)).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn logout(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Json(body): Json<LogoutRequest>,
+) -> ApiResult<LogoutResponse> {
+    let response = gw.auth.logout(Gateway::request(&headers, body)).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn register_local_user(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Json(body): Json<RegisterLocalUserRequest>,
+) -> ApiResult<RegisterLocalUserResponse> {
+    let response = gw.auth.register_local_user(Gateway::request(&headers, body)).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn list_identity_providers(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+) -> ApiResult<ListIdentityProvidersResponse> {
+    let request = Gateway::request(&headers, ListIdentityProvidersRequest {});
+    let response = gw.auth.list_identity_providers(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn get_current_user(State(gw): State<Gateway>, headers: HeaderMap) -> ApiResult<GetCurrentUserResponse> {
+    let request = Gateway::request(&headers, GetCurrentUserRequest {});
+    let response = gw.auth.get_current_user(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn link_identity(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Json(body): Json<LinkIdentityRequest>,
+) -> ApiResult<LinkIdentityResponse> {
+    let response = gw.auth.link_identity(Gateway::request(&headers, body)).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn unlink_identity(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Path((provider, subject)): Path<(String, String)>,
+) -> ApiResult<UnlinkIdentityResponse> {
+    let request = Gateway::request(&headers, UnlinkIdentityRequest { provider, subject });
+    let response = gw.auth.unlink_identity(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+// Matches
+
+async fn list_matches(State(gw): State<Gateway>, headers: HeaderMap) -> ApiResult<ListMatchesResponse> {
+    let request = gw.authenticated(&headers, ListMatchesRequest {})?;
+    let response = gw.matches.list_matches(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn upsert_match_data(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Json(body): Json<UpsertMatchDataRequest>,
+) -> ApiResult<UpsertMatchDataResponse> {
+    let request = gw.authenticated(&headers, body)?;
+    let response = gw.matches.upsert_match_data(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn list_match_summaries(State(gw): State<Gateway>, headers: HeaderMap) -> ApiResult<ListMatchSummariesResponse> {
+    let request = gw.authenticated(&headers, ListMatchSummariesRequest {})?;
+    let response = gw.matches.list_match_summaries(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn get_match_stats(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Query(query): Query<GetMatchStatsRequest>,
+) -> ApiResult<GetMatchStatsResponse> {
+    let request = gw.authenticated(&headers, query)?;
+    let response = gw.matches.get_match_stats(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn get_match_data(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Path(match_id): Path<String>,
+    Query(query): Query<GetMatchDataRequest>,
+) -> ApiResult<GetMatchDataResponse> {
+    let request = gw.authenticated(&headers, GetMatchDataRequest { match_id, ..query })?;
+    let response = gw.matches.get_match_data(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn delete_match(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Path(match_id): Path<String>,
+) -> ApiResult<DeleteMatchResponse> {
+    let request = gw.authenticated(&headers, DeleteMatchRequest { match_id })?;
+    let response = gw.matches.delete_match(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn classify_match(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Path(match_id): Path<String>,
+) -> ApiResult<ClassifyMatchResponse> {
+    let request = gw.authenticated(&headers, ClassifyMatchRequest { match_id })?;
+    let response = gw.matches.classify_match(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn set_archetype_override(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Path((match_id, side)): Path<(String, String)>,
+    Json(body): Json<SetArchetypeOverrideRequest>,
+) -> ApiResult<SetArchetypeOverrideResponse> {
+    let request = gw.authenticated(&headers, SetArchetypeOverrideRequest { match_id, side, ..body })?;
+    let response = gw.matches.set_archetype_override(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+// Debug
+
+async fn report_parse_errors(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Json(body): Json<ReportParseErrorsRequest>,
+) -> ApiResult<ReportParseErrorsResponse> {
+    let request = gw.authenticated(&headers, body)?;
+    let response = gw.debug.report_parse_errors(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn list_parse_error_groups(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Query(query): Query<ListParseErrorGroupsRequest>,
+) -> ApiResult<ListParseErrorGroupsResponse> {
+    let request = gw.authenticated(&headers, query)?;
+    let response = gw.debug.list_parse_error_groups(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+async fn resolve_parse_error_group(
+    State(gw): State<Gateway>,
+    headers: HeaderMap,
+    Path(fingerprint): Path<String>,
+    Json(body): Json<ResolveParseErrorGroupRequest>,
+) -> ApiResult<ResolveParseErrorGroupResponse> {
+    let request = gw.authenticated(&headers, ResolveParseErrorGroupRequest { fingerprint, ..body })?;
+    let response = gw.debug.resolve_parse_error_group(request).await?;
+    Ok(Json(response.into_inner()))
+}
+
+pub(crate) fn router(gateway: Gateway) -> Router {
+    Router::new()
+        .route("/api/v1/auth/token", post(exchange_token))
+        .route("/api/v1/auth/refresh", post(refresh_token))
+        .route("/api/v1/auth/logout", post(logout))
+        .route("/api/v1/auth/register", post(register_local_user))
+        .route("/api/v1/auth/providers", get(list_identity_providers))
+        .route("/api/v1/auth/me", get(get_current_user))
+        .route("/api/v1/auth/identities", post(link_identity))
+        .route("/api/v1/auth/identities/{provider}/{subject}", delete(unlink_identity))
+        .route("/api/v1/matches", get(list_matches).post(upsert_match_data))
+        .route("/api/v1/matches/summaries", get(list_match_summaries))
+        .route("/api/v1/matches/stats", get(get_match_stats))
+        .route("/api/v1/matches/{match_id}", get(get_match_data).delete(delete_match))
+        .route("/api/v1/matches/{match_id}/classify", post(classify_match))
+        .route(
+            "/api/v1/matches/{match_id}/archetypes/{side}",
+            put(set_archetype_override),
+        )
+        .route("/api/v1/debug/parse-errors", post(report_parse_errors))
+        .route("/api/v1/debug/parse-error-groups", get(list_parse_error_groups))
+        .route(
+            "/api/v1/debug/parse-error-groups/{fingerprint}/resolve",
+            post(resolve_parse_error_group),
+        )
+        .route_layer(middleware::from_fn_with_state(gateway.clone(), rate_limit))
+        // Axum's 2 MB default would reject match uploads the payload limits allow.
+        .layer(DefaultBodyLimit::max(
+            gateway.limiter.payload_limits().max_request_bytes(),
+        ))
+        .with_state(gateway)
+}
+
+#[cfg(test)]
+mod tests {
+    use arenabuddy_core::cards::CardsDatabase;
+    use arenabuddy_data::testing::test_db;
+    use axum::{
+        body::{Body, to_bytes},
+        http::{Method, StatusCode},
+    };
+    use tonic::metadata::MetadataValue;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{
+        auth::{Admins, AuthConfig},
+        export::Exporter,
+        identity::{IdentityProviders, LocalProvider},
+        webhooks::Webhooks,
+    };
+
+    const JWT_SECRET: &str = "rest-gateway-test-secret";
+
+    async fn gateway() -> Option<Gateway> {
+        let db = test_db().await?;
+        let cards = CardsDatabase::default();
+        let limiter = RateLimiter::from_env(false);
+        let mut providers = IdentityProviders::default();
+        providers.register_local(LocalProvider::new(db.clone(), true));
+        let config = Arc::new(AuthConfig {
+            jwt_secret: JWT_SECRET.to_string(),
+        });
+
+        Some(Gateway {
+            matches: MatchServiceImpl {
+                db: db.clone(),
+                cards: cards.clone(),
+                exporter: Exporter::new(db.clone(), cards.clone(), std::env::temp_dir(), None),
+                webhooks: Webhooks::new(db.clone(), cards),
+                payload_limits: limiter.payload_limits(),
+            },
+            debug: DebugServiceImpl {
+                db: db.clone(),
+                admins: Admins::from_env().expect("ADMIN_USER_IDS should be unset or valid"),
+                payload_limits: limiter.payload_limits(),
+            },
+            auth: AuthServiceImpl::new(db, config, providers),
+            jwt_secret: JWT_SECRET.into(),
+            limiter,
+        })
+    }
+
+    async fn call(
+        router: &Router,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Body,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let response = router
+            .clone()
+            .oneshot(request.body(body).expect("request"))
+            .await
+            .expect("infallible");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.expect("body");
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn api_errors_carry_the_grpc_code_and_http_status() {
+        let response = ApiError(Status::not_found("match not found")).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = to_bytes(response.into_body(), usize::MAX).await.expect("body");
+        let body: serde_json::Value = serde_json::from_slice(&body).expect("json");
+        assert_eq!(body["code"], tonic::Code::NotFound as i32);
+        assert_eq!(body["message"], "match not found");
+
+        let mut status = Status::resource_exhausted("slow down");
+        status
+            .metadata_mut()
+            .insert("retry-after", MetadataValue::from_static("7"));
+        let response = ApiError(status).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "7");
+
+        let response = ApiError(Status::invalid_argument("bad")).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = ApiError(Status::internal("boom")).into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn routes_reach_the_services_behind_them() {
+        let Some(gateway) = gateway().await else { return };
+        let router = router(gateway);
+
+        let (status, body) = call(&router, Method::GET, "/api/v1/auth/providers", None, Body::empty()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["providers"], serde_json::json!(["local"]));
+
+        let register = serde_json::json!({"username": "alice", "password": "correct horse"}).to_string();
+        let (status, body) = call(&router, Method::POST, "/api/v1/auth/register", None, register.into()).await;
+        assert_eq!(status, StatusCode::OK);
+        let token = body["access_token"].as_str().expect("access token").to_string();
+
+        let (status, body) = call(&router, Method::GET, "/api/v1/auth/me", Some(&token), Body::empty()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["user"]["username"], "alice");
+
+        let (status, body) = call(&router, Method::GET, "/api/v1/matches", Some(&token), Body::empty()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["matches"], serde_json::json!([]));
+
+        let (status, _) = call(&router, Method::GET, "/api/v1/nope", Some(&token), Body::empty()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn service_errors_map_to_http_statuses() {
+        let Some(gateway) = gateway().await else { return };
+        let router = router(gateway);
+
+        let (status, body) = call(&router, Method::GET, "/api/v1/matches", None, Body::empty()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["code"], tonic::Code::Unauthenticated as i32);
+
+        let (status, _) = call(
+            &router,
+            Method::GET,
+            "/api/v1/matches",
+            Some("not-a-jwt"),
+            Body::empty(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let register = serde_json::json!({"username": "bob", "password": "correct horse"}).to_string();
+        let (_, body) = call(
+            &router,
+            Method::POST,
+            "/api/v1/auth/register",
+            None,
+            register.clone().into(),
+        )
+        .await;
+        let token = body["access_token"].as_str().expect("access token").to_string();
+        let (status, body) = call(&router, Method::POST, "/api/v1/auth/register", None, register.into()).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], tonic::Code::AlreadyExists as i32);
+
+        let groups = "/api/v1/debug/parse-error-groups";
+        let (status, body) = call(&router, Method::GET, groups, Some(&token), Body::empty()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["code"], tonic::Code::PermissionDenied as i32);
+
+        let missing = format!("/api/v1/matches/{}", uuid::Uuid::new_v4());
+        let (status, body) = call(&router, Method::GET, &missing, Some(&token), Body::empty()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], tonic::Code::NotFound as i32);
+    }
+
+    #[tokio::test]
+    async fn bodies_over_axums_default_limit_reach_the_handler() {
+        let Some(gateway) = gateway().await else { return };
+        let limit = gateway.limiter.payload_limits().max_request_bytes();
+        assert!(limit > 2 * 1024 * 1024);
+        let router = router(gateway);
+
+        // Not valid JSON, so getting past the body limit shows up as a 400 rather than a 413.
+        let large = vec![b' '; 3 * 1024 * 1024];
+        let (status, _) = call(&router, Method::POST, "/api/v1/matches", None, large.into()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let oversized = vec![b' '; limit + 1];
+        let (status, _) = call(&router, Method::POST, "/api/v1/matches", None, oversized.into()).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use arenabuddy_core::{
+    cards::CardsDatabase,
+    models::ArenaId,
+    services::share_service::{
+        CreateMatchShareRequest, CreateMatchShareResponse, GetSharedMatchRequest, GetSharedMatchResponse,
+        ListMatchSharesRequest, ListMatchSharesResponse, MatchShare as MatchShareProto, RevokeMatchShareRequest,
+        RevokeMatchShareResponse, share_service_server::ShareService, shared_match_service_server::SharedMatchService,
+    },
+};
+use arenabuddy_data::{MatchDB, ShareRepository, share_repository::MatchShare};
+use tonic::{Request, Response, Status};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    auth::{generate_opaque_token, hash_token, require_user_id},
+    match_service::load_match_data,
+};
+
+const DEFAULT_SHARE_HOURS: i32 = 24 * 7;
+const MAX_SHARE_HOURS: i32 = 24 * 90;
+
+pub(crate) struct ShareServiceImpl {
+    pub(crate) db: MatchDB,
+}
+
+/// Serves shared matches without authentication; the share token is the credential.
+#[derive(Clone)]
+pub(crate) struct SharedMatchServiceImpl {
+    pub(crate) db: MatchDB,
+    pub(crate) cards: CardsDatabase,
+}
+
+fn share_to_proto(share: &MatchShare) -> MatchShareProto {
+    MatchShareProto {
+        id: share.id.to_string(),
+        match_id: share.match_id.to_string(),
+        created_at: share.created_at.to_rfc3339(),
+        expires_at: share.expires_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+        revoked: share.revoked_at.is_some(),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, Status> {
+    value
+        .parse()
+        .map_err(|_| Status::invalid_argument(format!("{field} must be a valid UUID")))
+}
+
+#[tonic::async_trait]
+impl ShareService for ShareServiceImpl {
+    #[instrument(skip(self, request))]
+    async fn create_match_share(
+        &self,
+        request: Request<CreateMatchShareRequest>,
+    ) -> Result<Response<CreateMatchShareResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let req = request.into_inner();
+        let match_id = parse_uuid(&req.match_id, "match_id")?.to_string();
+
+        let hours = if req.expires_in_hours <= 0 {
+            DEFAULT_SHARE_HOURS
+        } else {
+            req.expires_in_hours.min(MAX_SHARE_HOURS)
+        };
+        let expires_at = chrono::Utc::now() + chrono::Duration::hours(i64::from(hours));
+
+        let token = generate_opaque_token();
+        let share = self
+            .db
+            .create_match_share(user_id, &match_id, &hash_token(&token), Some(expires_at))
+            .await
+            .map_err(|e| {
+                error!("Failed to create match share: {e}");
+                Status::internal("failed to create share link")
+            })?
+            .ok_or_else(|| Status::not_found(format!("match not found: {match_id}")))?;
+
+        info!("User {user_id} shared match {match_id} until {expires_at}");
+        Ok(Response::new(CreateMatchShareResponse {
+            share: Some(share_to_proto(&share)),
+            token,
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn list_match_shares(
+        &self,
+        request: Request<ListMatchSharesRequest>,
+    ) -> Result<Response<ListMatchSharesResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let match_id = request.into_inner().match_id;
+        let match_id = if match_id.is_empty() {
+            None
+        } else {
+            Some(parse_uuid(&match_id, "match_id")?.to_string())
+        };
+
+        let shares = self
+            .db
+            .list_match_shares(user_id, match_id.as_deref())
+            .await
+            .map_err(|e| {
+                error!("Failed to list match shares: {e}");
+                Status::internal("failed to list share links")
+            })?;
+
+        Ok(Response::new(ListMatchSharesResponse {
+            shares: shares.iter().map(share_to_proto).collect(),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn revoke_match_share(
+        &self,
+        request: Request<RevokeMatchShareRequest>,
+    ) -> Result<Response<RevokeMatchShareResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let share_id = parse_uuid(&request.into_inner().share_id, "share_id")?;
+
+        let revoked = self.db.revoke_match_share(user_id, share_id).await.map_err(|e| {
+            error!("Failed to revoke match share: {e}");
+            Status::internal("failed to revoke share link")
+        })?;
+        if !revoked {
+            return Err(Status::not_found("share link not found"));
+        }
+
+        info!("User {user_id} revoked share {share_id}");
+        Ok(Response::new(RevokeMatchShareResponse {}))
+    }
+}
+
+impl SharedMatchServiceImpl {
+    /// Resolve a share token to the shared match. Unknown, expired and revoked tokens
+    /// are indistinguishable to the caller.
+    pub(crate) async fn shared_match(&self, token: &str) -> Result<GetSharedMatchResponse, Status> {
+        if token.is_empty() {
+            return Err(Status::invalid_argument("token is required"));
+        }
+
+        let share = self
+            .db
+            .find_active_match_share(&hash_token(token))
+            .await
+            .map_err(|e| {
+                error!("Failed to look up match share: {e}");
+                Status::internal("failed to load shared match")
+            })?
+            .ok_or_else(|| Status::not_found("share link not found or expired"))?;
+
+        let match_data = load_match_data(&self.db, &share.match_id.to_string(), Some(share.user_id)).await?;
+
+        let mut card_names = BTreeMap::new();
+        let deck_cards = match_data
+            .decks
+            .iter()
+            .flat_map(|deck| deck.mainboard().iter().chain(deck.sideboard()).copied());
+        let opponent_cards = match_data.opponent_deck.cards.iter().map(ArenaId::inner);
+        for id in deck_cards.chain(opponent_cards) {
+            if let Some(card) = self.cards.get(&id) {
+                card_names.entry(id).or_insert_with(|| card.name.clone());
+            }
+        }
+
+        Ok(GetSharedMatchResponse {
+            match_data: Some((&match_data).into()),
+            card_names,
+            expires_at: share.expires_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl SharedMatchService for SharedMatchServiceImpl {
+    #[instrument(skip(self, request))]
+    async fn get_shared_match(
+        &self,
+        request: Request<GetSharedMatchRequest>,
+    ) -> Result<Response<GetSharedMatchResponse>, Status> {
+        let token = request.into_inner().token;
+        Ok(Response::new(self.shared_match(&token).await?))
+    }
+}
@@ -0,0 +1,743 @@
+use arenabuddy_core::{
+    display::stats::TimeWindow,
+    services::team_service::{
+        ArchetypeMatchupRecord, CreateTeamRequest, CreateTeamResponse, GetTeamRequest, GetTeamResponse,
+        GetTeamStatsRequest, GetTeamStatsResponse, JoinTeamRequest, JoinTeamResponse, LeaveTeamRequest,
+        LeaveTeamResponse, ListTeamDraftsRequest, ListTeamDraftsResponse, ListTeamMatchesRequest,
+        ListTeamMatchesResponse, ListTeamsRequest, ListTeamsResponse, MemberRecord, RemoveMemberRequest,
+        RemoveMemberResponse, RotateInviteCodeRequest, RotateInviteCodeResponse, SetMemberRoleRequest,
+        SetMemberRoleResponse, Team, TeamDraft, TeamMatch, TeamMember, TeamRole as TeamRoleProto, TeamStats,
+        UpdateSharingRequest, UpdateSharingResponse, team_service_server::TeamService,
+    },
+};
+use arenabuddy_data::{
+    MatchDB, TeamRepository,
+    team_models::{self, TeamMembership, TeamRole},
+};
+use tonic::{Request, Response, Status};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+use crate::auth::require_user_id;
+
+const MAX_TEAM_NAME_LEN: usize = 64;
+const DEFAULT_LIST_LIMIT: i32 = 50;
+const MAX_LIST_LIMIT: i32 = 200;
+/// 32 symbols without look-alikes (0/O, 1/I) so a byte maps onto it without modulo bias.
+const INVITE_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const INVITE_CODE_LEN: usize = 8;
+const INVITE_CODE_ATTEMPTS: usize = 5;
+
+pub(crate) struct TeamServiceImpl {
+    pub(crate) db: MatchDB,
+}
+
+impl TeamServiceImpl {
+    /// Load the caller's membership, failing with `NotFound` for non-members so team ids
+    /// cannot be probed.
+    async fn membership(&self, team_id: Uuid, user_id: Uuid) -> Result<TeamMembership, Status> {
+        self.db
+            .get_membership(team_id, user_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to load team membership: {e}");
+                Status::internal("failed to load team")
+            })?
+            .ok_or_else(|| Status::not_found("team not found"))
+    }
+
+    async fn managed_membership(&self, team_id: Uuid, user_id: Uuid) -> Result<TeamMembership, Status> {
+        let membership = self.membership(team_id, user_id).await?;
+        if !membership.role.can_manage() {
+            return Err(Status::permission_denied("only team owners and admins can do that"));
+        }
+        Ok(membership)
+    }
+
+    async fn target_role(&self, team_id: Uuid, user_id: Uuid) -> Result<TeamRole, Status> {
+        Ok(self.membership(team_id, user_id).await?.role)
+    }
+}
+
+fn generate_invite_code() -> String {
+    let mut bytes = [0u8; INVITE_CODE_LEN];
+    rand::fill(&mut bytes);
+    bytes
+        .iter()
+        .map(|b| char::from(INVITE_ALPHABET[usize::from(*b) % INVITE_ALPHABET.len()]))
+        .collect()
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, Status> {
+    value
+        .parse()
+        .map_err(|_| Status::invalid_argument(format!("{field} must be a valid UUID")))
+}
+
+fn role_to_proto(role: TeamRole) -> TeamRoleProto {
+    match role {
+        TeamRole::Owner => TeamRoleProto::Owner,
+        TeamRole::Admin => TeamRoleProto::Admin,
+        TeamRole::Member => TeamRoleProto::Member,
+    }
+}
+
+fn team_to_proto(membership: &TeamMembership) -> Team {
+    Team {
+        id: membership.team.id.to_string(),
+        name: membership.team.name.clone(),
+        invite_code: if membership.role.can_manage() {
+            membership.team.invite_code.clone()
+        } else {
+            String::new()
+        },
+        member_count: i32::try_from(membership.member_count).unwrap_or(i32::MAX),
+        role: role_to_proto(membership.role).into(),
+        share_matches: membership.share_matches,
+        share_drafts: membership.share_drafts,
+    }
+}
+
+fn member_to_proto(member: &team_models::TeamMember) -> TeamMember {
+    TeamMember {
+        user_id: member.user_id.to_string(),
+        username: member.username.clone(),
+        avatar_url: member.avatar_url.clone().unwrap_or_default(),
+        role: role_to_proto(member.role).into(),
+        share_matches: member.share_matches,
+        share_drafts: member.share_drafts,
+        joined_at: member.joined_at.to_rfc3339(),
+    }
+}
+
+/// `limit` from a list request, defaulted and capped.
+fn list_limit(limit: i32) -> i64 {
+    i64::from(if limit <= 0 {
+        DEFAULT_LIST_LIMIT
+    } else {
+        limit.min(MAX_LIST_LIMIT)
+    })
+}
+
+fn team_match_to_proto(team_match: &team_models::TeamMatch) -> TeamMatch {
+    let summary = &team_match.summary;
+    TeamMatch {
+        match_id: summary.id.clone(),
+        user_id: team_match.user_id.to_string(),
+        username: team_match.username.clone(),
+        opponent_player_name: summary.opponent_player_name.clone(),
+        created_at: summary.created_at.to_rfc3339(),
+        format: summary.format.clone().unwrap_or_default(),
+        won: summary.did_controller_win,
+        game_wins: summary.game_wins,
+        game_losses: summary.game_losses,
+        archetype: summary.controller_archetype.clone().unwrap_or_default(),
+        opponent_archetype: summary.opponent_archetype.clone().unwrap_or_default(),
+    }
+}
+
+#[tonic::async_trait]
+impl TeamService for TeamServiceImpl {
+    #[instrument(skip(self, request))]
+    async fn create_team(&self, request: Request<CreateTeamRequest>) -> Result<Response<CreateTeamResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let name = request.into_inner().name.trim().to_string();
+        if name.is_empty() || name.chars().count() > MAX_TEAM_NAME_LEN {
+            return Err(Status::invalid_argument(format!(
+                "name must be 1-{MAX_TEAM_NAME_LEN} characters"
+            )));
+        }
+
+        for _ in 0..INVITE_CODE_ATTEMPTS {
+            let membership = self
+                .db
+                .create_team(user_id, &name, &generate_invite_code())
+                .await
+                .map_err(|e| {
+                    error!("Failed to create team: {e}");
+                    Status::internal("failed to create team")
+                })?;
+            if let Some(membership) = membership {
+                info!("User {user_id} created team {}", membership.team.id);
+                return Ok(Response::new(CreateTeamResponse {
+                    team: Some(team_to_proto(&membership)),
+                }));
+            }
+        }
+
+        error!("Exhausted invite code attempts creating team");
+        Err(Status::internal("failed to create team"))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn join_team(&self, request: Request<JoinTeamRequest>) -> Result<Response<JoinTeamResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let invite_code = request.into_inner().invite_code.trim().to_ascii_uppercase();
+        if invite_code.is_empty() {
+            return Err(Status::invalid_argument("invite_code is required"));
+        }
+
+        let membership = self
+            .db
+            .join_team(user_id, &invite_code)
+            .await
+            .map_err(|e| {
+                error!("Failed to join team: {e}");
+                Status::internal("failed to join team")
+            })?
+            .ok_or_else(|| Status::not_found("invalid invite code"))?;
+
+        info!("User {user_id} joined team {}", membership.team.id);
+        Ok(Response::new(JoinTeamResponse {
+            team: Some(team_to_proto(&membership)),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn list_teams(&self, request: Request<ListTeamsRequest>) -> Result<Response<ListTeamsResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let teams = self.db.list_teams(user_id).await.map_err(|e| {
+            error!("Failed to list teams: {e}");
+            Status::internal("failed to list teams")
+        })?;
+
+        Ok(Response::new(ListTeamsResponse {
+            teams: teams.iter().map(team_to_proto).collect(),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn get_team(&self, request: Request<GetTeamRequest>) -> Result<Response<GetTeamResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let team_id = parse_uuid(&request.into_inner().team_id, "team_id")?;
+        let membership = self.membership(team_id, user_id).await?;
+
+        let members = self.db.list_team_members(team_id).await.map_err(|e| {
+            error!("Failed to list team members: {e}");
+            Status::internal("failed to list team members")
+        })?;
+
+        Ok(Response::new(GetTeamResponse {
+            team: Some(team_to_proto(&membership)),
+            members: members.iter().map(member_to_proto).collect(),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn leave_team(&self, request: Request<LeaveTeamRequest>) -> Result<Response<LeaveTeamResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let team_id = parse_uuid(&request.into_inner().team_id, "team_id")?;
+        let membership = self.membership(team_id, user_id).await?;
+
+        if membership.role == TeamRole::Owner {
+            if membership.member_count > 1 {
+                return Err(Status::failed_precondition(
+                    "transfer ownership before leaving the team",
+                ));
+            }
+            // Last member out: the team goes with them.
+            self.db.delete_team(team_id).await.map_err(|e| {
+                error!("Failed to delete team: {e}");
+                Status::internal("failed to leave team")
+            })?;
+            info!("Deleted team {team_id} after its last member left");
+            return Ok(Response::new(LeaveTeamResponse {}));
+        }
+
+        self.db.remove_member(team_id, user_id).await.map_err(|e| {
+            error!("Failed to leave team: {e}");
+            Status::internal("failed to leave team")
+        })?;
+        Ok(Response::new(LeaveTeamResponse {}))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn update_sharing(
+        &self,
+        request: Request<UpdateSharingRequest>,
+    ) -> Result<Response<UpdateSharingResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let req = request.into_inner();
+        let team_id = parse_uuid(&req.team_id, "team_id")?;
+
+        let updated = self
+            .db
+            .update_sharing(team_id, user_id, req.share_matches, req.share_drafts)
+            .await
+            .map_err(|e| {
+                error!("Failed to update sharing: {e}");
+                Status::internal("failed to update sharing")
+            })?;
+        if !updated {
+            return Err(Status::not_found("team not found"));
+        }
+
+        let membership = self.membership(team_id, user_id).await?;
+        Ok(Response::new(UpdateSharingResponse {
+            team: Some(team_to_proto(&membership)),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn set_member_role(
+        &self,
+        request: Request<SetMemberRoleRequest>,
+    ) -> Result<Response<SetMemberRoleResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let req = request.into_inner();
+        let team_id = parse_uuid(&req.team_id, "team_id")?;
+        let target_id = parse_uuid(&req.user_id, "user_id")?;
+
+        let membership = self.membership(team_id, user_id).await?;
+        if membership.role != TeamRole::Owner {
+            return Err(Status::permission_denied("only the team owner can change roles"));
+        }
+        if target_id == user_id {
+            return Err(Status::invalid_argument("the owner cannot change their own role"));
+        }
+        // Make sure the target is a member before touching anything.
+        self.target_role(team_id, target_id).await?;
+
+        let changed = match req.role() {
+            TeamRoleProto::Owner => self.db.transfer_ownership(team_id, user_id, target_id).await,
+            TeamRoleProto::Admin => self.db.set_member_role(team_id, target_id, TeamRole::Admin).await,
+            TeamRoleProto::Member => self.db.set_member_role(team_id, target_id, TeamRole::Member).await,
+            TeamRoleProto::Unspecified => return Err(Status::invalid_argument("role is required")),
+        }
+        .map_err(|e| {
+            error!("Failed to set member role: {e}");
+            Status::internal("failed to set member role")
+        })?;
+        if !changed {
+            return Err(Status::not_found("member not found"));
+        }
+
+        Ok(Response::new(SetMemberRoleResponse {}))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn remove_member(
+        &self,
+        request: Request<RemoveMemberRequest>,
+    ) -> Result<Response<RemoveMemberResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let req = request.into_inner();
+        let team_id = parse_uuid(&req.team_id, "team_id")?;
+        let target_id = parse_uuid(&req.user_id, "user_id")?;
+
+        if target_id == user_id {
+            return Err(Status::invalid_argument("use LeaveTeam to leave a team"));
+        }
+        let membership = self.managed_membership(team_id, user_id).await?;
+        let target_role = self.target_role(team_id, target_id).await?;
+        // Admins can only remove plain members; the owner can remove anyone.
+        if membership.role != TeamRole::Owner && target_role != TeamRole::Member {
+            return Err(Status::permission_denied("admins can only remove members"));
+        }
+
+        self.db.remove_member(team_id, target_id).await.map_err(|e| {
+            error!("Failed to remove team member: {e}");
+            Status::internal("failed to remove team member")
+        })?;
+        info!("User {user_id} removed {target_id} from team {team_id}");
+        Ok(Response::new(RemoveMemberResponse {}))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn rotate_invite_code(
+        &self,
+        request: Request<RotateInviteCodeRequest>,
+    ) -> Result<Response<RotateInviteCodeResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let team_id = parse_uuid(&request.into_inner().team_id, "team_id")?;
+        self.managed_membership(team_id, user_id).await?;
+
+        for _ in 0..INVITE_CODE_ATTEMPTS {
+            let invite_code = generate_invite_code();
+            let rotated = self.db.rotate_invite_code(team_id, &invite_code).await.map_err(|e| {
+                error!("Failed to rotate invite code: {e}");
+                Status::internal("failed to rotate invite code")
+            })?;
+            if rotated {
+                return Ok(Response::new(RotateInviteCodeResponse { invite_code }));
+            }
+        }
+
+        error!("Exhausted invite code attempts rotating team {team_id}");
+        Err(Status::internal("failed to rotate invite code"))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn get_team_stats(
+        &self,
+        request: Request<GetTeamStatsRequest>,
+    ) -> Result<Response<GetTeamStatsResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let req = request.into_inner();
+        let team_id = parse_uuid(&req.team_id, "team_id")?;
+        self.membership(team_id, user_id).await?;
+
+        let team_stats = self
+            .db
+            .get_team_stats(team_id, TimeWindow::from(req.time_window()))
+            .await
+            .map_err(|e| {
+                error!("Failed to compute team stats: {e}");
+                Status::internal("failed to compute team stats")
+            })?;
+
+        let mut stats = TeamStats::from(&team_stats.stats);
+        stats.matchups = team_stats.matchups.iter().map(ArchetypeMatchupRecord::from).collect();
+        stats.members = team_stats
+            .members
+            .iter()
+            .map(|member| MemberRecord {
+                user_id: member.user_id.to_string(),
+                username: member.username.clone(),
+                matches: member.matches,
+                wins: member.wins,
+                losses: member.losses,
+            })
+            .collect();
+
+        Ok(Response::new(GetTeamStatsResponse { stats: Some(stats) }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn list_team_matches(
+        &self,
+        request: Request<ListTeamMatchesRequest>,
+    ) -> Result<Response<ListTeamMatchesResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let req = request.into_inner();
+        let team_id = parse_uuid(&req.team_id, "team_id")?;
+        self.membership(team_id, user_id).await?;
+
+        let matches = self
+            .db
+            .list_team_matches(team_id, list_limit(req.limit))
+            .await
+            .map_err(|e| {
+                error!("Failed to list team matches: {e}");
+                Status::internal("failed to list team matches")
+            })?;
+
+        Ok(Response::new(ListTeamMatchesResponse {
+            matches: matches.iter().map(team_match_to_proto).collect(),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn list_team_drafts(
+        &self,
+        request: Request<ListTeamDraftsRequest>,
+    ) -> Result<Response<ListTeamDraftsResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let req = request.into_inner();
+        let team_id = parse_uuid(&req.team_id, "team_id")?;
+        self.membership(team_id, user_id).await?;
+
+        let drafts = self
+            .db
+            .list_team_drafts(team_id, list_limit(req.limit))
+            .await
+            .map_err(|e| {
+                error!("Failed to list team drafts: {e}");
+                Status::internal("failed to list team drafts")
+            })?;
+
+        Ok(Response::new(ListTeamDraftsResponse {
+            drafts: drafts
+                .iter()
+                .map(|team_draft| TeamDraft {
+                    user_id: team_draft.user_id.to_string(),
+                    username: team_draft.username.clone(),
+                    draft: Some((&team_draft.draft).into()),
+                })
+                .collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arenabuddy_core::models::{MTGAMatch, MatchResult};
+    use arenabuddy_data::{ArenabuddyRepository, AuthRepository, testing::test_db};
+
+    use super::*;
+    use crate::auth::UserId;
+
+    fn authed<T>(user_id: Uuid, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request.extensions_mut().insert(UserId(user_id));
+        request
+    }
+
+    async fn user(db: &MatchDB, name: &str) -> Uuid {
+        db.upsert_identity_user("local", name, name, None).await.expect("user")
+    }
+
+    /// A team created by `owner`, with its id and invite code.
+    async fn create_team(service: &TeamServiceImpl, owner: Uuid) -> (String, String) {
+        let team = service
+            .create_team(authed(
+                owner,
+                CreateTeamRequest {
+                    name: "Team".to_string(),
+                },
+            ))
+            .await
+            .expect("team")
+            .into_inner()
+            .team
+            .expect("team");
+        (team.id, team.invite_code)
+    }
+
+    async fn join(service: &TeamServiceImpl, user_id: Uuid, invite_code: &str) -> Result<Team, Status> {
+        let response = service
+            .join_team(authed(
+                user_id,
+                JoinTeamRequest {
+                    invite_code: invite_code.to_string(),
+                },
+            ))
+            .await?;
+        Ok(response.into_inner().team.expect("team"))
+    }
+
+    async fn remove(service: &TeamServiceImpl, caller: Uuid, team_id: &str, target: Uuid) -> Result<(), Status> {
+        service
+            .remove_member(authed(
+                caller,
+                RemoveMemberRequest {
+                    team_id: team_id.to_string(),
+                    user_id: target.to_string(),
+                },
+            ))
+            .await
+            .map(|_| ())
+    }
+
+    async fn set_role(
+        service: &TeamServiceImpl,
+        caller: Uuid,
+        team_id: &str,
+        target: Uuid,
+        role: TeamRoleProto,
+    ) -> Result<(), Status> {
+        service
+            .set_member_role(authed(
+                caller,
+                SetMemberRoleRequest {
+                    team_id: team_id.to_string(),
+                    user_id: target.to_string(),
+                    role: role.into(),
+                },
+            ))
+            .await
+            .map(|_| ())
+    }
+
+    async fn share(service: &TeamServiceImpl, user_id: Uuid, team_id: &str, share_matches: bool) {
+        service
+            .update_sharing(authed(
+                user_id,
+                UpdateSharingRequest {
+                    team_id: team_id.to_string(),
+                    share_matches,
+                    share_drafts: false,
+                },
+            ))
+            .await
+            .expect("sharing");
+    }
+
+    /// A completed match for `user_id`.
+    async fn play(db: &MatchDB, user_id: Uuid, won: bool) {
+        let match_id = Uuid::new_v4().to_string();
+        let winning_team_id = if won { 1 } else { 2 };
+        db.upsert_match_data(
+            &MTGAMatch::new(&match_id, 1, "me", "them"),
+            &[],
+            &[],
+            &[MatchResult::new(&match_id, 0, winning_team_id, "MatchScope_Match")],
+            &[],
+            &[],
+            Some(user_id),
+        )
+        .await
+        .expect("match");
+    }
+
+    #[tokio::test]
+    async fn only_owners_and_admins_manage_members() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let (owner, bob, carol, dave) = (
+            user(&db, "owner").await,
+            user(&db, "bob").await,
+            user(&db, "carol").await,
+            user(&db, "dave").await,
+        );
+        let service = TeamServiceImpl { db };
+        let (team_id, invite_code) = create_team(&service, owner).await;
+        for member in [bob, carol, dave] {
+            let team = join(&service, member, &invite_code).await.expect("joined");
+            assert!(team.invite_code.is_empty(), "members don't see the invite code");
+        }
+
+        let status = remove(&service, bob, &team_id, carol)
+            .await
+            .expect_err("bob isn't an admin");
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        let status = set_role(&service, bob, &team_id, bob, TeamRoleProto::Admin)
+            .await
+            .expect_err("bob isn't the owner");
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        let outsider = user(&service.db, "eve").await;
+        let status = remove(&service, outsider, &team_id, carol)
+            .await
+            .expect_err("not a member");
+        assert_eq!(status.code(), tonic::Code::NotFound);
+
+        set_role(&service, owner, &team_id, bob, TeamRoleProto::Admin)
+            .await
+            .expect("promoted");
+        set_role(&service, owner, &team_id, dave, TeamRoleProto::Admin)
+            .await
+            .expect("promoted");
+        remove(&service, bob, &team_id, carol)
+            .await
+            .expect("admins remove members");
+        let status = remove(&service, bob, &team_id, dave)
+            .await
+            .expect_err("dave is an admin");
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        let status = remove(&service, bob, &team_id, owner).await.expect_err("owner");
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        let status = set_role(&service, bob, &team_id, dave, TeamRoleProto::Member)
+            .await
+            .expect_err("only the owner changes roles");
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        remove(&service, owner, &team_id, dave)
+            .await
+            .expect("the owner removes anyone");
+
+        let members = service
+            .get_team(authed(
+                owner,
+                GetTeamRequest {
+                    team_id: team_id.clone(),
+                },
+            ))
+            .await
+            .expect("team")
+            .into_inner()
+            .members;
+        let mut ids: Vec<_> = members.iter().map(|m| m.user_id.clone()).collect();
+        ids.sort();
+        let mut expected = vec![owner.to_string(), bob.to_string()];
+        expected.sort();
+        assert_eq!(ids, expected);
+    }
+
+    #[tokio::test]
+    async fn invite_codes_work_after_leaving_until_rotated() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let (owner, bob) = (user(&db, "owner").await, user(&db, "bob").await);
+        let service = TeamServiceImpl { db };
+        let (team_id, invite_code) = create_team(&service, owner).await;
+        join(&service, bob, &invite_code).await.expect("joined");
+
+        service
+            .leave_team(authed(
+                bob,
+                LeaveTeamRequest {
+                    team_id: team_id.clone(),
+                },
+            ))
+            .await
+            .expect("left");
+        join(&service, bob, &invite_code.to_lowercase())
+            .await
+            .expect("the code still works");
+
+        let status = service
+            .rotate_invite_code(authed(
+                bob,
+                RotateInviteCodeRequest {
+                    team_id: team_id.clone(),
+                },
+            ))
+            .await
+            .expect_err("bob isn't an admin");
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        let rotated = service
+            .rotate_invite_code(authed(owner, RotateInviteCodeRequest { team_id }))
+            .await
+            .expect("rotated")
+            .into_inner()
+            .invite_code;
+        assert_ne!(rotated, invite_code);
+
+        let carol = user(&service.db, "carol").await;
+        let status = join(&service, carol, &invite_code).await.expect_err("old code");
+        assert_eq!(status.code(), tonic::Code::NotFound);
+        join(&service, carol, &rotated).await.expect("new code");
+    }
+
+    #[tokio::test]
+    async fn team_stats_only_include_members_who_share_matches() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let (owner, bob) = (user(&db, "owner").await, user(&db, "bob").await);
+        play(&db, owner, true).await;
+        play(&db, owner, false).await;
+        play(&db, bob, true).await;
+        let service = TeamServiceImpl { db };
+        let (team_id, invite_code) = create_team(&service, owner).await;
+        join(&service, bob, &invite_code).await.expect("joined");
+
+        let stats = |caller| {
+            service.get_team_stats(authed(
+                caller,
+                GetTeamStatsRequest {
+                    team_id: team_id.clone(),
+                    ..Default::default()
+                },
+            ))
+        };
+        let matches = |caller| {
+            service.list_team_matches(authed(
+                caller,
+                ListTeamMatchesRequest {
+                    team_id: team_id.clone(),
+                    limit: 0,
+                },
+            ))
+        };
+
+        let nobody = stats(bob).await.expect("stats").into_inner().stats.expect("stats");
+        assert_eq!(nobody.total_matches, 0);
+        assert!(nobody.members.is_empty());
+        assert!(matches(bob).await.expect("matches").into_inner().matches.is_empty());
+
+        share(&service, owner, &team_id, true).await;
+        let shared = stats(bob).await.expect("stats").into_inner().stats.expect("stats");
+        assert_eq!((shared.total_matches, shared.match_wins), (2, 1));
+        let members: Vec<_> = shared.members.iter().map(|m| m.username.as_str()).collect();
+        assert_eq!(members, ["owner"]);
+        let listed = matches(bob).await.expect("matches").into_inner().matches;
+        assert_eq!(listed.len(), 2);
+
+        share(&service, bob, &team_id, true).await;
+        let everyone = stats(owner).await.expect("stats").into_inner().stats.expect("stats");
+        assert_eq!((everyone.total_matches, everyone.match_wins), (3, 2));
+        assert_eq!(everyone.members.len(), 2);
+    }
+}
@@ -0,0 +1,186 @@
+use arenabuddy_core::services::webhook_service::{
+    CreateWebhookRequest, CreateWebhookResponse, DeleteWebhookRequest, DeleteWebhookResponse,
+    ListWebhookDeliveriesRequest, ListWebhookDeliveriesResponse, ListWebhooksRequest, ListWebhooksResponse,
+    Webhook as WebhookProto, WebhookDelivery as WebhookDeliveryProto, WebhookEvent as WebhookEventProto,
+    webhook_service_server::WebhookService,
+};
+use arenabuddy_data::{
+    MatchDB, WebhookRepository,
+    webhook_repository::{Webhook, WebhookDelivery, WebhookEvent},
+};
+use tonic::{Request, Response, Status};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+use crate::{
+    auth::{generate_opaque_token, require_user_id},
+    webhooks::check_target,
+};
+
+const MAX_WEBHOOKS_PER_USER: usize = 10;
+const DEFAULT_DELIVERY_LIMIT: i32 = 50;
+const MAX_DELIVERY_LIMIT: i32 = 500;
+
+pub(crate) struct WebhookServiceImpl {
+    pub(crate) db: MatchDB,
+}
+
+fn event_to_proto(event: WebhookEvent) -> WebhookEventProto {
+    match event {
+        WebhookEvent::MatchCompleted => WebhookEventProto::MatchCompleted,
+        WebhookEvent::DraftCompleted => WebhookEventProto::DraftCompleted,
+        WebhookEvent::ClassificationUpdated => WebhookEventProto::ClassificationUpdated,
+    }
+}
+
+fn event_from_proto(event: WebhookEventProto) -> Option<WebhookEvent> {
+    match event {
+        WebhookEventProto::Unspecified => None,
+        WebhookEventProto::MatchCompleted => Some(WebhookEvent::MatchCompleted),
+        WebhookEventProto::DraftCompleted => Some(WebhookEvent::DraftCompleted),
+        WebhookEventProto::ClassificationUpdated => Some(WebhookEvent::ClassificationUpdated),
+    }
+}
+
+fn event_name_to_proto(name: &str) -> i32 {
+    WebhookEvent::from_name(name).map_or(WebhookEventProto::Unspecified, event_to_proto) as i32
+}
+
+fn webhook_to_proto(webhook: &Webhook) -> WebhookProto {
+    WebhookProto {
+        id: webhook.id.to_string(),
+        url: webhook.url.clone(),
+        events: webhook.events.iter().map(|name| event_name_to_proto(name)).collect(),
+        created_at: webhook.created_at.to_rfc3339(),
+    }
+}
+
+fn delivery_to_proto(delivery: &WebhookDelivery) -> WebhookDeliveryProto {
+    WebhookDeliveryProto {
+        delivery_id: delivery.delivery_id.to_string(),
+        event: event_name_to_proto(&delivery.event),
+        attempt: delivery.attempt,
+        status_code: delivery.status_code.unwrap_or_default(),
+        error: delivery.error.clone().unwrap_or_default(),
+        succeeded: delivery.succeeded,
+        created_at: delivery.created_at.to_rfc3339(),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, Status> {
+    value
+        .parse()
+        .map_err(|_| Status::invalid_argument(format!("{field} must be a valid UUID")))
+}
+
+#[tonic::async_trait]
+impl WebhookService for WebhookServiceImpl {
+    #[instrument(skip(self, request))]
+    async fn create_webhook(
+        &self,
+        request: Request<CreateWebhookRequest>,
+    ) -> Result<Response<CreateWebhookResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let req = request.into_inner();
+        check_target(&req.url).await.map_err(Status::invalid_argument)?;
+
+        let requested: Vec<WebhookEvent> = req.events().filter_map(event_from_proto).collect();
+        let events: Vec<WebhookEvent> = WebhookEvent::ALL
+            .into_iter()
+            .filter(|event| requested.contains(event))
+            .collect();
+        if events.is_empty() {
+            return Err(Status::invalid_argument("at least one event is required"));
+        }
+
+        let existing = self.db.list_webhooks(user_id).await.map_err(|e| {
+            error!("Failed to list webhooks: {e}");
+            Status::internal("failed to create webhook")
+        })?;
+        if existing.len() >= MAX_WEBHOOKS_PER_USER {
+            return Err(Status::resource_exhausted(format!(
+                "at most {MAX_WEBHOOKS_PER_USER} webhooks are allowed"
+            )));
+        }
+
+        let secret = generate_opaque_token();
+        let webhook = self
+            .db
+            .create_webhook(user_id, &req.url, &secret, &events)
+            .await
+            .map_err(|e| {
+                error!("Failed to create webhook: {e}");
+                Status::internal("failed to create webhook")
+            })?;
+
+        info!("User {user_id} registered webhook {}", webhook.id);
+        Ok(Response::new(CreateWebhookResponse {
+            webhook: Some(webhook_to_proto(&webhook)),
+            secret,
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn list_webhooks(
+        &self,
+        request: Request<ListWebhooksRequest>,
+    ) -> Result<Response<ListWebhooksResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let webhooks = self.db.list_webhooks(user_id).await.map_err(|e| {
+            error!("Failed to list webhooks: {e}");
+            Status::internal("failed to list webhooks")
+        })?;
+
+        Ok(Response::new(ListWebhooksResponse {
+            webhooks: webhooks.iter().map(webhook_to_proto).collect(),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn delete_webhook(
+        &self,
+        request: Request<DeleteWebhookRequest>,
+    ) -> Result<Response<DeleteWebhookResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let webhook_id = parse_uuid(&request.into_inner().webhook_id, "webhook_id")?;
+
+        let deleted = self.db.delete_webhook(user_id, webhook_id).await.map_err(|e| {
+            error!("Failed to delete webhook: {e}");
+            Status::internal("failed to delete webhook")
+        })?;
+        if !deleted {
+            return Err(Status::not_found("webhook not found"));
+        }
+
+        info!("User {user_id} deleted webhook {webhook_id}");
+        Ok(Response::new(DeleteWebhookResponse {}))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn list_webhook_deliveries(
+        &self,
+        request: Request<ListWebhookDeliveriesRequest>,
+    ) -> Result<Response<ListWebhookDeliveriesResponse>, Status> {
+        let user_id = require_user_id(&request)?;
+        let req = request.into_inner();
+        let webhook_id = parse_uuid(&req.webhook_id, "webhook_id")?;
+        let limit = if req.limit <= 0 {
+            DEFAULT_DELIVERY_LIMIT
+        } else {
+            req.limit.min(MAX_DELIVERY_LIMIT)
+        };
+
+        let deliveries = self
+            .db
+            .list_webhook_deliveries(user_id, webhook_id, i64::from(limit))
+            .await
+            .map_err(|e| {
+                error!("Failed to list webhook deliveries: {e}");
+                Status::internal("failed to list webhook deliveries")
+            })?;
+
+        Ok(Response::new(ListWebhookDeliveriesResponse {
+            deliveries: deliveries.iter().map(delivery_to_proto).collect(),
+        }))
+    }
+}
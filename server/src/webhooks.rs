@@ -0,0 +1,479 @@
+//! Outbound webhooks: signed JSON notifications about ingest events, delivered in the
+//! background with retries.
+//!
+//! Every request is a `POST` of an [`Envelope`] with these headers:
+//! - `X-Arenabuddy-Event`: the event name, e.g. `match.completed`
+//! - `X-Arenabuddy-Delivery`: a UUID shared by all attempts at the same delivery
+//! - `X-Arenabuddy-Signature`: `sha256=<hex HMAC-SHA256 of the body keyed with the webhook secret>`
+//!
+//! Targets must resolve to public addresses, checked when a webhook is registered and again on
+//! every connection, so webhooks can't be pointed at the server's own network.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use arenabuddy_core::{
+    cards::CardsDatabase,
+    display::{deck::DeckDisplayRecord, match_details::MatchDetails},
+    models::MTGADraft,
+    services::match_service::ArchetypeClassification,
+};
+use arenabuddy_data::{
+    MatchDB, MetagameRepository, WebhookRepository,
+    webhook_repository::{DeliveryAttempt, Webhook, WebhookEvent},
+};
+use hmac::{Hmac, KeyInit, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::match_service::{classification_to_proto, load_match_details};
+
+const MAX_ATTEMPTS: i32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    id: Uuid,
+    event: &'static str,
+    created_at: String,
+    data: &'a T,
+}
+
+#[derive(Serialize)]
+struct GamePayload {
+    game_number: i32,
+    won: bool,
+}
+
+#[derive(Serialize)]
+struct MatchPayload {
+    match_id: String,
+    created_at: String,
+    format: Option<String>,
+    did_controller_win: bool,
+    controller_player_name: String,
+    opponent_player_name: String,
+    controller_archetype: Option<String>,
+    opponent_archetype: Option<String>,
+    games: Vec<GamePayload>,
+    decklist: Option<String>,
+    opponent_cards: Option<String>,
+}
+
+impl From<&MatchDetails> for MatchPayload {
+    fn from(details: &MatchDetails) -> Self {
+        Self {
+            match_id: details.id.clone(),
+            created_at: details.created_at.to_rfc3339(),
+            format: details.format.clone(),
+            did_controller_win: details.did_controller_win,
+            controller_player_name: details.controller_player_name.clone(),
+            opponent_player_name: details.opponent_player_name.clone(),
+            controller_archetype: details.controller_archetype.clone(),
+            opponent_archetype: details.opponent_archetype.clone(),
+            games: details
+                .game_results
+                .iter()
+                .map(|game| GamePayload {
+                    game_number: game.game_number,
+                    won: game.winning_player == details.controller_player_name,
+                })
+                .collect(),
+            decklist: details.primary_decklist.as_ref().map(DeckDisplayRecord::pretty_print),
+            opponent_cards: details.opponent_deck.as_ref().map(DeckDisplayRecord::pretty_print),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DraftPayload {
+    draft_id: Uuid,
+    set_code: String,
+    format: String,
+    created_at: String,
+    picks: Vec<i32>,
+}
+
+impl From<&MTGADraft> for DraftPayload {
+    fn from(draft: &MTGADraft) -> Self {
+        let meta = draft.draft();
+        Self {
+            draft_id: meta.id(),
+            set_code: meta.set_code().to_string(),
+            format: meta.format().to_string(),
+            created_at: meta.created_at().to_rfc3339(),
+            picks: draft.packs().iter().map(|pack| pack.picked_card().inner()).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ClassificationPayload<'a> {
+    match_id: &'a str,
+    classifications: &'a [ArchetypeClassification],
+}
+
+/// Whether `ip` is routable on the public internet. Loopback, private, link-local (which holds
+/// cloud metadata endpoints), shared, documentation and other special-purpose ranges are not.
+pub(crate) fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8 "this network", 100.64.0.0/10 shared address space, 192.0.0.0/24 protocol
+        // assignments, 198.18.0.0/15 benchmarking and 240.0.0.0/4 reserved.
+        || a == 0
+        || (a == 100 && (b & 0xc0) == 64)
+        || (a == 192 && b == 0 && ip.octets()[2] == 0)
+        || (a == 198 && (b & 0xfe) == 18)
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(mapped) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(mapped);
+    }
+    let segments = ip.segments();
+    // NAT64 (64:ff9b::/96) and 6to4 (2002::/16) addresses reach the IPv4 address they embed.
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [.., a, b, c, d] = ip.octets();
+        return is_public_ipv4(Ipv4Addr::new(a, b, c, d));
+    }
+    if segments[0] == 0x2002 {
+        let [_, _, a, b, c, d, ..] = ip.octets();
+        return is_public_ipv4(Ipv4Addr::new(a, b, c, d));
+    }
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || ip.is_unique_local()
+        || ip.is_unicast_link_local()
+        // 2001:db8::/32 documentation.
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+}
+
+/// Resolve `host`, failing unless it has addresses and all of them are public.
+async fn resolve_public(host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("could not resolve {host}: {e}"))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("{host} has no addresses"));
+    }
+    if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(format!("{host} resolves to non-public address {}", addr.ip()));
+    }
+    Ok(addrs)
+}
+
+/// Check that `url` is an absolute http(s) URL whose host is, or resolves only to, public addresses.
+pub(crate) async fn check_target(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|_| "url must be a valid URL".to_string())?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("url must be an http or https URL".to_string());
+    }
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| "url must be an http or https URL".to_string())?;
+    // IPv6 hosts come bracketed; the parser has already normalized IPv4 forms like `2130706433`.
+    match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(ip) if is_public_ip(ip) => Ok(()),
+        Ok(ip) => Err(format!("{ip} is not a public address")),
+        Err(_) => {
+            let port = parsed.port_or_known_default().unwrap_or_default();
+            resolve_public(host, port).await.map(|_| ())
+        }
+    }
+}
+
+/// Resolver for webhook deliveries that refuses hosts resolving to non-public addresses, so a
+/// DNS change after registration can't redirect deliveries to internal services.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs = resolve_public(name.as_str(), 0).await?;
+            let addrs: reqwest::dns::Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// `sha256=<hex>` HMAC of `body` keyed with `secret`.
+fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Publishes events to the webhooks users have registered.
+#[derive(Clone)]
+pub(crate) struct Webhooks {
+    db: MatchDB,
+    cards: CardsDatabase,
+    http: reqwest::Client,
+}
+
+impl Webhooks {
+    pub(crate) fn new(db: MatchDB, cards: CardsDatabase) -> Self {
+        // Redirects and proxies would bypass the target checks.
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .dns_resolver(PublicResolver)
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy()
+            .build()
+            .expect("webhook HTTP client should build");
+        Self { db, cards, http }
+    }
+
+    /// Notify `user_id`'s webhooks that a match finished. Runs in the background.
+    pub(crate) fn match_completed(&self, user_id: Uuid, match_id: String) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = this.publish_match(user_id, &match_id).await {
+                error!("Failed to publish match.completed for match {match_id}: {e}");
+            }
+        });
+    }
+
+    /// Notify `user_id`'s webhooks that a draft finished. Runs in the background.
+    pub(crate) fn draft_completed(&self, user_id: Uuid, draft: &MTGADraft) {
+        let this = self.clone();
+        let payload = DraftPayload::from(draft);
+        tokio::spawn(async move {
+            if let Err(e) = this.publish(user_id, WebhookEvent::DraftCompleted, &payload).await {
+                error!("Failed to publish draft.completed for draft {}: {e}", payload.draft_id);
+            }
+        });
+    }
+
+    /// Notify `user_id`'s webhooks that a match was (re)classified. Runs in the background.
+    pub(crate) fn classification_updated(
+        &self,
+        user_id: Uuid,
+        match_id: String,
+        classifications: Vec<ArchetypeClassification>,
+    ) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            let payload = ClassificationPayload {
+                match_id: &match_id,
+                classifications: &classifications,
+            };
+            if let Err(e) = this
+                .publish(user_id, WebhookEvent::ClassificationUpdated, &payload)
+                .await
+            {
+                error!("Failed to publish classification.updated for match {match_id}: {e}");
+            }
+        });
+    }
+
+    /// Notify `user_id`'s webhooks that a scheduled reclassification changed a match's
+    /// archetypes. Loads the classifications, overrides included, in the background.
+    pub(crate) fn match_reclassified(&self, user_id: Uuid, match_id: String) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            let classifications = match this.db.get_match_classifications(&match_id).await {
+                Ok(classifications) => classifications.iter().map(classification_to_proto).collect(),
+                Err(e) => {
+                    error!("Failed to load classifications of reclassified match {match_id}: {e}");
+                    return;
+                }
+            };
+            this.classification_updated(user_id, match_id, classifications);
+        });
+    }
+
+    async fn publish_match(&self, user_id: Uuid, match_id: &str) -> Result<(), BoxError> {
+        let webhooks = self
+            .db
+            .webhooks_for_event(user_id, WebhookEvent::MatchCompleted)
+            .await?;
+        if webhooks.is_empty() {
+            return Ok(());
+        }
+
+        let details = load_match_details(&self.db, &self.cards, match_id, Some(user_id)).await?;
+
+        self.deliver_all(&webhooks, WebhookEvent::MatchCompleted, &MatchPayload::from(&details))
+    }
+
+    async fn publish<T: Serialize + Sync>(&self, user_id: Uuid, event: WebhookEvent, data: &T) -> Result<(), BoxError> {
+        let webhooks = self.db.webhooks_for_event(user_id, event).await?;
+        self.deliver_all(&webhooks, event, data)
+    }
+
+    /// Start a delivery of `data` to each webhook. Deliveries run (and retry) in the background.
+    fn deliver_all<T: Serialize>(&self, webhooks: &[Webhook], event: WebhookEvent, data: &T) -> Result<(), BoxError> {
+        for webhook in webhooks {
+            let envelope = Envelope {
+                id: Uuid::new_v4(),
+                event: event.as_str(),
+                created_at: chrono::Utc::now().to_rfc3339(),
+                data,
+            };
+            let delivery_id = envelope.id;
+            let body = serde_json::to_vec(&envelope)?;
+            let this = self.clone();
+            let webhook = webhook.clone();
+            tokio::spawn(async move { this.deliver(&webhook, event, delivery_id, body).await });
+        }
+        Ok(())
+    }
+
+    /// POST `body` to the webhook, retrying with exponential backoff on network errors,
+    /// 429 and 5xx responses. Every attempt is recorded in the delivery log.
+    async fn deliver(&self, webhook: &Webhook, event: WebhookEvent, delivery_id: Uuid, body: Vec<u8>) {
+        let signature = signature(&webhook.secret, &body);
+        let mut backoff = INITIAL_BACKOFF;
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            // Hostnames are re-checked by the resolver; this catches IP literals.
+            if let Err(e) = check_target(&webhook.url).await {
+                self.record_attempt(webhook, event, delivery_id, attempt, None, Some(&e))
+                    .await;
+                warn!("Refusing delivery {delivery_id} to webhook {}: {e}", webhook.id);
+                return;
+            }
+            let result = self
+                .http
+                .post(&webhook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header("X-Arenabuddy-Event", event.as_str())
+                .header("X-Arenabuddy-Delivery", delivery_id.to_string())
+                .header("X-Arenabuddy-Signature", &signature)
+                .body(body.clone())
+                .send()
+                .await;
+
+            let (status, error) = match result {
+                Ok(response) => {
+                    let status = response.status();
+                    let error = (!status.is_success()).then(|| format!("endpoint returned {status}"));
+                    (Some(status), error)
+                }
+                Err(e) => (None, Some(e.to_string())),
+            };
+            let succeeded = error.is_none();
+
+            self.record_attempt(webhook, event, delivery_id, attempt, status, error.as_deref())
+                .await;
+
+            if succeeded {
+                info!(
+                    "Delivered {} to webhook {} (attempt {attempt})",
+                    event.as_str(),
+                    webhook.id
+                );
+                return;
+            }
+
+            let retryable = status.is_none_or(|s| s.is_server_error() || s == reqwest::StatusCode::TOO_MANY_REQUESTS);
+            if !retryable || attempt == MAX_ATTEMPTS {
+                break;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+
+        warn!(
+            "Giving up on delivery {delivery_id} of {} to webhook {}",
+            event.as_str(),
+            webhook.id
+        );
+    }
+
+    async fn record_attempt(
+        &self,
+        webhook: &Webhook,
+        event: WebhookEvent,
+        delivery_id: Uuid,
+        attempt: i32,
+        status: Option<reqwest::StatusCode>,
+        error: Option<&str>,
+    ) {
+        let logged = self
+            .db
+            .record_webhook_delivery(&DeliveryAttempt {
+                webhook_id: webhook.id,
+                delivery_id,
+                event,
+                attempt,
+                status_code: status.map(|s| i32::from(s.as_u16())),
+                error,
+                succeeded: error.is_none(),
+            })
+            .await;
+        if let Err(e) = logged {
+            warn!("Failed to record webhook delivery {delivery_id}: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00:ec2::254",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+            "2002:7f00:1::",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{ip} should not be public");
+        }
+        for ip in ["93.184.216.34", "1.1.1.1", "2606:4700:4700::1111", "::ffff:1.1.1.1"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{ip} should be public");
+        }
+    }
+
+    #[tokio::test]
+    async fn targets_must_be_public_http_urls() {
+        for url in [
+            "ftp://93.184.216.34/hook",
+            "not a url",
+            "http://127.0.0.1:8080/hook",
+            "http://2130706433/hook",
+            "http://[::1]/hook",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://localhost/hook",
+        ] {
+            assert!(check_target(url).await.is_err(), "{url} should be rejected");
+        }
+        assert_eq!(check_target("https://93.184.216.34/hook").await, Ok(()));
+    }
+}
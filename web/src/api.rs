@@ -0,0 +1,240 @@
+//! gRPC-Web calls to the arenabuddy server, converted into core's display types.
+
+use arenabuddy_core::{
+    cards::CardsDatabase,
+    display::{
+        draft::DraftDetailsDisplay,
+        match_details::MatchDetails,
+        match_summary::MatchSummary,
+        stats::{MatchStats, TimeWindow},
+    },
+    models::{Draft, MTGADraft, MatchData},
+    services::{
+        auth_service::{
+            ExchangeTokenRequest, ExchangeTokenResponse, IdentityProviderInfo, ListIdentityProvidersRequest,
+            LogoutRequest, RefreshTokenRequest, auth_service_client::AuthServiceClient,
+        },
+        draft_service::{GetDraftRequest, ListDraftsRequest, draft_service_client::DraftServiceClient},
+        match_service::{
+            GetMatchDataRequest, GetMatchStatsRequest, ListMatchSummariesRequest,
+            match_service_client::MatchServiceClient,
+        },
+        team_service::StatsTimeWindow,
+    },
+};
+use dioxus::prelude::*;
+use tonic_web_wasm_client::Client;
+
+use crate::{
+    oauth::PendingLogin,
+    session::{self, Session, SessionState},
+};
+
+/// Base URL of the server's gRPC-Web endpoint, overridable at build time.
+const GRPC_URL: &str = match option_env!("ARENABUDDY_GRPC_URL") {
+    Some(url) => url,
+    None => "https://api.arenabuddy.io",
+};
+
+pub(crate) type Result<T> = std::result::Result<T, String>;
+
+fn client() -> Client {
+    Client::new(GRPC_URL.to_string())
+}
+
+fn authed<T>(message: T, token: &str) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    if let Ok(value) = format!("Bearer {token}").parse() {
+        request.metadata_mut().insert("authorization", value);
+    }
+    request
+}
+
+/// Identity providers enabled on the server, in the order to offer them.
+pub(crate) async fn identity_providers() -> Result<Vec<IdentityProviderInfo>> {
+    let response = AuthServiceClient::new(client())
+        .list_identity_providers(ListIdentityProvidersRequest {})
+        .await
+        .map_err(|e| e.message().to_string())?
+        .into_inner();
+    Ok(response.details)
+}
+
+/// Sign in with a local username and password.
+pub(crate) async fn login(username: String, password: String) -> Result<Session> {
+    sign_in(ExchangeTokenRequest {
+        provider: "local".to_string(),
+        username,
+        password,
+        ..Default::default()
+    })
+    .await
+}
+
+/// Finish an OAuth sign-in by exchanging the authorization code the provider redirected back with.
+pub(crate) async fn exchange_code(pending: PendingLogin, authorization_code: String) -> Result<Session> {
+    sign_in(ExchangeTokenRequest {
+        authorization_code,
+        code_verifier: pending.code_verifier,
+        redirect_uri: pending.redirect_uri,
+        provider: pending.provider,
+        ..Default::default()
+    })
+    .await
+}
+
+async fn sign_in(request: ExchangeTokenRequest) -> Result<Session> {
+    let response: ExchangeTokenResponse = AuthServiceClient::new(client())
+        .exchange_token(request)
+        .await
+        .map_err(|e| e.message().to_string())?
+        .into_inner();
+
+    let user = response.user.ok_or("Server did not return user info")?;
+    let session = Session {
+        token: response.access_token,
+        token_expires_at: response.expires_at,
+        refresh_token: response.refresh_token,
+        refresh_expires_at: response.refresh_expires_at,
+        username: user.username,
+    };
+    session::save(&session);
+    Ok(session)
+}
+
+/// Revoke the refresh token (best effort) and forget the session.
+pub(crate) async fn logout(mut state: SessionState) {
+    let current = state.peek().clone();
+    if let Some(current) = current {
+        AuthServiceClient::new(client())
+            .logout(LogoutRequest {
+                refresh_token: current.refresh_token,
+            })
+            .await
+            .ok();
+    }
+    session::clear();
+    state.set(None);
+}
+
+/// The current access token, refreshed first if it is about to expire.
+/// Signs the user out if the refresh fails.
+async fn access_token(mut state: SessionState) -> Result<String> {
+    let current = state.peek().clone().ok_or("Not signed in")?;
+    if !current.needs_refresh() {
+        return Ok(current.token);
+    }
+
+    let response = AuthServiceClient::new(client())
+        .refresh_token(RefreshTokenRequest {
+            refresh_token: current.refresh_token.clone(),
+        })
+        .await;
+
+    match response {
+        Ok(response) => {
+            let response = response.into_inner();
+            let refreshed = Session {
+                token: response.access_token,
+                token_expires_at: response.expires_at,
+                refresh_token: response.refresh_token,
+                refresh_expires_at: response.refresh_expires_at,
+                ..current
+            };
+            session::save(&refreshed);
+            let token = refreshed.token.clone();
+            state.set(Some(refreshed));
+            Ok(token)
+        }
+        Err(e) => {
+            session::clear();
+            state.set(None);
+            Err(format!("Session expired: {}", e.message()))
+        }
+    }
+}
+
+pub(crate) async fn match_summaries(state: SessionState) -> Result<Vec<MatchSummary>> {
+    let token = access_token(state).await?;
+    let response = MatchServiceClient::new(client())
+        .list_match_summaries(authed(ListMatchSummariesRequest {}, &token))
+        .await
+        .map_err(|e| e.message().to_string())?
+        .into_inner();
+
+    Ok(response.matches.iter().map(MatchSummary::from).collect())
+}
+
+pub(crate) async fn match_details(state: SessionState, match_id: String) -> Result<MatchDetails> {
+    let token = access_token(state).await?;
+    let response = MatchServiceClient::new(client())
+        .get_match_data(authed(
+            GetMatchDataRequest {
+                match_id,
+                include_cards: true,
+            },
+            &token,
+        ))
+        .await
+        .map_err(|e| e.message().to_string())?
+        .into_inner();
+
+    let match_data = response.match_data.as_ref().ok_or("Server returned no match data")?;
+    let match_data = MatchData::try_from(match_data).map_err(|e| e.to_string())?;
+    let cards = CardsDatabase::from_cards(response.cards.map(|c| c.cards).unwrap_or_default());
+
+    let mut details = MatchDetails::from_match_data(&match_data, &cards);
+    for c in &response.classifications {
+        details.set_classification(&c.side, c.archetype_name.clone(), c.evidence.as_ref().map(Into::into));
+    }
+    Ok(details)
+}
+
+pub(crate) async fn stats(state: SessionState, time_window: TimeWindow) -> Result<MatchStats> {
+    let token = access_token(state).await?;
+    let response = MatchServiceClient::new(client())
+        .get_match_stats(authed(
+            GetMatchStatsRequest {
+                time_window: StatsTimeWindow::from(time_window).into(),
+                ..Default::default()
+            },
+            &token,
+        ))
+        .await
+        .map_err(|e| e.message().to_string())?
+        .into_inner();
+
+    let mut match_stats = response.stats.as_ref().map(MatchStats::from).unwrap_or_default();
+    match_stats.opponents = response.opponents.iter().map(Into::into).collect();
+    Ok(match_stats)
+}
+
+pub(crate) async fn drafts(state: SessionState) -> Result<Vec<Draft>> {
+    let token = access_token(state).await?;
+    let response = DraftServiceClient::new(client())
+        .list_drafts(authed(ListDraftsRequest {}, &token))
+        .await
+        .map_err(|e| e.message().to_string())?
+        .into_inner();
+
+    response
+        .drafts
+        .iter()
+        .map(Draft::try_from)
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) async fn draft_details(state: SessionState, draft_id: String) -> Result<DraftDetailsDisplay> {
+    let token = access_token(state).await?;
+    let response = DraftServiceClient::new(client())
+        .get_draft(authed(GetDraftRequest { draft_id }, &token))
+        .await
+        .map_err(|e| e.message().to_string())?
+        .into_inner();
+
+    let draft = response.draft.as_ref().ok_or("Server returned no draft")?;
+    let draft = MTGADraft::try_from(draft).map_err(|e| e.to_string())?;
+    let cards = CardsDatabase::from_cards(response.cards.map(|c| c.cards).unwrap_or_default());
+    Ok(DraftDetailsDisplay::new(draft, &cards))
+}
@@ -0,0 +1,197 @@
+//! Layout for the signed-in pages, and the sign-in form shown in its place when
+//! there is no session.
+
+use dioxus::prelude::*;
+
+use crate::{
+    Route, api, oauth,
+    session::SessionState,
+    style::{BUTTON_STYLE, ERROR_STYLE, HEADING_STYLE, INPUT_STYLE, LINK_STYLE, MUTED_STYLE, PAGE_STYLE, PANEL_STYLE},
+};
+
+#[component]
+pub(crate) fn Dashboard() -> Element {
+    let session = use_context::<SessionState>();
+    let Some(current) = session() else {
+        return rsx! {
+            Login {}
+        };
+    };
+
+    rsx! {
+        div { style: PAGE_STYLE,
+            div { style: "max-width: 72rem; margin: 0 auto;",
+                nav { style: "display: flex; align-items: center; gap: 1.5rem; margin-bottom: 1.5rem;",
+                    Link { to: Route::Home {}, style: "color: #ffffff; font-weight: bold; font-size: 1.25rem; text-decoration: none;",
+                        "Arena Buddy"
+                    }
+                    Link { to: Route::Matches {}, style: LINK_STYLE, "Matches" }
+                    Link { to: Route::Drafts {}, style: LINK_STYLE, "Drafts" }
+                    Link { to: Route::Stats {}, style: LINK_STYLE, "Stats" }
+                    span { style: "margin-left: auto; {MUTED_STYLE}", "{current.username}" }
+                    button {
+                        style: BUTTON_STYLE,
+                        onclick: move |_| {
+                            spawn(api::logout(session));
+                        },
+                        "Sign out"
+                    }
+                }
+                Outlet::<Route> {}
+            }
+        }
+    }
+}
+
+#[component]
+fn Login() -> Element {
+    let mut session = use_context::<SessionState>();
+    let mut username = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+    let mut loading = use_signal(|| false);
+    let providers = use_resource(api::identity_providers);
+
+    let on_submit = move |evt: FormEvent| {
+        evt.prevent_default();
+        spawn(async move {
+            loading.set(true);
+            error.set(None);
+            match api::login(username(), password()).await {
+                Ok(signed_in) => session.set(Some(signed_in)),
+                Err(e) => error.set(Some(e)),
+            }
+            loading.set(false);
+        });
+    };
+
+    let (redirect_providers, local_enabled) = match &*providers.read() {
+        Some(Ok(providers)) => (
+            providers
+                .iter()
+                .filter(|p| !p.authorization_endpoint.is_empty())
+                .cloned()
+                .collect::<Vec<_>>(),
+            providers.iter().any(|p| p.name == "local"),
+        ),
+        Some(Err(e)) => {
+            return rsx! {
+                div { style: PAGE_STYLE,
+                    div { style: "max-width: 24rem; margin: 4rem auto;",
+                        div { style: PANEL_STYLE,
+                            h1 { style: HEADING_STYLE, "Sign in" }
+                            p { style: ERROR_STYLE, "Could not reach the server: {e}" }
+                        }
+                    }
+                }
+            };
+        }
+        None => (Vec::new(), false),
+    };
+
+    rsx! {
+        div { style: PAGE_STYLE,
+            div { style: "max-width: 24rem; margin: 4rem auto;",
+                div { style: PANEL_STYLE,
+                    h1 { style: HEADING_STYLE, "Sign in" }
+                    p { style: MUTED_STYLE,
+                        "Use the account you sign in with in the desktop app."
+                    }
+                    for provider in redirect_providers {
+                        button {
+                            key: "{provider.name}",
+                            style: "{BUTTON_STYLE} display: block; width: 100%; margin-bottom: 0.75rem;",
+                            onclick: {
+                                let provider = provider.clone();
+                                move |_| {
+                                    if let Err(e) = oauth::begin(&provider) {
+                                        error.set(Some(e));
+                                    }
+                                }
+                            },
+                            "Continue with {provider_label(&provider.name)}"
+                        }
+                    }
+                    if !local_enabled {
+                        if let Some(message) = error() {
+                            p { style: ERROR_STYLE, "{message}" }
+                        }
+                    }
+                }
+                if local_enabled {
+                    form { style: PANEL_STYLE, onsubmit: on_submit,
+                        label { r#for: "username", "Username" }
+                        input {
+                            id: "username",
+                            style: INPUT_STYLE,
+                            autocomplete: "username",
+                            value: "{username}",
+                            oninput: move |evt| username.set(evt.value()),
+                        }
+                        label { r#for: "password", "Password" }
+                        input {
+                            id: "password",
+                            r#type: "password",
+                            style: INPUT_STYLE,
+                            autocomplete: "current-password",
+                            value: "{password}",
+                            oninput: move |evt| password.set(evt.value()),
+                        }
+                        if let Some(message) = error() {
+                            p { style: ERROR_STYLE, "{message}" }
+                        }
+                        button {
+                            r#type: "submit",
+                            style: BUTTON_STYLE,
+                            disabled: loading(),
+                            if loading() { "Signing in..." } else { "Sign in" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Button label for a provider name such as `discord` or a configured OIDC name.
+fn provider_label(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Where identity providers redirect back to after sign-in. Exchanges the code for a session and
+/// continues to the dashboard.
+#[component]
+pub(crate) fn AuthCallback() -> Element {
+    let mut session = use_context::<SessionState>();
+    let navigator = use_navigator();
+    let result = use_resource(move || async move {
+        let (pending, code) = oauth::finish()?;
+        let signed_in = api::exchange_code(pending, code).await?;
+        session.set(Some(signed_in));
+        navigator.replace(Route::Matches {});
+        Ok::<_, String>(())
+    });
+
+    rsx! {
+        div { style: PAGE_STYLE,
+            div { style: "max-width: 24rem; margin: 4rem auto;",
+                div { style: PANEL_STYLE,
+                    h1 { style: HEADING_STYLE, "Sign in" }
+                    match &*result.read() {
+                        Some(Err(e)) => rsx! {
+                            p { style: ERROR_STYLE, "{e}" }
+                            Link { to: Route::Matches {}, style: LINK_STYLE, "Try again" }
+                        },
+                        _ => rsx! {
+                            p { style: MUTED_STYLE, "Signing in..." }
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Uploaded drafts and their pick-by-pick breakdown.
+
+use dioxus::prelude::*;
+
+use crate::{
+    Route, api, format_local_datetime,
+    session::SessionState,
+    style::{CELL_STYLE, ERROR_STYLE, HEADING_STYLE, LINK_STYLE, MUTED_STYLE, PANEL_STYLE, TABLE_STYLE},
+};
+
+#[component]
+pub(crate) fn Drafts() -> Element {
+    let session = use_context::<SessionState>();
+    let drafts = use_resource(move || api::drafts(session));
+
+    rsx! {
+        div { style: PANEL_STYLE,
+            h1 { style: HEADING_STYLE, "Drafts" }
+            match &*drafts.read() {
+                None => rsx! {
+                    p { style: MUTED_STYLE, "Loading drafts..." }
+                },
+                Some(Err(err)) => rsx! {
+                    p { style: ERROR_STYLE, "Could not load drafts: {err}" }
+                },
+                Some(Ok(drafts)) if drafts.is_empty() => rsx! {
+                    p { style: MUTED_STYLE, "No drafts uploaded yet." }
+                },
+                Some(Ok(drafts)) => rsx! {
+                    table { style: TABLE_STYLE,
+                        thead {
+                            tr {
+                                th { style: CELL_STYLE, "Set" }
+                                th { style: CELL_STYLE, "Format" }
+                                th { style: CELL_STYLE, "Status" }
+                                th { style: CELL_STYLE, "Date" }
+                            }
+                        }
+                        tbody {
+                            for draft in drafts.iter() {
+                                tr { key: "{draft.id()}",
+                                    td { style: CELL_STYLE,
+                                        Link {
+                                            to: Route::DraftDetail { id: draft.id().to_string() },
+                                            style: LINK_STYLE,
+                                            "{draft.set_code()}"
+                                        }
+                                    }
+                                    td { style: CELL_STYLE, "{draft.format()}" }
+                                    td { style: CELL_STYLE,
+                                        if draft.status() == "DraftStatus_Complete" { "Complete" } else { "In progress" }
+                                    }
+                                    td { style: CELL_STYLE, "{format_local_datetime(*draft.created_at())}" }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+pub(crate) fn DraftDetail(id: String) -> Element {
+    let session = use_context::<SessionState>();
+    let draft_details = use_resource({
+        let id = id.clone();
+        move || api::draft_details(session, id.clone())
+    });
+
+    match &*draft_details.read() {
+        None => rsx! {
+            p { style: MUTED_STYLE, "Loading draft..." }
+        },
+        Some(Err(err)) => rsx! {
+            div { style: PANEL_STYLE,
+                h1 { style: HEADING_STYLE, "Draft unavailable" }
+                p { style: ERROR_STYLE, "{err}" }
+            }
+        },
+        Some(Ok(display)) => {
+            let draft = display.metadata();
+            let picked = display
+                .picked_cards()
+                .iter()
+                .map(|card| card.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            rsx! {
+                div { style: PANEL_STYLE,
+                    h1 { style: "font-size: 2rem; color: #ffffff; margin: 0 0 0.25rem 0;",
+                        "{draft.set_code()} · {draft.format()}"
+                    }
+                    p { style: MUTED_STYLE,
+                        "{display.total_picks()} picks · {format_local_datetime(*draft.created_at())}"
+                    }
+                }
+
+                div { style: PANEL_STYLE,
+                    h2 { style: HEADING_STYLE, "Picked cards" }
+                    p { "{picked}" }
+                }
+
+                for (index, pack) in display.by_packs().into_iter().enumerate() {
+                    if !pack.is_empty() {
+                        div { style: PANEL_STYLE,
+                            h2 { style: HEADING_STYLE, "Pack {index + 1}" }
+                            for pick in pack {
+                                details { style: "margin: 0.25rem 0;",
+                                    summary { style: "cursor: pointer;",
+                                        "Pick {pick.pick_number()}: "
+                                        span { style: "color: #ffffff;",
+                                            {pick.picked_card_name().unwrap_or("Unknown card")}
+                                        }
+                                        span { style: MUTED_STYLE, " ({pick.available_count()} cards)" }
+                                    }
+                                    p { style: MUTED_STYLE, {pick.available_card_names().join(", ")} }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,11 +1,66 @@
+use chrono::{DateTime, Local, Utc};
 use dioxus::prelude::*;
 
+mod api;
+mod dashboard;
+mod drafts;
+mod matches;
+mod oauth;
+mod session;
+mod shared_match;
+mod stats;
+mod style;
+
+use dashboard::{AuthCallback, Dashboard};
+use drafts::{DraftDetail, Drafts};
+use matches::{MatchDetail, Matches};
+use session::SessionState;
+use shared_match::SharedMatch;
+use stats::Stats;
+
+#[derive(Clone, Routable, Debug, PartialEq)]
+#[rustfmt::skip]
+enum Route {
+    #[route("/")]
+    Home {},
+    #[layout(Dashboard)]
+        #[route("/matches")]
+        Matches {},
+        #[route("/matches/:id")]
+        MatchDetail { id: String },
+        #[route("/drafts")]
+        Drafts {},
+        #[route("/drafts/:id")]
+        DraftDetail { id: String },
+        #[route("/stats")]
+        Stats {},
+    #[end_layout]
+    #[route("/auth/callback")]
+    AuthCallback {},
+    #[route("/share/:token")]
+    SharedMatch { token: String },
+}
+
+fn format_local_datetime(dt: DateTime<Utc>) -> String {
+    dt.with_timezone(&Local).format("%b %-d, %Y %-I:%M %p").to_string()
+}
+
 fn main() {
     dioxus::launch(app);
 }
 
 #[component]
 fn app() -> Element {
+    let session: SessionState = use_signal(session::load);
+    use_context_provider(|| session);
+
+    rsx! {
+        Router::<Route> {}
+    }
+}
+
+#[component]
+fn Home() -> Element {
     rsx! {
         div {
             style: "display: flex; flex-direction: column; align-items: center; justify-content: center; min-height: 100vh; font-family: system-ui, -apple-system, sans-serif; background: #1a1a2e; color: #e0e0e0;",
@@ -17,12 +72,19 @@ fn app() -> Element {
                 style: "font-size: 1.25rem; margin-bottom: 2rem; color: #a0a0b0;",
                 "A companion app for Magic: The Gathering Arena"
             }
-            a {
-                href: "https://github.com/gazure/arenabuddy/releases",
-                target: "_blank",
-                rel: "noopener noreferrer",
-                style: "padding: 0.75rem 1.5rem; background: #4a90d9; color: #ffffff; text-decoration: none; border-radius: 0.5rem; font-size: 1.1rem; transition: background 0.2s;",
-                "Download the latest release"
+            div { style: "display: flex; gap: 1rem;",
+                a {
+                    href: "https://github.com/gazure/arenabuddy/releases",
+                    target: "_blank",
+                    rel: "noopener noreferrer",
+                    style: "padding: 0.75rem 1.5rem; background: #4a90d9; color: #ffffff; text-decoration: none; border-radius: 0.5rem; font-size: 1.1rem; transition: background 0.2s;",
+                    "Download the latest release"
+                }
+                Link {
+                    to: Route::Matches {},
+                    style: "padding: 0.75rem 1.5rem; border: 1px solid #4a90d9; color: #ffffff; text-decoration: none; border-radius: 0.5rem; font-size: 1.1rem;",
+                    "Open dashboard"
+                }
             }
         }
     }
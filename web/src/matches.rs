@@ -0,0 +1,316 @@
+//! Match list and match details, including the per-game event log.
+
+use arenabuddy_core::{
+    display::{
+        classification::ClassificationEvidence,
+        deck::DeckDisplayRecord,
+        event_log::{ActionDisplay, ActionStyle},
+        match_summary::format_event_id,
+        mulligan::Mulligan,
+    },
+    models::CardType,
+    player_log::event_log::GameEventLog,
+};
+use dioxus::prelude::*;
+
+use crate::{
+    Route, api, format_local_datetime,
+    session::SessionState,
+    style::{
+        CELL_STYLE, ERROR_STYLE, HEADING_STYLE, LINK_STYLE, LOSS_STYLE, MUTED_STYLE, PANEL_STYLE, TABLE_STYLE,
+        WIN_STYLE,
+    },
+};
+
+/// Matched signature cards shown under a deck's archetype.
+const EVIDENCE_CARDS: usize = 5;
+
+fn result_label(did_controller_win: Option<bool>) -> (&'static str, &'static str) {
+    match did_controller_win {
+        Some(true) => ("Win", WIN_STYLE),
+        Some(false) => ("Loss", LOSS_STYLE),
+        None => ("Incomplete", MUTED_STYLE),
+    }
+}
+
+#[component]
+pub(crate) fn Matches() -> Element {
+    let session = use_context::<SessionState>();
+    let matches = use_resource(move || api::match_summaries(session));
+
+    rsx! {
+        div { style: PANEL_STYLE,
+            h1 { style: HEADING_STYLE, "Matches" }
+            match &*matches.read() {
+                None => rsx! {
+                    p { style: MUTED_STYLE, "Loading matches..." }
+                },
+                Some(Err(err)) => rsx! {
+                    p { style: ERROR_STYLE, "Could not load matches: {err}" }
+                },
+                Some(Ok(matches)) if matches.is_empty() => rsx! {
+                    p { style: MUTED_STYLE, "No synced matches yet. Sign in to the desktop app to upload your games." }
+                },
+                Some(Ok(matches)) => rsx! {
+                    table { style: TABLE_STYLE,
+                        thead {
+                            tr {
+                                th { style: CELL_STYLE, "Date" }
+                                th { style: CELL_STYLE, "Opponent" }
+                                th { style: CELL_STYLE, "Format" }
+                                th { style: CELL_STYLE, "Archetypes" }
+                                th { style: CELL_STYLE, "Result" }
+                                th { style: CELL_STYLE, "Games" }
+                            }
+                        }
+                        tbody {
+                            for summary in matches.iter() {
+                                {
+                                    let (label, result_style) = result_label(summary.did_controller_win);
+                                    let archetypes = format!(
+                                        "{} vs {}",
+                                        summary.controller_archetype.as_deref().unwrap_or("Unknown"),
+                                        summary.opponent_archetype.as_deref().unwrap_or("Unknown"),
+                                    );
+                                    rsx! {
+                                        tr { key: "{summary.id}",
+                                            td { style: CELL_STYLE,
+                                                Link {
+                                                    to: Route::MatchDetail { id: summary.id.clone() },
+                                                    style: LINK_STYLE,
+                                                    {format_local_datetime(summary.created_at)}
+                                                }
+                                            }
+                                            td { style: CELL_STYLE, "{summary.opponent_player_name}" }
+                                            td { style: CELL_STYLE, {summary.display_format()} }
+                                            td { style: CELL_STYLE, "{archetypes}" }
+                                            td { style: "{CELL_STYLE} {result_style}", "{label}" }
+                                            td { style: CELL_STYLE, {summary.game_score()} }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+pub(crate) fn MatchDetail(id: String) -> Element {
+    let session = use_context::<SessionState>();
+    let details = use_resource({
+        let id = id.clone();
+        move || api::match_details(session, id.clone())
+    });
+
+    match &*details.read() {
+        None => rsx! {
+            p { style: MUTED_STYLE, "Loading match..." }
+        },
+        Some(Err(err)) => rsx! {
+            div { style: PANEL_STYLE,
+                h1 { style: HEADING_STYLE, "Match unavailable" }
+                p { style: ERROR_STYLE, "{err}" }
+            }
+        },
+        Some(Ok(details)) => {
+            let (label, result_style) =
+                result_label((!details.game_results.is_empty()).then_some(details.did_controller_win));
+            let format = details.format.as_deref().map_or("Unknown", format_event_id);
+            let played_at = format_local_datetime(details.created_at);
+
+            rsx! {
+                div { style: PANEL_STYLE,
+                    h1 { style: "font-size: 2rem; color: #ffffff; margin: 0 0 0.25rem 0;",
+                        "{details.controller_player_name} vs {details.opponent_player_name}"
+                    }
+                    p { style: MUTED_STYLE,
+                        span { style: result_style, "{label}" }
+                        " · {format} · {played_at}"
+                    }
+                    for game in details.game_results.iter() {
+                        p { style: "margin: 0.15rem 0;", "Game {game.game_number}: {game.winning_player}" }
+                    }
+                }
+
+                div { style: "display: grid; grid-template-columns: repeat(auto-fit, minmax(18rem, 1fr)); gap: 1.5rem;",
+                    if let Some(deck) = details.primary_decklist.as_ref() {
+                        DeckPanel {
+                            title: "Your deck",
+                            archetype: details.controller_archetype.clone(),
+                            evidence: details.controller_evidence.clone(),
+                            deck: deck.clone(),
+                            show_quantities: true,
+                        }
+                    }
+                    if let Some(deck) = details.opponent_deck.as_ref() {
+                        DeckPanel {
+                            title: "Opponent's cards",
+                            archetype: details.opponent_archetype.clone(),
+                            evidence: details.opponent_evidence.clone(),
+                            deck: deck.clone(),
+                            show_quantities: false,
+                        }
+                    }
+                }
+
+                for (i, diff) in details.differences.iter().flatten().enumerate() {
+                    div { style: PANEL_STYLE,
+                        h2 { style: HEADING_STYLE, "Game {i + 1} → Game {i + 2}" }
+                        if diff.added.is_empty() && diff.removed.is_empty() {
+                            p { style: MUTED_STYLE, "No changes" }
+                        }
+                        for card in diff.removed.iter() {
+                            p { style: "margin: 0.15rem 0; {LOSS_STYLE}", "-{card.quantity} {card.name}" }
+                        }
+                        for card in diff.added.iter() {
+                            p { style: "margin: 0.15rem 0; {WIN_STYLE}", "+{card.quantity} {card.name}" }
+                        }
+                    }
+                }
+
+                MulliganPanel { mulligans: details.mulligans.clone() }
+
+                EventLog {
+                    event_logs: details.event_logs.clone(),
+                    controller_seat_id: details.controller_seat_id,
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DeckPanel(
+    title: &'static str,
+    archetype: Option<String>,
+    evidence: Option<ClassificationEvidence>,
+    deck: DeckDisplayRecord,
+    show_quantities: bool,
+) -> Element {
+    let groups: Vec<(CardType, usize)> = CardType::iter()
+        .filter(|card_type| deck.main_deck.get(card_type).is_some_and(|cards| !cards.is_empty()))
+        .map(|card_type| (card_type, deck.total_by_type(card_type)))
+        .collect();
+    let matched = evidence
+        .as_ref()
+        .map(|e| e.top_cards(EVIDENCE_CARDS).collect::<Vec<_>>().join(", "))
+        .unwrap_or_default();
+    let runner_up = evidence
+        .and_then(|e| e.runners_up.into_iter().next())
+        .map(|a| format!("{} ({:.2})", a.archetype_name, a.score));
+
+    rsx! {
+        div { style: PANEL_STYLE,
+            h2 { style: HEADING_STYLE, "{title}" }
+            if let Some(archetype) = archetype {
+                p { style: MUTED_STYLE, "{archetype}" }
+            }
+            if !matched.is_empty() {
+                p { style: MUTED_STYLE, "Matched: {matched}" }
+            }
+            if let Some(runner_up) = runner_up {
+                p { style: MUTED_STYLE, "Runner-up: {runner_up}" }
+            }
+            for (card_type, total) in groups {
+                h3 { style: "color: #ffffff; margin: 0.75rem 0 0.25rem 0;", "{card_type} ({total})" }
+                for card in deck.main_deck.get(&card_type).into_iter().flatten() {
+                    p { style: "margin: 0.15rem 0;",
+                        if show_quantities { "{card.quantity} " }
+                        "{card.name}"
+                    }
+                }
+            }
+            if !deck.sideboard.is_empty() {
+                h3 { style: "color: #ffffff; margin: 0.75rem 0 0.25rem 0;", "Sideboard" }
+                for card in deck.sideboard.iter() {
+                    p { style: "margin: 0.15rem 0;", "{card.quantity} {card.name}" }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn MulliganPanel(mulligans: Vec<Mulligan>) -> Element {
+    if mulligans.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { style: PANEL_STYLE,
+            h2 { style: HEADING_STYLE, "Mulligans" }
+            for mulligan in mulligans {
+                {
+                    let hand = mulligan.hand.iter().map(|card| card.name.as_str()).collect::<Vec<_>>().join(", ");
+                    rsx! {
+                        p { style: "margin: 0.5rem 0 0.15rem 0;",
+                            "Game {mulligan.game_number}: {mulligan.decision} {mulligan.number_to_keep} ({mulligan.play_draw}) vs {mulligan.opponent_identity}"
+                        }
+                        p { style: MUTED_STYLE, "{hand}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn action_color(style: ActionStyle) -> &'static str {
+    match style {
+        ActionStyle::Normal => "color: #e0e0e0;",
+        ActionStyle::Phase => "color: #70708a;",
+        ActionStyle::PlayerAction | ActionStyle::Positive => "color: #6fcf97;",
+        ActionStyle::OpponentAction | ActionStyle::Negative | ActionStyle::Damage => "color: #eb5757;",
+        ActionStyle::Attack => "color: #f2994a;",
+        ActionStyle::Defense => "color: #56ccf2;",
+        ActionStyle::Emphasized => "color: #ffffff; font-weight: bold;",
+    }
+}
+
+#[component]
+fn EventLog(event_logs: Vec<GameEventLog>, controller_seat_id: i32) -> Element {
+    rsx! {
+        div { style: PANEL_STYLE,
+            h2 { style: HEADING_STYLE, "Event Log" }
+            if event_logs.is_empty() {
+                p { style: MUTED_STYLE, "No event log available for this match." }
+            }
+            for log in event_logs {
+                {
+                    let event_count = log.events.len();
+                    let lines: Vec<(Option<i32>, ActionDisplay)> = log
+                        .events
+                        .iter()
+                        .filter_map(|event| {
+                            ActionDisplay::from_game_action(&event.action, controller_seat_id)
+                                .map(|display| (event.turn.as_ref().map(|turn| turn.turn_number), display))
+                        })
+                        .collect();
+                    rsx! {
+                        details { style: "margin-bottom: 0.75rem;",
+                            summary { style: "cursor: pointer; color: #ffffff;",
+                                "Game {log.game_number} ({event_count} events)"
+                            }
+                            for (turn, display) in lines {
+                                {
+                                    let color = action_color(display.style);
+                                    rsx! {
+                                        p { style: "margin: 0.15rem 0; font-size: 0.9rem; {color}",
+                                            if let Some(turn) = turn {
+                                                span { style: "color: #70708a; margin-right: 0.5rem;", "T{turn}" }
+                                            }
+                                            "{display.icon} {display.description}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Authorization code + PKCE sign-in through the identity providers the server advertises.
+//!
+//! [`begin`] remembers the verifier in `sessionStorage` and sends the browser to the provider;
+//! the provider redirects back to `/auth/callback`, where [`finish`] checks the `state` and hands
+//! the code and verifier to [`crate::api::exchange_code`].
+
+use std::borrow::Cow;
+
+use arenabuddy_core::services::auth_service::IdentityProviderInfo;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::api::Result;
+
+const STORAGE_KEY: &str = "arenabuddy.oauth";
+const CALLBACK_PATH: &str = "/auth/callback";
+
+/// A sign-in started by [`begin`], waiting for the provider's redirect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PendingLogin {
+    pub(crate) provider: String,
+    pub(crate) code_verifier: String,
+    pub(crate) redirect_uri: String,
+    state: String,
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.session_storage().ok()?
+}
+
+fn random_token() -> Result<String> {
+    let mut bytes = [0u8; 32];
+    web_sys::window()
+        .ok_or("No browser window")?
+        .crypto()
+        .and_then(|crypto| crypto.get_random_values_with_u8_array(&mut bytes))
+        .map_err(|_| "Secure random numbers are unavailable")?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Redirect the browser to `provider` to sign in.
+pub(crate) fn begin(provider: &IdentityProviderInfo) -> Result<()> {
+    let location = web_sys::window().ok_or("No browser window")?.location();
+    let origin = location.origin().map_err(|_| "Unknown page origin")?;
+    let pending = PendingLogin {
+        provider: provider.name.clone(),
+        code_verifier: random_token()?,
+        redirect_uri: format!("{origin}{CALLBACK_PATH}"),
+        state: random_token()?,
+    };
+    let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(pending.code_verifier.as_bytes()));
+
+    let json = serde_json::to_string(&pending).map_err(|e| e.to_string())?;
+    storage()
+        .ok_or("Session storage is unavailable")?
+        .set_item(STORAGE_KEY, &json)
+        .map_err(|_| "Session storage is unavailable")?;
+
+    let url = format!(
+        "{}?response_type=code&client_id={}&redirect_uri={}&scope={}&state={}&code_challenge={code_challenge}&code_challenge_method=S256",
+        provider.authorization_endpoint,
+        urlencoding::encode(&provider.client_id),
+        urlencoding::encode(&pending.redirect_uri),
+        urlencoding::encode(&provider.scope),
+        pending.state,
+    );
+    location
+        .set_href(&url)
+        .map_err(|_| "Could not open the sign-in page".to_string())
+}
+
+/// The value of `name` in a `?a=b&c=d` query string, percent-decoded.
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query.trim_start_matches('?').split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        if key != name {
+            return None;
+        }
+        urlencoding::decode(&value.replace('+', " ")).ok().map(Cow::into_owned)
+    })
+}
+
+/// Read the provider's redirect from the current URL and return the pending sign-in with the
+/// authorization code. The pending sign-in is consumed, so a reloaded callback page can't replay it.
+pub(crate) fn finish() -> Result<(PendingLogin, String)> {
+    let query = web_sys::window()
+        .ok_or("No browser window")?
+        .location()
+        .search()
+        .map_err(|_| "Could not read the sign-in response")?;
+
+    let storage = storage().ok_or("Session storage is unavailable")?;
+    let pending = storage.get_item(STORAGE_KEY).ok().flatten();
+    storage.remove_item(STORAGE_KEY).ok();
+    let pending: PendingLogin = pending
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .ok_or("No sign-in in progress")?;
+
+    if let Some(error) = query_param(&query, "error") {
+        let description = query_param(&query, "error_description").unwrap_or(error);
+        return Err(format!("Sign-in was not completed: {description}"));
+    }
+    if query_param(&query, "state").as_deref() != Some(pending.state.as_str()) {
+        return Err("Sign-in response did not match the request".to_string());
+    }
+    let code = query_param(&query, "code").ok_or("Sign-in response had no authorization code")?;
+    Ok((pending, code))
+}
@@ -0,0 +1,52 @@
+//! The signed-in session, kept in `localStorage` so it survives page reloads.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const STORAGE_KEY: &str = "arenabuddy.session";
+
+/// Tokens issued by `AuthService` plus the name to show in the header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Session {
+    pub(crate) token: String,
+    pub(crate) token_expires_at: i64,
+    pub(crate) refresh_token: String,
+    pub(crate) refresh_expires_at: i64,
+    pub(crate) username: String,
+}
+
+impl Session {
+    /// Returns true if the access token expires within 60 seconds.
+    pub(crate) fn needs_refresh(&self) -> bool {
+        self.token_expires_at - chrono::Utc::now().timestamp() < 60
+    }
+
+    fn is_expired(&self) -> bool {
+        self.refresh_expires_at <= chrono::Utc::now().timestamp()
+    }
+}
+
+/// App-wide session state, provided as context by the root component.
+pub(crate) type SessionState = Signal<Option<Session>>;
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Load the saved session, ignoring it if the refresh token has expired.
+pub(crate) fn load() -> Option<Session> {
+    let json = storage()?.get_item(STORAGE_KEY).ok()??;
+    serde_json::from_str::<Session>(&json).ok().filter(|s| !s.is_expired())
+}
+
+pub(crate) fn save(session: &Session) {
+    if let (Some(storage), Ok(json)) = (storage(), serde_json::to_string(session)) {
+        storage.set_item(STORAGE_KEY, &json).ok();
+    }
+}
+
+pub(crate) fn clear() {
+    if let Some(storage) = storage() {
+        storage.remove_item(STORAGE_KEY).ok();
+    }
+}
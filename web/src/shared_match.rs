@@ -0,0 +1,251 @@
+//! Read-only view of a match shared via a share link.
+//!
+//! The JSON mirrors the server's `GetSharedMatchResponse`; only the fields rendered here are declared.
+
+use std::collections::BTreeMap;
+
+use dioxus::prelude::*;
+use serde::Deserialize;
+
+use crate::style::{HEADING_STYLE, LOSS_STYLE, MUTED_STYLE, PAGE_STYLE, PANEL_STYLE, WIN_STYLE};
+
+/// Base URL of the server's public HTTP endpoints, overridable at build time.
+const API_URL: &str = match option_env!("ARENABUDDY_API_URL") {
+    Some(url) => url,
+    None => "https://api.arenabuddy.io",
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+struct SharedMatchResponse {
+    match_data: Option<MatchData>,
+    card_names: BTreeMap<i32, String>,
+    expires_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+struct MatchData {
+    mtga_match: Option<MtgaMatch>,
+    decks: Vec<Deck>,
+    mulligans: Vec<Mulligan>,
+    results: Vec<MatchResult>,
+    opponent_deck: Option<OpponentDeck>,
+    event_logs: Vec<GameEventLog>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+struct MtgaMatch {
+    controller_seat_id: i32,
+    controller_player_name: String,
+    opponent_player_name: String,
+    created_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+struct Deck {
+    name: String,
+    game_number: i32,
+    mainboard: Vec<i32>,
+    sideboard: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+struct Mulligan {
+    game_number: i32,
+    number_to_keep: i32,
+    play_draw: String,
+    decision: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+struct MatchResult {
+    game_number: i32,
+    winning_team_id: i32,
+    result_scope: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+struct OpponentDeck {
+    cards: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+struct GameEventLog {
+    game_number: i32,
+    events_json: String,
+}
+
+async fn fetch_shared_match(token: String) -> Result<SharedMatchResponse, String> {
+    let url = format!("{API_URL}/api/v1/shares/{}", urlencoding::encode(&token));
+    let response = reqwest::get(url).await.map_err(|e| e.to_string())?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err("This share link doesn't exist, has expired, or was revoked.".to_string());
+    }
+    if !response.status().is_success() {
+        return Err(format!("Server returned {}", response.status()));
+    }
+    response.json().await.map_err(|e| e.to_string())
+}
+
+/// Collapse a list of card ids into `(name, quantity)` rows, sorted by name.
+fn card_rows(cards: &[i32], names: &BTreeMap<i32, String>) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for id in cards {
+        let name = names.get(id).cloned().unwrap_or_else(|| format!("Unknown card #{id}"));
+        *counts.entry(name).or_default() += 1;
+    }
+    counts.into_iter().collect()
+}
+
+/// One-line summary of a serialized game event, e.g. `T3 · CardPlayed · Lightning Bolt`.
+fn event_summary(event: &serde_json::Value) -> String {
+    let action = &event["action"];
+    let mut parts = Vec::new();
+    if let Some(turn) = event["turn"]["turn_number"].as_i64() {
+        parts.push(format!("T{turn}"));
+    }
+    if let Some(kind) = action["type"].as_str() {
+        parts.push(kind.to_string());
+    }
+    if let Some(name) = action["card"]["name"].as_str() {
+        parts.push(name.to_string());
+    } else if let Some(name) = action["player"]["name"].as_str() {
+        parts.push(name.to_string());
+    }
+    parts.join(" · ")
+}
+
+#[component]
+pub fn SharedMatch(token: String) -> Element {
+    let shared = use_resource(move || fetch_shared_match(token.clone()));
+
+    rsx! {
+        div { style: PAGE_STYLE,
+            div { style: "max-width: 64rem; margin: 0 auto;",
+                match &*shared.read() {
+                    None => rsx! {
+                        p { style: MUTED_STYLE, "Loading shared match..." }
+                    },
+                    Some(Err(err)) => rsx! {
+                        div { style: PANEL_STYLE,
+                            h1 { style: HEADING_STYLE, "Match unavailable" }
+                            p { style: MUTED_STYLE, "{err}" }
+                        }
+                    },
+                    Some(Ok(shared)) => rsx! {
+                        MatchView { shared: shared.clone() }
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn MatchView(shared: SharedMatchResponse) -> Element {
+    let data = shared.match_data.clone().unwrap_or_default();
+    let info = data.mtga_match.clone().unwrap_or_default();
+    let names = &shared.card_names;
+
+    let match_winner = data
+        .results
+        .iter()
+        .find(|r| r.result_scope == "MatchScope_Match")
+        .map(|r| r.winning_team_id == info.controller_seat_id);
+    let outcome = match match_winner {
+        Some(true) => "Win",
+        Some(false) => "Loss",
+        None => "Incomplete",
+    };
+    let played_on = info.created_at.get(..10).unwrap_or(&info.created_at).to_string();
+
+    let mut games: Vec<_> = data
+        .results
+        .iter()
+        .filter(|r| r.result_scope == "MatchScope_Game")
+        .collect();
+    games.sort_by_key(|r| r.game_number);
+
+    let first_deck = data.decks.iter().min_by_key(|d| d.game_number).cloned();
+    let opponent_cards = data
+        .opponent_deck
+        .as_ref()
+        .map(|d| card_rows(&d.cards, names))
+        .unwrap_or_default();
+
+    rsx! {
+        div { style: PANEL_STYLE,
+            h1 { style: "font-size: 2rem; color: #ffffff; margin: 0 0 0.25rem 0;",
+                "{info.controller_player_name} vs {info.opponent_player_name}"
+            }
+            p { style: MUTED_STYLE, "{outcome} · {played_on}" }
+            if !shared.expires_at.is_empty() {
+                p { style: "color: #70708a; font-size: 0.85rem;",
+                    "Link expires {shared.expires_at}"
+                }
+            }
+        }
+
+        div { style: PANEL_STYLE,
+            h2 { style: HEADING_STYLE, "Games" }
+            for game in games {
+                p {
+                    "Game {game.game_number}: "
+                    if game.winning_team_id == info.controller_seat_id {
+                        span { style: WIN_STYLE, "{info.controller_player_name}" }
+                    } else {
+                        span { style: LOSS_STYLE, "{info.opponent_player_name}" }
+                    }
+                }
+            }
+            for mulligan in data.mulligans.iter().filter(|m| m.decision == "Keep") {
+                p { style: MUTED_STYLE,
+                    "Game {mulligan.game_number}: kept {mulligan.number_to_keep} ({mulligan.play_draw})"
+                }
+            }
+        }
+
+        div { style: "display: grid; grid-template-columns: repeat(auto-fit, minmax(18rem, 1fr)); gap: 1.5rem;",
+            if let Some(deck) = first_deck {
+                div { style: PANEL_STYLE,
+                    h2 { style: HEADING_STYLE, "{deck.name}" }
+                    for (name, quantity) in card_rows(&deck.mainboard, names) {
+                        p { style: "margin: 0.15rem 0;", "{quantity} {name}" }
+                    }
+                    if !deck.sideboard.is_empty() {
+                        h3 { style: "color: #ffffff; margin: 1rem 0 0.5rem 0;", "Sideboard" }
+                        for (name, quantity) in card_rows(&deck.sideboard, names) {
+                            p { style: "margin: 0.15rem 0;", "{quantity} {name}" }
+                        }
+                    }
+                }
+            }
+            if !opponent_cards.is_empty() {
+                div { style: PANEL_STYLE,
+                    h2 { style: HEADING_STYLE, "Opponent's cards" }
+                    for (name, _) in opponent_cards {
+                        p { style: "margin: 0.15rem 0;", "{name}" }
+                    }
+                }
+            }
+        }
+
+        for log in data.event_logs.iter() {
+            details { style: PANEL_STYLE,
+                summary { style: "cursor: pointer; color: #ffffff;", "Game {log.game_number} event log" }
+                for event in serde_json::from_str::<Vec<serde_json::Value>>(&log.events_json).unwrap_or_default() {
+                    p { style: "margin: 0.15rem 0; font-family: monospace; font-size: 0.85rem;",
+                        {event_summary(&event)}
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Personal win rates, mulligan and opponent breakdowns over a chosen time window.
+
+use arenabuddy_core::display::stats::{MatchStats, TimeWindow};
+use dioxus::prelude::*;
+
+use crate::{
+    api,
+    session::SessionState,
+    style::{ERROR_STYLE, HEADING_STYLE, LOSS_STYLE, MUTED_STYLE, PANEL_STYLE, WIN_STYLE},
+};
+
+fn format_rate(rate: Option<f64>) -> String {
+    rate.map_or("N/A".to_string(), |r| format!("{r:.1}%"))
+}
+
+#[component]
+fn RecordLine(label: String, wins: i64, losses: i64, rate: Option<f64>) -> Element {
+    rsx! {
+        div { style: "display: flex; justify-content: space-between; padding: 0.4rem 0; border-bottom: 1px solid #33334d;",
+            span { style: MUTED_STYLE, "{label}" }
+            span {
+                span { style: WIN_STYLE, "{wins}W" }
+                " - "
+                span { style: LOSS_STYLE, "{losses}L" }
+                span { style: "margin-left: 0.5rem; {MUTED_STYLE}", "({format_rate(rate)})" }
+            }
+        }
+    }
+}
+
+#[component]
+fn StatsView(stats: MatchStats) -> Element {
+    rsx! {
+        div { style: "display: grid; grid-template-columns: repeat(auto-fit, minmax(20rem, 1fr)); gap: 1.5rem;",
+            div { style: PANEL_STYLE,
+                h2 { style: HEADING_STYLE, "Match Record" }
+                RecordLine {
+                    label: "Overall",
+                    wins: stats.match_wins,
+                    losses: stats.match_losses,
+                    rate: stats.match_win_rate(),
+                }
+                p { style: MUTED_STYLE, "{stats.total_matches} matches played" }
+            }
+
+            div { style: PANEL_STYLE,
+                h2 { style: HEADING_STYLE, "Game Record" }
+                RecordLine {
+                    label: "Overall",
+                    wins: stats.game_wins,
+                    losses: stats.game_losses,
+                    rate: stats.game_win_rate(),
+                }
+                RecordLine {
+                    label: "On the Play",
+                    wins: stats.play_wins,
+                    losses: stats.play_losses,
+                    rate: stats.play_win_rate(),
+                }
+                RecordLine {
+                    label: "On the Draw",
+                    wins: stats.draw_wins,
+                    losses: stats.draw_losses,
+                    rate: stats.draw_win_rate(),
+                }
+                p { style: MUTED_STYLE, "{stats.total_games} games played" }
+            }
+
+            div { style: PANEL_STYLE,
+                h2 { style: HEADING_STYLE, "Mulligans" }
+                if stats.mulligan_stats.is_empty() {
+                    p { style: MUTED_STYLE, "No mulligan data available" }
+                }
+                for bucket in stats.mulligan_stats.iter() {
+                    RecordLine {
+                        label: format!("Kept {}", bucket.cards_kept),
+                        wins: bucket.wins,
+                        losses: bucket.losses,
+                        rate: bucket.win_rate(),
+                    }
+                }
+            }
+
+            if !stats.opponents.is_empty() {
+                div { style: PANEL_STYLE,
+                    h2 { style: HEADING_STYLE, "Top Opponents" }
+                    for opponent in stats.opponents.iter() {
+                        RecordLine {
+                            label: opponent.name.clone(),
+                            wins: opponent.wins,
+                            losses: opponent.losses,
+                            rate: opponent.win_rate(),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub(crate) fn Stats() -> Element {
+    let session = use_context::<SessionState>();
+    let mut time_window = use_signal(TimeWindow::default);
+    let stats = use_resource(move || api::stats(session, time_window()));
+
+    rsx! {
+        div { style: "display: flex; align-items: center; gap: 1rem; margin-bottom: 1.5rem;",
+            h1 { style: "font-size: 1.5rem; color: #ffffff; margin: 0;", "Stats" }
+            select {
+                style: "margin-left: auto; padding: 0.4rem; background: #23233a; color: #e0e0e0; border: 1px solid #33334d; border-radius: 0.5rem;",
+                onchange: move |evt| {
+                    if let Some(window) = TimeWindow::ALL.into_iter().find(|w| w.key() == evt.value()) {
+                        time_window.set(window);
+                    }
+                },
+                for window in TimeWindow::ALL {
+                    option { value: window.key(), selected: window == time_window(), "{window}" }
+                }
+            }
+        }
+        match &*stats.read() {
+            None => rsx! {
+                p { style: MUTED_STYLE, "Loading stats..." }
+            },
+            Some(Err(err)) => rsx! {
+                p { style: ERROR_STYLE, "Could not load stats: {err}" }
+            },
+            Some(Ok(stats)) => rsx! {
+                StatsView { stats: stats.clone() }
+            },
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! Inline styles shared across pages.
+
+pub(crate) const PAGE_STYLE: &str = "min-height: 100vh; font-family: system-ui, -apple-system, sans-serif; background: #1a1a2e; color: #e0e0e0; padding: 2rem;";
+pub(crate) const PANEL_STYLE: &str = "background: #23233a; border: 1px solid #33334d; border-radius: 0.5rem; padding: 1rem 1.25rem; margin-bottom: 1.5rem;";
+pub(crate) const HEADING_STYLE: &str = "font-size: 1.25rem; color: #ffffff; margin: 0 0 0.75rem 0;";
+pub(crate) const MUTED_STYLE: &str = "color: #a0a0b0;";
+pub(crate) const ERROR_STYLE: &str = "color: #eb5757;";
+pub(crate) const WIN_STYLE: &str = "color: #6fcf97;";
+pub(crate) const LOSS_STYLE: &str = "color: #eb5757;";
+pub(crate) const LINK_STYLE: &str = "color: #4a90d9; text-decoration: none;";
+pub(crate) const BUTTON_STYLE: &str = "padding: 0.5rem 1rem; background: #4a90d9; color: #ffffff; border: none; border-radius: 0.5rem; font-size: 1rem; cursor: pointer;";
+pub(crate) const INPUT_STYLE: &str = "display: block; width: 100%; box-sizing: border-box; padding: 0.5rem 0.75rem; margin: 0.25rem 0 1rem 0; background: #1a1a2e; color: #e0e0e0; border: 1px solid #33334d; border-radius: 0.5rem; font-size: 1rem;";
+pub(crate) const TABLE_STYLE: &str = "width: 100%; border-collapse: collapse;";
+pub(crate) const CELL_STYLE: &str = "padding: 0.4rem 0.5rem; border-bottom: 1px solid #33334d; text-align: left;";